@@ -19,7 +19,7 @@ use ark_std::ops::Mul;
 const NUM_PROVE_REPEATITIONS: usize = 10;
 const NUM_VERIFY_REPEATITIONS: usize = 50;
 
-#[derive(Copy)]
+#[derive(Copy, Clone)]
 struct DummyCircuit<F: PrimeField> {
     pub a: Option<F>,
     pub b: Option<F>,
@@ -27,17 +27,6 @@ struct DummyCircuit<F: PrimeField> {
     pub num_constraints: usize,
 }
 
-impl<F: PrimeField> Clone for DummyCircuit<F> {
-    fn clone(&self) -> Self {
-        DummyCircuit {
-            a: self.a.clone(),
-            b: self.b.clone(),
-            num_variables: self.num_variables.clone(),
-            num_constraints: self.num_constraints.clone(),
-        }
-    }
-}
-
 impl<F: PrimeField> ConstraintSynthesizer<F> for DummyCircuit<F> {
     fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
         let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
@@ -107,7 +96,7 @@ macro_rules! groth16_verify_bench {
         let start = ark_std::time::Instant::now();
 
         for _ in 0..NUM_VERIFY_REPEATITIONS {
-            let _ = Groth16::<$bench_pairing_engine>::verify(&vk, &vec![v], &proof).unwrap();
+            let _ = Groth16::<$bench_pairing_engine>::verify(&vk, &[v], &proof).unwrap();
         }
 
         println!(
@@ -0,0 +1,586 @@
+//! Aggregation of Groth16 proofs for one verifying key into a single proof of size
+//! logarithmic in their number, after [[SnarkPack]](https://eprint.iacr.org/2021/529.pdf).
+//!
+//! # Specification
+//!
+//! For `n` proofs `(A_i, B_i, C_i)` the aggregator commits to the vectors `A`, `B` and `C`
+//! under the key `v` in `G2` and `w` in `G1` of an [`AggregationSrs`] with secrets `a` and
+//! `b`, where
+//!
+//! ```text
+//! v_i = (a^i · H, b^i · H),  w_i = (a^(n + i) · G, b^(n + i) · G)  for 0 ≤ i < n,
+//! ```
+//!
+//! and derives a challenge `r` from the commitments and all public inputs. The `n`
+//! verification equations are combined into
+//!
+//! ```text
+//! Z_AB = Σ r^i · e(A_i, B_i) = (Σ r^i) · e(α, β) + e(Σ r^i · IC(x_i), γ) + e(Z_C, δ)
+//! ```
+//!
+//! with `Z_C = Σ r^i · C_i`, written additively in `E::GT`. A target inner pairing product
+//! argument shows that `Z_AB` is the inner product of the committed `A` and `B`, and a
+//! multi-exponentiation inner product argument that `Z_C` is the inner product of the
+//! committed `C` with `(1, r, …, r^(n-1))`. Both halve their vectors in each of `log n`
+//! rounds with a shared challenge `x`, folding
+//!
+//! ```text
+//! A ← A_L + x · A_R,  B ← B_L + x⁻¹ · B_R,  C ← C_L + x · C_R,
+//! v ← v_L + x⁻¹ · v_R,  w ← w_L + x · w_R,
+//! ```
+//!
+//! and the final keys are shown to be correctly folded with KZG openings at a last
+//! challenge `z`, using the powers of `a` and `b` in the SRS. Challenges are derived by
+//! SHA-256 over the uncompressed encodings of all messages.
+//!
+//! The number of proofs must be a power of two; callers aggregating other numbers of
+//! proofs can repeat the last proof and its public inputs.
+use crate::{hash_to_field, r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey, Proof};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    scalar_mul::fixed_base::FixedBase,
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{cfg_iter, rand::Rng, vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Domain separator for the challenges of proof aggregation.
+const AGGREGATION_DOMAIN: &[u8] = b"ark-groth16/aggregation/v1";
+
+/// The structured reference string of proof aggregation: the powers of two secrets `a`
+/// and `b` in both groups. It is independent of the circuit, and supports aggregating up
+/// to [`max_proofs`](Self::max_proofs) proofs.
+///
+/// Anyone who knows `a` or `b` can forge aggregate proofs, so in production the powers
+/// must come from a trusted setup ceremony, such as two independent powers-of-tau.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationSrs<E: Pairing> {
+    /// The elements `a^i * G` for `i < 2 * max_proofs`.
+    pub g_a_powers: Vec<E::G1Affine>,
+    /// The elements `b^i * G` for `i < 2 * max_proofs`.
+    pub g_b_powers: Vec<E::G1Affine>,
+    /// The elements `a^i * H` for `i < max_proofs`.
+    pub h_a_powers: Vec<E::G2Affine>,
+    /// The elements `b^i * H` for `i < max_proofs`.
+    pub h_b_powers: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> AggregationSrs<E> {
+    /// The largest number of proofs that can be aggregated with this SRS.
+    pub fn max_proofs(&self) -> usize {
+        (self.g_a_powers.len() / 2)
+            .min(self.g_b_powers.len() / 2)
+            .min(self.h_a_powers.len())
+            .min(self.h_b_powers.len())
+    }
+
+    /// The part of the SRS needed to verify aggregate proofs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the SRS supports fewer than two proofs.
+    pub fn verifier_key(&self) -> AggregationVerifierKey<E> {
+        assert!(
+            self.max_proofs() >= 2,
+            "the SRS supports fewer than two proofs"
+        );
+        AggregationVerifierKey {
+            g: self.g_a_powers[0],
+            g_a: self.g_a_powers[1],
+            g_b: self.g_b_powers[1],
+            h: self.h_a_powers[0],
+            h_a: self.h_a_powers[1],
+            h_b: self.h_b_powers[1],
+        }
+    }
+}
+
+/// The verifier's part of an [`AggregationSrs`].
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationVerifierKey<E: Pairing> {
+    /// The generator `G` of `E::G1`.
+    pub g: E::G1Affine,
+    /// The element `a * G`.
+    pub g_a: E::G1Affine,
+    /// The element `b * G`.
+    pub g_b: E::G1Affine,
+    /// The generator `H` of `E::G2`.
+    pub h: E::G2Affine,
+    /// The element `a * H`.
+    pub h_a: E::G2Affine,
+    /// The element `b * H`.
+    pub h_b: E::G2Affine,
+}
+
+/// A commitment in `E::GT` to vectors of group elements, under the `a` and the `b` half
+/// of the commitment key.
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PairCommitment<E: Pairing> {
+    /// The commitment under the powers of `a`.
+    pub t: PairingOutput<E>,
+    /// The commitment under the powers of `b`.
+    pub u: PairingOutput<E>,
+}
+
+/// A half of a commitment key: its powers of `a` and its powers of `b`.
+type KeyHalves<'a, G> = (&'a [G], &'a [G]);
+
+impl<E: Pairing> PairCommitment<E> {
+    /// Commits to `g1` in `E::G1` under `v` and to `g2` in `E::G2` under `w`.
+    fn new(
+        v: KeyHalves<'_, E::G2Affine>,
+        w: KeyHalves<'_, E::G1Affine>,
+        g1: &[E::G1Affine],
+        g2: &[E::G2Affine],
+    ) -> Self {
+        Self {
+            t: E::multi_pairing(g1.iter().chain(w.0).copied(), v.0.iter().chain(g2).copied()),
+            u: E::multi_pairing(g1.iter().chain(w.1).copied(), v.1.iter().chain(g2).copied()),
+        }
+    }
+
+    /// Folds the commitments of the cross terms of a round into `self`.
+    fn fold(&mut self, left: &Self, right: &Self, x: E::ScalarField, x_inv: E::ScalarField) {
+        self.t += left.t * x + right.t * x_inv;
+        self.u += left.u * x + right.u * x_inv;
+    }
+}
+
+/// The messages of one round of the inner product arguments, for the cross terms of the
+/// left and right halves of the vectors.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregationRound<E: Pairing> {
+    /// The commitment to `A_R` and `B_L` under `v_L` and `w_R`.
+    pub ab_left: PairCommitment<E>,
+    /// The commitment to `A_L` and `B_R` under `v_R` and `w_L`.
+    pub ab_right: PairCommitment<E>,
+    /// The inner pairing product of `A_R` and `B_L`.
+    pub z_ab_left: PairingOutput<E>,
+    /// The inner pairing product of `A_L` and `B_R`.
+    pub z_ab_right: PairingOutput<E>,
+    /// The commitment to `C_R` under `v_L`.
+    pub c_left: PairCommitment<E>,
+    /// The commitment to `C_L` under `v_R`.
+    pub c_right: PairCommitment<E>,
+    /// The inner product of `C_R` with the left half of the scalars.
+    pub z_c_left: E::G1Affine,
+    /// The inner product of `C_L` with the right half of the scalars.
+    pub z_c_right: E::G1Affine,
+}
+
+/// An aggregate of Groth16 proofs for one verifying key.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof<E: Pairing> {
+    /// The commitment to the `A` and `B` elements of the proofs.
+    pub com_ab: PairCommitment<E>,
+    /// The commitment to the `C` elements of the proofs.
+    pub com_c: PairCommitment<E>,
+    /// The element `Σ r^i · e(A_i, B_i)` in `E::GT`.
+    pub z_ab: PairingOutput<E>,
+    /// The element `Σ r^i · C_i` in `E::G1`.
+    pub z_c: E::G1Affine,
+    /// The rounds of the inner product arguments, `log n` for `n` proofs.
+    pub rounds: Vec<AggregationRound<E>>,
+    /// The folded `A`.
+    pub final_a: E::G1Affine,
+    /// The folded `B`.
+    pub final_b: E::G2Affine,
+    /// The folded `C`.
+    pub final_c: E::G1Affine,
+    /// The folded key `v`, under the powers of `a` and of `b`.
+    pub final_v: (E::G2Affine, E::G2Affine),
+    /// The folded key `w`, under the powers of `a` and of `b`.
+    pub final_w: (E::G1Affine, E::G1Affine),
+    /// The KZG openings of the folded key `v`, under the powers of `a` and of `b`.
+    pub opening_v: (E::G2Affine, E::G2Affine),
+    /// The KZG openings of the folded key `w`, under the powers of `a` and of `b`.
+    pub opening_w: (E::G1Affine, E::G1Affine),
+}
+
+/// A Fiat-Shamir transcript over SHA-256, holding the encoding of the messages so far.
+struct Transcript(Vec<u8>);
+
+impl Transcript {
+    fn new(num_proofs: usize) -> Self {
+        Self((num_proofs as u64).to_le_bytes().to_vec())
+    }
+
+    fn append(&mut self, message: &impl CanonicalSerialize) {
+        message
+            .serialize_uncompressed(&mut self.0)
+            .expect("serializing into a `Vec` cannot fail");
+    }
+
+    /// Derives a non-zero challenge from the messages so far, and appends it.
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        loop {
+            let challenge: F = hash_to_field(AGGREGATION_DOMAIN, &self.0);
+            self.append(&challenge);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+
+    fn append_round<E: Pairing>(&mut self, round: &AggregationRound<E>) -> E::ScalarField {
+        self.append(round);
+        self.challenge()
+    }
+}
+
+/// The powers `1, x, …, x^(n-1)`.
+fn powers<F: Field>(x: F, n: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(n);
+    let mut power = F::one();
+    for _ in 0..n {
+        powers.push(power);
+        power *= x;
+    }
+    powers
+}
+
+/// Returns `left_i + x · right_i`.
+fn fold_points<G: AffineRepr>(left: &[G], right: &[G], x: G::ScalarField) -> Vec<G> {
+    let x = x.into_bigint();
+    let folded = cfg_iter!(left)
+        .zip(right)
+        .map(|(l, r)| r.mul_bigint(x) + l)
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&folded)
+}
+
+/// Returns `scalars_i · points_i`.
+fn scale_points<G: AffineRepr>(points: &[G], scalars: &[G::ScalarField]) -> Vec<G> {
+    let scaled = cfg_iter!(points)
+        .zip(scalars)
+        .map(|(p, s)| p.mul_bigint(s.into_bigint()))
+        .collect::<Vec<_>>();
+    G::Group::normalize_batch(&scaled)
+}
+
+/// The coefficients of `Π_j (1 + c_j · X^(n / 2^(j+1)))`, of degree below `n = 2^c.len()`.
+fn folding_coefficients<F: Field>(c: &[F]) -> Vec<F> {
+    let mut coefficients = vec![F::one()];
+    for c_j in c.iter().rev() {
+        let shifted = coefficients
+            .iter()
+            .map(|coeff| *coeff * c_j)
+            .collect::<Vec<_>>();
+        coefficients.extend(shifted);
+    }
+    coefficients
+}
+
+/// Evaluates the polynomial of [`folding_coefficients`] at `z`.
+fn evaluate_folding<F: Field>(c: &[F], z: F, n: usize) -> F {
+    c.iter()
+        .enumerate()
+        .map(|(j, c_j)| F::one() + *c_j * z.pow([(n >> (j + 1)) as u64]))
+        .product()
+}
+
+/// The quotient of `f(X) - f(z)` by `X - z`, for `f` given by its coefficients.
+fn kzg_quotient<F: Field>(f: &[F], z: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); f.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for (q, coeff) in quotient.iter_mut().zip(&f[1..]).rev() {
+        acc = acc * z + coeff;
+        *q = acc;
+    }
+    quotient
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a random structured reference string for aggregating up to `max_proofs`
+    /// proofs. This is for testing only; see [`AggregationSrs`].
+    ///
+    /// Returns [`SynthesisError::Unsatisfiable`] if `max_proofs` is below two.
+    pub fn generate_aggregation_srs(
+        max_proofs: usize,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<AggregationSrs<E>> {
+        if max_proofs < 2 {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let a = E::ScalarField::rand(rng);
+        let b = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let h = E::G2::rand(rng);
+
+        let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
+        let g1_window = FixedBase::get_mul_window_size(4 * max_proofs);
+        let g1_table = FixedBase::get_window_table::<E::G1>(scalar_bits, g1_window, g);
+        let g2_window = FixedBase::get_mul_window_size(2 * max_proofs);
+        let g2_table = FixedBase::get_window_table::<E::G2>(scalar_bits, g2_window, h);
+        let g1_powers = |x| {
+            let powers = powers(x, 2 * max_proofs);
+            E::G1::normalize_batch(&FixedBase::msm(scalar_bits, g1_window, &g1_table, &powers))
+        };
+        let g2_powers = |x| {
+            let powers = powers(x, max_proofs);
+            E::G2::normalize_batch(&FixedBase::msm(scalar_bits, g2_window, &g2_table, &powers))
+        };
+
+        Ok(AggregationSrs {
+            g_a_powers: g1_powers(a),
+            g_b_powers: g1_powers(b),
+            h_a_powers: g2_powers(a),
+            h_b_powers: g2_powers(b),
+        })
+    }
+
+    /// Aggregates `proofs`, each paired with its public inputs, into a single proof whose
+    /// size is logarithmic in their number. The proofs are not checked; an aggregate of
+    /// proofs of which any is invalid does not verify.
+    ///
+    /// Returns [`SynthesisError::Unsatisfiable`] if the number of proofs is not a power
+    /// of two between 2 and [`AggregationSrs::max_proofs`].
+    pub fn aggregate_proofs<I: AsRef<[E::ScalarField]>>(
+        srs: &AggregationSrs<E>,
+        proofs: &[(Proof<E>, I)],
+    ) -> R1CSResult<AggregateProof<E>> {
+        let n = proofs.len();
+        if n < 2 || !n.is_power_of_two() || n > srs.max_proofs() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut a = proofs.iter().map(|(proof, _)| proof.a).collect::<Vec<_>>();
+        let mut b = proofs.iter().map(|(proof, _)| proof.b).collect::<Vec<_>>();
+        let mut c = proofs.iter().map(|(proof, _)| proof.c).collect::<Vec<_>>();
+        let mut v_a = srs.h_a_powers[..n].to_vec();
+        let mut v_b = srs.h_b_powers[..n].to_vec();
+        let mut w_a = srs.g_a_powers[n..2 * n].to_vec();
+        let mut w_b = srs.g_b_powers[n..2 * n].to_vec();
+
+        let com_ab = PairCommitment::new((&v_a, &v_b), (&w_a, &w_b), &a, &b);
+        let com_c = PairCommitment::new((&v_a, &v_b), (&[], &[]), &c, &[]);
+        let mut transcript = Transcript::new(n);
+        for (_, public_inputs) in proofs {
+            transcript.append(&public_inputs.as_ref());
+        }
+        transcript.append(&com_ab);
+        transcript.append(&com_c);
+        let r = transcript.challenge::<E::ScalarField>();
+        let r_inv = r.inverse().expect("challenges are non-zero");
+
+        // Rescaling `B` by the powers of `r` and `w` by their inverses leaves `com_ab`
+        // a commitment to `A` and the rescaled `B`, whose inner pairing product is `z_ab`.
+        let mut r_powers = powers(r, n);
+        let r_inv_powers = powers(r_inv, n);
+        b = scale_points(&b, &r_powers);
+        w_a = scale_points(&w_a, &r_inv_powers);
+        w_b = scale_points(&w_b, &r_inv_powers);
+        let z_ab = E::multi_pairing(&a, &b);
+        let z_c = E::G1::msm_unchecked(&c, &r_powers).into_affine();
+        transcript.append(&z_ab);
+        transcript.append(&z_c);
+
+        let mut rounds = Vec::new();
+        let mut challenges = Vec::new();
+        while a.len() > 1 {
+            let m = a.len() / 2;
+            let (a_l, a_r) = a.split_at(m);
+            let (b_l, b_r) = b.split_at(m);
+            let (c_l, c_r) = c.split_at(m);
+            let (r_l, r_r) = r_powers.split_at(m);
+            let (v_a_l, v_a_r) = v_a.split_at(m);
+            let (v_b_l, v_b_r) = v_b.split_at(m);
+            let (w_a_l, w_a_r) = w_a.split_at(m);
+            let (w_b_l, w_b_r) = w_b.split_at(m);
+
+            let round = AggregationRound {
+                ab_left: PairCommitment::new((v_a_l, v_b_l), (w_a_r, w_b_r), a_r, b_l),
+                ab_right: PairCommitment::new((v_a_r, v_b_r), (w_a_l, w_b_l), a_l, b_r),
+                z_ab_left: E::multi_pairing(a_r, b_l),
+                z_ab_right: E::multi_pairing(a_l, b_r),
+                c_left: PairCommitment::new((v_a_l, v_b_l), (&[], &[]), c_r, &[]),
+                c_right: PairCommitment::new((v_a_r, v_b_r), (&[], &[]), c_l, &[]),
+                z_c_left: E::G1::msm_unchecked(c_r, r_l).into_affine(),
+                z_c_right: E::G1::msm_unchecked(c_l, r_r).into_affine(),
+            };
+            let x = transcript.append_round(&round);
+            let x_inv = x.inverse().expect("challenges are non-zero");
+
+            let folded_r = r_l.iter().zip(r_r).map(|(l, r)| *l + *r * x_inv).collect();
+            (a, b, c) = (
+                fold_points(a_l, a_r, x),
+                fold_points(b_l, b_r, x_inv),
+                fold_points(c_l, c_r, x),
+            );
+            (v_a, v_b) = (
+                fold_points(v_a_l, v_a_r, x_inv),
+                fold_points(v_b_l, v_b_r, x_inv),
+            );
+            (w_a, w_b) = (fold_points(w_a_l, w_a_r, x), fold_points(w_b_l, w_b_r, x));
+            r_powers = folded_r;
+            rounds.push(round);
+            challenges.push(x);
+        }
+
+        let final_v = (v_a[0], v_b[0]);
+        let final_w = (w_a[0], w_b[0]);
+        transcript.append(&(a[0], b[0], c[0]));
+        transcript.append(&(final_v, final_w));
+        let z = transcript.challenge::<E::ScalarField>();
+
+        // The folded keys are `f_v(a) · H` and `f_w(a) · G` (and likewise for `b`), with
+        // `f_v(X) = Π_j (1 + x_j⁻¹ · X^m_j)` and `f_w(X) = X^n · Π_j (1 + x_j · r^-m_j · X^m_j)`.
+        let c_v = challenges
+            .iter()
+            .map(|x| x.inverse().expect("challenges are non-zero"))
+            .collect::<Vec<_>>();
+        let c_w = challenges
+            .iter()
+            .enumerate()
+            .map(|(j, x)| *x * r_inv.pow([(n >> (j + 1)) as u64]))
+            .collect::<Vec<_>>();
+        let q_v = kzg_quotient(&folding_coefficients(&c_v), z);
+        let mut f_w = vec![E::ScalarField::zero(); n];
+        f_w.extend(folding_coefficients(&c_w));
+        let q_w = kzg_quotient(&f_w, z);
+
+        Ok(AggregateProof {
+            com_ab,
+            com_c,
+            z_ab,
+            z_c,
+            rounds,
+            final_a: a[0],
+            final_b: b[0],
+            final_c: c[0],
+            final_v,
+            final_w,
+            opening_v: (
+                E::G2::msm_unchecked(&srs.h_a_powers[..n - 1], &q_v).into_affine(),
+                E::G2::msm_unchecked(&srs.h_b_powers[..n - 1], &q_v).into_affine(),
+            ),
+            opening_w: (
+                E::G1::msm_unchecked(&srs.g_a_powers[..2 * n - 1], &q_w).into_affine(),
+                E::G1::msm_unchecked(&srs.g_b_powers[..2 * n - 1], &q_w).into_affine(),
+            ),
+        })
+    }
+
+    /// Verify an aggregate `proof` of proofs against the prepared verification key `pvk`,
+    /// where the `i`-th proof is with respect to the instance `public_inputs[i]`.
+    ///
+    /// This takes a constant number of pairings and `O(log n)` operations in `E::GT`,
+    /// plus the field operations to combine the `n` public inputs.
+    pub fn verify_aggregate_proof<I: AsRef<[E::ScalarField]>>(
+        avk: &AggregationVerifierKey<E>,
+        pvk: &PreparedVerifyingKey<E>,
+        public_inputs: &[I],
+        proof: &AggregateProof<E>,
+    ) -> R1CSResult<bool> {
+        let n = public_inputs.len();
+        if n < 2 || !n.is_power_of_two() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let num_inputs = pvk.vk.gamma_abc_g1.len();
+        if num_inputs == 0
+            || public_inputs
+                .iter()
+                .any(|x| x.as_ref().len() + 1 != num_inputs)
+        {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        if proof.rounds.len() != n.trailing_zeros() as usize {
+            return Ok(false);
+        }
+
+        let mut transcript = Transcript::new(n);
+        for x in public_inputs {
+            transcript.append(&x.as_ref());
+        }
+        transcript.append(&proof.com_ab);
+        transcript.append(&proof.com_c);
+        let r = transcript.challenge::<E::ScalarField>();
+        let r_inv = r.inverse().expect("challenges are non-zero");
+        transcript.append(&proof.z_ab);
+        transcript.append(&proof.z_c);
+
+        let mut com_ab = proof.com_ab;
+        let mut com_c = proof.com_c;
+        let mut z_ab = proof.z_ab;
+        let mut z_c = proof.z_c.into_group();
+        let mut challenges = Vec::with_capacity(proof.rounds.len());
+        for round in &proof.rounds {
+            let x = transcript.append_round(round);
+            let x_inv = x.inverse().expect("challenges are non-zero");
+            com_ab.fold(&round.ab_left, &round.ab_right, x, x_inv);
+            com_c.fold(&round.c_left, &round.c_right, x, x_inv);
+            z_ab += round.z_ab_left * x + round.z_ab_right * x_inv;
+            z_c += round.z_c_left * x + round.z_c_right * x_inv;
+            challenges.push(x);
+        }
+        let (final_a, final_b, final_c) = (proof.final_a, proof.final_b, proof.final_c);
+        transcript.append(&(final_a, final_b, final_c));
+        transcript.append(&(proof.final_v, proof.final_w));
+        let z = transcript.challenge::<E::ScalarField>();
+
+        // The inner product arguments.
+        let c_v = challenges
+            .iter()
+            .map(|x| x.inverse().expect("challenges are non-zero"))
+            .collect::<Vec<_>>();
+        let (v_a, v_b) = proof.final_v;
+        let (w_a, w_b) = proof.final_w;
+        let ab_ok = E::multi_pairing([final_a, w_a], [v_a, final_b]) == com_ab.t
+            && E::multi_pairing([final_a, w_b], [v_b, final_b]) == com_ab.u
+            && E::pairing(final_a, final_b) == z_ab;
+        let c_ok = E::pairing(final_c, v_a) == com_c.t
+            && E::pairing(final_c, v_b) == com_c.u
+            && final_c * evaluate_folding(&c_v, r, n) == z_c;
+
+        // The openings of the folded keys at `z`.
+        let c_w = challenges
+            .iter()
+            .enumerate()
+            .map(|(j, x)| *x * r_inv.pow([(n >> (j + 1)) as u64]))
+            .collect::<Vec<_>>();
+        let f_v = evaluate_folding(&c_v, z, n);
+        let f_w = z.pow([n as u64]) * evaluate_folding(&c_w, z, n);
+        let g = avk.g.into_group();
+        let h = avk.h.into_group();
+        let v_ok = |g_s: E::G1Affine, opening: E::G2Affine, key: E::G2Affine| {
+            E::multi_pairing(
+                [g_s.into_group() - g * z, -g],
+                [opening.into_group(), key.into_group() - h * f_v],
+            )
+            .is_zero()
+        };
+        let w_ok = |h_s: E::G2Affine, opening: E::G1Affine, key: E::G1Affine| {
+            E::multi_pairing(
+                [opening.into_group(), g * f_w - key],
+                [h_s.into_group() - h * z, h],
+            )
+            .is_zero()
+        };
+        let openings_ok = v_ok(avk.g_a, proof.opening_v.0, v_a)
+            && v_ok(avk.g_b, proof.opening_v.1, v_b)
+            && w_ok(avk.h_a, proof.opening_w.0, w_a)
+            && w_ok(avk.h_b, proof.opening_w.1, w_b);
+
+        // The combined verification equation of the aggregated proofs.
+        let mut input_scalars = vec![E::ScalarField::zero(); num_inputs];
+        for (x, r_i) in public_inputs.iter().zip(powers(r, n)) {
+            input_scalars[0] += r_i;
+            for (acc, x_j) in input_scalars[1..].iter_mut().zip(x.as_ref()) {
+                *acc += *x_j * r_i;
+            }
+        }
+        let prepared_inputs = E::G1::msm_unchecked(&pvk.vk.gamma_abc_g1, &input_scalars);
+        let groth16_ok = proof.z_ab
+            == PairingOutput(pvk.alpha_g1_beta_g2) * input_scalars[0]
+                + E::multi_pairing(
+                    [prepared_inputs, proof.z_c.into_group()],
+                    [pvk.vk.gamma_g2, pvk.vk.delta_g2],
+                );
+
+        Ok(ab_ok && c_ok && openings_ok && groth16_ok)
+    }
+}
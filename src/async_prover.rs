@@ -0,0 +1,228 @@
+use crate::{
+    prover::{QueryAccumulators, Synthesized},
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_poly::GeneralEvaluationDomain;
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult};
+use ark_std::{cfg_iter, rand::Rng, vec::Vec};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "std")]
+use {
+    crate::msm::CpuBackend,
+    core::task::Waker,
+    std::{
+        panic::{self, AssertUnwindSafe},
+        sync::{Arc, Mutex, PoisonError},
+        thread,
+    },
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+type D<F> = GeneralEvaluationDomain<F>;
+
+/// A `chunk_size` for [`Groth16::prove_async`] that balances the responsiveness of the
+/// executor against the efficiency of each multi-scalar multiplication.
+pub const DEFAULT_ASYNC_CHUNK_SIZE: usize = 1 << 14;
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided R1CS-to-QAP
+    /// reduction, as a future that yields to the executor after synthesis, after the
+    /// witness map, and after every `chunk_size` bases of each multi-scalar
+    /// multiplication.
+    /// This method samples randomness for zero knowledges via `rng`.
+    ///
+    /// The future does not depend on any particular runtime. Synthesis and the witness
+    /// map are not split, so for large circuits they still occupy the executor for their
+    /// whole duration; [`Groth16::prove_on_thread`] avoids that. The future is `Send` if
+    /// `pk`, `circuit` and `rng` are.
+    ///
+    /// The future owns `pk` and `rng`, so with an `Arc<ProvingKey<E>>` and an owned `rng`
+    /// it is `'static` and can be spawned, and many concurrent proofs can share one key.
+    /// A `&ProvingKey<E>` and a `&mut` rng work as well.
+    pub async fn prove_async<C, K>(
+        pk: K,
+        circuit: C,
+        chunk_size: usize,
+        mut rng: impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        K: ark_std::borrow::Borrow<ProvingKey<E>>,
+    {
+        // Not imported, as `Borrow` would shadow the `borrow` of the constraint system.
+        let pk: &ProvingKey<E> = ark_std::borrow::Borrow::borrow(&pk);
+        let r = Zeroizing::new(E::ScalarField::rand(&mut rng));
+        let s = Zeroizing::new(E::ScalarField::rand(&mut rng));
+        let chunk_size = chunk_size.max(1);
+
+        // `ConstraintSystemRef` is not `Send`, so only the matrices and the assignment
+        // are kept across await points.
+        let Synthesized {
+            r,
+            s,
+            num_inputs,
+            num_constraints,
+            full_assignment,
+            matrices,
+        } = Self::synthesize_job(circuit, r, s, true)?;
+        YieldNow(false).await;
+
+        let h = {
+            let h = Zeroizing::new(QAP::witness_map_from_matrices::<_, D<E::ScalarField>>(
+                &matrices.unwrap(),
+                num_inputs,
+                num_constraints,
+                &full_assignment,
+            )?);
+            Zeroizing::new(cfg_iter!(h).map(|s| s.into_bigint()).collect::<Vec<_>>())
+        };
+        let assignment = Zeroizing::new(
+            cfg_iter!(full_assignment[1..])
+                .map(|s| s.into_bigint())
+                .collect::<Vec<_>>(),
+        );
+        drop(full_assignment);
+        YieldNow(false).await;
+
+        let h_acc = chunked_msm(&pk.h_query, &h, chunk_size).await;
+        drop(h);
+        let l_acc = chunked_msm(&pk.l_query, &assignment[(num_inputs - 1)..], chunk_size).await;
+        let a_acc = chunked_msm(&pk.a_query[1..], &assignment, chunk_size).await;
+        let b_g1_acc = if r.is_zero() {
+            E::G1::zero()
+        } else {
+            chunked_msm(&pk.b_g1_query[1..], &assignment, chunk_size).await
+        };
+        let b_g2_acc = chunked_msm(&pk.b_g2_query[1..], &assignment, chunk_size).await;
+        drop(assignment);
+
+        let acc = QueryAccumulators {
+            a: a_acc + pk.a_query[0],
+            b_g1: b_g1_acc + pk.b_g1_query[0],
+            b_g2: b_g2_acc + pk.b_g2_query[0],
+            h: h_acc,
+            l: l_acc,
+        };
+        Ok(Self::assemble_proof(
+            (&pk.vk).into(),
+            pk.beta_g1,
+            pk.delta_g1,
+            &r,
+            &s,
+            acc,
+        ))
+    }
+
+    /// Create a Groth16 proof that is zero-knowledge using the provided R1CS-to-QAP
+    /// reduction on a new thread, as a future that completes when the thread finishes.
+    /// This method samples randomness for zero knowledges via `rng`.
+    ///
+    /// Unlike [`Groth16::prove_async`], the executor is never blocked, including by
+    /// synthesis and the witness map, at the cost of one thread per proof. The proof is
+    /// computed by the synchronous prover, which with the `parallel` feature uses the
+    /// global `rayon` pool. A panic of the prover is resumed when the future is polled.
+    #[cfg(feature = "std")]
+    pub fn prove_on_thread<C, K, R>(
+        pk: K,
+        circuit: C,
+        mut rng: R,
+    ) -> impl Future<Output = R1CSResult<Proof<E>>> + Send + 'static
+    where
+        C: ConstraintSynthesizer<E::ScalarField> + Send + 'static,
+        K: ark_std::borrow::Borrow<ProvingKey<E>> + Send + 'static,
+        R: Rng + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(ThreadState {
+            result: None,
+            waker: None,
+        }));
+        let thread_state = shared.clone();
+        thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let r = Zeroizing::new(E::ScalarField::rand(&mut rng));
+                let s = Zeroizing::new(E::ScalarField::rand(&mut rng));
+                Self::prove_with_backend(circuit, pk.borrow().into(), &r, &s, &CpuBackend)
+            }));
+            let mut state = thread_state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        ThreadProof(shared)
+    }
+}
+
+/// The state shared by the thread of [`Groth16::prove_on_thread`] and its future.
+#[cfg(feature = "std")]
+struct ThreadState<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// The future of [`Groth16::prove_on_thread`].
+#[cfg(feature = "std")]
+struct ThreadProof<T>(Arc<Mutex<ThreadState<T>>>);
+
+#[cfg(feature = "std")]
+impl<T> Future for ThreadProof<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// Computes the multi-scalar multiplication of `bases` and `scalars` in chunks of
+/// `chunk_size`, yielding to the executor after each chunk.
+async fn chunked_msm<G>(
+    bases: &[G],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    chunk_size: usize,
+) -> G::Group
+where
+    G: AffineRepr,
+    G::Group: VariableBaseMSM<MulBase = G>,
+{
+    let mut acc = G::Group::zero();
+    for (bases, scalars) in bases.chunks(chunk_size).zip(scalars.chunks(chunk_size)) {
+        acc += G::Group::msm_bigint(bases, scalars);
+        YieldNow(false).await;
+    }
+    acc
+}
+
+/// A future that returns `Pending` once, so that the executor can run other tasks.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -0,0 +1,162 @@
+//! A command-line interface to setup, proving and verification over BLS12-381, for circuits
+//! given as circom `.r1cs` files and witnesses given as circom `.wtns` files.
+//!
+//! ```text
+//! groth16 setup <circuit.r1cs> <proving-key>
+//! groth16 export-vk <proving-key> <verifying-key>
+//! groth16 prove <proving-key> <circuit.r1cs> <witness.wtns> <proof>
+//! groth16 verify <verifying-key> <proof> [public input]...
+//! ```
+//!
+//! Proving keys are written with their [`KeyMetadata`], and verifying keys and proofs are
+//! compressed. `prove` prints the public inputs of the witness, one per line as big-endian
+//! hexadecimal, in the form that `verify` accepts. `verify` exits with status 0 for a
+//! valid proof and 1 for an invalid one; any other failure exits with status 2. Setup and
+//! proving draw their secrets from the random number generator of the operating system.
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{
+    circom::{read_r1cs, read_wtns, CircomCircuit, R1cs},
+    entropy::{EntropyError, EntropyRng, EntropySource},
+    metadata::KeyMetadata,
+    Groth16, ProvingKey,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalSerialize, Compress, Validate};
+use std::{
+    env,
+    error::Error,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    process::ExitCode,
+};
+
+/// The curve identifier recorded in the metadata of proving keys.
+const CURVE_ID: &str = "bls12-381";
+
+const USAGE: &str = "usage:
+    groth16 setup <circuit.r1cs> <proving-key>
+    groth16 export-vk <proving-key> <verifying-key>
+    groth16 prove <proving-key> <circuit.r1cs> <witness.wtns> <proof>
+    groth16 verify <verifying-key> <proof> [public input]...";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// The random number generator of the operating system.
+struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> std::result::Result<(), EntropyError> {
+        getrandom::getrandom(dest).map_err(|_| EntropyError::Unavailable)
+    }
+}
+
+fn read_circuit(path: &str) -> Result<R1cs<Fr>> {
+    Ok(read_r1cs(BufReader::new(File::open(path)?))?)
+}
+
+fn read_proving_key(path: &str) -> Result<(ProvingKey<Bls12_381>, KeyMetadata)> {
+    let reader = BufReader::new(File::open(path)?);
+    let (pk, metadata) =
+        ProvingKey::deserialize_with_metadata(reader, Compress::Yes, Validate::Yes)?;
+    if metadata.curve_id != CURVE_ID {
+        return Err(format!("{path} is a key for {}, not {CURVE_ID}", metadata.curve_id).into());
+    }
+    Ok((pk, metadata))
+}
+
+fn write_compressed<T: CanonicalSerialize>(value: &T, path: &str) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    value.serialize_compressed(&mut writer)?;
+    Ok(writer.flush()?)
+}
+
+fn setup(r1cs: &str, pk_path: &str) -> Result<()> {
+    let r1cs = read_circuit(r1cs)?;
+    let circuit = CircomCircuit {
+        r1cs,
+        witness: None,
+    };
+    let metadata = KeyMetadata::for_circuit(circuit.clone(), CURVE_ID)?;
+    let pk = Groth16::<Bls12_381>::generate_parameters_with_entropy(circuit, &mut OsEntropy)?;
+    let mut writer = BufWriter::new(File::create(pk_path)?);
+    pk.serialize_with_metadata(&metadata, &mut writer, Compress::Yes)?;
+    Ok(writer.flush()?)
+}
+
+fn export_vk(pk_path: &str, vk_path: &str) -> Result<()> {
+    let (pk, _) = read_proving_key(pk_path)?;
+    write_compressed(&pk.vk, vk_path)
+}
+
+fn prove(pk_path: &str, r1cs_path: &str, wtns: &str, proof_path: &str) -> Result<()> {
+    let (pk, metadata) = read_proving_key(pk_path)?;
+    let r1cs = read_circuit(r1cs_path)?;
+    let circuit = CircomCircuit {
+        r1cs,
+        witness: None,
+    };
+    if KeyMetadata::for_circuit(circuit.clone(), CURVE_ID)?.circuit_hash != metadata.circuit_hash {
+        return Err(format!("{pk_path} was not generated for {r1cs_path}").into());
+    }
+    let witness: Vec<Fr> = read_wtns(BufReader::new(File::open(wtns)?))?;
+    let public_inputs = witness
+        .get(1..circuit.r1cs.num_public)
+        .ok_or("the witness is shorter than the public inputs")?
+        .to_vec();
+    let circuit = CircomCircuit {
+        witness: Some(witness),
+        ..circuit
+    };
+    let cs = ConstraintSystem::new_ref();
+    circuit.clone().generate_constraints(cs.clone())?;
+    if !cs.is_satisfied()? {
+        return Err(format!("{wtns} does not satisfy {r1cs_path}").into());
+    }
+    let mut rng = EntropyRng::new(&mut OsEntropy)?;
+    let proof = Groth16::<Bls12_381>::create_random_proof_with_reduction(circuit, &pk, &mut rng)?;
+    write_compressed(&proof, proof_path)?;
+    for input in public_inputs {
+        let hex: String = input
+            .into_bigint()
+            .to_bytes_be()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        println!("0x{hex}");
+    }
+    Ok(())
+}
+
+fn verify(vk_path: &str, proof_path: &str, public_inputs: &[String]) -> Result<bool> {
+    let vk = fs::read(vk_path)?;
+    let proof = fs::read(proof_path)?;
+    Ok(Groth16::<Bls12_381>::verify_bytes(
+        &vk,
+        &proof,
+        &public_inputs.join(" "),
+    )?)
+}
+
+fn run(args: &[String]) -> Result<ExitCode> {
+    match args {
+        [command, r1cs, pk] if command == "setup" => setup(r1cs, pk)?,
+        [command, pk, vk] if command == "export-vk" => export_vk(pk, vk)?,
+        [command, pk, r1cs, wtns, proof] if command == "prove" => prove(pk, r1cs, wtns, proof)?,
+        [command, vk, proof, public_inputs @ ..] if command == "verify" => {
+            let valid = verify(vk, proof, public_inputs)?;
+            println!("{}", if valid { "valid" } else { "invalid" });
+            return Ok(ExitCode::from(u8::from(!valid)));
+        },
+        _ => return Err(USAGE.into()),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    run(&args).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        ExitCode::from(2)
+    })
+}
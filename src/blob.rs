@@ -0,0 +1,76 @@
+use crate::Proof;
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::vec::Vec;
+
+/// The size in bytes of one blob field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// The number of payload bytes carried by one blob field element. The most
+/// significant byte of every (big-endian) element is left zero, so that each
+/// element is smaller than the BLS12-381 scalar field modulus.
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = 31;
+
+/// Pack `proof` and `public_inputs` into 32-byte field elements suitable for blob data.
+///
+/// The payload is the compressed serialization of the proof followed by the public inputs,
+/// prefixed with its length as a little-endian `u64`. It is split into 31-byte chunks, each
+/// stored in the low 31 bytes of a big-endian field element; the last chunk is zero-padded.
+pub fn encode_proof<E: Pairing>(
+    proof: &Proof<E>,
+    public_inputs: &[E::ScalarField],
+) -> Vec<[u8; BYTES_PER_FIELD_ELEMENT]> {
+    let mut payload = Vec::new();
+    let len = proof.compressed_size() + public_inputs.compressed_size();
+    (len as u64)
+        .serialize_compressed(&mut payload)
+        .expect("serializing into a `Vec` cannot fail");
+    proof
+        .serialize_compressed(&mut payload)
+        .expect("serializing into a `Vec` cannot fail");
+    public_inputs
+        .serialize_compressed(&mut payload)
+        .expect("serializing into a `Vec` cannot fail");
+
+    payload
+        .chunks(USABLE_BYTES_PER_FIELD_ELEMENT)
+        .map(|chunk| {
+            let mut element = [0u8; BYTES_PER_FIELD_ELEMENT];
+            element[1..=chunk.len()].copy_from_slice(chunk);
+            element
+        })
+        .collect()
+}
+
+/// Unpack a proof and its public inputs from field elements produced by [`encode_proof`].
+///
+/// Decoding is strict: every element must have a zero most significant byte, the points and
+/// scalars must be valid, and all padding after the payload must be zero.
+pub fn decode_proof<E: Pairing>(
+    elements: &[[u8; BYTES_PER_FIELD_ELEMENT]],
+) -> Result<(Proof<E>, Vec<E::ScalarField>), SerializationError> {
+    let mut bytes = Vec::with_capacity(elements.len() * USABLE_BYTES_PER_FIELD_ELEMENT);
+    for element in elements {
+        if element[0] != 0 {
+            return Err(SerializationError::InvalidData);
+        }
+        bytes.extend_from_slice(&element[1..]);
+    }
+
+    let mut reader = &bytes[..];
+    let len = u64::deserialize_compressed(&mut reader)?;
+    let len = usize::try_from(len).map_err(|_| SerializationError::InvalidData)?;
+    if len > reader.len() {
+        return Err(SerializationError::InvalidData);
+    }
+    let (mut payload, padding) = reader.split_at(len);
+
+    let proof = Proof::deserialize_compressed(&mut payload)?;
+    let public_inputs = Vec::deserialize_compressed(&mut payload)?;
+    let needed_elements = (8 + len).div_ceil(USABLE_BYTES_PER_FIELD_ELEMENT);
+    if !payload.is_empty() || padding.iter().any(|b| *b != 0) || elements.len() != needed_elements {
+        return Err(SerializationError::InvalidData);
+    }
+
+    Ok((proof, public_inputs))
+}
@@ -0,0 +1,68 @@
+use crate::{prepare_verifying_key, PreparedVerifyingKey, VerifyingKey};
+use ark_ec::pairing::Pairing;
+use ark_std::collections::BTreeMap;
+
+/// Prepared verifying keys, computed on first use and keyed by
+/// [`VerifyingKey::fingerprint`], for verifiers that handle proofs for many circuits.
+///
+/// Looking up a key costs one hash of its serialization instead of the pairing and the
+/// line-coefficient precomputation of [`prepare_verifying_key`]. To share a cache between
+/// threads, wrap it in a lock.
+#[derive(Clone, Debug)]
+pub struct PvkCache<E: Pairing> {
+    pvks: BTreeMap<[u8; 32], PreparedVerifyingKey<E>>,
+}
+
+impl<E: Pairing> Default for PvkCache<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> PvkCache<E> {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self {
+            pvks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the prepared form of `vk`, preparing and storing it if it is not cached yet.
+    pub fn get_or_prepare(&mut self, vk: &VerifyingKey<E>) -> &PreparedVerifyingKey<E> {
+        self.pvks
+            .entry(vk.fingerprint())
+            .or_insert_with(|| prepare_verifying_key(vk))
+    }
+
+    /// Returns the cached prepared verifying key with the given fingerprint, if any.
+    pub fn get(&self, fingerprint: &[u8; 32]) -> Option<&PreparedVerifyingKey<E>> {
+        self.pvks.get(fingerprint)
+    }
+
+    /// Stores `pvk`, which was prepared elsewhere, and returns its fingerprint.
+    pub fn insert(&mut self, pvk: PreparedVerifyingKey<E>) -> [u8; 32] {
+        let fingerprint = pvk.vk.fingerprint();
+        self.pvks.insert(fingerprint, pvk);
+        fingerprint
+    }
+
+    /// Removes and returns the prepared verifying key with the given fingerprint, if any.
+    pub fn remove(&mut self, fingerprint: &[u8; 32]) -> Option<PreparedVerifyingKey<E>> {
+        self.pvks.remove(fingerprint)
+    }
+
+    /// The number of cached prepared verifying keys.
+    pub fn len(&self) -> usize {
+        self.pvks.len()
+    }
+
+    /// Returns `true` if no prepared verifying key is cached.
+    pub fn is_empty(&self) -> bool {
+        self.pvks.is_empty()
+    }
+
+    /// Removes all cached prepared verifying keys.
+    pub fn clear(&mut self) {
+        self.pvks.clear();
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    msm::{CpuBackend, MsmBackend, MsmQuery, ScalarBigInt},
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProverError, ProvingKey,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{UniformRand, Zero};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_std::rand::Rng;
+use core::{
+    ops::AddAssign,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use zeroize::Zeroizing;
+
+/// The number of bases that the prover processes between two checks for cancellation.
+pub const CANCELLATION_CHUNK_SIZE: usize = 1 << 16;
+
+/// Splits the MSMs of `backend` into chunks, and stops computing them once
+/// `should_abort` returns `true`.
+struct CancellableBackend<'a, B> {
+    backend: &'a B,
+    should_abort: &'a (dyn Fn() -> bool + Sync),
+    aborted: AtomicBool,
+}
+
+impl<B> CancellableBackend<'_, B> {
+    fn abort_requested(&self) -> bool {
+        if self.aborted.load(Ordering::SeqCst) || (self.should_abort)() {
+            self.aborted.store(true, Ordering::SeqCst);
+            return true;
+        }
+        false
+    }
+
+    fn chunked_msm<G, S, O>(&self, bases: &[G], scalars: &[S], msm: impl Fn(&[G], &[S]) -> O) -> O
+    where
+        O: Zero + AddAssign,
+    {
+        let mut acc = O::zero();
+        for (bases, scalars) in bases
+            .chunks(CANCELLATION_CHUNK_SIZE)
+            .zip(scalars.chunks(CANCELLATION_CHUNK_SIZE))
+        {
+            if self.abort_requested() {
+                break;
+            }
+            acc += msm(bases, scalars);
+        }
+        acc
+    }
+}
+
+impl<E: Pairing, B: MsmBackend<E>> MsmBackend<E> for CancellableBackend<'_, B> {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        self.chunked_msm(bases, scalars, |b, s| self.backend.msm_g1(query, b, s))
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        self.chunked_msm(bases, scalars, |b, s| self.backend.msm_g2(query, b, s))
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, which can be aborted through `should_abort`.
+    /// See [`Groth16::create_proof_with_cancellation`].
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_cancellation<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        should_abort: &(dyn Fn() -> bool + Sync),
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_with_cancellation(circuit, pk, *r, *s, should_abort)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided
+    /// R1CS-to-QAP reduction, which can be aborted through `should_abort`.
+    ///
+    /// `should_abort` is called after synthesis, after the witness map, and before every
+    /// [`CANCELLATION_CHUNK_SIZE`] bases of each multi-scalar multiplication. Once it
+    /// returns `true`, the remaining work is skipped and [`ProverError::Cancelled`] is
+    /// returned.
+    pub fn create_proof_with_cancellation<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+        should_abort: &(dyn Fn() -> bool + Sync),
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let cs = Self::synthesize(circuit, &())?;
+        if should_abort() {
+            return Err(ProverError::Cancelled);
+        }
+        let h = Self::reduce(cs.clone(), &())?;
+        if should_abort() {
+            return Err(ProverError::Cancelled);
+        }
+
+        let backend = CancellableBackend {
+            backend: &CpuBackend,
+            should_abort,
+            aborted: AtomicBool::new(false),
+        };
+        let prover = cs.borrow().unwrap();
+        let proof = Self::create_proof_with_assignment(
+            pk.into(),
+            &r,
+            &s,
+            &h,
+            &prover.instance_assignment[1..],
+            &prover.witness_assignment,
+            &backend,
+        )?;
+        if backend.aborted.load(Ordering::SeqCst) {
+            return Err(ProverError::Cancelled);
+        }
+
+        Ok(proof)
+    }
+}
@@ -0,0 +1,420 @@
+//! Phase-2 multi-party ceremonies for circuit-specific parameters, after
+//! [[BGM17]](https://eprint.iacr.org/2017/1050.pdf).
+//!
+//! # Specification
+//!
+//! The parameters start from any proving key for the circuit, for example one generated
+//! with [`Groth16::generate_random_parameters_with_reduction`]. Each participant samples a
+//! secret `x` and updates `delta` to `x · delta`:
+//!
+//! ```text
+//! delta_g1 ← x · delta_g1,  delta_g2 ← x · delta_g2,  h_i ← x⁻¹ · h_i,  l_i ← x⁻¹ · l_i,
+//! ```
+//!
+//! leaving every other element unchanged. The [`ContributionProof`] is a random `s` in `G1`
+//! with `s_x = x · s`, and `r_x = x · r` for the point `r = hash_to_g2(t)` of the transcript
+//!
+//! ```text
+//! t = SHA-256(domain || SHA-256(before) || s || s_x),
+//! ```
+//!
+//! where `before` is the compressed encoding of the parameters before the contribution and
+//! points are compressed. `hash_to_g2` tries `SHA-256(t || i || j)` for `j = 0, 1, …` as
+//! random bytes of a point, for `i = 0, 1, …` until one decodes, and clears its cofactor.
+//!
+//! A contribution is accepted if the unchanged elements are equal and
+//!
+//! ```text
+//! e(s, r_x) = e(s_x, r),
+//! e(delta_g1, r_x) = e(delta_g1', r),
+//! e(delta_g1, delta_g2') = e(delta_g1', delta_g2),
+//! e(Σ ρ_i · h_i', delta_g2') = e(Σ ρ_i · h_i, delta_g2),
+//! e(Σ ρ_i · l_i', delta_g2') = e(Σ ρ_i · l_i, delta_g2),
+//! ```
+//!
+//! with primes for the parameters after the contribution, and 128-bit challenges `ρ_i`
+//! derived by SHA-256 from both parameters. The parameters are as secure as a key from a
+//! trusted setup as long as one participant discarded their `x`.
+//!
+//! A ceremony is usually closed with [`Groth16::apply_beacon`], a contribution that draws
+//! its randomness from ChaCha20 seeded with `h_n` for `h_0 = SHA-256(beacon)` and
+//! `h_(i+1) = SHA-256(h_i)`. The beacon is a public random value that nobody knows before
+//! the last participant commits to their contribution, such as a future block hash, and
+//! the `n` hashes delay the computation of the seed until after the beacon is known.
+//!
+//! A [`Transcript`] records the whole ceremony for auditing: the initial parameters, every
+//! contribution in order, and the beacon. [`Groth16::verify_transcript`] checks every step
+//! against the parameters of the previous one.
+//!
+//! [`Groth16::generate_random_parameters_with_reduction`]: crate::Groth16
+use crate::{r1cs_to_qap::R1CSToQAP, Groth16, ProvingKey, VerifyingKey};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Write};
+use ark_std::{
+    cfg_iter, fmt,
+    ops::Mul,
+    rand::{Rng, SeedableRng},
+    vec::Vec,
+};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Domain separator for the transcript of a contribution.
+const CONTRIBUTION_DOMAIN: &[u8] = b"ark-groth16/ceremony/contribution/v1";
+
+/// Domain separator for the challenges of contribution verification.
+const CHALLENGE_DOMAIN: &[u8] = b"ark-groth16/ceremony/challenges/v1";
+
+/// A proof that a contribution to a ceremony multiplied `delta` by a secret the participant
+/// knows.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<E: Pairing> {
+    /// A random point `s` in `E::G1`.
+    pub s: E::G1Affine,
+    /// The point `x · s`, for the secret `x` of the contribution.
+    pub s_x: E::G1Affine,
+    /// The point `x · r`, where `r` is the point of `E::G2` hashed from the transcript.
+    pub r_x: E::G2Affine,
+}
+
+/// A contribution to a ceremony, as recorded in a [`Transcript`].
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: Pairing> {
+    /// The parameters after the contribution.
+    pub params: ProvingKey<E>,
+    /// The proof of the contribution.
+    pub proof: ContributionProof<E>,
+}
+
+/// The beacon contribution that closes a ceremony, as recorded in a [`Transcript`].
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaconContribution<E: Pairing> {
+    /// The public random value of the beacon.
+    pub beacon: Vec<u8>,
+    /// The number of rounds of SHA-256 applied to the beacon.
+    pub iterations: u64,
+    /// The parameters after the contribution.
+    pub params: ProvingKey<E>,
+    /// The proof of the contribution.
+    pub proof: ContributionProof<E>,
+}
+
+/// The transcript of a phase-2 ceremony, from which anyone can audit how the final
+/// parameters were produced.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Transcript<E: Pairing> {
+    /// The parameters the ceremony started from.
+    pub initial: ProvingKey<E>,
+    /// The contributions, in the order they were made.
+    pub contributions: Vec<Contribution<E>>,
+    /// The beacon contribution after the last participant, if the ceremony is closed.
+    pub beacon: Option<BeaconContribution<E>>,
+}
+
+impl<E: Pairing> Transcript<E> {
+    /// Start the transcript of a ceremony from the parameters `initial`.
+    pub fn new(initial: ProvingKey<E>) -> Self {
+        Self {
+            initial,
+            contributions: Vec::new(),
+            beacon: None,
+        }
+    }
+
+    /// The parameters after the last step of the ceremony.
+    pub fn final_parameters(&self) -> &ProvingKey<E> {
+        match &self.beacon {
+            Some(beacon) => &beacon.params,
+            None => self
+                .contributions
+                .last()
+                .map_or(&self.initial, |contribution| &contribution.params),
+        }
+    }
+}
+
+/// A reason why a [`Transcript`] does not verify.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptError {
+    /// The contribution with this index does not verify against the parameters before it.
+    InvalidContribution(usize),
+    /// The beacon contribution does not match the beacon.
+    InvalidBeacon,
+}
+
+impl ark_std::error::Error for TranscriptError {}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::InvalidContribution(index) => {
+                write!(f, "contribution {} does not verify", index)
+            },
+            TranscriptError::InvalidBeacon => write!(f, "the beacon contribution does not verify"),
+        }
+    }
+}
+
+/// Feeds the bytes written to it into a SHA-256 hash.
+struct HashWriter(Sha256);
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> ark_std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> ark_std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Feeds the compressed encoding of `value` into `hasher`, without holding the encoding
+/// of a whole proving key in memory.
+fn chain_compressed<T: CanonicalSerialize + ?Sized>(hasher: Sha256, value: &T) -> Sha256 {
+    let mut writer = HashWriter(hasher);
+    value
+        .serialize_compressed(&mut writer)
+        .expect("hashing cannot fail");
+    writer.0
+}
+
+/// The SHA-256 digest of the compressed encoding of `value`.
+fn compressed_digest<T: CanonicalSerialize + ?Sized>(value: &T) -> [u8; 32] {
+    chain_compressed(Sha256::new(), value).finalize().into()
+}
+
+/// Computes the point `r` of the transcript of a contribution to `before`.
+fn transcript_point<E: Pairing>(
+    before: &ProvingKey<E>,
+    s: &E::G1Affine,
+    s_x: &E::G1Affine,
+) -> E::G2Affine {
+    let hasher = Sha256::new()
+        .chain_update(CONTRIBUTION_DOMAIN)
+        .chain_update(compressed_digest(before));
+    let transcript = chain_compressed(chain_compressed(hasher, s), s_x).finalize();
+
+    let len = E::G2Affine::generator().compressed_size();
+    (0u64..)
+        .find_map(|i| {
+            let bytes = (0u64..)
+                .flat_map(|j| {
+                    let block = Sha256::new()
+                        .chain_update(transcript)
+                        .chain_update(i.to_le_bytes())
+                        .chain_update(j.to_le_bytes())
+                        .finalize();
+                    block.into_iter()
+                })
+                .take(len)
+                .collect::<Vec<_>>();
+            E::G2Affine::from_random_bytes(&bytes)
+                .map(|point| point.clear_cofactor())
+                .filter(|point| !point.is_zero())
+        })
+        .expect("a point is found after a few attempts")
+}
+
+/// Returns whether `e(g1.0, g2.1) = e(g1.1, g2.0)`, that is, whether both pairs are related
+/// by the same scalar.
+fn same_ratio<E: Pairing>(g1: (E::G1Affine, E::G1Affine), g2: (E::G2Affine, E::G2Affine)) -> bool {
+    E::multi_pairing([g1.0.into_group(), -g1.1.into_group()], [g2.1, g2.0]).is_zero()
+}
+
+/// Returns `params` with `delta` multiplied by `x`.
+fn scale_delta<E: Pairing>(
+    params: &ProvingKey<E>,
+    x: &E::ScalarField,
+    x_inv: &E::ScalarField,
+) -> ProvingKey<E> {
+    let scale = |query: &[E::G1Affine]| {
+        E::G1::normalize_batch(&cfg_iter!(query).map(|p| p.mul(x_inv)).collect::<Vec<_>>())
+    };
+    let mut after = params.clone();
+    after.delta_g1 = params.delta_g1.mul(x).into_affine();
+    after.vk.delta_g2 = params.vk.delta_g2.mul(x).into_affine();
+    after.h_query = scale(&params.h_query);
+    after.l_query = scale(&params.l_query);
+    after
+}
+
+/// Computes the seed of the randomness of a beacon contribution.
+fn beacon_seed(beacon: &[u8], iterations: u64) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(beacon).into();
+    for _ in 0..iterations {
+        hash = Sha256::digest(hash).into();
+    }
+    hash
+}
+
+/// Samples a nonzero secret and its inverse from `rng`.
+fn sample_secret<F: Field, R: Rng>(rng: &mut R) -> (Zeroizing<F>, Zeroizing<F>) {
+    let x = Zeroizing::new(loop {
+        let x = F::rand(rng);
+        if !x.is_zero() {
+            break x;
+        }
+    });
+    let x_inv = Zeroizing::new(x.inverse().expect("`x` is nonzero"));
+    (x, x_inv)
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Rerandomize `delta` in the parameters `params` with a fresh secret sampled from
+    /// `rng`, and return the new proving and verifying keys.
+    ///
+    /// This is a contribution to a ceremony without a proof, for example a final local one
+    /// on top of imported parameters. Proofs for the old keys do not verify with the new
+    /// ones. The secret is erased before returning.
+    pub fn rerandomize_delta<R: Rng>(
+        params: &ProvingKey<E>,
+        rng: &mut R,
+    ) -> (ProvingKey<E>, VerifyingKey<E>) {
+        let (x, x_inv) = sample_secret::<E::ScalarField, _>(rng);
+        let pk = scale_delta(params, &x, &x_inv);
+        let vk = pk.vk.clone();
+        (pk, vk)
+    }
+
+    /// Contribute to a phase-2 ceremony: rerandomize `delta` in the parameters `params` with
+    /// a secret sampled from `entropy`, and prove knowledge of the secret.
+    ///
+    /// The secret is erased before returning. The new parameters and the proof are passed
+    /// to the next participant, and anyone can check them with
+    /// [`Groth16::verify_contribution`].
+    pub fn contribute<R: Rng>(
+        params: &ProvingKey<E>,
+        entropy: &mut R,
+    ) -> (ProvingKey<E>, ContributionProof<E>) {
+        let (x, x_inv) = sample_secret::<E::ScalarField, _>(entropy);
+
+        let s = E::G1::rand(entropy).into_affine();
+        let s_x = s.mul(*x).into_affine();
+        let r_x = transcript_point::<E>(params, &s, &s_x)
+            .mul(*x)
+            .into_affine();
+
+        let after = scale_delta(params, &x, &x_inv);
+        (after, ContributionProof { s, s_x, r_x })
+    }
+
+    /// Verify that the parameters `after` are the result of a contribution to the
+    /// parameters `before` with the proof `proof`.
+    ///
+    /// A ceremony is checked by verifying every contribution against the parameters of the
+    /// previous one, starting at the initial parameters.
+    pub fn verify_contribution(
+        before: &ProvingKey<E>,
+        after: &ProvingKey<E>,
+        proof: &ContributionProof<E>,
+    ) -> bool {
+        let unchanged = before.vk.alpha_g1 == after.vk.alpha_g1
+            && before.vk.beta_g2 == after.vk.beta_g2
+            && before.vk.gamma_g2 == after.vk.gamma_g2
+            && before.vk.gamma_abc_g1 == after.vk.gamma_abc_g1
+            && before.beta_g1 == after.beta_g1
+            && before.a_query == after.a_query
+            && before.b_g1_query == after.b_g1_query
+            && before.b_g2_query == after.b_g2_query
+            && before.h_query.len() == after.h_query.len()
+            && before.l_query.len() == after.l_query.len();
+        if !unchanged || proof.s.is_zero() || proof.s_x.is_zero() || after.delta_g1.is_zero() {
+            return false;
+        }
+
+        let r = transcript_point::<E>(before, &proof.s, &proof.s_x);
+        if !same_ratio::<E>((proof.s, proof.s_x), (r, proof.r_x))
+            || !same_ratio::<E>((before.delta_g1, after.delta_g1), (r, proof.r_x))
+            || !same_ratio::<E>(
+                (before.delta_g1, after.delta_g1),
+                (before.vk.delta_g2, after.vk.delta_g2),
+            )
+        {
+            return false;
+        }
+
+        let hasher = Sha256::new()
+            .chain_update(CHALLENGE_DOMAIN)
+            .chain_update(compressed_digest(before))
+            .chain_update(compressed_digest(after));
+        let num_challenges = before.h_query.len().max(before.l_query.len()) as u64;
+        let challenges = (0..num_challenges)
+            .map(|i| {
+                let digest = hasher.clone().chain_update(i.to_le_bytes()).finalize();
+                E::ScalarField::from_le_bytes_mod_order(&digest[..16])
+            })
+            .collect::<Vec<_>>();
+        [
+            (&before.h_query, &after.h_query),
+            (&before.l_query, &after.l_query),
+        ]
+        .into_iter()
+        .all(|(query_before, query_after)| {
+            let combined_before = E::G1::msm_unchecked(query_before, &challenges);
+            let combined_after = E::G1::msm_unchecked(query_after, &challenges);
+            same_ratio::<E>(
+                (combined_after.into_affine(), combined_before.into_affine()),
+                (before.vk.delta_g2, after.vk.delta_g2),
+            )
+        })
+    }
+
+    /// Close a phase-2 ceremony by contributing to the parameters `params` with randomness
+    /// derived from the public random `beacon` by `iterations` rounds of SHA-256.
+    ///
+    /// Unlike [`Groth16::contribute`], anyone can recompute the contribution, and check it
+    /// with [`Groth16::verify_beacon`].
+    pub fn apply_beacon(
+        params: &ProvingKey<E>,
+        beacon: &[u8],
+        iterations: u64,
+    ) -> (ProvingKey<E>, ContributionProof<E>) {
+        let mut rng = ChaCha20Rng::from_seed(beacon_seed(beacon, iterations));
+        Self::contribute(params, &mut rng)
+    }
+
+    /// Verify that the parameters `after` and the proof `proof` are the result of
+    /// [`Groth16::apply_beacon`] on the parameters `before` with `beacon` and `iterations`.
+    pub fn verify_beacon(
+        before: &ProvingKey<E>,
+        after: &ProvingKey<E>,
+        proof: &ContributionProof<E>,
+        beacon: &[u8],
+        iterations: u64,
+    ) -> bool {
+        let (expected, expected_proof) = Self::apply_beacon(before, beacon, iterations);
+        &expected == after && &expected_proof == proof
+    }
+
+    /// Verify every step of the ceremony recorded in `transcript`, in order.
+    ///
+    /// This only checks the chain of contributions: the initial parameters should also be
+    /// checked against the circuit with [`Groth16::verify_parameters`].
+    pub fn verify_transcript(transcript: &Transcript<E>) -> Result<(), TranscriptError> {
+        let mut before = &transcript.initial;
+        for (index, contribution) in transcript.contributions.iter().enumerate() {
+            if !Self::verify_contribution(before, &contribution.params, &contribution.proof) {
+                return Err(TranscriptError::InvalidContribution(index));
+            }
+            before = &contribution.params;
+        }
+        if let Some(beacon) = &transcript.beacon {
+            if !Self::verify_beacon(
+                before,
+                &beacon.params,
+                &beacon.proof,
+                &beacon.beacon,
+                beacon.iterations,
+            ) {
+                return Err(TranscriptError::InvalidBeacon);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{r1cs_to_qap::R1CSToQAP, Groth16, Proof, ProverError, ProvingKey};
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystemRef, LinearCombination,
+    SynthesisError, Variable,
+};
+use ark_serialize::{Read, SerializationError};
+use ark_std::{rand::Rng, vec, vec::Vec};
+use zeroize::Zeroizing;
+
+/// The magic number of circom `.wtns` witness files.
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+
+/// The magic number of circom `.r1cs` constraint system files.
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+
+/// The number of bytes by which a section buffer grows while it is read.
+const READ_CHUNK_SIZE: usize = 1 << 16;
+
+/// A section of a circom binary file: its type and contents.
+pub(crate) type Section = (u32, Vec<u8>);
+
+/// Reads the sections of a circom binary file (`.wtns`, `.r1cs`) that starts with `magic`.
+pub(crate) fn read_sections<R: Read>(
+    mut reader: R,
+    magic: &[u8; 4],
+) -> Result<Vec<Section>, SerializationError> {
+    let mut file_magic = [0u8; 4];
+    reader.read_exact(&mut file_magic)?;
+    if &file_magic != magic {
+        return Err(SerializationError::InvalidData);
+    }
+    let _version = read_u32(&mut reader)?;
+    let num_sections = read_u32(&mut reader)?;
+
+    let mut sections = Vec::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size =
+            usize::try_from(read_u64(&mut reader)?).map_err(|_| SerializationError::InvalidData)?;
+        // Grow the buffer as data arrives, so that a bogus size cannot exhaust memory.
+        let mut contents = Vec::new();
+        while contents.len() < size {
+            let start = contents.len();
+            contents.resize(size.min(start + READ_CHUNK_SIZE), 0);
+            reader.read_exact(&mut contents[start..])?;
+        }
+        sections.push((section_type, contents));
+    }
+    Ok(sections)
+}
+
+/// Returns the contents of the unique section of type `section_type`.
+pub(crate) fn section(
+    sections: &[Section],
+    section_type: u32,
+) -> Result<&[u8], SerializationError> {
+    let mut matching = sections.iter().filter(|(t, _)| *t == section_type);
+    match (matching.next(), matching.next()) {
+        (Some((_, contents)), None) => Ok(contents),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+pub(crate) fn read_u32<R: Read>(mut reader: R) -> Result<u32, SerializationError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_u64<R: Read>(mut reader: R) -> Result<u64, SerializationError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads the field header shared by circom binary files: the element size `n8` and
+/// the prime, which must be the modulus of `F`. Returns `n8`.
+pub(crate) fn read_field_header<F: PrimeField, R: Read>(
+    mut reader: R,
+) -> Result<usize, SerializationError> {
+    let n8 = read_u32(&mut reader)? as usize;
+    let modulus = F::MODULUS.to_bytes_le();
+    if !n8.is_multiple_of(8) || n8 > modulus.len() {
+        return Err(SerializationError::InvalidData);
+    }
+    let mut prime = vec![0u8; n8];
+    reader.read_exact(&mut prime)?;
+    if prime[..] != modulus[..n8] || modulus[n8..].iter().any(|b| *b != 0) {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(n8)
+}
+
+/// Reads a little-endian field element of `n8` bytes in canonical (non-Montgomery) form.
+pub(crate) fn read_field_element<F: PrimeField, R: Read>(
+    mut reader: R,
+    n8: usize,
+) -> Result<F, SerializationError> {
+    let mut bigint = F::BigInt::default();
+    for limb in bigint.as_mut().iter_mut().take(n8 / 8) {
+        *limb = read_u64(&mut reader)?;
+    }
+    F::from_bigint(bigint).ok_or(SerializationError::InvalidData)
+}
+
+/// Read the full assignment from a circom `.wtns` witness file.
+///
+/// The assignment starts with the constant `1`, followed by the public outputs, the
+/// public inputs, and the private witness, which is the variable order of circom's
+/// `.r1cs` files. The prime of the file must be the modulus of `F`.
+pub fn read_wtns<F: PrimeField, R: Read>(reader: R) -> Result<Vec<F>, SerializationError> {
+    let sections = read_sections(reader, WTNS_MAGIC)?;
+
+    let mut header = section(&sections, 1)?;
+    let n8 = read_field_header::<F, _>(&mut header)?;
+    let num_witness = read_u32(&mut header)? as usize;
+
+    let mut witness = section(&sections, 2)?;
+    // The header is untrusted, so its sizes must not wrap around.
+    if num_witness.checked_mul(n8) != Some(witness.len()) {
+        return Err(SerializationError::InvalidData);
+    }
+    (0..num_witness)
+        .map(|_| read_field_element(&mut witness, n8))
+        .collect()
+}
+
+/// A linear combination of wires, as `(wire, coefficient)` pairs.
+pub type WireCombination<F> = Vec<(usize, F)>;
+
+/// A constraint system read from a circom `.r1cs` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1cs<F: PrimeField> {
+    /// The number of public wires, including the constant wire `0`, followed by the
+    /// public outputs and the public inputs.
+    pub num_public: usize,
+    /// The total number of wires.
+    pub num_wires: usize,
+    /// The constraints `<a, w> * <b, w> = <c, w>` over the wire assignment `w`.
+    pub constraints: Vec<(WireCombination<F>, WireCombination<F>, WireCombination<F>)>,
+    /// The label of each wire, if the file contains the wire-to-label map.
+    pub wire_labels: Option<Vec<u64>>,
+}
+
+/// Read a constraint system from a circom `.r1cs` file. The prime of the file must be
+/// the modulus of `F`.
+pub fn read_r1cs<F: PrimeField, R: Read>(reader: R) -> Result<R1cs<F>, SerializationError> {
+    let sections = read_sections(reader, R1CS_MAGIC)?;
+
+    let mut header = section(&sections, 1)?;
+    let n8 = read_field_header::<F, _>(&mut header)?;
+    let num_wires = read_u32(&mut header)? as usize;
+    let num_public_outputs = read_u32(&mut header)? as usize;
+    let num_public_inputs = read_u32(&mut header)? as usize;
+    let _num_private_inputs = read_u32(&mut header)?;
+    let _num_labels = read_u64(&mut header)?;
+    let num_constraints = read_u32(&mut header)? as usize;
+    let num_public = 1 + num_public_outputs + num_public_inputs;
+    if num_public > num_wires {
+        return Err(SerializationError::InvalidData);
+    }
+
+    let mut body = section(&sections, 2)?;
+    let mut read_combination = || -> Result<WireCombination<F>, SerializationError> {
+        let num_terms = read_u32(&mut body)?;
+        (0..num_terms)
+            .map(|_| {
+                let wire = read_u32(&mut body)? as usize;
+                if wire >= num_wires {
+                    return Err(SerializationError::InvalidData);
+                }
+                Ok((wire, read_field_element(&mut body, n8)?))
+            })
+            .collect()
+    };
+    let constraints = (0..num_constraints)
+        .map(|_| {
+            Ok((
+                read_combination()?,
+                read_combination()?,
+                read_combination()?,
+            ))
+        })
+        .collect::<Result<Vec<_>, SerializationError>>()?;
+    if !body.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+
+    let wire_labels = if sections.iter().any(|(t, _)| *t == 3) {
+        let mut map = section(&sections, 3)?;
+        let labels = (0..num_wires)
+            .map(|_| read_u64(&mut map))
+            .collect::<Result<Vec<_>, _>>()?;
+        Some(labels)
+    } else {
+        None
+    };
+
+    Ok(R1cs {
+        num_public,
+        num_wires,
+        constraints,
+        wire_labels,
+    })
+}
+
+/// A circuit defined by a circom constraint system, with an optional wire assignment
+/// read from a `.wtns` file.
+///
+/// The assignment is only needed for proving; setup can use a circuit without one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircomCircuit<F: PrimeField> {
+    /// The constraint system.
+    pub r1cs: R1cs<F>,
+    /// The assignment of all wires, starting with the constant `1`.
+    pub witness: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let witness = self.witness.as_deref();
+        if witness.is_some_and(|w| w.len() != self.r1cs.num_wires) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let value = |wire: usize| {
+            witness
+                .map(|w| w[wire])
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+
+        let mut variables = Vec::with_capacity(self.r1cs.num_wires);
+        variables.push(Variable::One);
+        for wire in 1..self.r1cs.num_wires {
+            variables.push(if wire < self.r1cs.num_public {
+                cs.new_input_variable(|| value(wire))?
+            } else {
+                cs.new_witness_variable(|| value(wire))?
+            });
+        }
+
+        let lc = |combination: &[(usize, F)]| {
+            combination
+                .iter()
+                .map(|(wire, coeff)| (*coeff, variables[*wire]))
+                .collect::<Vec<_>>()
+        };
+        for (a, b, c) in &self.r1cs.constraints {
+            cs.enforce_constraint(
+                LinearCombination(lc(a)),
+                LinearCombination(lc(b)),
+                LinearCombination(lc(c)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge from a circom `.wtns` witness file.
+    /// See [`Groth16::create_proof_from_wtns`].
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_from_wtns<R: Read>(
+        pk: &ProvingKey<E>,
+        matrices: &ConstraintMatrices<E::ScalarField>,
+        wtns: R,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError> {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_from_wtns(pk, matrices, wtns, *r, *s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided R1CS-to-QAP
+    /// reduction from a circom `.wtns` witness file, for the circuit with constraint
+    /// `matrices` that `pk` was generated for.
+    pub fn create_proof_from_wtns<R: Read>(
+        pk: &ProvingKey<E>,
+        matrices: &ConstraintMatrices<E::ScalarField>,
+        wtns: R,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> Result<Proof<E>, ProverError> {
+        let full_assignment = Zeroizing::new(read_wtns::<E::ScalarField, _>(wtns)?);
+        let num_inputs = matrices.num_instance_variables;
+        if full_assignment.len() != num_inputs + matrices.num_witness_variables
+            || full_assignment.len() != pk.a_query.len()
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+
+        Ok(Self::create_proof_with_reduction_and_matrices(
+            pk,
+            r,
+            s,
+            matrices,
+            num_inputs,
+            matrices.num_constraints,
+            &full_assignment,
+        )?)
+    }
+}
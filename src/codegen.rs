@@ -0,0 +1,260 @@
+//! Generation of verifier contracts with a verifying key embedded.
+//!
+//! # Specification
+//!
+//! [`sui_move_verifier`] emits a Move module for Sui with one entry point,
+//!
+//! ```text
+//! public fun verify(public_inputs: vector<u8>, proof: vector<u8>): bool
+//! ```
+//!
+//! where `proof` is the compressed proof and `public_inputs` are the public inputs as
+//! concatenated 32-byte little-endian scalars. The module embeds the compressed verifying
+//! key and verifies with the native pairing of `sui::groth16`, which reads keys and proofs
+//! in the encoding of this crate.
+//!
+//! [`aptos_move_verifier`] emits a Move module for Aptos with the same entry point, for
+//! BN254 only. Aptos has no Groth16 verifier in its framework, so the module embeds the
+//! points of the verifying key one by one and checks
+//!
+//! ```text
+//! e(A, B) = e(alpha, beta) · e(gamma_abc_0 + Σ x_i · gamma_abc_i, gamma) · e(C, delta)
+//! ```
+//!
+//! with the generic operations of `aptos_std::crypto_algebra`. Its compressed formats
+//! for `aptos_std::bn254_algebra` are those of `ark-serialize`, so the proof is again
+//! the compressed proof of this crate. The BLS12-381 formats of Aptos follow the Zcash
+//! encoding instead, which this crate does not produce.
+use crate::VerifyingKey;
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{fmt, format, string::String, vec::Vec};
+
+/// A curve that Sui verifies Groth16 proofs over natively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveCurve {
+    /// BN254, `groth16::bn254()`.
+    Bn254,
+    /// BLS12-381, `groth16::bls12381()`.
+    Bls12381,
+}
+
+impl MoveCurve {
+    /// The name of the function of `sui::groth16` that returns the curve.
+    fn function(self) -> &'static str {
+        match self {
+            MoveCurve::Bn254 => "bn254",
+            MoveCurve::Bls12381 => "bls12381",
+        }
+    }
+
+    /// The size of a compressed `G1` point.
+    fn g1_size(self) -> usize {
+        match self {
+            MoveCurve::Bn254 => 32,
+            MoveCurve::Bls12381 => 48,
+        }
+    }
+}
+
+/// A failure of [`sui_move_verifier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodegenError {
+    /// The package address or the module name is not a Move identifier.
+    InvalidIdentifier,
+    /// The verifying key is not over the requested curve, or over a curve that the
+    /// target chain cannot verify with this crate's encoding.
+    CurveMismatch,
+}
+
+impl ark_std::error::Error for CodegenError {}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::InvalidIdentifier => write!(f, "the name is not a Move identifier"),
+            CodegenError::CurveMismatch => {
+                write!(f, "the verifying key is not over the requested curve")
+            },
+        }
+    }
+}
+
+/// Whether `name` is a Move identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The compressed encoding of `point` in lowercase hexadecimal.
+fn compressed_hex(point: &impl CanonicalSerialize) -> String {
+    let mut bytes = Vec::new();
+    point
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Returns the source of the Move module `package::module` that verifies proofs against
+/// `vk` over `curve` on Sui, as in the [module documentation](self).
+pub fn sui_move_verifier<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    curve: MoveCurve,
+    package: &str,
+    module: &str,
+) -> Result<String, CodegenError> {
+    if !is_identifier(package) || !is_identifier(module) {
+        return Err(CodegenError::InvalidIdentifier);
+    }
+    if E::G1Affine::zero().compressed_size() != curve.g1_size() {
+        return Err(CodegenError::CurveMismatch);
+    }
+    let vk_hex = Vec::from(vk)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    Ok(format!(
+        "// Generated by ark-groth16 {version}. Do not edit.
+module {package}::{module} {{
+    use sui::groth16;
+
+    /// The compressed verifying key.
+    const VERIFYING_KEY: vector<u8> = x\"{vk_hex}\";
+
+    /// The number of public inputs.
+    const NUM_PUBLIC_INPUTS: u64 = {num_public_inputs};
+
+    /// Returns whether the compressed `proof` verifies for `public_inputs`, the public
+    /// inputs as concatenated 32-byte little-endian scalars.
+    public fun verify(public_inputs: vector<u8>, proof: vector<u8>): bool {{
+        if (std::vector::length(&public_inputs) != NUM_PUBLIC_INPUTS * 32) {{
+            return false
+        }};
+        let curve = groth16::{curve}();
+        let pvk = groth16::prepare_verifying_key(&curve, &VERIFYING_KEY);
+        let public_inputs = groth16::public_proof_inputs_from_bytes(public_inputs);
+        let proof = groth16::proof_points_from_bytes(proof);
+        groth16::verify_groth16_proof(&curve, &pvk, &public_inputs, &proof)
+    }}
+}}
+",
+        version = env!("CARGO_PKG_VERSION"),
+        num_public_inputs = vk.num_public_inputs(),
+        curve = curve.function(),
+    ))
+}
+
+/// Returns the source of the Move module `package::module` that verifies proofs against
+/// `vk` on Aptos, as in the [module documentation](self).
+///
+/// Returns [`CodegenError::CurveMismatch`] unless `vk` is over BN254.
+pub fn aptos_move_verifier<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    package: &str,
+    module: &str,
+) -> Result<String, CodegenError> {
+    if !is_identifier(package) || !is_identifier(module) {
+        return Err(CodegenError::InvalidIdentifier);
+    }
+    let g1_size = E::G1Affine::zero().compressed_size();
+    let g2_size = E::G2Affine::zero().compressed_size();
+    let scalar_size = E::ScalarField::from(0u64).compressed_size();
+    if (g1_size, g2_size, scalar_size) != (32, 64, 32) {
+        return Err(CodegenError::CurveMismatch);
+    }
+    let gamma_abc_g1 = vk
+        .gamma_abc_g1
+        .iter()
+        .map(|point| format!("x\"{}\"", compressed_hex(point)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!(
+        "// Generated by ark-groth16 {version}. Do not edit.
+module {package}::{module} {{
+    use std::option;
+    use std::vector;
+    use aptos_std::bn254_algebra::{{Fr, FormatFrLsb, FormatG1Compr, FormatG2Compr, G1, G2, Gt}};
+    use aptos_std::crypto_algebra;
+
+    /// The compressed `alpha_g1` of the verifying key.
+    const ALPHA_G1: vector<u8> = x\"{alpha_g1}\";
+
+    /// The compressed `beta_g2` of the verifying key.
+    const BETA_G2: vector<u8> = x\"{beta_g2}\";
+
+    /// The compressed `gamma_g2` of the verifying key.
+    const GAMMA_G2: vector<u8> = x\"{gamma_g2}\";
+
+    /// The compressed `delta_g2` of the verifying key.
+    const DELTA_G2: vector<u8> = x\"{delta_g2}\";
+
+    /// The compressed `gamma_abc_g1` of the verifying key, for the constant followed by
+    /// the public inputs.
+    const GAMMA_ABC_G1: vector<vector<u8>> = vector[{gamma_abc_g1}];
+
+    /// The number of public inputs.
+    const NUM_PUBLIC_INPUTS: u64 = {num_public_inputs};
+
+    /// Returns whether the compressed `proof` verifies for `public_inputs`, the public
+    /// inputs as concatenated 32-byte little-endian scalars.
+    public fun verify(public_inputs: vector<u8>, proof: vector<u8>): bool {{
+        if (vector::length(&public_inputs) != NUM_PUBLIC_INPUTS * 32
+            || vector::length(&proof) != 128) {{
+            return false
+        }};
+        let a = crypto_algebra::deserialize<G1, FormatG1Compr>(&vector::slice(&proof, 0, 32));
+        let b = crypto_algebra::deserialize<G2, FormatG2Compr>(&vector::slice(&proof, 32, 96));
+        let c = crypto_algebra::deserialize<G1, FormatG1Compr>(&vector::slice(&proof, 96, 128));
+        if (option::is_none(&a) || option::is_none(&b) || option::is_none(&c)) {{
+            return false
+        }};
+
+        let bases = vector[g1(vector::borrow(&GAMMA_ABC_G1, 0))];
+        let scalars = vector[crypto_algebra::one<Fr>()];
+        let i = 0;
+        while (i < NUM_PUBLIC_INPUTS) {{
+            let input = crypto_algebra::deserialize<Fr, FormatFrLsb>(
+                &vector::slice(&public_inputs, i * 32, i * 32 + 32)
+            );
+            if (option::is_none(&input)) {{
+                return false
+            }};
+            vector::push_back(&mut bases, g1(vector::borrow(&GAMMA_ABC_G1, i + 1)));
+            vector::push_back(&mut scalars, option::destroy_some(input));
+            i = i + 1;
+        }};
+        let prepared_inputs = crypto_algebra::multi_scalar_mul(&bases, &scalars);
+
+        let lhs = crypto_algebra::pairing<G1, G2, Gt>(
+            &option::destroy_some(a),
+            &option::destroy_some(b)
+        );
+        let rhs = crypto_algebra::multi_pairing<G1, G2, Gt>(
+            &vector[g1(&ALPHA_G1), prepared_inputs, option::destroy_some(c)],
+            &vector[g2(&BETA_G2), g2(&GAMMA_G2), g2(&DELTA_G2)]
+        );
+        crypto_algebra::eq(&lhs, &rhs)
+    }}
+
+    /// Decodes an embedded point, which was valid when the module was generated.
+    fun g1(bytes: &vector<u8>): crypto_algebra::Element<G1> {{
+        option::destroy_some(crypto_algebra::deserialize<G1, FormatG1Compr>(bytes))
+    }}
+
+    /// Decodes an embedded point, which was valid when the module was generated.
+    fun g2(bytes: &vector<u8>): crypto_algebra::Element<G2> {{
+        option::destroy_some(crypto_algebra::deserialize<G2, FormatG2Compr>(bytes))
+    }}
+}}
+",
+        version = env!("CARGO_PKG_VERSION"),
+        alpha_g1 = compressed_hex(&vk.alpha_g1),
+        beta_g2 = compressed_hex(&vk.beta_g2),
+        gamma_g2 = compressed_hex(&vk.gamma_g2),
+        delta_g2 = compressed_hex(&vk.delta_g2),
+        num_public_inputs = vk.num_public_inputs(),
+    ))
+}
@@ -0,0 +1,360 @@
+use crate::{
+    msm::{MsmBackend, MsmQuery, ScalarBigInt},
+    precompute::window,
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProverError, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, Group, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_serialize::*;
+use ark_std::{cfg_chunks, cfg_into_iter, rand::Rng, vec, vec::Vec};
+use zeroize::Zeroizing;
+
+pub use crate::precompute::MAX_WINDOW_SIZE;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The largest window size that [`OperationCosts::window_size`] chooses, which bounds
+/// the memory of the buckets.
+const MAX_TUNED_WINDOW_SIZE: usize = 20;
+
+/// The measured costs in picoseconds of the group operations of the Pippenger
+/// multi-scalar multiplication in one group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OperationCosts {
+    /// The cost of adding an affine base to a projective bucket.
+    pub mixed_addition: u64,
+    /// The cost of adding two projective points.
+    pub addition: u64,
+    /// The cost of doubling a projective point.
+    pub doubling: u64,
+}
+
+impl OperationCosts {
+    /// The window size that minimizes the estimated cost of a multi-scalar multiplication
+    /// with `num_bases` bases and scalars of `num_bits` bits, whose windows are computed
+    /// on `num_threads` threads.
+    ///
+    /// Each window costs one mixed addition per base and two additions per bucket, and
+    /// the windows are combined with one doubling per bit.
+    pub fn window_size(&self, num_bases: usize, num_bits: usize, num_threads: usize) -> usize {
+        let cost = |window_size: usize| {
+            let num_windows = num_bits.div_ceil(window_size);
+            let rounds = num_windows.div_ceil(num_threads.max(1)) as u128;
+            let window = num_bases as u128 * self.mixed_addition as u128
+                + (2u128 << window_size) * self.addition as u128;
+            rounds * window + num_bits as u128 * self.doubling as u128
+        };
+        (1..=MAX_TUNED_WINDOW_SIZE)
+            .min_by_key(|w| cost(*w))
+            .unwrap()
+    }
+
+    /// Measures the costs of the operations in the group of `G` on this machine.
+    ///
+    /// Not available on `wasm32-unknown-unknown`, which has no clock.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn measure<G: AffineRepr>() -> Self {
+        use std::{hint::black_box, time::Instant};
+
+        const ROUNDS: u32 = 1 << 10;
+        // The fastest of a few repetitions, to discount interruptions.
+        let time = |op: &mut dyn FnMut()| {
+            (0..3)
+                .map(|_| {
+                    let start = Instant::now();
+                    for _ in 0..ROUNDS {
+                        op();
+                    }
+                    start.elapsed().as_nanos() as u64 * 1000 / ROUNDS as u64
+                })
+                .min()
+                .unwrap()
+                .max(1)
+        };
+
+        let base = G::generator();
+        let mut acc = base.into_group().double();
+        let other = acc.double();
+        let mixed_addition = time(&mut || acc += black_box(&base));
+        let addition = time(&mut || acc += black_box(&other));
+        let doubling = time(&mut || {
+            acc.double_in_place();
+        });
+        black_box(acc);
+        Self {
+            mixed_addition,
+            addition,
+            doubling,
+        }
+    }
+}
+
+/// The operation costs of both groups of a pairing, from which [`ProverConfig`] chooses
+/// the window size of each multi-scalar multiplication by its number of bases.
+///
+/// The costs depend on the machine only, so they can be measured once with
+/// [`WindowTuning::calibrate`] and stored with `ark-serialize`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WindowTuning {
+    /// The costs in `G1`.
+    pub g1: OperationCosts,
+    /// The costs in `G2`.
+    pub g2: OperationCosts,
+}
+
+/// The tunings measured in this process, by pairing.
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+static TUNINGS: std::sync::Mutex<ark_std::collections::BTreeMap<core::any::TypeId, WindowTuning>> =
+    std::sync::Mutex::new(ark_std::collections::BTreeMap::new());
+
+impl WindowTuning {
+    /// Measures the costs of the group operations of `E` on this machine, which takes a
+    /// few milliseconds.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn calibrate<E: Pairing>() -> Self {
+        Self {
+            g1: OperationCosts::measure::<E::G1Affine>(),
+            g2: OperationCosts::measure::<E::G2Affine>(),
+        }
+    }
+
+    /// Returns the tuning for `E` of this process, calibrating it at the first call.
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    pub fn cached<E: Pairing>() -> Self {
+        let mut tunings = TUNINGS.lock().unwrap_or_else(|e| e.into_inner());
+        *tunings
+            .entry(core::any::TypeId::of::<E>())
+            .or_insert_with(Self::calibrate::<E>)
+    }
+}
+
+/// The number of threads that the windows of a multi-scalar multiplication run on.
+pub(crate) fn num_threads() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+/// Tuning of the prover for the machine it runs on. The default leaves every choice to
+/// `ark-ec` and the global `rayon` thread pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProverConfig {
+    /// If set, split each multi-scalar multiplication into chunks of this many bases,
+    /// which are computed in parallel and summed. It must not be zero.
+    pub msm_chunk_size: Option<usize>,
+    /// If set, prove on a dedicated pool of this many worker threads. This has no effect
+    /// without the `parallel` feature.
+    ///
+    /// If the pool cannot be built, because the platform cannot spawn threads (as
+    /// `wasm32` without web workers) or has run out of them, the setting is ignored and
+    /// the proof runs on the current pool.
+    pub num_threads: Option<usize>,
+    /// If set, the window size in bits of the Pippenger multi-scalar multiplication,
+    /// which must be between 1 and [`MAX_WINDOW_SIZE`]. Otherwise it is chosen from the
+    /// number of bases.
+    pub window_size: Option<usize>,
+    /// If set and `window_size` is not, the window size of each multi-scalar
+    /// multiplication is chosen from its number of bases with these measured costs.
+    /// Otherwise `ark-ec` chooses it with a fixed heuristic.
+    pub window_tuning: Option<WindowTuning>,
+}
+
+impl ProverConfig {
+    /// A configuration whose window sizes are tuned with [`WindowTuning::cached`], so the
+    /// first call in a process calibrates the costs of the operations of `E`.
+    ///
+    /// On `wasm32-unknown-unknown` the costs cannot be measured, so this is the default
+    /// configuration and `ark-ec` chooses the window sizes.
+    #[cfg(feature = "std")]
+    pub fn tuned<E: Pairing>() -> Self {
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        return Self::default();
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        Self {
+            window_tuning: Some(WindowTuning::cached::<E>()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns [`ProverError::InvalidConfig`] if `msm_chunk_size` is zero or
+    /// `window_size` is not between 1 and [`MAX_WINDOW_SIZE`].
+    fn validate(&self) -> Result<(), ProverError> {
+        let chunk_size_ok = self.msm_chunk_size != Some(0);
+        let window_size_ok = self
+            .window_size
+            .is_none_or(|w| (1..=MAX_WINDOW_SIZE).contains(&w));
+        if chunk_size_ok && window_size_ok {
+            Ok(())
+        } else {
+            Err(ProverError::InvalidConfig)
+        }
+    }
+
+    fn msm<G>(
+        &self,
+        bases: &[G],
+        scalars: &[<G::ScalarField as PrimeField>::BigInt],
+        costs: Option<&OperationCosts>,
+    ) -> G::Group
+    where
+        G: AffineRepr,
+        G::Group: VariableBaseMSM<MulBase = G>,
+    {
+        let len = bases.len().min(scalars.len());
+        let (bases, scalars) = (&bases[..len], &scalars[..len]);
+        // A configuration used as a backend directly has not been validated.
+        if self.validate().is_err() {
+            return G::Group::msm_bigint(bases, scalars);
+        }
+        let chunk_msm = |bases: &[G], scalars: &[_]| {
+            let window_size = self.window_size.or_else(|| {
+                let num_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+                costs.map(|costs| costs.window_size(bases.len(), num_bits, num_threads()))
+            });
+            match window_size {
+                Some(window_size) => pippenger(bases, scalars, window_size),
+                None => G::Group::msm_bigint(bases, scalars),
+            }
+        };
+
+        match self.msm_chunk_size {
+            Some(chunk_size) if chunk_size < len => cfg_chunks!(bases, chunk_size)
+                .zip(cfg_chunks!(scalars, chunk_size))
+                .map(|(bases, scalars)| chunk_msm(bases, scalars))
+                .sum(),
+            _ => chunk_msm(bases, scalars),
+        }
+    }
+}
+
+/// The Pippenger multi-scalar multiplication with windows of `window_size` bits.
+pub(crate) fn pippenger<G: AffineRepr>(
+    bases: &[G],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    window_size: usize,
+) -> G::Group {
+    assert!(
+        (1..=MAX_WINDOW_SIZE).contains(&window_size),
+        "window size must be between 1 and {} bits",
+        MAX_WINDOW_SIZE
+    );
+    let num_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+
+    let window_sums = cfg_into_iter!((0..num_bits).step_by(window_size).collect::<Vec<_>>())
+        .map(|offset| {
+            let mut buckets = vec![G::Group::zero(); (1 << window_size) - 1];
+            for (base, scalar) in bases.iter().zip(scalars) {
+                let digit = window(scalar, offset, window_size);
+                if digit != 0 {
+                    buckets[digit - 1] += base;
+                }
+            }
+
+            // sum_k (k + 1) * buckets[k], via running sums.
+            let mut running_sum = G::Group::zero();
+            let mut acc = G::Group::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += bucket;
+                acc += running_sum;
+            }
+            acc
+        })
+        .collect::<Vec<_>>();
+
+    let mut acc = G::Group::zero();
+    for window_sum in window_sums.into_iter().rev() {
+        for _ in 0..window_size {
+            acc.double_in_place();
+        }
+        acc += window_sum;
+    }
+    acc
+}
+
+/// A configuration that [`create_proof_with_config`](Groth16::create_proof_with_config)
+/// would reject with [`ProverError::InvalidConfig`] is ignored here, and every
+/// multi-scalar multiplication falls back to `ark-ec`.
+impl<E: Pairing> MsmBackend<E> for ProverConfig {
+    fn msm_g1(&self, _: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        self.msm(bases, scalars, self.window_tuning.as_ref().map(|t| &t.g1))
+    }
+
+    fn msm_g2(&self, _: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        self.msm(bases, scalars, self.window_tuning.as_ref().map(|t| &t.g2))
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, tuned by `config`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_config<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        config: &ProverConfig,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField> + Send,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_with_config(circuit, pk, config, *r, *s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided
+    /// R1CS-to-QAP reduction, tuned by `config`.
+    ///
+    /// If `config.num_threads` is set, a thread pool is built for this proof alone, so
+    /// callers proving many circuits should rather run them inside their own pool. Where
+    /// threads cannot be spawned, as on `wasm32` without web workers, the proof runs on
+    /// the current pool instead.
+    ///
+    /// Returns [`ProverError::InvalidConfig`] if `config.msm_chunk_size` is zero or
+    /// `config.window_size` is not between 1 and [`MAX_WINDOW_SIZE`].
+    pub fn create_proof_with_config<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        config: &ProverConfig,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField> + Send,
+    {
+        config.validate()?;
+        let prove = || {
+            Ok(Self::create_proof_with_reduction_and_backend(
+                circuit, pk, r, s, config,
+            )?)
+        };
+
+        #[cfg(feature = "parallel")]
+        if let Some(num_threads) = config.num_threads {
+            if let Ok(pool) = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+            {
+                return pool.install(prove);
+            }
+        }
+        prove()
+    }
+}
@@ -2,10 +2,12 @@ use crate::{
     r1cs_to_qap::{LibsnarkReduction, R1CSToQAP},
     Groth16, PreparedVerifyingKey, Proof, VerifyingKey,
 };
-use ark_crypto_primitives::snark::constraints::{CircuitSpecificSetupSNARKGadget, SNARKGadget};
+use ark_crypto_primitives::snark::constraints::{
+    CircuitSpecificSetupSNARKGadget, FromFieldElementsGadget, SNARKGadget,
+};
 use ark_crypto_primitives::snark::{BooleanInputVar, SNARK};
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
-use ark_ff::Field;
+use ark_ff::{Field, Zero};
 use ark_r1cs_std::groups::CurveVar;
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
@@ -15,8 +17,8 @@ use ark_r1cs_std::{
     pairing::PairingVar,
     ToBitsGadget, ToBytesGadget,
 };
-use ark_relations::r1cs::{Namespace, SynthesisError};
-use ark_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData, vec, vec::Vec};
 
 type BasePrimeField<E> = <<<E as Pairing>::G1 as CurveGroup>::BaseField as Field>::BasePrimeField;
 
@@ -290,7 +292,7 @@ where
             let pvk = pvk.borrow();
             let alpha_g1_beta_g2 = P::GTVar::new_variable(
                 ark_relations::ns!(cs, "alpha_g1_beta_g2"),
-                || Ok(pvk.alpha_g1_beta_g2.clone()),
+                || Ok(pvk.alpha_g1_beta_g2),
                 mode,
             )?;
 
@@ -409,15 +411,87 @@ where
     }
 }
 
+/// A circuit over the base field of `E` that verifies Groth16 proofs for one verifying
+/// key, so that a proof of it, over a pairing whose scalar field is that base field,
+/// attests to the validity of all of them.
+///
+/// The public inputs of the circuit are the public inputs of the inner proofs, packed into
+/// its field by [`VerifierCircuit::outer_public_inputs`].
+pub struct VerifierCircuit<E: Pairing, P: PairingVar<E, BasePrimeField<E>>> {
+    /// The verifying key of the inner proofs, which is fixed in the circuit.
+    pub vk: VerifyingKey<E>,
+    /// The inner proofs, each with its public inputs.
+    pub proofs: Vec<(Proof<E>, Vec<E::ScalarField>)>,
+    _pairing_gadget: PhantomData<P>,
+}
+
+impl<E: Pairing, P: PairingVar<E, BasePrimeField<E>>> VerifierCircuit<E, P> {
+    /// Create a circuit that verifies `proofs` against `vk`.
+    pub fn new(vk: VerifyingKey<E>, proofs: Vec<(Proof<E>, Vec<E::ScalarField>)>) -> Self {
+        Self {
+            vk,
+            proofs,
+            _pairing_gadget: PhantomData,
+        }
+    }
+
+    /// Create a circuit of the shape for verifying `num_proofs` proofs against `vk`, with
+    /// placeholder proofs, for generating the parameters of the outer proof.
+    pub fn blank(vk: VerifyingKey<E>, num_proofs: usize) -> Self {
+        let placeholder = Proof {
+            a: E::G1Affine::generator(),
+            b: E::G2Affine::generator(),
+            c: E::G1Affine::generator(),
+        };
+        let num_inputs = vk.num_public_inputs();
+        let inputs = vec![E::ScalarField::zero(); num_inputs];
+        Self::new(vk, vec![(placeholder, inputs); num_proofs])
+    }
+
+    /// The public inputs of the outer proof for inner proofs with `public_inputs`.
+    pub fn outer_public_inputs(public_inputs: &[Vec<E::ScalarField>]) -> Vec<BasePrimeField<E>> {
+        public_inputs
+            .iter()
+            .flat_map(BooleanInputVar::<E::ScalarField, BasePrimeField<E>>::repack_input)
+            .collect()
+    }
+}
+
+impl<E, P> ConstraintSynthesizer<BasePrimeField<E>> for VerifierCircuit<E, P>
+where
+    E: Pairing,
+    P: PairingVar<E, BasePrimeField<E>>,
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<BasePrimeField<E>>,
+    ) -> Result<(), SynthesisError> {
+        let vk = VerifyingKeyVar::<E, P>::new_constant(cs.clone(), self.vk)?;
+        let pvk = vk.prepare()?;
+        for (proof, public_inputs) in self.proofs {
+            let inputs = BooleanInputVar::new_input(cs.clone(), || Ok(public_inputs))?;
+            let proof = ProofVar::new_witness(cs.clone(), || Ok(proof))?;
+            <Groth16VerifierGadget<E, P> as SNARKGadget<_, _, Groth16<E>>>::verify_with_processed_vk(
+                &pvk, &inputs, &proof,
+            )?
+            .enforce_equal(&Boolean::TRUE)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{constraints::Groth16VerifierGadget, Groth16};
+    use crate::{
+        constraints::{Groth16VerifierGadget, VerifierCircuit},
+        Groth16,
+    };
     use ark_crypto_primitives::snark::constraints::SNARKGadget;
     use ark_crypto_primitives::snark::SNARK;
     use ark_ec::pairing::Pairing;
     use ark_ff::{Field, UniformRand};
     use ark_mnt4_298::{constraints::PairingVar as MNT4PairingVar, Fr as MNT4Fr, MNT4_298 as MNT4};
-    use ark_mnt6_298::Fr as MNT6Fr;
+    use ark_mnt6_298::{Fr as MNT6Fr, MNT6_298 as MNT6};
     use ark_r1cs_std::bits::boolean::Boolean;
     use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget};
     use ark_relations::{
@@ -477,18 +551,18 @@ mod test {
         c.mul_assign(&b);
 
         let circ = Circuit {
-            a: Some(a.clone()),
-            b: Some(b.clone()),
+            a: Some(a),
+            b: Some(b),
             num_constraints: 100,
             num_variables: 25,
         };
 
         let (pk, vk) = TestSNARK::circuit_specific_setup(circ, &mut rng).unwrap();
 
-        let proof = TestSNARK::prove(&pk, circ.clone(), &mut rng).unwrap();
+        let proof = TestSNARK::prove(&pk, circ, &mut rng).unwrap();
 
         assert!(
-            TestSNARK::verify(&vk, &vec![c], &proof).unwrap(),
+            TestSNARK::verify(&vk, &[c], &proof).unwrap(),
             "The native verification check fails."
         );
 
@@ -548,4 +622,75 @@ mod test {
             cs.which_is_unsatisfied().unwrap().unwrap_or_default()
         );
     }
+
+    #[test]
+    fn recursive_proof() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let a = MNT4Fr::rand(&mut rng);
+        let b = MNT4Fr::rand(&mut rng);
+        let circ = Circuit {
+            a: Some(a),
+            b: Some(b),
+            num_constraints: 10,
+            num_variables: 5,
+        };
+        let (pk, vk) = TestSNARK::circuit_specific_setup(circ, &mut rng).unwrap();
+        let proof = TestSNARK::prove(&pk, circ, &mut rng).unwrap();
+
+        // Prove over MNT6, whose scalar field is the base field of MNT4, that the MNT4
+        // proof is valid.
+        type Outer = VerifierCircuit<MNT4, MNT4PairingVar>;
+        let (outer_pk, outer_vk) =
+            Groth16::<MNT6>::circuit_specific_setup(Outer::blank(vk.clone(), 1), &mut rng).unwrap();
+        let public_inputs = vec![vec![a * b]];
+        let outer_proof = Groth16::<MNT6>::prove(
+            &outer_pk,
+            Outer::new(vk, vec![(proof, public_inputs[0].clone())]),
+            &mut rng,
+        )
+        .unwrap();
+
+        let outer_inputs = Outer::outer_public_inputs(&public_inputs);
+        assert!(Groth16::<MNT6>::verify(&outer_vk, &outer_inputs, &outer_proof).unwrap());
+        let wrong_inputs = Outer::outer_public_inputs(&[vec![a]]);
+        assert!(!Groth16::<MNT6>::verify(&outer_vk, &wrong_inputs, &outer_proof).unwrap());
+    }
+
+    #[test]
+    fn recursive_proof_bls12_377_bw6_761() {
+        use ark_bls12_377::{constraints::PairingVar as Bls12_377PairingVar, Bls12_377, Fr};
+        use ark_bw6_761::BW6_761;
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let a = Fr::rand(&mut rng);
+        let b = Fr::rand(&mut rng);
+        let circ = Circuit {
+            a: Some(a),
+            b: Some(b),
+            num_constraints: 10,
+            num_variables: 5,
+        };
+        let (pk, vk) = Groth16::<Bls12_377>::circuit_specific_setup(circ, &mut rng).unwrap();
+        let proof = Groth16::<Bls12_377>::prove(&pk, circ, &mut rng).unwrap();
+
+        // BW6-761 has the base field of BLS12-377 as its scalar field, and both curves
+        // have highly 2-adic scalar fields, so the outer proof uses the same FFT-based
+        // prover.
+        type Outer = VerifierCircuit<Bls12_377, Bls12_377PairingVar>;
+        let (outer_pk, outer_vk) =
+            Groth16::<BW6_761>::circuit_specific_setup(Outer::blank(vk.clone(), 1), &mut rng)
+                .unwrap();
+        let public_inputs = vec![vec![a * b]];
+        let outer_proof = Groth16::<BW6_761>::prove(
+            &outer_pk,
+            Outer::new(vk, vec![(proof, public_inputs[0].clone())]),
+            &mut rng,
+        )
+        .unwrap();
+
+        let outer_inputs = Outer::outer_public_inputs(&public_inputs);
+        assert!(Groth16::<BW6_761>::verify(&outer_vk, &outer_inputs, &outer_proof).unwrap());
+        let wrong_inputs = Outer::outer_public_inputs(&[vec![a]]);
+        assert!(!Groth16::<BW6_761>::verify(&outer_vk, &wrong_inputs, &outer_proof).unwrap());
+    }
 }
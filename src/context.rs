@@ -0,0 +1,83 @@
+//! Proofs bound to an application context, so that they cannot be replayed in another
+//! application or session.
+//!
+//! The circuit of a context-bound proof has one more public input than the circuit it
+//! wraps, the *context input* `H(domain || len(context) || context)` of an
+//! application-supplied context string, as the last public input, where `H` is SHA-256
+//! reduced to the scalar field and the length is a little-endian `u64`. The circuit
+//! constrains the context input `c` by `c · 1 = c`, like the binding of [`se`](crate::se),
+//! so it enters the verification equation under any [`R1CSToQAP`] reduction.
+//! A proof created for one context does not verify for any other, and since the context
+//! input is part of the statement, rerandomizing the proof does not change its context.
+use crate::{hash_to_field, r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey, Proof};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::Result as R1CSResult;
+#[cfg(feature = "prover")]
+use {
+    crate::{se::BoundCircuit, ProvingKey},
+    ark_relations::r1cs::ConstraintSynthesizer,
+    ark_std::rand::Rng,
+};
+
+/// The domain separator of the context input.
+const CONTEXT_DOMAIN: &[u8] = b"ark-groth16/context/v1";
+
+/// Computes the public input that binds a proof to `context`.
+pub fn context_input<F: PrimeField>(context: &[u8]) -> F {
+    let bytes = [&(context.len() as u64).to_le_bytes()[..], context].concat();
+    hash_to_field(CONTEXT_DOMAIN, &bytes)
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a proving key for context-bound proofs of `circuit`, whose verifying key
+    /// has one more public input than `circuit`.
+    pub fn generate_random_context_parameters<C>(
+        circuit: C,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let circuit = BoundCircuit {
+            circuit,
+            binding: None,
+        };
+        Self::generate_random_parameters_with_reduction(circuit, rng)
+    }
+
+    /// Create a proof for `circuit` that is bound to `context`, with a proving key from
+    /// [`Groth16::generate_random_context_parameters`].
+    /// This method samples randomness for zero knowledge via `rng`.
+    pub fn create_random_proof_with_context<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        context: &[u8],
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let circuit = BoundCircuit {
+            circuit,
+            binding: Some(context_input::<E::ScalarField>(context)),
+        };
+        Self::create_random_proof_with_reduction(circuit, pk, rng)
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify a context-bound proof `proof` against the prepared verification key `pvk`,
+    /// with respect to the instance `public_inputs` of the wrapped circuit and the
+    /// expected `context`.
+    pub fn verify_proof_with_context(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+        context: &[u8],
+    ) -> R1CSResult<bool> {
+        let public_inputs = [public_inputs, &[context_input(context)]].concat();
+        Self::verify_proof(pvk, proof, &public_inputs)
+    }
+}
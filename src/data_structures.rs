@@ -1,9 +1,26 @@
-use ark_ec::pairing::Pairing;
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
 use ark_serialize::*;
-use ark_std::vec::Vec;
+use ark_std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Add,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use sha2::{Digest, Sha256};
 
 /// A proof in the Groth16 SNARK.
-#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+///
+/// Proofs are hashed and ordered by their compressed serialization, so that the order is
+/// the same on every platform and for every curve implementation.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<E: Pairing> {
     /// The `A` element in `G1`.
     pub a: E::G1Affine,
@@ -23,11 +40,147 @@ impl<E: Pairing> Default for Proof<E> {
     }
 }
 
+impl<E: Pairing> Proof<E> {
+    /// Returns the compressed serialization of `self` as an array of `N` bytes, for callers
+    /// that cannot allocate or need the size at compile time.
+    ///
+    /// `N` must be the compressed size of a proof for `E`, which is 192 for BLS12-381 and
+    /// BLS12-377, and 128 for BN254.
+    pub fn to_array<const N: usize>(&self) -> Result<[u8; N], SerializationError> {
+        if self.compressed_size() != N {
+            return Err(SerializationError::InvalidData);
+        }
+        let mut bytes = [0u8; N];
+        self.serialize_compressed(&mut bytes[..])?;
+        Ok(bytes)
+    }
+
+    /// Parses a proof from the array returned by [`Proof::to_array`], checking that its
+    /// elements are in the prime-order subgroups.
+    pub fn from_array<const N: usize>(bytes: &[u8; N]) -> Result<Self, SerializationError> {
+        if Self::default().compressed_size() != N {
+            return Err(SerializationError::InvalidData);
+        }
+        Self::deserialize_compressed(&bytes[..])
+    }
+}
+
+impl<E: Pairing> Hash for Proof<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&Vec::from(self), state)
+    }
+}
+
+impl<E: Pairing> PartialOrd for Proof<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: Pairing> Ord for Proof<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Vec::from(self).cmp(&Vec::from(other))
+    }
+}
+
+impl<E: Pairing> TryFrom<&[u8]> for Proof<E> {
+    type Error = SerializationError;
+
+    /// Parses a compressed proof that takes up all of `bytes`, checking that its elements
+    /// are in the prime-order subgroups.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        deserialize_exact(bytes)
+    }
+}
+
+impl<E: Pairing> From<&Proof<E>> for Vec<u8> {
+    /// Returns the compressed serialization of `proof`.
+    fn from(proof: &Proof<E>) -> Self {
+        serialize_to_vec(proof)
+    }
+}
+
+impl<E: Pairing> fmt::Display for Proof<E> {
+    /// Formats the compressed serialization of `self` as hexadecimal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", Hex(&Vec::from(self)))
+    }
+}
+
+impl<E: Pairing> fmt::Debug for Proof<E> {
+    /// Formats the compressed points of `self` as hexadecimal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Proof")
+            .field("a", &Hex(&serialize_to_vec(&self.a)))
+            .field("b", &Hex(&serialize_to_vec(&self.b)))
+            .field("c", &Hex(&serialize_to_vec(&self.c)))
+            .finish()
+    }
+}
+
+/// Formats bytes as hexadecimal, prefixed with `0x` in `Debug`.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl fmt::Debug for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", self)
+    }
+}
+
+/// Serializes `value` compressed into a new `Vec`.
+fn serialize_to_vec<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    bytes
+}
+
+/// Deserializes a compressed `T` and checks that no bytes are left over.
+fn deserialize_exact<T: CanonicalDeserialize>(mut bytes: &[u8]) -> Result<T, SerializationError> {
+    let value = T::deserialize_compressed(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(value)
+}
+
+/// A proof whose elements are prepared for use in pairings, so that a proof verified
+/// many times is prepared only once.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PreparedProof<E: Pairing> {
+    /// The unprepared proof.
+    pub proof: Proof<E>,
+    /// The `A` element in `G1`, prepared for use in pairings.
+    pub a: E::G1Prepared,
+    /// The `B` element in `G2`, prepared for use in pairings.
+    pub b: E::G2Prepared,
+    /// The `C` element in `G1`, prepared for use in pairings.
+    pub c: E::G1Prepared,
+}
+
+impl<E: Pairing> From<Proof<E>> for PreparedProof<E> {
+    fn from(proof: Proof<E>) -> Self {
+        Self {
+            a: proof.a.into(),
+            b: proof.b.into(),
+            c: proof.c.into(),
+            proof,
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////////////////////
 
 /// A verification key in the Groth16 SNARK.
-#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct VerifyingKey<E: Pairing> {
     /// The `alpha * G`, where `G` is the generator of `E::G1`.
     pub alpha_g1: E::G1Affine,
@@ -53,6 +206,137 @@ impl<E: Pairing> Default for VerifyingKey<E> {
     }
 }
 
+impl<E: Pairing> VerifyingKey<E> {
+    /// The number of public inputs of the circuit, which verification requires exactly.
+    ///
+    /// This is `gamma_abc_g1.len() - 1`, or `0` for a malformed key without `gamma_abc_g1`.
+    pub fn num_public_inputs(&self) -> usize {
+        self.gamma_abc_g1.len().saturating_sub(1)
+    }
+
+    /// Returns the SHA-256 hash of the compressed serialization of `self`, which identifies
+    /// the circuit that `self` verifies proofs for.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(Vec::from(self)).into()
+    }
+}
+
+impl<E: Pairing> fmt::Display for VerifyingKey<E> {
+    /// Summarizes `self` by its number of public inputs, its compressed size and its
+    /// [fingerprint](VerifyingKey::fingerprint).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "verifying key (public inputs: {}, compressed size: {} bytes, fingerprint: {:?})",
+            self.num_public_inputs(),
+            self.compressed_size(),
+            Hex(&self.fingerprint())
+        )
+    }
+}
+
+impl<E: Pairing> fmt::Debug for VerifyingKey<E> {
+    /// Formats the compressed points of `self` as hexadecimal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gamma_abc_g1 = self
+            .gamma_abc_g1
+            .iter()
+            .map(serialize_to_vec)
+            .collect::<Vec<_>>();
+        f.debug_struct("VerifyingKey")
+            .field("alpha_g1", &Hex(&serialize_to_vec(&self.alpha_g1)))
+            .field("beta_g2", &Hex(&serialize_to_vec(&self.beta_g2)))
+            .field("gamma_g2", &Hex(&serialize_to_vec(&self.gamma_g2)))
+            .field("delta_g2", &Hex(&serialize_to_vec(&self.delta_g2)))
+            .field(
+                "gamma_abc_g1",
+                &gamma_abc_g1
+                    .iter()
+                    .map(|point| Hex(point))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<E: Pairing> TryFrom<&[u8]> for VerifyingKey<E> {
+    type Error = SerializationError;
+
+    /// Parses a compressed verifying key that takes up all of `bytes`, checking that its
+    /// elements are in the prime-order subgroups.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        deserialize_exact(bytes)
+    }
+}
+
+impl<E: Pairing> From<&VerifyingKey<E>> for Vec<u8> {
+    /// Returns the compressed serialization of `vk`.
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        serialize_to_vec(vk)
+    }
+}
+
+/// A verifying key with a name for each of its public inputs, so that inputs can be
+/// supplied by name instead of by position.
+///
+/// The serialization is that of the verifying key followed by the labels, so a plain
+/// [`VerifyingKey`] can be read from the start of a serialized `LabeledVerifyingKey`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LabeledVerifyingKey<E: Pairing> {
+    /// The verifying key.
+    pub vk: VerifyingKey<E>,
+    /// The position of each named input among the public inputs of `vk`.
+    pub labels: BTreeMap<String, usize>,
+}
+
+impl<E: Pairing> LabeledVerifyingKey<E> {
+    /// Attaches `labels` to `vk`. Returns `SynthesisError::MalformedVerifyingKey` unless
+    /// every public input of `vk` has exactly one label.
+    pub fn new(vk: VerifyingKey<E>, labels: BTreeMap<String, usize>) -> R1CSResult<Self> {
+        let num_inputs = vk.num_public_inputs();
+        let mut labeled = vec![false; num_inputs];
+        for &index in labels.values() {
+            match labeled.get_mut(index) {
+                Some(seen) if !*seen => *seen = true,
+                _ => return Err(SynthesisError::MalformedVerifyingKey),
+            }
+        }
+        if labels.len() != num_inputs {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        Ok(Self { vk, labels })
+    }
+
+    /// Orders the named `inputs` by the positions of their labels, for passing to
+    /// [`Groth16::verify_proof`](crate::Groth16::verify_proof).
+    #[cfg(feature = "std")]
+    pub fn order_public_inputs<S: std::hash::BuildHasher>(
+        &self,
+        inputs: &std::collections::HashMap<String, E::ScalarField, S>,
+    ) -> Result<Vec<E::ScalarField>, crate::VerificationError> {
+        let expected = self.labels.len();
+        if inputs.len() != expected {
+            return Err(crate::VerificationError::WrongNumberOfPublicInputs {
+                expected,
+                found: inputs.len(),
+            });
+        }
+        let mut ordered = vec![None; expected];
+        for (label, &index) in &self.labels {
+            let input = inputs
+                .get(label)
+                .ok_or(crate::VerificationError::UnknownInputLabel)?;
+            *ordered
+                .get_mut(index)
+                .ok_or(SynthesisError::MalformedVerifyingKey)? = Some(*input);
+        }
+        ordered
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(SynthesisError::MalformedVerifyingKey.into())
+    }
+}
+
 /// Preprocessed verification key parameters that enable faster verification
 /// at the expense of larger size in memory.
 #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -90,6 +374,77 @@ impl<E: Pairing> Default for PreparedVerifyingKey<E> {
     }
 }
 
+/// Public inputs prepared for verification against a verifying key, so that many proofs
+/// of the same public inputs can be verified without recomputing the input MSM.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PreparedInputs<E: Pairing> {
+    /// The element `gamma_abc_g1[0] + sum_i x_i * gamma_abc_g1[i]` in `E::G1`, for the
+    /// public inputs `x_1, ..., x_l`.
+    pub prepared_inputs: E::G1Affine,
+}
+
+/// A verification key specialized to fixed public inputs, so that checking a proof of
+/// the statement takes only two pairings and one comparison in `E::GT`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PreparedStatement<E: Pairing> {
+    /// The element `e(alpha * G, beta * H) * e(ic, gamma * H)` in `E::GT`, where `ic`
+    /// is the prepared public inputs.
+    pub target: E::TargetField,
+    /// The element `- delta * H` in `E::G2`, prepared for use in pairings.
+    pub delta_g2_neg_pc: E::G2Prepared,
+}
+
+/// The output of witness generation, from which a proof can be computed without the circuit.
+///
+/// See [`Groth16::synthesize_assignment`](crate::Groth16::synthesize_assignment) and
+/// [`Groth16::create_proof_from_assignment`](crate::Groth16::create_proof_from_assignment).
+/// The assignment is secret, so it is wiped when it is dropped, and its `Debug` output
+/// only shows the public inputs.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProverAssignment<F: PrimeField> {
+    /// The assignment of the instance variables, without the leading constant `1`.
+    pub input_assignment: Vec<F>,
+    /// The assignment of the witness variables.
+    pub aux_assignment: Vec<F>,
+    /// The coefficients of the QAP witness polynomial `h`.
+    pub h: Vec<F>,
+}
+
+impl<F: PrimeField> zeroize::Zeroize for ProverAssignment<F> {
+    fn zeroize(&mut self) {
+        self.input_assignment.zeroize();
+        self.aux_assignment.zeroize();
+        self.h.zeroize();
+    }
+}
+
+impl<F: PrimeField> Drop for ProverAssignment<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+impl<F: PrimeField> zeroize::ZeroizeOnDrop for ProverAssignment<F> {}
+
+impl<F: PrimeField> fmt::Debug for ProverAssignment<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverAssignment")
+            .field("input_assignment", &self.input_assignment)
+            .field("aux_assignment", &Redacted(self.aux_assignment.len()))
+            .field("h", &Redacted(self.h.len()))
+            .finish()
+    }
+}
+
+/// Stands in for `len` secret values in `Debug` output.
+struct Redacted(usize);
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} redacted values>", self.0)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -113,3 +468,330 @@ pub struct ProvingKey<E: Pairing> {
     /// The elements `l_i * G` in `E::G1`.
     pub l_query: Vec<E::G1Affine>,
 }
+
+/// A [`VerifyingKey`] whose `gamma_abc_g1` is borrowed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyingKeyRef<'a, E: Pairing> {
+    /// The `alpha * G`, where `G` is the generator of `E::G1`.
+    pub alpha_g1: E::G1Affine,
+    /// The `alpha * H`, where `H` is the generator of `E::G2`.
+    pub beta_g2: E::G2Affine,
+    /// The `gamma * H`, where `H` is the generator of `E::G2`.
+    pub gamma_g2: E::G2Affine,
+    /// The `delta * H`, where `H` is the generator of `E::G2`.
+    pub delta_g2: E::G2Affine,
+    /// The `gamma^{-1} * (beta * a_i + alpha * b_i + c_i) * H`, where `H` is the generator
+    /// of `E::G1`.
+    pub gamma_abc_g1: &'a [E::G1Affine],
+}
+
+impl<E: Pairing> Clone for VerifyingKeyRef<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Pairing> Copy for VerifyingKeyRef<'_, E> {}
+
+impl<'a, E: Pairing> From<&'a VerifyingKey<E>> for VerifyingKeyRef<'a, E> {
+    fn from(vk: &'a VerifyingKey<E>) -> Self {
+        Self {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            gamma_abc_g1: &vk.gamma_abc_g1,
+        }
+    }
+}
+
+impl<E: Pairing> VerifyingKeyRef<'_, E> {
+    /// Copies the borrowed points into an owned [`VerifyingKey`].
+    pub fn to_owned(&self) -> VerifyingKey<E> {
+        VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            gamma_abc_g1: self.gamma_abc_g1.to_vec(),
+        }
+    }
+}
+
+/// A [`ProvingKey`] whose query vectors are borrowed, so that a key held elsewhere, for
+/// example in memory shared by several provers, can be used without copying its points.
+///
+/// The provers that take a `ProvingKeyRef` compute the same proofs as with the owned
+/// key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProvingKeyRef<'a, E: Pairing> {
+    /// The underlying verification key.
+    pub vk: VerifyingKeyRef<'a, E>,
+    /// The element `beta * G` in `E::G1`.
+    pub beta_g1: E::G1Affine,
+    /// The element `delta * G` in `E::G1`.
+    pub delta_g1: E::G1Affine,
+    /// The elements `a_i * G` in `E::G1`.
+    pub a_query: &'a [E::G1Affine],
+    /// The elements `b_i * G` in `E::G1`.
+    pub b_g1_query: &'a [E::G1Affine],
+    /// The elements `b_i * H` in `E::G2`.
+    pub b_g2_query: &'a [E::G2Affine],
+    /// The elements `h_i * G` in `E::G1`.
+    pub h_query: &'a [E::G1Affine],
+    /// The elements `l_i * G` in `E::G1`.
+    pub l_query: &'a [E::G1Affine],
+}
+
+impl<E: Pairing> Clone for ProvingKeyRef<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Pairing> Copy for ProvingKeyRef<'_, E> {}
+
+impl<'a, E: Pairing> From<&'a ProvingKey<E>> for ProvingKeyRef<'a, E> {
+    fn from(pk: &'a ProvingKey<E>) -> Self {
+        Self {
+            vk: (&pk.vk).into(),
+            beta_g1: pk.beta_g1,
+            delta_g1: pk.delta_g1,
+            a_query: &pk.a_query,
+            b_g1_query: &pk.b_g1_query,
+            b_g2_query: &pk.b_g2_query,
+            h_query: &pk.h_query,
+            l_query: &pk.l_query,
+        }
+    }
+}
+
+impl<E: Pairing> ProvingKeyRef<'_, E> {
+    /// Copies the borrowed points into an owned [`ProvingKey`].
+    pub fn to_owned(&self) -> ProvingKey<E> {
+        ProvingKey {
+            vk: self.vk.to_owned(),
+            beta_g1: self.beta_g1,
+            delta_g1: self.delta_g1,
+            a_query: self.a_query.to_vec(),
+            b_g1_query: self.b_g1_query.to_vec(),
+            b_g2_query: self.b_g2_query.to_vec(),
+            h_query: self.h_query.to_vec(),
+            l_query: self.l_query.to_vec(),
+        }
+    }
+}
+
+impl<E: Pairing> ProvingKey<E> {
+    /// Returns the number of elements in each query vector of `self`.
+    pub fn key_size(&self) -> KeySize {
+        KeySize {
+            gamma_abc_g1: self.vk.gamma_abc_g1.len(),
+            a_query: self.a_query.len(),
+            b_g1_query: self.b_g1_query.len(),
+            b_g2_query: self.b_g2_query.len(),
+            h_query: self.h_query.len(),
+            l_query: self.l_query.len(),
+        }
+    }
+
+    /// Checks that the lengths of the query vectors of `self` are consistent with each
+    /// other, to catch truncated or mismatched key files before proving with them.
+    pub fn check_consistency(&self) -> Result<(), KeyConsistencyError> {
+        let size = self.key_size();
+        if size.gamma_abc_g1 == 0 {
+            return Err(KeyConsistencyError::EmptyGammaAbc);
+        }
+        for (query, found) in [
+            ("b_g1_query", size.b_g1_query),
+            ("b_g2_query", size.b_g2_query),
+        ] {
+            if found != size.a_query {
+                return Err(KeyConsistencyError::LengthMismatch {
+                    query,
+                    expected: size.a_query,
+                    found,
+                });
+            }
+        }
+        // Every variable is either an instance variable or a witness variable.
+        if size.gamma_abc_g1 + size.l_query != size.a_query {
+            return Err(KeyConsistencyError::LengthMismatch {
+                query: "l_query",
+                expected: size.a_query.saturating_sub(size.gamma_abc_g1),
+                found: size.l_query,
+            });
+        }
+        // The evaluation domain contains a point for every instance variable.
+        if size.h_query + 1 < size.gamma_abc_g1 {
+            return Err(KeyConsistencyError::HQueryTooShort {
+                minimum: size.gamma_abc_g1 - 1,
+                found: size.h_query,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// An inconsistency between the lengths of the query vectors of a [`ProvingKey`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyConsistencyError {
+    /// `vk.gamma_abc_g1` is empty, although it has an element for the constant one.
+    EmptyGammaAbc,
+    /// A query vector has a different length than the other query vectors imply.
+    LengthMismatch {
+        /// The name of the query vector.
+        query: &'static str,
+        /// The length the other query vectors imply.
+        expected: usize,
+        /// The length in the key.
+        found: usize,
+    },
+    /// `h_query` is too short for an evaluation domain with a point per instance
+    /// variable.
+    HQueryTooShort {
+        /// The smallest length for the number of instance variables.
+        minimum: usize,
+        /// The length in the key.
+        found: usize,
+    },
+}
+
+impl ark_std::error::Error for KeyConsistencyError {}
+
+impl fmt::Display for KeyConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyConsistencyError::EmptyGammaAbc => write!(f, "vk.gamma_abc_g1 is empty"),
+            KeyConsistencyError::LengthMismatch {
+                query,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} points, but the other queries imply {}",
+                query, found, expected
+            ),
+            KeyConsistencyError::HQueryTooShort { minimum, found } => write!(
+                f,
+                "h_query has {} points, but the public inputs need at least {}",
+                found, minimum
+            ),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////
+
+/// The number of group elements in each variable-length part of a Groth16 key.
+/// Together with a choice of curve and point compression this determines the
+/// exact serialized size of the key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeySize {
+    /// The length of `vk.gamma_abc_g1`, i.e. the number of public inputs plus one.
+    pub gamma_abc_g1: usize,
+    /// The length of `a_query`.
+    pub a_query: usize,
+    /// The length of `b_g1_query`.
+    pub b_g1_query: usize,
+    /// The length of `b_g2_query`.
+    pub b_g2_query: usize,
+    /// The length of `h_query`.
+    pub h_query: usize,
+    /// The length of `l_query`.
+    pub l_query: usize,
+}
+
+/// The size of the length prefix that `ark-serialize` writes before a `Vec`.
+const VEC_LENGTH_PREFIX_SIZE: usize = 8;
+
+impl KeySize {
+    /// Computes the key size that [`LibsnarkReduction`](crate::r1cs_to_qap::LibsnarkReduction)
+    /// produces for a circuit with the given dimensions, where `num_instance_variables`
+    /// includes the constant one. Returns `None` if the circuit is too large for an
+    /// evaluation domain over `F`.
+    #[cfg(feature = "prover")]
+    pub fn for_circuit<F: PrimeField>(
+        num_constraints: usize,
+        num_instance_variables: usize,
+        num_witness_variables: usize,
+    ) -> Option<Self> {
+        let domain_size =
+            GeneralEvaluationDomain::<F>::new(num_constraints + num_instance_variables)?.size();
+        let num_variables = num_instance_variables + num_witness_variables;
+        Some(Self {
+            gamma_abc_g1: num_instance_variables,
+            a_query: num_variables,
+            b_g1_query: num_variables,
+            b_g2_query: num_variables,
+            h_query: domain_size - 1,
+            l_query: num_witness_variables,
+        })
+    }
+
+    /// The number of public inputs a verifying key of this size accepts.
+    pub fn num_public_inputs(&self) -> usize {
+        self.gamma_abc_g1.saturating_sub(1)
+    }
+
+    /// The total number of `G1` elements in a proving key of this size.
+    pub fn num_g1_elements(&self) -> usize {
+        // `alpha_g1`, `beta_g1` and `delta_g1`, plus the queries.
+        3 + self.gamma_abc_g1 + self.a_query + self.b_g1_query + self.h_query + self.l_query
+    }
+
+    /// The total number of `G2` elements in a proving key of this size.
+    pub fn num_g2_elements(&self) -> usize {
+        // `beta_g2`, `gamma_g2` and `delta_g2`, plus the query.
+        3 + self.b_g2_query
+    }
+
+    /// The exact length of a serialized [`VerifyingKey`] of this size over `E`.
+    pub fn verifying_key_bytes<E: Pairing>(&self, compress: Compress) -> usize {
+        let g1 = E::G1Affine::zero().serialized_size(compress);
+        let g2 = E::G2Affine::zero().serialized_size(compress);
+        (1 + self.gamma_abc_g1) * g1 + 3 * g2 + VEC_LENGTH_PREFIX_SIZE
+    }
+
+    /// The exact length of a serialized [`ProvingKey`] of this size over `E`.
+    pub fn proving_key_bytes<E: Pairing>(&self, compress: Compress) -> usize {
+        let g1 = E::G1Affine::zero().serialized_size(compress);
+        let g2 = E::G2Affine::zero().serialized_size(compress);
+        self.num_g1_elements() * g1 + self.num_g2_elements() * g2 + 6 * VEC_LENGTH_PREFIX_SIZE
+    }
+
+    /// The exact length of a serialized [`Proof`] over `E`, which does not depend on the key size.
+    pub fn proof_bytes<E: Pairing>(compress: Compress) -> usize {
+        let g1 = E::G1Affine::zero().serialized_size(compress);
+        let g2 = E::G2Affine::zero().serialized_size(compress);
+        2 * g1 + g2
+    }
+}
+
+impl fmt::Display for KeySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "public inputs: {}, G1 elements: {}, G2 elements: {}",
+            self.num_public_inputs(),
+            self.num_g1_elements(),
+            self.num_g2_elements()
+        )
+    }
+}
+
+impl Add for KeySize {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            gamma_abc_g1: self.gamma_abc_g1 + other.gamma_abc_g1,
+            a_query: self.a_query + other.a_query,
+            b_g1_query: self.b_g1_query + other.b_g1_query,
+            b_g2_query: self.b_g2_query + other.b_g2_query,
+            h_query: self.h_query + other.h_query,
+            l_query: self.l_query + other.l_query,
+        }
+    }
+}
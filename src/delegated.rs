@@ -0,0 +1,327 @@
+//! Delegated proving, in which a weak client has an untrusted server compute the
+//! multi-scalar multiplications of a proof without revealing its witness.
+//!
+//! The multi-scalar multiplications of the prover are linear in the assignment, so the
+//! client can hide the assignment `z` and the coefficients of `h` behind one-time pads
+//! `m_z, m_h` and subtract the contribution of the pads from the server's results:
+//!
+//! ```text
+//! MSM(bases, z) = MSM(bases, z + m_z) - MSM(bases, m_z).
+//! ```
+//!
+//! A [`DelegationMask`] holds the seed of the pads and their multi-scalar multiplications
+//! with the queries of one proving key. It is computed ahead of time, by the client while
+//! it is idle or by a trusted dealer, with [`Groth16::generate_delegation_mask`], and can
+//! be used for exactly one proof. The protocol for a circuit is then:
+//!
+//! 1. The client computes its [`ProverAssignment`] with [`Groth16::synthesize_assignment`]
+//!    and sends the [`BlindedAssignment`] of [`Groth16::blind_assignment`] to the server.
+//!    This consumes the mask and leaves the client a [`DelegationToken`].
+//! 2. The server answers with the [`BlindedEvaluation`] of
+//!    [`Groth16::evaluate_blinded_assignment`], which only sees uniformly random scalars.
+//! 3. The client unblinds the evaluation with the token and adds the zero-knowledge
+//!    randomness with [`Groth16::finish_delegated_proof`], which verifies the proof before returning it,
+//!    so a server that answers incorrectly is detected.
+use crate::{
+    msm::{MsmBackend, MsmQuery},
+    prepare_verifying_key,
+    prover::QueryAccumulators,
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProverAssignment, ProverError, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::*;
+use ark_std::{
+    cfg_iter, fmt,
+    rand::{Rng, SeedableRng},
+    vec::Vec,
+};
+use rand_chacha::ChaCha20Rng;
+use zeroize::{Zeroize, Zeroizing};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// One-time pads for the assignment and `h` of one delegated proof, with their
+/// multi-scalar multiplications with the queries of a proving key.
+///
+/// The pads are derived from a secret seed. Using a mask for two proofs reveals the
+/// difference of their assignments to the server, so [`Groth16::blind_assignment`]
+/// consumes it. The seed is zeroized when the mask is dropped.
+///
+/// # Security
+///
+/// The mask can be serialized so that a dealer can hand it to the client, but every
+/// deserialization yields a fresh mask with the same seed, which the type system can no
+/// longer keep from being used twice. Store a serialized mask encrypted, load it once,
+/// and delete it before blinding an assignment with it.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct DelegationMask<E: Pairing> {
+    seed: [u8; 32],
+    /// The multi-scalar multiplication of the assignment pad with `a_query[1..]`.
+    pub a_g1: E::G1Affine,
+    /// The multi-scalar multiplication of the assignment pad with `b_g1_query[1..]`.
+    pub b_g1: E::G1Affine,
+    /// The multi-scalar multiplication of the assignment pad with `b_g2_query[1..]`.
+    pub b_g2: E::G2Affine,
+    /// The multi-scalar multiplication of the `h` pad with `h_query`.
+    pub h_g1: E::G1Affine,
+    /// The multi-scalar multiplication of the witness part of the assignment pad with
+    /// `l_query`.
+    pub l_g1: E::G1Affine,
+}
+
+impl<E: Pairing> DelegationMask<E> {
+    /// The pads of the assignment and of `h`, for a proving key with `num_variables`
+    /// variables without the constant and `num_h` coefficients of `h`.
+    #[allow(clippy::type_complexity)]
+    fn pads(
+        &self,
+        num_variables: usize,
+        num_h: usize,
+    ) -> (
+        Zeroizing<Vec<E::ScalarField>>,
+        Zeroizing<Vec<E::ScalarField>>,
+    ) {
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        let z = (0..num_variables)
+            .map(|_| E::ScalarField::rand(&mut rng))
+            .collect();
+        let h = (0..num_h).map(|_| E::ScalarField::rand(&mut rng)).collect();
+        (Zeroizing::new(z), Zeroizing::new(h))
+    }
+}
+
+impl<E: Pairing> Drop for DelegationMask<E> {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+impl<E: Pairing> fmt::Debug for DelegationMask<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DelegationMask { .. }")
+    }
+}
+
+/// What the client keeps of a [`DelegationMask`] after blinding an assignment with it:
+/// the multi-scalar multiplications of its pads, which [`Groth16::finish_delegated_proof`]
+/// subtracts from the server's evaluation. It cannot blind another assignment.
+pub struct DelegationToken<E: Pairing> {
+    a_g1: E::G1Affine,
+    b_g1: E::G1Affine,
+    b_g2: E::G2Affine,
+    h_g1: E::G1Affine,
+    l_g1: E::G1Affine,
+}
+
+impl<E: Pairing> fmt::Debug for DelegationToken<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DelegationToken { .. }")
+    }
+}
+
+/// The assignment and the coefficients of `h` of a proof, hidden behind the pads of a
+/// [`DelegationMask`], as sent to the server.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BlindedAssignment<F: PrimeField> {
+    /// The blinded assignment of the instance variables, without the constant, followed by
+    /// the witness variables.
+    pub assignment: Vec<F>,
+    /// The blinded coefficients of `h`.
+    pub h: Vec<F>,
+}
+
+/// The multi-scalar multiplications of a [`BlindedAssignment`] with the queries of a
+/// proving key, as returned by the server.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BlindedEvaluation<E: Pairing> {
+    /// The multi-scalar multiplication with `a_query[1..]`.
+    pub a_g1: E::G1Affine,
+    /// The multi-scalar multiplication with `b_g1_query[1..]`.
+    pub b_g1: E::G1Affine,
+    /// The multi-scalar multiplication with `b_g2_query[1..]`.
+    pub b_g2: E::G2Affine,
+    /// The multi-scalar multiplication of `h` with `h_query`.
+    pub h_g1: E::G1Affine,
+    /// The multi-scalar multiplication of the witness variables with `l_query`.
+    pub l_g1: E::G1Affine,
+}
+
+/// The number of variables without the constant, and the number of instance variables
+/// without the constant, of `pk`.
+fn shape<E: Pairing>(pk: &ProvingKey<E>) -> Result<(usize, usize), ProverError> {
+    let num_variables = pk
+        .a_query
+        .len()
+        .checked_sub(1)
+        .ok_or(ProverError::MalformedProvingKey)?;
+    let num_inputs = num_variables
+        .checked_sub(pk.l_query.len())
+        .ok_or(ProverError::MalformedProvingKey)?;
+    Ok((num_variables, num_inputs))
+}
+
+/// Computes the five multi-scalar multiplications of `assignment` and `h` with the
+/// queries of `pk`.
+fn evaluate<E: Pairing, B: MsmBackend<E>>(
+    pk: &ProvingKey<E>,
+    num_inputs: usize,
+    assignment: &[E::ScalarField],
+    h: &[E::ScalarField],
+    backend: &B,
+) -> BlindedEvaluation<E> {
+    let assignment = cfg_iter!(assignment)
+        .map(|s| s.into_bigint())
+        .collect::<Vec<_>>();
+    let h = cfg_iter!(h).map(|s| s.into_bigint()).collect::<Vec<_>>();
+    BlindedEvaluation {
+        a_g1: backend
+            .msm_g1(MsmQuery::A, &pk.a_query[1..], &assignment)
+            .into_affine(),
+        b_g1: backend
+            .msm_g1(MsmQuery::BG1, &pk.b_g1_query[1..], &assignment)
+            .into_affine(),
+        b_g2: backend
+            .msm_g2(MsmQuery::BG2, &pk.b_g2_query[1..], &assignment)
+            .into_affine(),
+        h_g1: backend.msm_g1(MsmQuery::H, &pk.h_query, &h).into_affine(),
+        l_g1: backend
+            .msm_g1(MsmQuery::L, &pk.l_query, &assignment[num_inputs..])
+            .into_affine(),
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a one-time [`DelegationMask`] for proofs with `pk`.
+    ///
+    /// This costs as much as the multi-scalar multiplications of a proof, and is meant to
+    /// run ahead of time on the client or on a dealer that the client trusts with the
+    /// mask.
+    pub fn generate_delegation_mask(
+        pk: &ProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> Result<DelegationMask<E>, ProverError> {
+        let (num_variables, num_inputs) = shape(pk)?;
+        let mut mask = DelegationMask {
+            seed: rng.gen(),
+            a_g1: E::G1Affine::zero(),
+            b_g1: E::G1Affine::zero(),
+            b_g2: E::G2Affine::zero(),
+            h_g1: E::G1Affine::zero(),
+            l_g1: E::G1Affine::zero(),
+        };
+        let (z, h) = mask.pads(num_variables, pk.h_query.len());
+        let evaluation = evaluate(pk, num_inputs, &z, &h, &crate::msm::CpuBackend);
+        mask.a_g1 = evaluation.a_g1;
+        mask.b_g1 = evaluation.b_g1;
+        mask.b_g2 = evaluation.b_g2;
+        mask.h_g1 = evaluation.h_g1;
+        mask.l_g1 = evaluation.l_g1;
+        Ok(mask)
+    }
+
+    /// Hides `assignment` behind the pads of `mask`, for the server to evaluate, and
+    /// returns the token that [`Groth16::finish_delegated_proof`] unblinds the evaluation
+    /// with. The mask is consumed, so its pads cannot blind a second assignment.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if the numbers of variables of
+    /// `assignment` do not match `pk`.
+    #[allow(clippy::type_complexity)]
+    pub fn blind_assignment(
+        pk: &ProvingKey<E>,
+        assignment: &ProverAssignment<E::ScalarField>,
+        mask: DelegationMask<E>,
+    ) -> Result<(BlindedAssignment<E::ScalarField>, DelegationToken<E>), ProverError> {
+        let (num_variables, num_inputs) = shape(pk)?;
+        if assignment.input_assignment.len() != num_inputs
+            || assignment.aux_assignment.len() != pk.l_query.len()
+            || assignment.h.len() < pk.h_query.len()
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+
+        let (z_pad, h_pad) = mask.pads(num_variables, pk.h_query.len());
+        let assignment_values = assignment
+            .input_assignment
+            .iter()
+            .chain(&assignment.aux_assignment);
+        let blinded = BlindedAssignment {
+            assignment: assignment_values
+                .zip(z_pad.iter())
+                .map(|(z, pad)| *z + pad)
+                .collect(),
+            h: assignment
+                .h
+                .iter()
+                .zip(h_pad.iter())
+                .map(|(h, pad)| *h + pad)
+                .collect(),
+        };
+        let token = DelegationToken {
+            a_g1: mask.a_g1,
+            b_g1: mask.b_g1,
+            b_g2: mask.b_g2,
+            h_g1: mask.h_g1,
+            l_g1: mask.l_g1,
+        };
+        Ok((blinded, token))
+    }
+
+    /// Computes the multi-scalar multiplications of `blinded` with the queries of `pk`
+    /// with `backend`. This is the work of the server.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if the lengths of `blinded` do not
+    /// match `pk`.
+    pub fn evaluate_blinded_assignment<B: MsmBackend<E>>(
+        pk: &ProvingKey<E>,
+        blinded: &BlindedAssignment<E::ScalarField>,
+        backend: &B,
+    ) -> Result<BlindedEvaluation<E>, ProverError> {
+        let (num_variables, num_inputs) = shape(pk)?;
+        if blinded.assignment.len() != num_variables || blinded.h.len() != pk.h_query.len() {
+            return Err(ProverError::MalformedProvingKey);
+        }
+        Ok(evaluate(
+            pk,
+            num_inputs,
+            &blinded.assignment,
+            &blinded.h,
+            backend,
+        ))
+    }
+
+    /// Removes the pads recorded in `token` from the server's `evaluation` and completes
+    /// a zero-knowledge proof for `assignment`.
+    /// This method samples randomness for zero knowledge via `rng`.
+    ///
+    /// Returns [`ProverError::InvalidProof`] if the completed proof does not verify, in
+    /// particular if the server did not evaluate the blinded assignment correctly.
+    pub fn finish_delegated_proof(
+        pk: &ProvingKey<E>,
+        assignment: &ProverAssignment<E::ScalarField>,
+        token: DelegationToken<E>,
+        evaluation: &BlindedEvaluation<E>,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError> {
+        shape(pk)?;
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        let acc = QueryAccumulators {
+            a: evaluation.a_g1.into_group() - token.a_g1 + pk.a_query[0],
+            b_g1: evaluation.b_g1.into_group() - token.b_g1 + pk.b_g1_query[0],
+            b_g2: evaluation.b_g2.into_group() - token.b_g2 + pk.b_g2_query[0],
+            h: evaluation.h_g1.into_group() - token.h_g1,
+            l: evaluation.l_g1.into_group() - token.l_g1,
+        };
+        let proof = Self::assemble_proof((&pk.vk).into(), pk.beta_g1, pk.delta_g1, &r, &s, acc);
+
+        let pvk = prepare_verifying_key(&pk.vk);
+        if !Self::verify_proof(&pvk, &proof, &assignment.input_assignment)? {
+            return Err(ProverError::InvalidProof);
+        }
+        Ok(proof)
+    }
+}
@@ -0,0 +1,259 @@
+//! Designated-verifier proofs, which only convince the holder of a verifier secret key.
+//!
+//! A [`DesignatedProof`] is a non-interactive OR-proof of the statement "I know a valid
+//! Groth16 proof for the public inputs, *or* I know the secret key of the designated
+//! verifier". The designated verifier knows that they did not produce the proof, so it
+//! convinces them, but they can produce indistinguishable proofs for any statement with
+//! [`Groth16::simulate_designated_proof`], so the proof convinces nobody else.
+//!
+//! # Specification
+//!
+//! The verifier key pair is `(sk, pk = sk · G)` in `G1`. The prover rerandomizes its proof
+//! `(A, B, C)`, reveals `A` and `B` and hides `C`, whose knowledge is a preimage of
+//! `C ↦ e(C, δ)` at
+//!
+//! ```text
+//! T = e(A, B) - e(α, β) - e(IC(x), γ)
+//! ```
+//!
+//! in additive notation. The two branches are sigma protocols: `(R_1, Z)` with
+//! `e(Z, δ) = R_1 + c_1 · T` for the proof, and the Schnorr proof `(R_2, z)` with
+//! `z · G = R_2 + c_2 · pk` for the key. The challenges satisfy `c_1 + c_2 = c` with
+//!
+//! ```text
+//! c = H(domain || vk || x || pk || A || R_2 || B || R_1),
+//! ```
+//!
+//! where `H` is SHA-256 reduced to the scalar field. The prover simulates the key branch
+//! and the verifier's simulator the proof branch.
+use crate::{hash_to_field, r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::*;
+use ark_std::{fmt, ops::Mul, rand::Rng, vec::Vec};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+#[cfg(feature = "prover")]
+use crate::Proof;
+
+/// The domain separator of the Fiat-Shamir challenge of [`DesignatedProof`]s.
+const DESIGNATED_DOMAIN: &[u8] = b"ark-groth16/designated-verifier/v1";
+
+/// The secret key of a designated verifier.
+///
+/// Anyone who knows this key can simulate proofs for the public key derived from it.
+/// The key is zeroized when it is dropped.
+pub struct DesignatedVerifierKey<F: PrimeField> {
+    secret: F,
+}
+
+impl<F: PrimeField> DesignatedVerifierKey<F> {
+    /// Wrap the given secret.
+    pub fn new(secret: F) -> Self {
+        Self { secret }
+    }
+
+    /// Sample the secret from `rng`.
+    pub fn sample<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(F::rand(rng))
+    }
+
+    /// The public key `sk · G` in `G1` that provers designate this verifier with.
+    pub fn public_key<E: Pairing<ScalarField = F>>(&self) -> E::G1Affine {
+        E::G1Affine::generator().mul(self.secret).into_affine()
+    }
+}
+
+impl<F: PrimeField> Zeroize for DesignatedVerifierKey<F> {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl<F: PrimeField> Drop for DesignatedVerifierKey<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField> ZeroizeOnDrop for DesignatedVerifierKey<F> {}
+
+impl<F: PrimeField> fmt::Debug for DesignatedVerifierKey<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DesignatedVerifierKey { .. }")
+    }
+}
+
+/// A proof that convinces only the holder of the secret key of a designated verifier.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DesignatedProof<E: Pairing> {
+    /// The `A` element of the rerandomized proof.
+    pub a: E::G1Affine,
+    /// The `B` element of the rerandomized proof.
+    pub b: E::G2Affine,
+    /// The commitment `R_1` of the proof branch.
+    pub r_proof: PairingOutput<E>,
+    /// The response `Z` of the proof branch.
+    pub z_proof: E::G1Affine,
+    /// The challenge `c_1` of the proof branch; the key branch has `c - c_1`.
+    pub c_proof: E::ScalarField,
+    /// The commitment `R_2` of the key branch.
+    pub r_key: E::G1Affine,
+    /// The response `z` of the key branch.
+    pub z_key: E::ScalarField,
+}
+
+/// The Fiat-Shamir challenge `c` of a [`DesignatedProof`].
+fn challenge<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    verifier: &E::G1Affine,
+    public_inputs: &[E::ScalarField],
+    proof: &DesignatedProof<E>,
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    pvk.vk
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    public_inputs
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    for point in [verifier, &proof.a, &proof.r_key] {
+        point
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+    }
+    (proof.b, proof.r_proof)
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    hash_to_field(DESIGNATED_DOMAIN, &bytes)
+}
+
+/// Computes `T = e(A, B) - e(α, β) - e(IC(x), γ)`, the image of `C` in a valid proof.
+fn target<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[E::ScalarField],
+    a: E::G1Affine,
+    b: E::G2Affine,
+) -> R1CSResult<PairingOutput<E>> {
+    let prepared_inputs = Groth16::<E>::prepare_public_inputs(pvk, public_inputs)?;
+    let ml = E::multi_miller_loop(
+        [
+            <E::G1Affine as Into<E::G1Prepared>>::into(a),
+            prepared_inputs.prepared_inputs.into(),
+        ],
+        [b.into(), pvk.gamma_g2_neg_pc.clone()],
+    );
+    let ab_ic = E::final_exponentiation(ml).ok_or(SynthesisError::UnexpectedIdentity)?;
+    Ok(ab_ic - PairingOutput(pvk.alpha_g1_beta_g2))
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Turns `proof` into a proof that only convinces the designated verifier with the
+    /// public key `verifier`. The proof is rerandomized first, so its `A` and `B` do not
+    /// link it to `proof`.
+    ///
+    /// An invalid `proof` yields a [`DesignatedProof`] that does not verify.
+    pub fn create_designated_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+        verifier: &E::G1Affine,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<DesignatedProof<E>> {
+        let proof = Self::rerandomize_proof(&pvk.vk, proof, rng);
+        let generator = E::G1Affine::generator();
+
+        // Simulate the key branch.
+        let c_key = E::ScalarField::rand(rng);
+        let z_key = E::ScalarField::rand(rng);
+        let r_key = (generator.mul(z_key) - verifier.mul(c_key)).into_affine();
+
+        // Commit in the proof branch.
+        let k = Zeroizing::new(E::ScalarField::rand(rng));
+        let k_g1 = generator.mul(*k);
+        let r_proof = E::pairing(k_g1, pvk.vk.delta_g2);
+
+        let mut designated = DesignatedProof {
+            a: proof.a,
+            b: proof.b,
+            r_proof,
+            z_proof: E::G1Affine::zero(),
+            c_proof: E::ScalarField::zero(),
+            r_key,
+            z_key,
+        };
+        let c = challenge(pvk, verifier, public_inputs, &designated);
+        designated.c_proof = c - c_key;
+        designated.z_proof = (k_g1 + proof.c.mul(designated.c_proof)).into_affine();
+        Ok(designated)
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Creates a [`DesignatedProof`] for `public_inputs` from the secret key of the
+    /// designated verifier, without any witness. Such a proof verifies like an honest one,
+    /// which is why designated proofs cannot convince anyone else.
+    pub fn simulate_designated_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        key: &DesignatedVerifierKey<E::ScalarField>,
+        public_inputs: &[E::ScalarField],
+        rng: &mut impl Rng,
+    ) -> R1CSResult<DesignatedProof<E>> {
+        let generator = E::G1Affine::generator();
+        let a = E::G1::rand(rng).into_affine();
+        let b = E::G2::rand(rng).into_affine();
+        let t = target(pvk, public_inputs, a, b)?;
+
+        // Simulate the proof branch.
+        let c_proof = E::ScalarField::rand(rng);
+        let z_proof = E::G1::rand(rng).into_affine();
+        let r_proof = E::pairing(z_proof, pvk.vk.delta_g2) - t * c_proof;
+
+        // Commit in the key branch.
+        let k = Zeroizing::new(E::ScalarField::rand(rng));
+        let r_key = generator.mul(*k).into_affine();
+
+        let mut designated = DesignatedProof {
+            a,
+            b,
+            r_proof,
+            z_proof,
+            c_proof,
+            r_key,
+            z_key: E::ScalarField::zero(),
+        };
+        let c = challenge(pvk, &key.public_key::<E>(), public_inputs, &designated);
+        designated.z_key = *k + (c - c_proof) * key.secret;
+        Ok(designated)
+    }
+
+    /// Verify a designated-verifier proof `proof` for the verifier with the public key
+    /// `verifier` against the prepared verification key `pvk`, with respect to the
+    /// instance `public_inputs`.
+    ///
+    /// The check needs no secret, but only convinces the holder of the secret key of
+    /// `verifier`.
+    pub fn verify_designated_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        verifier: &E::G1Affine,
+        proof: &DesignatedProof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        let c = challenge(pvk, verifier, public_inputs, proof);
+        let c_key = c - proof.c_proof;
+
+        let key_branch = E::G1Affine::generator().mul(proof.z_key)
+            == proof.r_key.into_group() + verifier.mul(c_key);
+        if !key_branch {
+            return Ok(false);
+        }
+
+        let t = target(pvk, public_inputs, proof.a, proof.b)?;
+        Ok(E::pairing(proof.z_proof, pvk.vk.delta_g2) == proof.r_proof + t * proof.c_proof)
+    }
+}
@@ -0,0 +1,314 @@
+//! Proving with the multi-scalar multiplications sharded across worker machines.
+//!
+//! Every worker holds a copy of the proving key and runs [`serve_msm_worker`] on a
+//! connection from the coordinator, which proves with a [`DistributedBackend`]. For each
+//! multi-scalar multiplication over a query of the proving key, the coordinator splits
+//! the bases into one contiguous shard per worker and sends each worker a request
+//!
+//! ```text
+//! query: u8 | start: u64 | scalars: Vec<Fr>
+//! ```
+//!
+//! to which the worker answers with the partial sum over bases `start..start + len` of the
+//! query, as an uncompressed group element. Scalars and results use the uncompressed
+//! `ark-serialize` encoding, and the query is the index of the [`MsmQuery`] variant.
+//! Shards whose worker fails are computed locally, and a worker that failed once is not
+//! used again, since a late answer on its connection could be read as the answer to a
+//! later request.
+//!
+//! **The workers learn the witness.** The scalars of the multi-scalar multiplications
+//! are the full assignment of the circuit and the coefficients of `h`, and every worker
+//! receives its shard of them in the clear. Only run workers on machines that may see
+//! the witness; to prove with an untrusted server, use [`crate::delegated`] instead.
+//!
+//! The coordinator cannot check the partial sums of the workers one by one, so
+//! [`Groth16::create_distributed_proof`] verifies the assembled proof before returning
+//! it.
+use crate::{
+    msm::{MsmBackend, MsmQuery, ScalarBigInt},
+    prepare_verifying_key,
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProverError, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::rand::Rng;
+use std::{
+    io::{self, Read, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    vec::Vec,
+};
+use zeroize::Zeroizing;
+
+fn query_tag(query: MsmQuery) -> u8 {
+    match query {
+        MsmQuery::H => 0,
+        MsmQuery::L => 1,
+        MsmQuery::A => 2,
+        MsmQuery::BG1 => 3,
+        MsmQuery::BG2 => 4,
+    }
+}
+
+fn query_from_tag(tag: u8) -> Option<MsmQuery> {
+    match tag {
+        0 => Some(MsmQuery::H),
+        1 => Some(MsmQuery::L),
+        2 => Some(MsmQuery::A),
+        3 => Some(MsmQuery::BG1),
+        4 => Some(MsmQuery::BG2),
+        _ => None,
+    }
+}
+
+fn g1_query<E: Pairing>(pk: &ProvingKey<E>, query: MsmQuery) -> Option<&[E::G1Affine]> {
+    match query {
+        MsmQuery::H => Some(&pk.h_query),
+        MsmQuery::L => Some(&pk.l_query),
+        MsmQuery::A => pk.a_query.get(1..),
+        MsmQuery::BG1 => pk.b_g1_query.get(1..),
+        MsmQuery::BG2 => None,
+    }
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Answers the shard requests of a [`DistributedBackend`] on `stream` with the queries
+/// of `pk`, until the coordinator closes the connection.
+pub fn serve_msm_worker<E: Pairing, S: Read + Write>(
+    pk: &ProvingKey<E>,
+    mut stream: S,
+) -> io::Result<()> {
+    loop {
+        let mut tag = [0u8];
+        match stream.read_exact(&mut tag) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            result => result?,
+        }
+        let query = query_from_tag(tag[0]).ok_or_else(|| invalid_data("unknown query"))?;
+        let mut start = [0u8; 8];
+        stream.read_exact(&mut start)?;
+        let start = usize::try_from(u64::from_le_bytes(start)).map_err(invalid_data)?;
+        let scalars =
+            Vec::<E::ScalarField>::deserialize_with_mode(&mut stream, Compress::No, Validate::Yes)
+                .map_err(invalid_data)?;
+        let range = start..start.saturating_add(scalars.len());
+
+        let result = match query {
+            MsmQuery::BG2 => {
+                let bases = pk
+                    .b_g2_query
+                    .get(1..)
+                    .and_then(|bases| bases.get(range))
+                    .ok_or_else(|| invalid_data("shard out of range"))?;
+                E::G2::msm_unchecked(bases, &scalars).serialize_uncompressed(&mut stream)
+            },
+            _ => {
+                let bases = g1_query(pk, query)
+                    .and_then(|bases| bases.get(range))
+                    .ok_or_else(|| invalid_data("shard out of range"))?;
+                E::G1::msm_unchecked(bases, &scalars).serialize_uncompressed(&mut stream)
+            },
+        };
+        result.map_err(invalid_data)?;
+        stream.flush()?;
+    }
+}
+
+/// A backend that shards the multi-scalar multiplications over the queries of a proving
+/// key across workers running [`serve_msm_worker`] with the same key.
+///
+/// Shards whose worker fails are computed locally, and a worker that fails once is
+/// dropped.
+///
+/// The workers receive the witness in the clear and their answers are not checked one
+/// by one: prove with [`Groth16::create_distributed_proof`], which verifies the proof,
+/// rather than passing the backend to the generic provers.
+pub struct DistributedBackend<'a, E: Pairing, S> {
+    pk: &'a ProvingKey<E>,
+    /// The connections to the workers, or `None` for a worker that has failed.
+    workers: Vec<Mutex<Option<S>>>,
+    failures: AtomicUsize,
+}
+
+impl<'a, E: Pairing, S: Read + Write + Send> DistributedBackend<'a, E, S> {
+    /// Create a backend for proving with `pk` that dispatches to `workers`, the
+    /// connections to the workers.
+    pub fn new(pk: &'a ProvingKey<E>, workers: Vec<S>) -> Self {
+        Self {
+            pk,
+            workers: workers.into_iter().map(|w| Mutex::new(Some(w))).collect(),
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of shards that were computed locally because their worker failed.
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::SeqCst)
+    }
+
+    /// The number of workers that have not failed yet.
+    pub fn live_workers(&self) -> usize {
+        self.workers
+            .iter()
+            .filter(|worker| worker.lock().is_ok_and(|worker| worker.is_some()))
+            .count()
+    }
+
+    /// Sends the request for a shard of `query` that starts at `start` to `worker`.
+    fn send(
+        worker: &mut S,
+        query: MsmQuery,
+        start: usize,
+        scalars: &[ScalarBigInt<E>],
+    ) -> io::Result<()> {
+        let scalars = scalars
+            .iter()
+            .map(|s| E::ScalarField::from_bigint(*s).ok_or_else(|| invalid_data("scalar")))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut request = vec![query_tag(query)];
+        request.extend((start as u64).to_le_bytes());
+        scalars
+            .serialize_uncompressed(&mut request)
+            .map_err(invalid_data)?;
+
+        worker.write_all(&request)?;
+        worker.flush()
+    }
+
+    /// Computes the MSM of `bases`, the query `query` of the proving key, in one shard
+    /// per worker.
+    ///
+    /// All requests are sent before any response is read, so the workers compute their
+    /// shards concurrently without a thread per worker.
+    fn sharded<G>(&self, query: MsmQuery, bases: &[G], scalars: &[ScalarBigInt<E>]) -> G::Group
+    where
+        G: AffineRepr<ScalarField = E::ScalarField>,
+        G::Group: VariableBaseMSM<MulBase = G>,
+    {
+        let len = bases.len().min(scalars.len());
+        if self.workers.is_empty() || len == 0 {
+            return G::Group::msm_bigint(bases, scalars);
+        }
+        let shard_size = len.div_ceil(self.workers.len());
+
+        // Locking the workers in a fixed order keeps concurrent MSMs from deadlocking. A
+        // worker whose lock is poisoned may be halfway through a request, so it is
+        // dropped.
+        let mut workers = self
+            .workers
+            .iter()
+            .map(|worker| {
+                worker.lock().unwrap_or_else(|poisoned| {
+                    let mut worker = poisoned.into_inner();
+                    *worker = None;
+                    worker
+                })
+            })
+            .collect::<Vec<_>>();
+        let shards = scalars[..len]
+            .chunks(shard_size)
+            .zip(&mut workers)
+            .enumerate()
+            .map(|(i, (scalars, worker))| {
+                let start = i * shard_size;
+                if let Some(stream) = worker.as_mut() {
+                    if Self::send(stream, query, start, scalars).is_err() {
+                        **worker = None;
+                    }
+                }
+                (start, scalars)
+            })
+            .collect::<Vec<_>>();
+
+        let mut acc = G::Group::zero();
+        for ((start, scalars), worker) in shards.into_iter().zip(&mut workers) {
+            let partial = worker.as_mut().and_then(|stream| {
+                G::Group::deserialize_with_mode(stream, Compress::No, Validate::Yes).ok()
+            });
+            if partial.is_none() {
+                **worker = None;
+            }
+            acc += partial.unwrap_or_else(|| {
+                self.failures.fetch_add(1, Ordering::SeqCst);
+                G::Group::msm_bigint(&bases[start..start + scalars.len()], scalars)
+            });
+        }
+        acc
+    }
+}
+
+impl<E: Pairing, S: Read + Write + Send> MsmBackend<E> for DistributedBackend<'_, E, S> {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        self.sharded(query, bases, scalars)
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        self.sharded(query, bases, scalars)
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, with the multi-scalar multiplications sharded by `backend`.
+    /// See [`Groth16::create_distributed_proof`].
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_distributed_proof<C, S>(
+        circuit: C,
+        backend: &DistributedBackend<'_, E, S>,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        S: Read + Write + Send,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_distributed_proof(circuit, backend, *r, *s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided R1CS-to-QAP
+    /// reduction, with the multi-scalar multiplications sharded by `backend` over the
+    /// proving key it was created with.
+    ///
+    /// Returns [`ProverError::InvalidProof`] if the proof does not verify, which happens
+    /// when a worker answers with a wrong partial sum.
+    pub fn create_distributed_proof<C, S>(
+        circuit: C,
+        backend: &DistributedBackend<'_, E, S>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        S: Read + Write + Send,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
+        let prover = cs.borrow().unwrap();
+        let public_inputs = &prover.instance_assignment[1..];
+        let proof = Self::create_proof_with_assignment(
+            backend.pk.into(),
+            &r,
+            &s,
+            &h,
+            public_inputs,
+            &prover.witness_assignment,
+            backend,
+        )?;
+
+        let pvk = prepare_verifying_key(&backend.pk.vk);
+        if !Self::verify_proof(&pvk, &proof, public_inputs)? {
+            return Err(ProverError::InvalidProof);
+        }
+        Ok(proof)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{ceremony::ContributionProof, r1cs_to_qap::R1CSToQAP, Groth16, ProvingKey};
+use ark_ec::pairing::Pairing;
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_std::{
+    fmt,
+    rand::{CryptoRng, RngCore, SeedableRng},
+};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Domain separator for the seed derived from an entropy source.
+const SEED_DOMAIN: &[u8] = b"ark-groth16/entropy/seed/v1";
+
+/// The number of bytes drawn from an entropy source for a seed.
+pub const ENTROPY_LENGTH: usize = 64;
+
+/// A source of entropy for setups and ceremony contributions, such as a hardware random
+/// number generator, a transcript of dice rolls or an HSM.
+///
+/// Every use draws [`ENTROPY_LENGTH`] bytes exactly once, which are hashed into the seed
+/// of an [`EntropyRng`] that samples all secrets.
+pub trait EntropySource {
+    /// Fill `dest` with entropy.
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError>;
+}
+
+/// A failure of an [`EntropySource`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntropyError {
+    /// The source is not available, for example because a device is disconnected.
+    Unavailable,
+    /// The source has less entropy left than requested, for example because a
+    /// transcript is too short.
+    Exhausted,
+}
+
+impl ark_std::error::Error for EntropyError {}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntropyError::Unavailable => write!(f, "the entropy source is unavailable"),
+            EntropyError::Exhausted => write!(f, "the entropy source is exhausted"),
+        }
+    }
+}
+
+/// A failure of [`Groth16::generate_parameters_with_entropy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// Synthesizing the circuit or generating the parameters failed.
+    Synthesis(SynthesisError),
+    /// Drawing entropy failed.
+    Entropy(EntropyError),
+}
+
+impl From<SynthesisError> for SetupError {
+    fn from(e: SynthesisError) -> Self {
+        SetupError::Synthesis(e)
+    }
+}
+
+impl From<EntropyError> for SetupError {
+    fn from(e: EntropyError) -> Self {
+        SetupError::Entropy(e)
+    }
+}
+
+impl ark_std::error::Error for SetupError {}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetupError::Synthesis(e) => write!(f, "{}", e),
+            SetupError::Entropy(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A random number generator seeded from an [`EntropySource`].
+///
+/// The seed of ChaCha20 is `SHA-256(domain || entropy)` for [`ENTROPY_LENGTH`] bytes of
+/// entropy, so that a source with biased bytes, such as dice rolls, is only as weak as
+/// its total entropy.
+pub struct EntropyRng(ChaCha20Rng);
+
+impl EntropyRng {
+    /// Seed a generator with entropy drawn from `source`.
+    pub fn new<S: EntropySource + ?Sized>(source: &mut S) -> Result<Self, EntropyError> {
+        let mut entropy = Zeroizing::new([0u8; ENTROPY_LENGTH]);
+        source.fill_entropy(entropy.as_mut())?;
+        let seed = Zeroizing::new(<[u8; 32]>::from(
+            Sha256::new()
+                .chain_update(SEED_DOMAIN)
+                .chain_update(entropy.as_ref())
+                .finalize(),
+        ));
+        Ok(Self(ChaCha20Rng::from_seed(*seed)))
+    }
+}
+
+impl RngCore for EntropyRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for EntropyRng {}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a common reference string for a circuit with secrets drawn from `source`.
+    pub fn generate_parameters_with_entropy<C, S>(
+        circuit: C,
+        source: &mut S,
+    ) -> Result<ProvingKey<E>, SetupError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        S: EntropySource + ?Sized,
+    {
+        let mut rng = EntropyRng::new(source)?;
+        Ok(Self::generate_random_parameters_with_reduction(
+            circuit, &mut rng,
+        )?)
+    }
+
+    /// Contribute to a phase-2 ceremony like [`Groth16::contribute`], with the secret
+    /// drawn from `source`.
+    pub fn contribute_with_entropy<S: EntropySource + ?Sized>(
+        params: &ProvingKey<E>,
+        source: &mut S,
+    ) -> Result<(ProvingKey<E>, ContributionProof<E>), EntropyError> {
+        let mut rng = EntropyRng::new(source)?;
+        Ok(Self::contribute(params, &mut rng))
+    }
+}
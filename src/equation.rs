@@ -0,0 +1,79 @@
+//! The Groth16 verification equation as pure functions over field and pairing
+//! operations, with no I/O, allocation, or error types.
+//!
+//! # Specification
+//!
+//! Let `e: G1 × G2 → GT` be the pairing of `E`, and let a verifying key consist of
+//! `α ∈ G1`, `β, γ, δ ∈ G2`, and `IC_0, …, IC_ℓ ∈ G1`. For public inputs
+//! `x_1, …, x_ℓ` in the scalar field, the prepared inputs are
+//!
+//! ```text
+//! IC(x) = IC_0 + x_1 · IC_1 + … + x_ℓ · IC_ℓ.
+//! ```
+//!
+//! A proof `(A, B, C) ∈ G1 × G2 × G1` is accepted for `x` if and only if
+//!
+//! ```text
+//! e(A, B) = e(α, β) · e(IC(x), γ) · e(C, δ),
+//! ```
+//!
+//! which [`check`] evaluates as `e(A, B) · e(IC(x), -γ) · e(C, -δ) = e(α, β)` with a single
+//! multi-Miller loop and final exponentiation.
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::PrimeField;
+
+/// Computes the prepared inputs `IC(x)`, or `None` if the number of public inputs `ℓ`
+/// does not match `gamma_abc_g1 = [IC_0, …, IC_ℓ]`.
+pub fn prepared_inputs<E: Pairing>(
+    gamma_abc_g1: &[E::G1Affine],
+    public_inputs: &[E::ScalarField],
+) -> Option<E::G1> {
+    let (ic_0, ic) = gamma_abc_g1.split_first()?;
+    if ic.len() != public_inputs.len() {
+        return None;
+    }
+
+    let mut acc = ic_0.into_group();
+    for (x_i, ic_i) in public_inputs.iter().zip(ic) {
+        acc += ic_i.mul_bigint(x_i.into_bigint());
+    }
+    Some(acc)
+}
+
+/// Evaluates the verification equation for the proof `(a, b, c)` and prepared inputs
+/// `prepared_inputs`, where `alpha_g1_beta_g2 = e(α, β)`, `gamma_g2_neg = -γ`, and
+/// `delta_g2_neg = -δ`. Returns `None` if the final exponentiation is undefined.
+pub fn check<E: Pairing>(
+    alpha_g1_beta_g2: E::TargetField,
+    gamma_g2_neg: E::G2Prepared,
+    delta_g2_neg: E::G2Prepared,
+    prepared_inputs: E::G1Affine,
+    a: E::G1Affine,
+    b: E::G2Affine,
+    c: E::G1Affine,
+) -> Option<bool> {
+    let qap = E::multi_miller_loop(
+        [
+            <E::G1Affine as Into<E::G1Prepared>>::into(a),
+            prepared_inputs.into(),
+            c.into(),
+        ],
+        [b.into(), gamma_g2_neg, delta_g2_neg],
+    );
+    let PairingOutput(test) = E::final_exponentiation(qap)?;
+
+    Some(test == alpha_g1_beta_g2)
+}
+
+/// Computes `e(α, β)`, the right-hand side of [`check`].
+pub fn alpha_g1_beta_g2<E: Pairing>(alpha_g1: E::G1Affine, beta_g2: E::G2Affine) -> E::TargetField {
+    E::pairing(alpha_g1, beta_g2).0
+}
+
+/// Negates a `G2` element of the verifying key and prepares it for [`check`].
+pub fn negate_and_prepare<E: Pairing>(g2: E::G2Affine) -> E::G2Prepared {
+    (-g2.into_group()).into_affine().into()
+}
@@ -0,0 +1,318 @@
+//! A C interface to setup, proving and verification over BLS12-381, for circuits given as
+//! circom `.r1cs` files and witnesses given as circom `.wtns` files.
+//!
+//! Build it as a shared library with
+//! `CARGO_PROFILE_RELEASE_PANIC=unwind cargo rustc --release --features ffi --crate-type cdylib`.
+//! The functions correspond to these C declarations:
+//!
+//! ```c
+//! typedef struct groth16_proving_key groth16_proving_key;
+//! typedef struct { uint8_t *data; size_t len; } groth16_buffer;
+//!
+//! int32_t groth16_setup(const uint8_t *r1cs, size_t r1cs_len, const uint8_t seed[32],
+//!                       groth16_proving_key **pk_out);
+//! int32_t groth16_verifying_key(const groth16_proving_key *pk, groth16_buffer *vk_out);
+//! int32_t groth16_prove(const groth16_proving_key *pk, const uint8_t *wtns, size_t wtns_len,
+//!                       const uint8_t seed[32], groth16_buffer *proof_out);
+//! int32_t groth16_verify(const uint8_t *vk, size_t vk_len, const uint8_t *proof,
+//!                        size_t proof_len, const uint8_t *inputs, size_t inputs_len);
+//! void groth16_proving_key_free(groth16_proving_key *pk);
+//! void groth16_buffer_free(groth16_buffer buffer);
+//! ```
+//!
+//! # Error codes
+//!
+//! Every function that can fail returns [`GROTH16_OK`] on success and a negative error
+//! code otherwise; [`groth16_verify`] returns [`GROTH16_INVALID_PROOF`] for a proof that
+//! does not verify. The codes are stable across versions. A panic inside setup, proving or
+//! verification is reported as [`GROTH16_PANIC`] instead of unwinding into the caller,
+//! which requires the library to be built with `panic = "unwind"`: the profiles of this
+//! crate abort on panic, hence the override in the build command above. Verifying keys and proofs are
+//! compressed, and public inputs are 32-byte little-endian scalars. The library never
+//! samples randomness itself: setup and proving take a 32-byte seed, which must be secret
+//! and uniformly random.
+#![allow(unsafe_code)]
+
+use crate::{
+    circom::{read_r1cs, read_wtns, CircomCircuit, R1cs},
+    Groth16,
+};
+use ark_bls12_381::Bls12_381;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{boxed::Box, ptr, rand::SeedableRng, slice, vec::Vec};
+use rand_chacha::ChaCha20Rng;
+use std::panic::{self, AssertUnwindSafe};
+
+#[allow(dead_code)]
+mod curve {
+    crate::groth16_for_curve!(ark_bls12_381::Bls12_381);
+}
+
+use curve::Fr;
+
+/// The call succeeded, or the proof verified.
+pub const GROTH16_OK: i32 = 0;
+/// The proof does not verify.
+pub const GROTH16_INVALID_PROOF: i32 = 1;
+/// A required pointer argument is null.
+pub const GROTH16_NULL_POINTER: i32 = -1;
+/// A file, key, proof or public input could not be decoded.
+pub const GROTH16_INVALID_ENCODING: i32 = -2;
+/// The circuit could not be synthesized, or the witness does not satisfy it.
+pub const GROTH16_SYNTHESIS_FAILED: i32 = -3;
+/// The number of public inputs does not match the verifying key.
+pub const GROTH16_WRONG_NUMBER_OF_INPUTS: i32 = -4;
+/// The library panicked. This is a bug, which should be reported.
+pub const GROTH16_PANIC: i32 = -5;
+
+/// The size of a seed and of a public input in bytes.
+const SCALAR_SIZE: usize = 32;
+
+/// An opaque handle to a proving key and the constraint system it was generated for.
+pub struct Groth16ProvingKey {
+    pk: curve::ProvingKey,
+    r1cs: R1cs<Fr>,
+}
+
+/// A byte buffer allocated by this library, to be released with [`groth16_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct Groth16Buffer {
+    /// The bytes of the buffer.
+    pub data: *mut u8,
+    /// The number of bytes.
+    pub len: usize,
+}
+
+impl From<Vec<u8>> for Groth16Buffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        Self {
+            data: bytes.cast(),
+            len: bytes.len(),
+        }
+    }
+}
+
+/// Runs `f`, and returns [`GROTH16_PANIC`] if it panics instead of unwinding into the
+/// caller, which is undefined behavior across the C boundary.
+pub(crate) fn catch_panic(f: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(GROTH16_PANIC)
+}
+
+/// Borrows `len` bytes at `data`, which may be null if `len` is zero.
+///
+/// # Safety
+///
+/// Unless null, `data` must point to `len` readable bytes that outlive the borrow.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
+
+/// Borrows a seed of [`SCALAR_SIZE`] bytes at `seed`.
+///
+/// # Safety
+///
+/// Unless null, `seed` must point to [`SCALAR_SIZE`] readable bytes.
+unsafe fn rng(seed: *const u8) -> Option<ChaCha20Rng> {
+    let seed = bytes(seed, SCALAR_SIZE)?;
+    Some(ChaCha20Rng::from_seed(seed.try_into().ok()?))
+}
+
+/// Generate a proving key for the circuit of the circom `.r1cs` file of `r1cs_len` bytes
+/// at `r1cs`, with randomness derived from the 32 bytes at `seed`, and store a handle to
+/// it in `pk_out`.
+///
+/// # Safety
+///
+/// `r1cs` must point to `r1cs_len` readable bytes, `seed` to 32 readable bytes, and
+/// `pk_out` to a writable pointer. The handle must be released with
+/// [`groth16_proving_key_free`].
+#[no_mangle]
+pub unsafe extern "C" fn groth16_setup(
+    r1cs: *const u8,
+    r1cs_len: usize,
+    seed: *const u8,
+    pk_out: *mut *mut Groth16ProvingKey,
+) -> i32 {
+    catch_panic(|| {
+        let (Some(r1cs), Some(mut rng)) = (bytes(r1cs, r1cs_len), rng(seed)) else {
+            return GROTH16_NULL_POINTER;
+        };
+        if pk_out.is_null() {
+            return GROTH16_NULL_POINTER;
+        }
+        let Ok(r1cs) = read_r1cs::<Fr, _>(r1cs) else {
+            return GROTH16_INVALID_ENCODING;
+        };
+        let circuit = CircomCircuit {
+            r1cs: r1cs.clone(),
+            witness: None,
+        };
+        let Ok((pk, _)) = curve::setup(circuit, &mut rng) else {
+            return GROTH16_SYNTHESIS_FAILED;
+        };
+        *pk_out = Box::into_raw(Box::new(Groth16ProvingKey { pk, r1cs }));
+        GROTH16_OK
+    })
+}
+
+/// Store the compressed verifying key of the proving key `pk` in `vk_out`.
+///
+/// # Safety
+///
+/// `pk` must be a handle returned by [`groth16_setup`] and `vk_out` must point to a
+/// writable [`Groth16Buffer`]. The buffer must be released with [`groth16_buffer_free`].
+#[no_mangle]
+pub unsafe extern "C" fn groth16_verifying_key(
+    pk: *const Groth16ProvingKey,
+    vk_out: *mut Groth16Buffer,
+) -> i32 {
+    let (Some(pk), false) = (pk.as_ref(), vk_out.is_null()) else {
+        return GROTH16_NULL_POINTER;
+    };
+    *vk_out = curve::verifying_key_to_bytes(&pk.pk.vk).into();
+    GROTH16_OK
+}
+
+/// Prove the circuit of the proving key `pk` with the witness of the circom `.wtns` file
+/// of `wtns_len` bytes at `wtns`, with randomness derived from the 32 bytes at `seed`,
+/// and store the compressed proof in `proof_out`.
+///
+/// # Safety
+///
+/// `pk` must be a handle returned by [`groth16_setup`], `wtns` must point to `wtns_len`
+/// readable bytes, `seed` to 32 readable bytes, and `proof_out` to a writable
+/// [`Groth16Buffer`]. The buffer must be released with [`groth16_buffer_free`].
+#[no_mangle]
+pub unsafe extern "C" fn groth16_prove(
+    pk: *const Groth16ProvingKey,
+    wtns: *const u8,
+    wtns_len: usize,
+    seed: *const u8,
+    proof_out: *mut Groth16Buffer,
+) -> i32 {
+    catch_panic(|| {
+        let (Some(pk), Some(wtns), Some(mut rng)) = (pk.as_ref(), bytes(wtns, wtns_len), rng(seed))
+        else {
+            return GROTH16_NULL_POINTER;
+        };
+        if proof_out.is_null() {
+            return GROTH16_NULL_POINTER;
+        }
+        let Ok(witness) = read_wtns::<Fr, _>(wtns) else {
+            return GROTH16_INVALID_ENCODING;
+        };
+        let circuit = CircomCircuit {
+            r1cs: pk.r1cs.clone(),
+            witness: Some(witness),
+        };
+        // An unsatisfying witness would yield a proof that does not verify.
+        let Ok(proof) =
+            Groth16::<Bls12_381>::create_random_proof_checked(circuit, &pk.pk, &mut rng)
+        else {
+            return GROTH16_SYNTHESIS_FAILED;
+        };
+        *proof_out = curve::proof_to_bytes(&proof).into();
+        GROTH16_OK
+    })
+}
+
+/// Verify the compressed proof of `proof_len` bytes at `proof` against the compressed
+/// verifying key of `vk_len` bytes at `vk` and the public inputs of `inputs_len` bytes at
+/// `inputs`, a multiple of 32.
+///
+/// Returns [`GROTH16_OK`] if the proof verifies and [`GROTH16_INVALID_PROOF`] if it does
+/// not.
+///
+/// # Safety
+///
+/// Each pointer must point to as many readable bytes as its length.
+#[no_mangle]
+pub unsafe extern "C" fn groth16_verify(
+    vk: *const u8,
+    vk_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    inputs: *const u8,
+    inputs_len: usize,
+) -> i32 {
+    catch_panic(|| verify(vk, vk_len, proof, proof_len, inputs, inputs_len))
+}
+
+/// The body of [`groth16_verify`].
+///
+/// # Safety
+///
+/// See [`groth16_verify`].
+unsafe fn verify(
+    vk: *const u8,
+    vk_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    inputs: *const u8,
+    inputs_len: usize,
+) -> i32 {
+    let (Some(vk), Some(proof), Some(inputs)) = (
+        bytes(vk, vk_len),
+        bytes(proof, proof_len),
+        bytes(inputs, inputs_len),
+    ) else {
+        return GROTH16_NULL_POINTER;
+    };
+    let (Ok(vk), Ok(proof)) = (
+        curve::verifying_key_from_bytes(vk),
+        curve::proof_from_bytes(proof),
+    ) else {
+        return GROTH16_INVALID_ENCODING;
+    };
+    if inputs.len() % SCALAR_SIZE != 0 {
+        return GROTH16_INVALID_ENCODING;
+    }
+    let Ok(inputs) = inputs
+        .chunks(SCALAR_SIZE)
+        .map(Fr::deserialize_compressed)
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return GROTH16_INVALID_ENCODING;
+    };
+    if inputs.len() != vk.num_public_inputs() {
+        return GROTH16_WRONG_NUMBER_OF_INPUTS;
+    }
+    match curve::verify(&curve::prepare_verifying_key(&vk), &proof, &inputs) {
+        Ok(true) => GROTH16_OK,
+        Ok(false) => GROTH16_INVALID_PROOF,
+        Err(_) => GROTH16_INVALID_ENCODING,
+    }
+}
+
+/// Release a proving key handle returned by [`groth16_setup`]. Null is ignored.
+///
+/// # Safety
+///
+/// `pk` must be null or a handle returned by [`groth16_setup`] that has not been released.
+#[no_mangle]
+pub unsafe extern "C" fn groth16_proving_key_free(pk: *mut Groth16ProvingKey) {
+    if !pk.is_null() {
+        drop(Box::from_raw(pk));
+    }
+}
+
+/// Release a buffer returned by this library. A buffer with null `data` is ignored.
+///
+/// # Safety
+///
+/// `buffer` must have been returned by this library and not been released.
+#[no_mangle]
+pub unsafe extern "C" fn groth16_buffer_free(buffer: Groth16Buffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
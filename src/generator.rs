@@ -1,17 +1,112 @@
-use crate::{r1cs_to_qap::R1CSToQAP, Groth16, ProvingKey, Vec, VerifyingKey};
-use ark_ec::{pairing::Pairing, scalar_mul::fixed_base::FixedBase, CurveGroup, Group};
+use crate::{
+    progress::{SetupObserver, SetupStage},
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, ProvingKey, Vec, VerifyingKey,
+};
+use ark_ec::{
+    pairing::Pairing,
+    scalar_mul::{fixed_base::FixedBase, ScalarMul},
+    CurveGroup, Group,
+};
 use ark_ff::{Field, PrimeField, UniformRand, Zero};
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_relations::r1cs::{
     ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, Result as R1CSResult,
     SynthesisError, SynthesisMode,
 };
-use ark_std::rand::Rng;
+use ark_std::fmt;
+use ark_std::rand::{Rng, SeedableRng};
 use ark_std::{cfg_into_iter, cfg_iter};
+use rand_chacha::ChaCha20Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+/// The number of chunks a query vector is computed in, for progress reports.
+const PROGRESS_CHUNKS: usize = 100;
+
+/// Computes `FixedBase::msm` of `scalars` in chunks, and reports the progress of `stage` to
+/// `observer` before the first chunk and after each one.
+fn chunked_msm<T: ScalarMul, O: SetupObserver>(
+    scalar_bits: usize,
+    window: usize,
+    table: &[Vec<T::MulBase>],
+    scalars: &[T::ScalarField],
+    stage: SetupStage,
+    observer: &O,
+) -> Vec<T> {
+    observer.on_progress(stage, 0);
+    let chunk_size = scalars.len().div_ceil(PROGRESS_CHUNKS).max(1);
+    let mut result = Vec::with_capacity(scalars.len());
+    for chunk in scalars.chunks(chunk_size) {
+        result.extend(FixedBase::msm::<T>(scalar_bits, window, table, chunk));
+        observer.on_progress(stage, (result.len() * 100 / scalars.len()) as u8);
+    }
+    if scalars.is_empty() {
+        observer.on_progress(stage, 100);
+    }
+    result
+}
+
+/// The toxic waste of a setup: the secrets `alpha`, `beta`, `gamma` and `delta`.
+///
+/// Anyone who knows these values can forge proofs for the parameters generated from them.
+/// The values are zeroized when the wrapper is dropped, and
+/// [`Groth16::generate_parameters_with_secret`] takes ownership of the wrapper so that
+/// it is dropped as soon as the parameters are generated, together with the evaluation
+/// point `tau` and the scalars derived from the secrets.
+pub struct SecretSetupRandomness<F: PrimeField> {
+    pub(crate) alpha: F,
+    pub(crate) beta: F,
+    pub(crate) gamma: F,
+    pub(crate) delta: F,
+}
+
+impl<F: PrimeField> SecretSetupRandomness<F> {
+    /// Wrap the given secrets.
+    pub fn new(alpha: F, beta: F, gamma: F, delta: F) -> Self {
+        Self {
+            alpha,
+            beta,
+            gamma,
+            delta,
+        }
+    }
+
+    /// Sample the secrets from `rng`.
+    pub fn sample<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let alpha = F::rand(rng);
+        let beta = F::rand(rng);
+        let gamma = F::rand(rng);
+        let delta = F::rand(rng);
+        Self::new(alpha, beta, gamma, delta)
+    }
+}
+
+impl<F: PrimeField> Zeroize for SecretSetupRandomness<F> {
+    fn zeroize(&mut self) {
+        self.alpha.zeroize();
+        self.beta.zeroize();
+        self.gamma.zeroize();
+        self.delta.zeroize();
+    }
+}
+
+impl<F: PrimeField> Drop for SecretSetupRandomness<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField> ZeroizeOnDrop for SecretSetupRandomness<F> {}
+
+impl<F: PrimeField> fmt::Debug for SecretSetupRandomness<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretSetupRandomness { .. }")
+    }
+}
+
 impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     /// Generates a random common reference string for
     /// a circuit using the provided R1CS-to-QAP reduction.
@@ -23,27 +118,32 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     where
         C: ConstraintSynthesizer<E::ScalarField>,
     {
-        let alpha = E::ScalarField::rand(rng);
-        let beta = E::ScalarField::rand(rng);
-        let gamma = E::ScalarField::rand(rng);
-        let delta = E::ScalarField::rand(rng);
+        let secret = SecretSetupRandomness::sample(rng);
 
         let g1_generator = E::G1::rand(rng);
         let g2_generator = E::G2::rand(rng);
 
-        Self::generate_parameters_with_qap(
-            circuit,
-            alpha,
-            beta,
-            gamma,
-            delta,
-            g1_generator,
-            g2_generator,
-            rng,
-        )
+        Self::generate_parameters_with_secret(circuit, secret, g1_generator, g2_generator, rng)
+    }
+
+    /// Generates parameters for a circuit deterministically from `seed`, for test
+    /// fixtures and golden files that are cheaper to regenerate than to store.
+    ///
+    /// Anyone who knows the seed knows the toxic waste and can forge proofs, so these
+    /// parameters must never be used outside of tests. The randomness is drawn from
+    /// ChaCha20 seeded with `seed`, so the parameters only change if the generator does.
+    pub fn generate_parameters_deterministic<C>(
+        circuit: C,
+        seed: [u8; 32],
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        Self::generate_random_parameters_with_reduction(circuit, &mut ChaCha20Rng::from_seed(seed))
     }
 
     /// Create parameters for a circuit, given some toxic waste, R1CS to QAP calculator and group generators
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_parameters_with_qap<C>(
         circuit: C,
         alpha: E::ScalarField,
@@ -56,6 +156,47 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     ) -> R1CSResult<ProvingKey<E>>
     where
         C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let secret = SecretSetupRandomness::new(alpha, beta, gamma, delta);
+        Self::generate_parameters_with_secret(circuit, secret, g1_generator, g2_generator, rng)
+    }
+
+    /// Create parameters for a circuit from the toxic waste `secret` and the group
+    /// generators. The secret and the values derived from it are zeroized before
+    /// returning.
+    pub fn generate_parameters_with_secret<C>(
+        circuit: C,
+        secret: SecretSetupRandomness<E::ScalarField>,
+        g1_generator: E::G1,
+        g2_generator: E::G2,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        Self::generate_parameters_with_observer(
+            circuit,
+            &secret,
+            g1_generator,
+            g2_generator,
+            &(),
+            rng,
+        )
+    }
+
+    /// Create parameters like [`Groth16::generate_parameters_with_secret`], reporting
+    /// progress to `observer`.
+    pub(crate) fn generate_parameters_with_observer<C, O>(
+        circuit: C,
+        secret: &SecretSetupRandomness<E::ScalarField>,
+        g1_generator: E::G1,
+        g2_generator: E::G2,
+        observer: &O,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: SetupObserver,
     {
         type D<F> = GeneralEvaluationDomain<F>;
 
@@ -65,6 +206,7 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         cs.set_mode(SynthesisMode::Setup);
 
         // Synthesize the circuit.
+        observer.on_progress(SetupStage::Synthesis, 0);
         let synthesis_time = start_timer!(|| "Constraint synthesis");
         circuit.generate_constraints(cs.clone())?;
         end_timer!(synthesis_time);
@@ -72,6 +214,7 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         let lc_time = start_timer!(|| "Inlining LCs");
         cs.finalize();
         end_timer!(lc_time);
+        observer.on_progress(SetupStage::Synthesis, 100);
 
         // Following is the mapping of symbols from the Groth16 paper to this implementation
         // l -> num_instance_variables
@@ -87,16 +230,24 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
 
         let domain_size = cs.num_constraints() + cs.num_instance_variables();
         let domain = D::new(domain_size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
-        let t = domain.sample_element_outside_domain(rng);
+        let t = Zeroizing::new(domain.sample_element_outside_domain(rng));
 
         end_timer!(domain_time);
         ///////////////////////////////////////////////////////////////////////////
 
+        observer.on_progress(SetupStage::InstanceMap, 0);
         let reduction_time = start_timer!(|| "R1CS to QAP Instance Map with Evaluation");
         let num_instance_variables = cs.num_instance_variables();
         let (a, b, c, zt, qap_num_variables, m_raw) =
             QAP::instance_map_with_evaluation::<E::ScalarField, D<E::ScalarField>>(cs, &t)?;
+        let (a, b, c, zt) = (
+            Zeroizing::new(a),
+            Zeroizing::new(b),
+            Zeroizing::new(c),
+            Zeroizing::new(zt),
+        );
         end_timer!(reduction_time);
+        observer.on_progress(SetupStage::InstanceMap, 100);
 
         // Compute query densities
         let non_zero_a: usize = cfg_into_iter!(0..qap_num_variables)
@@ -109,77 +260,136 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
 
         let scalar_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
 
-        let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
-        let delta_inverse = delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+        let gamma_inverse = Zeroizing::new(
+            secret
+                .gamma
+                .inverse()
+                .ok_or(SynthesisError::UnexpectedIdentity)?,
+        );
+        let delta_inverse = Zeroizing::new(
+            secret
+                .delta
+                .inverse()
+                .ok_or(SynthesisError::UnexpectedIdentity)?,
+        );
 
-        let gamma_abc = cfg_iter!(a[..num_instance_variables])
-            .zip(&b[..num_instance_variables])
-            .zip(&c[..num_instance_variables])
-            .map(|((a, b), c)| (beta * a + &(alpha * b) + c) * &gamma_inverse)
-            .collect::<Vec<_>>();
+        let gamma_abc = Zeroizing::new(
+            cfg_iter!(a[..num_instance_variables])
+                .zip(&b[..num_instance_variables])
+                .zip(&c[..num_instance_variables])
+                .map(|((a, b), c)| (secret.beta * a + &(secret.alpha * b) + c) * &*gamma_inverse)
+                .collect::<Vec<_>>(),
+        );
 
-        let l = cfg_iter!(a[num_instance_variables..])
-            .zip(&b[num_instance_variables..])
-            .zip(&c[num_instance_variables..])
-            .map(|((a, b), c)| (beta * a + &(alpha * b) + c) * &delta_inverse)
-            .collect::<Vec<_>>();
+        let l = Zeroizing::new(
+            cfg_iter!(a[num_instance_variables..])
+                .zip(&b[num_instance_variables..])
+                .zip(&c[num_instance_variables..])
+                .map(|((a, b), c)| (secret.beta * a + &(secret.alpha * b) + c) * &*delta_inverse)
+                .collect::<Vec<_>>(),
+        );
 
         drop(c);
 
-        // Compute B window table
-        let g2_time = start_timer!(|| "Compute G2 table");
-        let g2_window = FixedBase::get_mul_window_size(non_zero_b);
-        let g2_table = FixedBase::get_window_table::<E::G2>(scalar_bits, g2_window, g2_generator);
-        end_timer!(g2_time);
-
-        // Compute the B-query in G2
-        let b_g2_time = start_timer!(|| "Calculate B G2");
-        let b_g2_query = FixedBase::msm::<E::G2>(scalar_bits, g2_window, &g2_table, &b);
-        drop(g2_table);
-        end_timer!(b_g2_time);
-
-        // Compute G window table
-        let g1_window_time = start_timer!(|| "Compute G1 window table");
+        // The G2 queries and the G1 window table do not depend on each other, so they are
+        // computed concurrently.
         let g1_window =
             FixedBase::get_mul_window_size(non_zero_a + non_zero_b + qap_num_variables + m_raw + 1);
-        let g1_table = FixedBase::get_window_table::<E::G1>(scalar_bits, g1_window, g1_generator);
-        end_timer!(g1_window_time);
+        let (b_g2_query, g1_table) = crate::join(
+            || {
+                // Compute B window table
+                let g2_time = start_timer!(|| "Compute G2 table");
+                let g2_window = FixedBase::get_mul_window_size(non_zero_b);
+                let g2_table =
+                    FixedBase::get_window_table::<E::G2>(scalar_bits, g2_window, g2_generator);
+                end_timer!(g2_time);
+
+                // Compute the B-query in G2
+                let b_g2_time = start_timer!(|| "Calculate B G2");
+                let b_g2_query = chunked_msm::<E::G2, _>(
+                    scalar_bits,
+                    g2_window,
+                    &g2_table,
+                    &b,
+                    SetupStage::BG2Query,
+                    observer,
+                );
+                end_timer!(b_g2_time);
+                b_g2_query
+            },
+            || {
+                // Compute G window table
+                let g1_window_time = start_timer!(|| "Compute G1 window table");
+                let g1_table =
+                    FixedBase::get_window_table::<E::G1>(scalar_bits, g1_window, g1_generator);
+                end_timer!(g1_window_time);
+                g1_table
+            },
+        );
 
         // Generate the R1CS proving key
         let proving_key_time = start_timer!(|| "Generate the R1CS proving key");
 
-        let alpha_g1 = g1_generator.mul_bigint(&alpha.into_bigint());
-        let beta_g1 = g1_generator.mul_bigint(&beta.into_bigint());
-        let beta_g2 = g2_generator.mul_bigint(&beta.into_bigint());
-        let delta_g1 = g1_generator.mul_bigint(&delta.into_bigint());
-        let delta_g2 = g2_generator.mul_bigint(&delta.into_bigint());
+        let alpha_g1 = g1_generator.mul_bigint(&secret.alpha.into_bigint());
+        let beta_g1 = g1_generator.mul_bigint(&secret.beta.into_bigint());
+        let beta_g2 = g2_generator.mul_bigint(&secret.beta.into_bigint());
+        let delta_g1 = g1_generator.mul_bigint(&secret.delta.into_bigint());
+        let delta_g2 = g2_generator.mul_bigint(&secret.delta.into_bigint());
 
         // Compute the A-query
         let a_time = start_timer!(|| "Calculate A");
-        let a_query = FixedBase::msm::<E::G1>(scalar_bits, g1_window, &g1_table, &a);
+        let a_query = chunked_msm::<E::G1, _>(
+            scalar_bits,
+            g1_window,
+            &g1_table,
+            &a,
+            SetupStage::AQuery,
+            observer,
+        );
         drop(a);
         end_timer!(a_time);
 
         // Compute the B-query in G1
         let b_g1_time = start_timer!(|| "Calculate B G1");
-        let b_g1_query = FixedBase::msm::<E::G1>(scalar_bits, g1_window, &g1_table, &b);
+        let b_g1_query = chunked_msm::<E::G1, _>(
+            scalar_bits,
+            g1_window,
+            &g1_table,
+            &b,
+            SetupStage::BG1Query,
+            observer,
+        );
         drop(b);
         end_timer!(b_g1_time);
 
         // Compute the H-query
         let h_time = start_timer!(|| "Calculate H");
-        let h_query = FixedBase::msm::<E::G1>(
+        let h_query = chunked_msm::<E::G1, _>(
             scalar_bits,
             g1_window,
             &g1_table,
-            &QAP::h_query_scalars::<_, D<E::ScalarField>>(m_raw - 1, t, zt, delta_inverse)?,
+            &Zeroizing::new(QAP::h_query_scalars::<_, D<E::ScalarField>>(
+                m_raw - 1,
+                *t,
+                *zt,
+                *delta_inverse,
+            )?),
+            SetupStage::HQuery,
+            observer,
         );
 
         end_timer!(h_time);
 
         // Compute the L-query
         let l_time = start_timer!(|| "Calculate L");
-        let l_query = FixedBase::msm::<E::G1>(scalar_bits, g1_window, &g1_table, &l);
+        let l_query = chunked_msm::<E::G1, _>(
+            scalar_bits,
+            g1_window,
+            &g1_table,
+            &l,
+            SetupStage::LQuery,
+            observer,
+        );
         drop(l);
         end_timer!(l_time);
 
@@ -187,8 +397,15 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
 
         // Generate R1CS verification key
         let verifying_key_time = start_timer!(|| "Generate the R1CS verification key");
-        let gamma_g2 = g2_generator.mul_bigint(&gamma.into_bigint());
-        let gamma_abc_g1 = FixedBase::msm::<E::G1>(scalar_bits, g1_window, &g1_table, &gamma_abc);
+        let gamma_g2 = g2_generator.mul_bigint(&secret.gamma.into_bigint());
+        let gamma_abc_g1 = chunked_msm::<E::G1, _>(
+            scalar_bits,
+            g1_window,
+            &g1_table,
+            &gamma_abc,
+            SetupStage::GammaAbcQuery,
+            observer,
+        );
 
         drop(g1_table);
 
@@ -0,0 +1,601 @@
+//! The SNARK of [[GM17]](https://eprint.iacr.org/2017/540.pdf), as a sibling of
+//! [`Groth16`] with the same data-structure and serialization conventions.
+//!
+//! GM17 proofs are simulation-extractable: unlike Groth16 proofs, they cannot be
+//! rerandomized or otherwise mauled into new proofs of the same statement. [`Gm17`]
+//! implements the `SNARK` and `CircuitSpecificSetupSNARK` traits of `ark-snark`, so generic
+//! code can select either proof system through the trait.
+//!
+//! # Specification
+//!
+//! The circuit is reduced to a *square arithmetic program* (SAP) with polynomials
+//! `u_i, w_i` over a domain with vanishing polynomial `t`, such that an assignment `a`
+//! with `a_0 = 1` satisfies the circuit iff `(Σ a_i u_i)^2 - Σ a_i w_i` is divisible by
+//! `t`. Every R1CS constraint `⟨a, z⟩ · ⟨b, z⟩ = ⟨c, z⟩` becomes the two squares
+//!
+//! ```text
+//! (⟨a, z⟩ + ⟨b, z⟩)^2 = 4⟨c, z⟩ + e,   (⟨a, z⟩ - ⟨b, z⟩)^2 = e,
+//! ```
+//!
+//! with a new witness variable `e`, and every public input `x_i` the two squares
+//! `(1 ± x_i)^2 = 1 ± 2x_i + f_i` with a new witness variable `f_i`, which make the
+//! polynomials of the public inputs linearly independent. A last constraint `1^2 = 1`
+//! covers the constant.
+//!
+//! For secrets `α, β, γ` and the evaluation point `x`, a proof is
+//!
+//! ```text
+//! A = G^{γ(r t(x) + Σ a_i u_i(x))},  B = H^{γ(r t(x) + Σ a_i u_i(x))},
+//! C = G^{Σ_{i>ℓ} a_i ((α+β)γ u_i(x) + γ² w_i(x)) + (α+β)γ r t(x) + γ² (r t(x) + Σ a_i u_i(x))^2 - γ² Σ a_i w_i(x)},
+//! ```
+//!
+//! where the last terms are computed from `h = ((Σ a_i u_i)^2 - Σ a_i w_i) / t`, and the
+//! verifier checks
+//!
+//! ```text
+//! e(A + G^α, B + H^β) = e(G^α, H^β) · e(Ψ(x), H^γ) · e(C, H),   e(A, H^γ) = e(G^γ, B),
+//! ```
+//!
+//! with `Ψ(x) = Σ_{i≤ℓ} x_i G^{(α+β) u_i(x) + γ w_i(x)}` and `x_0 = 1`.
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::One;
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::*;
+use ark_std::{marker::PhantomData, ops::Neg, vec::Vec};
+#[cfg(feature = "prover")]
+use {
+    crate::{r1cs_to_qap::evaluate_constraint, Groth16},
+    ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK},
+    ark_ec::scalar_mul::fixed_base::FixedBase,
+    ark_ff::{Field, PrimeField, UniformRand, Zero},
+    ark_poly::{EvaluationDomain, GeneralEvaluationDomain},
+    ark_relations::r1cs::{
+        ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal,
+        SynthesisMode,
+    },
+    ark_std::{
+        cfg_iter, cfg_iter_mut,
+        ops::Mul,
+        rand::{Rng, RngCore},
+        vec,
+    },
+    zeroize::Zeroizing,
+};
+
+#[cfg(all(feature = "parallel", feature = "prover"))]
+use rayon::prelude::*;
+
+#[cfg(feature = "prover")]
+type D<F> = GeneralEvaluationDomain<F>;
+
+/// The SNARK of [[GM17]](https://eprint.iacr.org/2017/540.pdf).
+pub struct Gm17<E: Pairing> {
+    _p: PhantomData<E>,
+}
+
+/// A proof in the GM17 SNARK.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Gm17Proof<E: Pairing> {
+    /// The `A` element in `G1`.
+    pub a: E::G1Affine,
+    /// The `B` element in `G2`.
+    pub b: E::G2Affine,
+    /// The `C` element in `G1`.
+    pub c: E::G1Affine,
+}
+
+/// A verification key in the GM17 SNARK.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Gm17VerifyingKey<E: Pairing> {
+    /// The generator `H` of `E::G2`.
+    pub h_g2: E::G2Affine,
+    /// The element `alpha * G` in `E::G1`.
+    pub g_alpha_g1: E::G1Affine,
+    /// The element `beta * H` in `E::G2`.
+    pub h_beta_g2: E::G2Affine,
+    /// The element `gamma * G` in `E::G1`.
+    pub g_gamma_g1: E::G1Affine,
+    /// The element `gamma * H` in `E::G2`.
+    pub h_gamma_g2: E::G2Affine,
+    /// The elements `((alpha + beta) * u_i(x) + gamma * w_i(x)) * G` of the constant
+    /// and the public inputs.
+    pub query: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> Gm17VerifyingKey<E> {
+    /// The number of public inputs, excluding the constant `1`.
+    pub fn num_public_inputs(&self) -> usize {
+        self.query.len().saturating_sub(1)
+    }
+}
+
+/// Preprocessed GM17 verification key parameters that enable faster verification.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Gm17PreparedVerifyingKey<E: Pairing> {
+    /// The unprepared verification key.
+    pub vk: Gm17VerifyingKey<E>,
+    /// The element `e(alpha * G, beta * H)` in `E::GT`.
+    pub g_alpha_h_beta: E::TargetField,
+    /// The element `- H` in `E::G2`, prepared for use in pairings.
+    pub h_g2_neg_pc: E::G2Prepared,
+    /// The element `- gamma * H` in `E::G2`, prepared for use in pairings.
+    pub h_gamma_g2_neg_pc: E::G2Prepared,
+    /// The element `gamma * H` in `E::G2`, prepared for use in pairings.
+    pub h_gamma_g2_pc: E::G2Prepared,
+}
+
+impl<E: Pairing> From<Gm17VerifyingKey<E>> for Gm17PreparedVerifyingKey<E> {
+    fn from(other: Gm17VerifyingKey<E>) -> Self {
+        Gm17::prepare_verifying_key(&other)
+    }
+}
+
+/// The proving key for the GM17 SNARK.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Gm17ProvingKey<E: Pairing> {
+    /// The underlying verification key.
+    pub vk: Gm17VerifyingKey<E>,
+    /// The elements `gamma * u_i(x) * G` of all SAP variables.
+    pub a_query: Vec<E::G1Affine>,
+    /// The elements `gamma * u_i(x) * H` of all SAP variables.
+    pub b_query: Vec<E::G2Affine>,
+    /// The elements `((alpha + beta) * gamma * u_i(x) + gamma^2 * w_i(x)) * G` of the
+    /// witness variables.
+    pub c_query_1: Vec<E::G1Affine>,
+    /// The elements `2 * gamma^2 * t(x) * u_i(x) * G` of all SAP variables.
+    pub c_query_2: Vec<E::G1Affine>,
+    /// The element `gamma * t(x) * G` in `E::G1`.
+    pub g_gamma_z: E::G1Affine,
+    /// The element `gamma * t(x) * H` in `E::G2`.
+    pub h_gamma_z: E::G2Affine,
+    /// The element `(alpha + beta) * gamma * t(x) * G` in `E::G1`.
+    pub g_ab_gamma_z: E::G1Affine,
+    /// The element `gamma^2 * t(x)^2 * G` in `E::G1`.
+    pub g_gamma2_z2: E::G1Affine,
+    /// The elements `gamma^2 * t(x) * x^i * G` for the coefficients of `h`.
+    pub g_gamma2_z_t: Vec<E::G1Affine>,
+}
+
+impl<E: Pairing> Gm17<E> {
+    /// Prepare the verifying key `vk` for use in proof verification.
+    pub fn prepare_verifying_key(vk: &Gm17VerifyingKey<E>) -> Gm17PreparedVerifyingKey<E> {
+        Gm17PreparedVerifyingKey {
+            vk: vk.clone(),
+            g_alpha_h_beta: E::pairing(vk.g_alpha_g1, vk.h_beta_g2).0,
+            h_g2_neg_pc: vk.h_g2.into_group().neg().into_affine().into(),
+            h_gamma_g2_neg_pc: vk.h_gamma_g2.into_group().neg().into_affine().into(),
+            h_gamma_g2_pc: vk.h_gamma_g2.into(),
+        }
+    }
+
+    /// Verify a GM17 proof `proof` against the prepared verification key `pvk`,
+    /// with respect to the instance `public_inputs`.
+    pub fn verify_proof(
+        pvk: &Gm17PreparedVerifyingKey<E>,
+        proof: &Gm17Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        let (query_0, query) = pvk
+            .vk
+            .query
+            .split_first()
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        if query.len() != public_inputs.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        let psi = E::G1::msm_unchecked(query, public_inputs) + query_0;
+
+        // e(A + G^α, B + H^β) · e(Ψ(x), -H^γ) · e(C, -H) = e(G^α, H^β)
+        let a_alpha = (proof.a.into_group() + pvk.vk.g_alpha_g1).into_affine();
+        let b_beta = (proof.b.into_group() + pvk.vk.h_beta_g2).into_affine();
+        let test1 = E::multi_miller_loop(
+            [
+                <E::G1Affine as Into<E::G1Prepared>>::into(a_alpha),
+                psi.into_affine().into(),
+                proof.c.into(),
+            ],
+            [
+                b_beta.into(),
+                pvk.h_gamma_g2_neg_pc.clone(),
+                pvk.h_g2_neg_pc.clone(),
+            ],
+        );
+        let PairingOutput(test1) =
+            E::final_exponentiation(test1).ok_or(SynthesisError::UnexpectedIdentity)?;
+        if test1 != pvk.g_alpha_h_beta {
+            return Ok(false);
+        }
+
+        // e(A, H^γ) · e(-G^γ, B) = 1
+        let test2 = E::multi_miller_loop(
+            [
+                <E::G1Affine as Into<E::G1Prepared>>::into(proof.a),
+                pvk.vk.g_gamma_g1.into_group().neg().into_affine().into(),
+            ],
+            [pvk.h_gamma_g2_pc.clone(), proof.b.into()],
+        );
+        let PairingOutput(test2) =
+            E::final_exponentiation(test2).ok_or(SynthesisError::UnexpectedIdentity)?;
+        Ok(test2.is_one())
+    }
+}
+
+/// The shape of the SAP of an R1CS instance with `num_constraints` constraints,
+/// `num_inputs` instance variables (including the constant) and `num_witness` witness
+/// variables.
+#[cfg(feature = "prover")]
+struct SapShape {
+    num_constraints: usize,
+    num_inputs: usize,
+    num_witness: usize,
+}
+
+#[cfg(feature = "prover")]
+impl SapShape {
+    /// The number of SAP constraints before padding to the domain.
+    fn num_sap_constraints(&self) -> usize {
+        2 * self.num_constraints + 2 * (self.num_inputs - 1) + 1
+    }
+
+    /// The number of SAP variables, including the constant.
+    fn num_variables(&self) -> usize {
+        self.num_inputs + self.num_witness + self.num_constraints + self.num_inputs - 1
+    }
+
+    /// The index of the variable `e_j` of the `j`-th R1CS constraint.
+    fn e(&self, j: usize) -> usize {
+        self.num_inputs + self.num_witness + j
+    }
+
+    /// The index of the variable `f_i` of the `i`-th instance variable, for `i ≥ 1`.
+    fn f(&self, i: usize) -> usize {
+        self.num_inputs + self.num_witness + self.num_constraints + i - 1
+    }
+}
+
+/// Evaluates the SAP polynomials `u_i` and `w_i` at `x` from their Lagrange
+/// coefficients `lagrange`.
+#[cfg(feature = "prover")]
+fn sap_instance_map<F: PrimeField>(
+    matrices: &ConstraintMatrices<F>,
+    shape: &SapShape,
+    lagrange: &[F],
+) -> (Vec<F>, Vec<F>) {
+    let mut u = vec![F::zero(); shape.num_variables()];
+    let mut w = vec![F::zero(); shape.num_variables()];
+    let four = F::from(4u64);
+    let two = F::from(2u64);
+
+    for j in 0..shape.num_constraints {
+        let (l0, l1) = (lagrange[2 * j], lagrange[2 * j + 1]);
+        for (coeff, index) in &matrices.a[j] {
+            u[*index] += *coeff * (l0 + l1);
+        }
+        for (coeff, index) in &matrices.b[j] {
+            u[*index] += *coeff * (l0 - l1);
+        }
+        for (coeff, index) in &matrices.c[j] {
+            w[*index] += four * coeff * l0;
+        }
+        w[shape.e(j)] += l0 + l1;
+    }
+
+    let offset = 2 * shape.num_constraints;
+    for i in 1..shape.num_inputs {
+        let (l0, l1) = (lagrange[offset + 2 * i - 2], lagrange[offset + 2 * i - 1]);
+        u[0] += l0 + l1;
+        u[i] += l0 - l1;
+        w[0] += l0 + l1;
+        w[i] += two * (l0 - l1);
+        w[shape.f(i)] += l0 + l1;
+    }
+
+    let last = lagrange[shape.num_sap_constraints() - 1];
+    u[0] += last;
+    w[0] += last;
+    (u, w)
+}
+
+/// Computes the SAP assignment, which extends `full_assignment` by the variables `e_j`
+/// and `f_i`, and the coefficients of `h = ((Σ a_i u_i)^2 - Σ a_i w_i) / t`.
+#[cfg(feature = "prover")]
+#[allow(clippy::type_complexity)]
+fn sap_witness_map<F: PrimeField>(
+    matrices: &ConstraintMatrices<F>,
+    shape: &SapShape,
+    full_assignment: &[F],
+) -> R1CSResult<(Zeroizing<Vec<F>>, Zeroizing<Vec<F>>)> {
+    let domain =
+        D::<F>::new(shape.num_sap_constraints()).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let domain_size = domain.size();
+    let zero = F::zero();
+    let four = F::from(4u64);
+    let two = F::from(2u64);
+
+    let mut u = Zeroizing::new(vec![zero; domain_size]);
+    let mut w = Zeroizing::new(vec![zero; domain_size]);
+    let mut assignment = Zeroizing::new(Vec::with_capacity(shape.num_variables()));
+    assignment.extend_from_slice(full_assignment);
+
+    let products = cfg_iter!(matrices.a)
+        .zip(cfg_iter!(matrices.b))
+        .zip(cfg_iter!(matrices.c))
+        .map(|((a, b), c)| {
+            let a: F = evaluate_constraint(a, full_assignment);
+            let b: F = evaluate_constraint(b, full_assignment);
+            let c: F = evaluate_constraint(c, full_assignment);
+            (a, b, c)
+        })
+        .collect::<Vec<_>>();
+    for (j, (a, b, c)) in products.into_iter().enumerate() {
+        let e = (a - b).square();
+        u[2 * j] = a + b;
+        u[2 * j + 1] = a - b;
+        w[2 * j] = four * c + e;
+        w[2 * j + 1] = e;
+        assignment.push(e);
+    }
+
+    let offset = 2 * shape.num_constraints;
+    for (i, x) in full_assignment[1..shape.num_inputs].iter().enumerate() {
+        let f = x.square();
+        u[offset + 2 * i] = F::one() + x;
+        u[offset + 2 * i + 1] = F::one() - x;
+        w[offset + 2 * i] = F::one() + two * x + f;
+        w[offset + 2 * i + 1] = F::one() - two * x + f;
+        assignment.push(f);
+    }
+    u[shape.num_sap_constraints() - 1] = F::one();
+    w[shape.num_sap_constraints() - 1] = F::one();
+
+    domain.ifft_in_place(&mut u);
+    domain.ifft_in_place(&mut w);
+
+    let coset_domain = domain.get_coset(F::GENERATOR).unwrap();
+    coset_domain.fft_in_place(&mut u);
+    coset_domain.fft_in_place(&mut w);
+
+    let vanishing_polynomial_over_coset = domain
+        .evaluate_vanishing_polynomial(F::GENERATOR)
+        .inverse()
+        .unwrap();
+    cfg_iter_mut!(u).zip(&*w).for_each(|(u_i, w_i)| {
+        *u_i = (u_i.square() - w_i) * vanishing_polynomial_over_coset;
+    });
+    drop(w);
+
+    coset_domain.ifft_in_place(&mut u);
+    // `h` has degree at most `domain_size - 2`.
+    u.truncate(domain_size - 1);
+
+    Ok((assignment, u))
+}
+
+/// Computes `scalars[i] * generator` for all `i` with a fixed-base window table.
+#[cfg(feature = "prover")]
+fn fixed_base_msm<G: CurveGroup>(generator: G, scalars: &[G::ScalarField]) -> Vec<G::Affine> {
+    let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let window = FixedBase::get_mul_window_size(scalars.len());
+    let table = FixedBase::get_window_table::<G>(scalar_bits, window, generator);
+    G::normalize_batch(&FixedBase::msm::<G>(scalar_bits, window, &table, scalars))
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing> Gm17<E> {
+    /// Generates a random common reference string for `circuit`.
+    pub fn generate_random_parameters<C>(
+        circuit: C,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Gm17ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let alpha = Zeroizing::new(E::ScalarField::rand(rng));
+        let beta = Zeroizing::new(E::ScalarField::rand(rng));
+        let gamma = Zeroizing::new(E::ScalarField::rand(rng));
+        let g1_generator = E::G1::rand(rng);
+        let g2_generator = E::G2::rand(rng);
+
+        let setup_time = start_timer!(|| "GM17::Generator");
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+
+        let shape = SapShape {
+            num_constraints: cs.num_constraints(),
+            num_inputs: cs.num_instance_variables(),
+            num_witness: cs.num_witness_variables(),
+        };
+        let matrices = cs.to_matrices().unwrap();
+        let domain = D::<E::ScalarField>::new(shape.num_sap_constraints())
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let x = Zeroizing::new(domain.sample_element_outside_domain(rng));
+        let zt = Zeroizing::new(domain.evaluate_vanishing_polynomial(*x));
+
+        let reduction_time = start_timer!(|| "R1CS to SAP instance map");
+        let lagrange = Zeroizing::new(domain.evaluate_all_lagrange_coefficients(*x));
+        let (u, w) = sap_instance_map(&matrices, &shape, &lagrange);
+        let (u, w) = (Zeroizing::new(u), Zeroizing::new(w));
+        end_timer!(reduction_time);
+
+        let alpha_plus_beta = Zeroizing::new(*alpha + *beta);
+        let gamma_square = Zeroizing::new(gamma.square());
+        let gamma_z = Zeroizing::new(*gamma * *zt);
+        let gamma2_z = Zeroizing::new(*gamma_square * *zt);
+
+        let a_scalars = Zeroizing::new(cfg_iter!(u).map(|u| *gamma * u).collect::<Vec<_>>());
+        let c_1_scalars = Zeroizing::new(
+            cfg_iter!(u[shape.num_inputs..])
+                .zip(&w[shape.num_inputs..])
+                .map(|(u, w)| *alpha_plus_beta * *gamma * u + *gamma_square * w)
+                .collect::<Vec<_>>(),
+        );
+        let two_gamma2_z = Zeroizing::new(gamma2_z.double());
+        let c_2_scalars =
+            Zeroizing::new(cfg_iter!(u).map(|u| *two_gamma2_z * u).collect::<Vec<_>>());
+        let mut power = Zeroizing::new(*gamma2_z);
+        let h_scalars = Zeroizing::new(
+            (0..domain.size() - 1)
+                .map(|_| {
+                    let scalar = *power;
+                    *power *= *x;
+                    scalar
+                })
+                .collect::<Vec<_>>(),
+        );
+        let query_scalars = Zeroizing::new(
+            cfg_iter!(u[..shape.num_inputs])
+                .zip(&w[..shape.num_inputs])
+                .map(|(u, w)| *alpha_plus_beta * u + *gamma * w)
+                .collect::<Vec<_>>(),
+        );
+
+        let key_time = start_timer!(|| "Compute the proving key");
+        let vk = Gm17VerifyingKey {
+            h_g2: g2_generator.into_affine(),
+            g_alpha_g1: g1_generator.mul(*alpha).into_affine(),
+            h_beta_g2: g2_generator.mul(*beta).into_affine(),
+            g_gamma_g1: g1_generator.mul(*gamma).into_affine(),
+            h_gamma_g2: g2_generator.mul(*gamma).into_affine(),
+            query: fixed_base_msm(g1_generator, &query_scalars),
+        };
+        let pk = Gm17ProvingKey {
+            vk,
+            a_query: fixed_base_msm(g1_generator, &a_scalars),
+            b_query: fixed_base_msm(g2_generator, &a_scalars),
+            c_query_1: fixed_base_msm(g1_generator, &c_1_scalars),
+            c_query_2: fixed_base_msm(g1_generator, &c_2_scalars),
+            g_gamma_z: g1_generator.mul(*gamma_z).into_affine(),
+            h_gamma_z: g2_generator.mul(*gamma_z).into_affine(),
+            g_ab_gamma_z: g1_generator.mul(*alpha_plus_beta * *gamma_z).into_affine(),
+            g_gamma2_z2: g1_generator.mul(*gamma2_z * *zt).into_affine(),
+            g_gamma2_z_t: fixed_base_msm(g1_generator, &h_scalars),
+        };
+        end_timer!(key_time);
+        end_timer!(setup_time);
+
+        Ok(pk)
+    }
+
+    /// Create a GM17 proof that is zero-knowledge.
+    /// This method samples randomness for zero knowledge via `rng`.
+    pub fn create_random_proof<C>(
+        circuit: C,
+        pk: &Gm17ProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Gm17Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = E::ScalarField::rand(rng);
+        Self::create_proof(circuit, pk, r)
+    }
+
+    /// Create a GM17 proof using randomness `r`.
+    ///
+    /// Returns [`SynthesisError::MalformedVerifyingKey`] if `pk` was generated for a
+    /// circuit of another shape.
+    pub fn create_proof<C>(
+        circuit: C,
+        pk: &Gm17ProvingKey<E>,
+        r: E::ScalarField,
+    ) -> R1CSResult<Gm17Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let prover_time = start_timer!(|| "GM17::Prover");
+        let r = Zeroizing::new(r);
+        let synthesized = Groth16::<E>::synthesize_job(
+            circuit,
+            Zeroizing::new(E::ScalarField::zero()),
+            Zeroizing::new(E::ScalarField::zero()),
+            true,
+        )?;
+        let matrices = synthesized.matrices.as_ref().unwrap();
+        let shape = SapShape {
+            num_constraints: synthesized.num_constraints,
+            num_inputs: synthesized.num_inputs,
+            num_witness: synthesized.full_assignment.len() - synthesized.num_inputs,
+        };
+        if shape.num_variables() != pk.a_query.len() || shape.num_inputs != pk.vk.query.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        let witness_map_time = start_timer!(|| "R1CS to SAP witness map");
+        let (assignment, h) = sap_witness_map(matrices, &shape, &synthesized.full_assignment)?;
+        end_timer!(witness_map_time);
+        if h.len() != pk.g_gamma2_z_t.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        let a_acc = E::G1::msm_unchecked(&pk.a_query, &assignment);
+        let b_acc = E::G2::msm_unchecked(&pk.b_query, &assignment);
+        let c_1_acc = E::G1::msm_unchecked(&pk.c_query_1, &assignment[shape.num_inputs..]);
+        let c_2_acc = E::G1::msm_unchecked(&pk.c_query_2, &assignment);
+        let h_acc = E::G1::msm_unchecked(&pk.g_gamma2_z_t, &h);
+
+        let g_a = pk.g_gamma_z.mul(*r) + a_acc;
+        let g_b = pk.h_gamma_z.mul(*r) + b_acc;
+        let g_c = c_1_acc
+            + pk.g_ab_gamma_z.mul(*r)
+            + pk.g_gamma2_z2.mul(r.square())
+            + c_2_acc.mul(*r)
+            + h_acc;
+        end_timer!(prover_time);
+
+        Ok(Gm17Proof {
+            a: g_a.into_affine(),
+            b: g_b.into_affine(),
+            c: g_c.into_affine(),
+        })
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing> SNARK<E::ScalarField> for Gm17<E> {
+    type ProvingKey = Gm17ProvingKey<E>;
+    type VerifyingKey = Gm17VerifyingKey<E>;
+    type Proof = Gm17Proof<E>;
+    type ProcessedVerifyingKey = Gm17PreparedVerifyingKey<E>;
+    type Error = SynthesisError;
+
+    fn circuit_specific_setup<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore>(
+        circuit: C,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let pk = Self::generate_random_parameters(circuit, rng)?;
+        let vk = pk.vk.clone();
+
+        Ok((pk, vk))
+    }
+
+    fn prove<C: ConstraintSynthesizer<E::ScalarField>, R: RngCore>(
+        pk: &Self::ProvingKey,
+        circuit: C,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        Self::create_random_proof(circuit, pk, rng)
+    }
+
+    fn process_vk(
+        circuit_vk: &Self::VerifyingKey,
+    ) -> Result<Self::ProcessedVerifyingKey, Self::Error> {
+        Ok(Self::prepare_verifying_key(circuit_vk))
+    }
+
+    fn verify_with_processed_vk(
+        circuit_pvk: &Self::ProcessedVerifyingKey,
+        x: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        Self::verify_proof(circuit_pvk, proof, x)
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing> CircuitSpecificSetupSNARK<E::ScalarField> for Gm17<E> {}
@@ -0,0 +1,106 @@
+//! Circuits whose only public input is a hash of their public values.
+//!
+//! The verifier's work and the size of `gamma_abc_g1` grow with the number of public
+//! inputs. A circuit can instead take its public values as witnesses and expose a single
+//! public input, the hash computed by [`hash_public_inputs`]. With the `r1cs` feature,
+//! [`enforce_input_hash`] allocates that input and constrains it to the hash of the values
+//! inside the circuit, and [`Groth16::verify_proof_with_input_hash`] verifies a proof
+//! against the values themselves.
+//!
+//! # Specification
+//!
+//! For public values `x_1, …, x_n`, the hash is
+//!
+//! ```text
+//! SHA-256(domain || le(x_1) || … || le(x_n)),
+//! ```
+//!
+//! where `le(x)` is the little-endian encoding of the canonical representative of `x` in
+//! `8 · NUM_LIMBS` bytes. The digest is read as a little-endian bit string, of which the
+//! first `F::MODULUS_BIT_SIZE - 1` bits (at most all 256) are the little-endian bits of
+//! the field element, so that it is always below the modulus.
+use crate::{r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey, Proof};
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+#[cfg(feature = "r1cs")]
+use {
+    ark_crypto_primitives::crh::sha256::constraints::Sha256Gadget,
+    ark_r1cs_std::{
+        alloc::AllocVar, bits::boolean::Boolean, bits::uint8::UInt8, eq::EqGadget,
+        fields::fp::FpVar, R1CSVar, ToBitsGadget, ToBytesGadget,
+    },
+    ark_relations::r1cs::ConstraintSystemRef,
+};
+
+/// The domain separator of the hash of the public values.
+const INPUT_HASH_DOMAIN: &[u8] = b"ark-groth16/input-hash/v1";
+
+/// The number of bits of the digest that make up the hash.
+fn hash_bits<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize - 1).min(256)
+}
+
+/// Computes the hash of the public values `values`, the single public input of a circuit
+/// that uses [`enforce_input_hash`].
+pub fn hash_public_inputs<F: PrimeField>(values: &[F]) -> F {
+    let mut hasher = Sha256::new().chain_update(INPUT_HASH_DOMAIN);
+    for value in values {
+        hasher.update(value.into_bigint().to_bytes_le());
+    }
+    let digest = hasher.finalize();
+
+    let bits = digest
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .take(hash_bits::<F>())
+        .collect::<Vec<_>>();
+    F::from_bigint(F::BigInt::from_bits_le(&bits)).expect("the hash is below the modulus")
+}
+
+/// Allocates the single public input of a circuit with hashed public inputs, and enforces
+/// that it is the hash of `values`, the public values allocated as witnesses.
+///
+/// The circuit must not allocate any other public input.
+#[cfg(feature = "r1cs")]
+pub fn enforce_input_hash<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    values: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut bytes = UInt8::constant_vec(INPUT_HASH_DOMAIN);
+    for value in values {
+        bytes.extend(value.to_bytes()?);
+    }
+    let digest = Sha256Gadget::digest(&bytes)?;
+    let bits = digest
+        .to_bytes()?
+        .iter()
+        .map(|byte| byte.to_bits_le())
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    let hash = Boolean::le_bits_to_fp_var(&bits[..hash_bits::<F>()])?;
+
+    let input = FpVar::new_input(cs, || hash.value())?;
+    input.enforce_equal(&hash)?;
+    Ok(input)
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify a proof `proof` of a circuit with hashed public inputs against the prepared
+    /// verification key `pvk`, with respect to the public values `values`.
+    ///
+    /// Returns [`SynthesisError::MalformedVerifyingKey`] if `pvk` has more than one public
+    /// input.
+    pub fn verify_proof_with_input_hash(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        values: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        if pvk.vk.gamma_abc_g1.len() != 2 {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        Self::verify_proof(pvk, proof, &[hash_public_inputs(values)])
+    }
+}
@@ -0,0 +1,414 @@
+//! A LegoGroth16-style commit-and-prove variant of Groth16, after
+//! [[LegoSNARK]](https://eprint.iacr.org/2019/142.pdf).
+//!
+//! The first `commit_witness_count` witness variables of the circuit are *committed*:
+//! the proof carries an extra element
+//!
+//! ```text
+//! D = w_1 · P_1 + … + w_m · P_m + v · Q,
+//! ```
+//!
+//! a Pedersen commitment to the committed witness values `w_1, …, w_m` with blinding `v`
+//! under the commitment key `(P_1, …, P_m, Q)` of [`LegoVerifyingKey::commitment_key`].
+//! The verifier checks `e(A, B) = e(α, β) · e(IC(x) + D, γ) · e(C, δ)`. Because `D` is an
+//! ordinary Pedersen commitment, a holder who knows the opening can prove with a sigma
+//! protocol that several proofs commit to the same attributes, without revealing them.
+//!
+//! [`Groth16::prove_link`] is such a protocol for an externally produced commitment: it
+//! shows that a Pedersen commitment under an arbitrary [`PedersenCommitmentKey`] in
+//! `G1` opens to the values committed in `D`. Where LegoSNARK's CP-link is a
+//! quasi-adaptive NIZK with its own setup, this is a Fiat-Shamir proof of equal
+//! representations, whose size is linear in the number of committed values.
+use crate::{
+    equation, hash_to_field, msm::CpuBackend, r1cs_to_qap::R1CSToQAP, Groth16, PreparedInputs,
+    PreparedVerifyingKey, Proof, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField, UniformRand};
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult, SynthesisError};
+use ark_serialize::*;
+use ark_std::{cfg_iter, ops::Mul, rand::Rng, vec::Vec};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A proof of the commit-and-prove variant: a Groth16 proof together with the
+/// commitment `D` to the committed witness values.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LegoProof<E: Pairing> {
+    /// The Groth16 proof, whose `C` is shifted by the blinding of `d`.
+    pub proof: Proof<E>,
+    /// The commitment `D` in `G1`.
+    pub d: E::G1Affine,
+}
+
+/// The domain separator of the Fiat-Shamir challenge of [`LinkProof`]s.
+const LINK_DOMAIN: &[u8] = b"ark-groth16/lego-link/v1";
+
+/// A Pedersen commitment key in `E::G1`, outside of any proving key.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PedersenCommitmentKey<E: Pairing> {
+    /// The bases of the committed values.
+    pub bases: Vec<E::G1Affine>,
+    /// The base of the blinding.
+    pub blinding_base: E::G1Affine,
+}
+
+impl<E: Pairing> PedersenCommitmentKey<E> {
+    /// Commits to `values` with `blinding`, or returns `None` if the number of values
+    /// does not match the number of bases.
+    pub fn commit(
+        &self,
+        values: &[E::ScalarField],
+        blinding: E::ScalarField,
+    ) -> Option<E::G1Affine> {
+        commit::<E>(&self.bases, self.blinding_base, values, blinding)
+    }
+}
+
+fn commit<E: Pairing>(
+    bases: &[E::G1Affine],
+    blinding_base: E::G1Affine,
+    values: &[E::ScalarField],
+    blinding: E::ScalarField,
+) -> Option<E::G1Affine> {
+    if values.len() != bases.len() {
+        return None;
+    }
+    Some((E::G1::msm_unchecked(bases, values) + blinding_base.mul(blinding)).into_affine())
+}
+
+/// A proof that an external Pedersen commitment opens to the values committed in a
+/// [`LegoProof`].
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LinkProof<E: Pairing> {
+    /// The first message, under the commitment key of the [`LegoVerifyingKey`].
+    pub t_lego: E::G1Affine,
+    /// The first message, under the external commitment key.
+    pub t_external: E::G1Affine,
+    /// The responses for the committed values.
+    pub z_values: Vec<E::ScalarField>,
+    /// The response for the blinding of the [`LegoProof`] commitment.
+    pub z_lego: E::ScalarField,
+    /// The response for the blinding of the external commitment.
+    pub z_external: E::ScalarField,
+}
+
+/// The Fiat-Shamir challenge of a [`LinkProof`], bound to both commitment keys, both
+/// commitments and both first messages.
+fn link_challenge<E: Pairing>(
+    (bases, blinding_base): (&[E::G1Affine], E::G1Affine),
+    ck: &PedersenCommitmentKey<E>,
+    d: &E::G1Affine,
+    commitment: &E::G1Affine,
+    t_lego: &E::G1Affine,
+    t_external: &E::G1Affine,
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    bases
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    ck.serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    for point in [&blinding_base, d, commitment, t_lego, t_external] {
+        point
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+    }
+    hash_to_field(LINK_DOMAIN, &bytes)
+}
+
+/// The proving key of the commit-and-prove variant.
+///
+/// `pk.vk.gamma_abc_g1` covers the public inputs followed by the committed witness
+/// variables, and `pk.l_query` only the remaining witness variables.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LegoProvingKey<E: Pairing> {
+    /// The underlying Groth16 proving key.
+    pub pk: ProvingKey<E>,
+    /// The element `eta/gamma * G` in `E::G1`, the blinding base of the commitment.
+    pub eta_gamma_inv_g1: E::G1Affine,
+    /// The element `eta/delta * G` in `E::G1`.
+    pub eta_delta_inv_g1: E::G1Affine,
+    /// The number of committed witness variables.
+    pub commit_witness_count: usize,
+}
+
+impl<E: Pairing> LegoProvingKey<E> {
+    /// The verifying key corresponding to this proving key.
+    pub fn verifying_key(&self) -> LegoVerifyingKey<E> {
+        LegoVerifyingKey {
+            pvk: crate::prepare_verifying_key(&self.pk.vk),
+            eta_gamma_inv_g1: self.eta_gamma_inv_g1,
+            commit_witness_count: self.commit_witness_count,
+        }
+    }
+}
+
+/// The prepared verifying key of the commit-and-prove variant.
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LegoVerifyingKey<E: Pairing> {
+    /// The prepared Groth16 verifying key, whose `gamma_abc_g1` covers the public inputs
+    /// followed by the committed witness variables.
+    pub pvk: PreparedVerifyingKey<E>,
+    /// The element `eta/gamma * G` in `E::G1`, the blinding base of the commitment.
+    pub eta_gamma_inv_g1: E::G1Affine,
+    /// The number of committed witness variables.
+    pub commit_witness_count: usize,
+}
+
+impl<E: Pairing> LegoVerifyingKey<E> {
+    /// The Pedersen commitment key `([P_1, …, P_m], Q)` under which `D` commits to the
+    /// committed witness values, or `None` if `gamma_abc_g1` has fewer than
+    /// `commit_witness_count` points.
+    pub fn commitment_key(&self) -> Option<(&[E::G1Affine], E::G1Affine)> {
+        let gamma_abc_g1 = &self.pvk.vk.gamma_abc_g1;
+        let start = gamma_abc_g1.len().checked_sub(self.commit_witness_count)?;
+        Some((&gamma_abc_g1[start..], self.eta_gamma_inv_g1))
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a random common reference string for the commit-and-prove variant, in
+    /// which proofs commit to the first `commit_witness_count` witness variables of
+    /// `circuit`.
+    ///
+    /// Returns [`SynthesisError::Unsatisfiable`] if the circuit has fewer witness
+    /// variables than that.
+    pub fn generate_random_lego_parameters<C>(
+        circuit: C,
+        commit_witness_count: usize,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<LegoProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let alpha = E::ScalarField::rand(rng);
+        let beta = E::ScalarField::rand(rng);
+        let gamma = E::ScalarField::rand(rng);
+        let delta = E::ScalarField::rand(rng);
+        let eta = E::ScalarField::rand(rng);
+
+        let g1_generator = E::G1::rand(rng);
+        let g2_generator = E::G2::rand(rng);
+
+        let mut pk = Self::generate_parameters_with_qap(
+            circuit,
+            alpha,
+            beta,
+            gamma,
+            delta,
+            g1_generator,
+            g2_generator,
+            rng,
+        )?;
+        if commit_witness_count > pk.l_query.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // Move the committed variables from the `1/delta` to the `1/gamma` side.
+        let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+        let delta_inverse = delta.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+        let delta_over_gamma = (delta * gamma_inverse).into_bigint();
+        let committed = cfg_iter!(pk.l_query[..commit_witness_count])
+            .map(|l| l.mul_bigint(delta_over_gamma))
+            .collect::<Vec<_>>();
+        pk.vk
+            .gamma_abc_g1
+            .extend(E::G1::normalize_batch(&committed));
+        pk.l_query.drain(..commit_witness_count);
+
+        Ok(LegoProvingKey {
+            pk,
+            eta_gamma_inv_g1: g1_generator.mul(eta * gamma_inverse).into_affine(),
+            eta_delta_inv_g1: g1_generator.mul(eta * delta_inverse).into_affine(),
+            commit_witness_count,
+        })
+    }
+
+    /// Create a commit-and-prove proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction. Returns the proof and the blinding `v` of its commitment.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_lego_proof<C>(
+        circuit: C,
+        pk: &LegoProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<(LegoProof<E>, E::ScalarField)>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+        let v = E::ScalarField::rand(rng);
+
+        Ok((Self::create_lego_proof(circuit, pk, *r, *s, v)?, v))
+    }
+
+    /// Create a commit-and-prove proof using randomness `r` and `s`, commitment
+    /// blinding `v`, and the provided R1CS-to-QAP reduction.
+    pub fn create_lego_proof<C>(
+        circuit: C,
+        pk: &LegoProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+        v: E::ScalarField,
+    ) -> R1CSResult<LegoProof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
+        let prover = cs.borrow().unwrap();
+        if prover.witness_assignment.len() < pk.commit_witness_count {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let (committed, uncommitted) = prover.witness_assignment.split_at(pk.commit_witness_count);
+
+        // The committed variables take the place of public inputs, which keeps the
+        // order of the full assignment for the `A` and `B` queries.
+        let input_assignment =
+            Zeroizing::new([&prover.instance_assignment[1..], committed].concat());
+        let proof = Self::create_proof_with_assignment(
+            (&pk.pk).into(),
+            &r,
+            &s,
+            &h,
+            &input_assignment,
+            uncommitted,
+            &CpuBackend,
+        )?;
+
+        let bases = &pk.pk.vk.gamma_abc_g1[prover.instance_assignment.len()..];
+        let d = E::G1::msm_unchecked(bases, committed) + pk.eta_gamma_inv_g1.mul(v);
+        let c = proof.c.into_group() - pk.eta_delta_inv_g1.mul(v);
+
+        Ok(LegoProof {
+            proof: Proof {
+                c: c.into_affine(),
+                ..proof
+            },
+            d: d.into_affine(),
+        })
+    }
+
+    /// Verify a commit-and-prove proof `proof` against the verifying key `vk`, with
+    /// respect to the instance `public_inputs`.
+    pub fn verify_lego_proof(
+        vk: &LegoVerifyingKey<E>,
+        proof: &LegoProof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        let gamma_abc_g1 = &vk.pvk.vk.gamma_abc_g1;
+        let num_public = gamma_abc_g1
+            .len()
+            .checked_sub(vk.commit_witness_count)
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        let prepared_inputs =
+            equation::prepared_inputs::<E>(&gamma_abc_g1[..num_public], public_inputs)
+                .ok_or(SynthesisError::MalformedVerifyingKey)?;
+
+        let prepared_inputs = PreparedInputs {
+            prepared_inputs: (prepared_inputs + proof.d).into_affine(),
+        };
+
+        Self::verify_proof_with_prepared_public_inputs(&vk.pvk, &proof.proof, &prepared_inputs)
+    }
+
+    /// Check that the commitment of `proof` opens to `committed_values` with blinding `v`.
+    ///
+    /// Returns `false` if `vk` has no valid commitment key.
+    pub fn verify_lego_commitment(
+        vk: &LegoVerifyingKey<E>,
+        proof: &LegoProof<E>,
+        committed_values: &[E::ScalarField],
+        v: E::ScalarField,
+    ) -> bool {
+        let Some((bases, blinding_base)) = vk.commitment_key() else {
+            return false;
+        };
+        if committed_values.len() != bases.len() {
+            return false;
+        }
+        let d = E::G1::msm_unchecked(bases, committed_values) + blinding_base.mul(v);
+        d.into_affine() == proof.d
+    }
+
+    /// Prove that `commitment`, a commitment under `ck` to `values` with `blinding`,
+    /// opens to the same values as the commitment of `proof`, whose blinding is `v`.
+    ///
+    /// Returns `None` if `vk` has no valid commitment key or the number of values does
+    /// not match either commitment key.
+    /// The statement is not checked, so a false one yields a proof that does not verify.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_link(
+        vk: &LegoVerifyingKey<E>,
+        proof: &LegoProof<E>,
+        ck: &PedersenCommitmentKey<E>,
+        commitment: &E::G1Affine,
+        values: &[E::ScalarField],
+        v: E::ScalarField,
+        blinding: E::ScalarField,
+        rng: &mut impl Rng,
+    ) -> Option<LinkProof<E>> {
+        let (bases, blinding_base) = vk.commitment_key()?;
+        let k_values = (0..values.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let k_lego = E::ScalarField::rand(rng);
+        let k_external = E::ScalarField::rand(rng);
+        let t_lego = commit::<E>(bases, blinding_base, &k_values, k_lego)?;
+        let t_external = ck.commit(&k_values, k_external)?;
+
+        let c = link_challenge(
+            (bases, blinding_base),
+            ck,
+            &proof.d,
+            commitment,
+            &t_lego,
+            &t_external,
+        );
+        Some(LinkProof {
+            t_lego,
+            t_external,
+            z_values: k_values
+                .iter()
+                .zip(values)
+                .map(|(k, w)| *k + c * w)
+                .collect(),
+            z_lego: k_lego + c * v,
+            z_external: k_external + c * blinding,
+        })
+    }
+
+    /// Check that `link` proves that `commitment`, a commitment under `ck`, opens to the
+    /// same values as the commitment of `proof`. This does not verify `proof` itself.
+    ///
+    /// Returns `false` if `vk` has no valid commitment key.
+    pub fn verify_link(
+        vk: &LegoVerifyingKey<E>,
+        proof: &LegoProof<E>,
+        ck: &PedersenCommitmentKey<E>,
+        commitment: &E::G1Affine,
+        link: &LinkProof<E>,
+    ) -> bool {
+        let Some((bases, blinding_base)) = vk.commitment_key() else {
+            return false;
+        };
+        let c = link_challenge(
+            (bases, blinding_base),
+            ck,
+            &proof.d,
+            commitment,
+            &link.t_lego,
+            &link.t_external,
+        );
+
+        let lego_ok = commit::<E>(bases, blinding_base, &link.z_values, link.z_lego)
+            .is_some_and(|lhs| lhs == (link.t_lego + proof.d.mul(c)).into_affine());
+        let external_ok = ck
+            .commit(&link.z_values, link.z_external)
+            .is_some_and(|lhs| lhs == (link.t_external + commitment.mul(c)).into_affine());
+        lego_ok && external_ok
+    }
+}
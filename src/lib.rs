@@ -10,7 +10,8 @@
     missing_docs
 )]
 #![allow(clippy::many_single_char_names, clippy::op_ref)]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(any(feature = "ffi", feature = "wasm")), forbid(unsafe_code))]
+#![cfg_attr(any(feature = "ffi", feature = "wasm"), deny(unsafe_code))]
 
 #[macro_use]
 extern crate ark_std;
@@ -19,43 +20,206 @@ extern crate ark_std;
 #[macro_use]
 extern crate derivative;
 
+mod macros;
+
 /// Reduce an R1CS instance to a *Quadratic Arithmetic Program* instance.
 pub mod r1cs_to_qap;
 
 /// Data structures used by the prover, verifier, and generator.
 pub mod data_structures;
 
+/// Metadata that identifies the circuit of a proving key file.
+pub mod metadata;
+
 /// Generate public parameters for the Groth16 zkSNARK construction.
+#[cfg(feature = "prover")]
 pub mod generator;
 
+/// Multi-party ceremonies that generate the circuit-specific parameters.
+#[cfg(feature = "prover")]
+pub mod ceremony;
+
+/// Import of phase-1 Powers of Tau ceremonies.
+#[cfg(feature = "prover")]
+pub mod powers_of_tau;
+
+/// Well-formedness checks of parameters against a circuit.
+#[cfg(feature = "prover")]
+pub mod parameters;
+
+/// Pluggable entropy sources for setups and ceremony contributions.
+#[cfg(feature = "prover")]
+pub mod entropy;
+
 /// Create proofs for the Groth16 zkSNARK construction.
+#[cfg(feature = "prover")]
 pub mod prover;
 
 /// Verify proofs for the Groth16 zkSNARK construction.
 pub mod verifier;
 
+/// The verification equation as pure functions, with its specification.
+pub mod equation;
+
+/// Multi-scalar multiplication backends used by the prover.
+#[cfg(feature = "prover")]
+pub mod msm;
+
+/// A commit-and-prove variant whose proofs commit to part of the witness.
+#[cfg(feature = "prover")]
+pub mod lego;
+
+/// A simulation-extractable variant of Groth16 with proofs signed under one-time keys.
+pub mod se;
+
+/// Proofs bound to an application context string.
+pub mod context;
+
+/// The GM17 zkSNARK, a sibling proof system with simulation-extractable proofs.
+pub mod gm17;
+
+/// Designated-verifier proofs that only convince the holder of a verifier secret key.
+pub mod designated;
+
+/// Delegated proving, with the witness hidden from the server that computes the proof.
+#[cfg(feature = "prover")]
+pub mod delegated;
+
+/// Threshold proving by parties that hold additive shares of the witness.
+#[cfg(feature = "prover")]
+pub mod threshold;
+
+/// Aggregation of many proofs into one proof of logarithmic size.
+pub mod aggregation;
+
+/// A cache of prepared verifying keys for verifiers of many circuits.
+pub mod cache;
+
+/// Tuning of the prover for the machine it runs on.
+#[cfg(feature = "prover")]
+pub mod config;
+
+/// Per-stage timings of the prover.
+#[cfg(all(feature = "std", feature = "prover"))]
+pub mod report;
+
+/// Distributed proving across worker machines.
+#[cfg(all(feature = "std", feature = "prover"))]
+pub mod distributed;
+
+/// Progress reporting for long-running proofs.
+#[cfg(feature = "prover")]
+pub mod progress;
+
+/// Proving as a runtime-agnostic future that yields to the executor.
+#[cfg(feature = "prover")]
+pub mod async_prover;
+
+/// Cancellation of in-flight proofs.
+#[cfg(feature = "prover")]
+pub mod cancellation;
+
+/// Support for the binary file formats of circom.
+///
+/// Proving keys in the `.zkey` format of snarkjs cannot be read yet, so the keys to prove
+/// circom witnesses with must come from the setup of this crate.
+#[cfg(feature = "prover")]
+pub mod circom;
+
+/// Fixed-base precomputation tables for faster repeated proving and verification with one
+/// key.
+pub mod precompute;
+
+/// A preprocessed in-memory form of proving keys for faster repeated proving.
+#[cfg(feature = "prover")]
+pub mod preprocess;
+
+/// Pipelined proving of many circuit instances across threads.
+#[cfg(all(feature = "std", feature = "prover"))]
+pub mod pipeline;
+
+/// Packing of proofs into blob-friendly field elements.
+pub mod blob;
+
+/// Verification with fixed-size encodings and bounded memory for blockchain runtimes.
+pub mod runtime;
+
+/// Encoding of keys and proofs for the `alt_bn128` syscalls of Solana.
+pub mod solana;
+
+/// Generation of verifier contracts with an embedded verifying key.
+pub mod codegen;
+
+/// Low-memory proving that streams the proving key from a reader.
+#[cfg(feature = "prover")]
+pub mod streaming;
+
+/// Proving keys held in two copies and cross-checked for corruption.
+#[cfg(feature = "prover")]
+pub mod redundant;
+
+/// Redaction of sensitive witness values in prover diagnostics.
+#[cfg(feature = "prover")]
+pub mod redact;
+
+/// Cheap checks that reject malformed proofs before any pairing is computed.
+pub mod prefilter;
+
+/// Circuits whose only public input is a hash of their public values.
+pub mod input_hash;
+
+mod snarkjs;
+
+mod zokrates;
+
+/// Known-answer test vectors for interoperability testing.
+pub mod test_vectors;
+
+/// C bindings for setup, proving and verification over BLS12-381.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// JavaScript bindings for verification over BLS12-381.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 /// Constraints for the Groth16 verifier.
 #[cfg(feature = "r1cs")]
 pub mod constraints;
 
-#[cfg(test)]
+/// Re-exports of the types and traits most users need.
+pub mod prelude;
+
+#[cfg(all(test, feature = "prover"))]
 mod test;
 
 pub use self::data_structures::*;
-pub use self::{generator::*, prover::*, verifier::*};
+#[cfg(feature = "prover")]
+pub use self::prover::*;
+pub use self::verifier::*;
 
-use ark_crypto_primitives::snark::*;
 use ark_ec::pairing::Pairing;
-use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
-use ark_std::rand::RngCore;
-use ark_std::{marker::PhantomData, vec::Vec};
+use ark_ff::PrimeField;
+use ark_std::marker::PhantomData;
 use r1cs_to_qap::{LibsnarkReduction, R1CSToQAP};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "prover")]
+use {
+    ark_crypto_primitives::snark::*,
+    ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError},
+    ark_std::{rand::RngCore, vec::Vec},
+};
 
 /// The SNARK of [[Groth16]](https://eprint.iacr.org/2016/260.pdf).
+///
+/// With the `prover` feature, this implements the `SNARK` and `CircuitSpecificSetupSNARK`
+/// traits of `ark-snark`, re-exported as `ark_crypto_primitives::snark`, so it can be used
+/// in generic arkworks code such as the proof gadgets of `ark-crypto-primitives`.
 pub struct Groth16<E: Pairing, QAP: R1CSToQAP = LibsnarkReduction> {
     _p: PhantomData<(E, QAP)>,
 }
 
+#[cfg(feature = "prover")]
 impl<E: Pairing, QAP: R1CSToQAP> SNARK<E::ScalarField> for Groth16<E, QAP> {
     type ProvingKey = ProvingKey<E>;
     type VerifyingKey = VerifyingKey<E>;
@@ -92,8 +256,44 @@ impl<E: Pairing, QAP: R1CSToQAP> SNARK<E::ScalarField> for Groth16<E, QAP> {
         x: &[E::ScalarField],
         proof: &Self::Proof,
     ) -> Result<bool, Self::Error> {
-        Ok(Self::verify_proof(&circuit_pvk, proof, &x)?)
+        Self::verify_proof(circuit_pvk, proof, x)
     }
 }
 
+#[cfg(feature = "prover")]
 impl<E: Pairing, QAP: R1CSToQAP> CircuitSpecificSetupSNARK<E::ScalarField> for Groth16<E, QAP> {}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use ark_std::{rand::RngCore, vec::Vec};
+}
+
+/// Runs `a` and `b` concurrently when the `parallel` feature is enabled,
+/// and one after the other otherwise.
+#[inline]
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "parallel")]
+    return rayon::join(a, b);
+    #[cfg(not(feature = "parallel"))]
+    return (a(), b());
+}
+
+/// Hashes `bytes` under the domain separator `domain` to an element of `F`.
+///
+/// This reduces the 512 bits of `SHA-256(domain || bytes || i)` for `i = 0, 1` modulo the
+/// characteristic, so that the bias of the result is negligible.
+pub(crate) fn hash_to_field<F: PrimeField>(domain: &[u8], bytes: &[u8]) -> F {
+    let hasher = Sha256::new().chain_update(domain).chain_update(bytes);
+
+    let mut wide = [0u8; 64];
+    for (i, half) in wide.chunks_mut(32).enumerate() {
+        half.copy_from_slice(&hasher.clone().chain_update([i as u8]).finalize());
+    }
+    F::from_le_bytes_mod_order(&wide)
+}
@@ -0,0 +1,153 @@
+/// Generates concrete type aliases and setup, prove, verify, rerandomization, and serialization
+/// functions for [`Groth16`](crate::Groth16) over the given pairing engine, at the call site.
+/// Without the `prover` feature, the setup, prove, and rerandomization functions are omitted.
+///
+/// Invoke it inside a module of its own to avoid name clashes:
+///
+/// ```
+/// mod bls12_377 {
+///     ark_groth16::groth16_for_curve!(ark_bls12_377::Bls12_377);
+/// }
+/// ```
+#[macro_export]
+macro_rules! groth16_for_curve {
+    ($curve:ty) => {
+        /// The scalar field of the curve, over which circuits are defined.
+        pub type Fr = <$curve as $crate::prelude::Pairing>::ScalarField;
+        /// A Groth16 proving key.
+        pub type ProvingKey = $crate::ProvingKey<$curve>;
+        /// A Groth16 verifying key.
+        pub type VerifyingKey = $crate::VerifyingKey<$curve>;
+        /// A Groth16 verifying key prepared for verification.
+        pub type PreparedVerifyingKey = $crate::PreparedVerifyingKey<$curve>;
+        /// A Groth16 proof.
+        pub type Proof = $crate::Proof<$curve>;
+
+        /// Prepare `vk` for verification.
+        pub fn prepare_verifying_key(vk: &VerifyingKey) -> PreparedVerifyingKey {
+            $crate::prepare_verifying_key(vk)
+        }
+
+        /// Verify `proof` against `public_inputs`.
+        pub fn verify(
+            pvk: &PreparedVerifyingKey,
+            proof: &Proof,
+            public_inputs: &[Fr],
+        ) -> ::core::result::Result<bool, $crate::prelude::SynthesisError> {
+            $crate::Groth16::<$curve>::verify_proof(pvk, proof, public_inputs)
+        }
+
+        /// Verify the compressed `proof_bytes` against the compressed `vk_bytes` and the
+        /// hexadecimal `public_inputs_hex`. See [`Groth16::verify_bytes`].
+        ///
+        /// [`Groth16::verify_bytes`]: $crate::Groth16::verify_bytes
+        pub fn verify_bytes(
+            vk_bytes: &[u8],
+            proof_bytes: &[u8],
+            public_inputs_hex: &str,
+        ) -> ::core::result::Result<bool, $crate::prelude::VerificationError> {
+            $crate::Groth16::<$curve>::verify_bytes(vk_bytes, proof_bytes, public_inputs_hex)
+        }
+
+        $crate::__groth16_prover_functions!($curve);
+
+        $crate::__groth16_serialization_helpers!(proof_to_bytes, proof_from_bytes, Proof);
+        $crate::__groth16_serialization_helpers!(
+            verifying_key_to_bytes,
+            verifying_key_from_bytes,
+            VerifyingKey
+        );
+        $crate::__groth16_serialization_helpers!(
+            proving_key_to_bytes,
+            proving_key_from_bytes,
+            ProvingKey
+        );
+    };
+}
+
+#[cfg(feature = "prover")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __groth16_prover_functions {
+    ($curve:ty) => {
+        /// Generate a proving and verifying key for `circuit`.
+        pub fn setup<C, R>(
+            circuit: C,
+            rng: &mut R,
+        ) -> ::core::result::Result<(ProvingKey, VerifyingKey), $crate::prelude::SynthesisError>
+        where
+            C: $crate::prelude::ConstraintSynthesizer<Fr>,
+            R: $crate::__private::RngCore,
+        {
+            let pk =
+                $crate::Groth16::<$curve>::generate_random_parameters_with_reduction(circuit, rng)?;
+            let vk = pk.vk.clone();
+            Ok((pk, vk))
+        }
+
+        /// Create a zero-knowledge proof for `circuit`.
+        pub fn prove<C, R>(
+            pk: &ProvingKey,
+            circuit: C,
+            rng: &mut R,
+        ) -> ::core::result::Result<Proof, $crate::prelude::SynthesisError>
+        where
+            C: $crate::prelude::ConstraintSynthesizer<Fr>,
+            R: $crate::__private::RngCore,
+        {
+            $crate::Groth16::<$curve>::create_random_proof_with_reduction(circuit, pk, rng)
+        }
+
+        /// Create a proof for `circuit` with the caller-supplied randomness `r` and `s`.
+        /// The proof is only zero-knowledge if `r` and `s` are secret and uniformly random.
+        pub fn prove_with_randomness<C>(
+            pk: &ProvingKey,
+            circuit: C,
+            r: Fr,
+            s: Fr,
+        ) -> ::core::result::Result<Proof, $crate::prelude::SynthesisError>
+        where
+            C: $crate::prelude::ConstraintSynthesizer<Fr>,
+        {
+            $crate::Groth16::<$curve>::create_proof_with_reduction(circuit, pk, r, s)
+        }
+
+        /// Rerandomize `proof` into a fresh proof of the same statement that cannot be
+        /// linked to the original.
+        pub fn rerandomize<R: $crate::__private::RngCore>(
+            vk: &VerifyingKey,
+            proof: &Proof,
+            rng: &mut R,
+        ) -> Proof {
+            $crate::Groth16::<$curve>::rerandomize_proof(vk, proof, rng)
+        }
+    };
+}
+
+#[cfg(not(feature = "prover"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __groth16_prover_functions {
+    ($curve:ty) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __groth16_serialization_helpers {
+    ($to_bytes:ident, $from_bytes:ident, $ty:ty) => {
+        #[doc = concat!("Serialize a `", stringify!($ty), "` in compressed form.")]
+        pub fn $to_bytes(value: &$ty) -> $crate::__private::Vec<u8> {
+            let mut bytes = $crate::__private::Vec::new();
+            $crate::prelude::CanonicalSerialize::serialize_compressed(value, &mut bytes)
+                .expect("serializing into a `Vec` cannot fail");
+            bytes
+        }
+
+        #[doc = concat!("Deserialize and validate a `", stringify!($ty), "` in compressed form.")]
+        pub fn $from_bytes(
+            bytes: &[u8],
+        ) -> ::core::result::Result<$ty, $crate::prelude::SerializationError> {
+            <$ty as $crate::prelude::CanonicalDeserialize>::deserialize_compressed(bytes)
+        }
+    };
+}
@@ -0,0 +1,155 @@
+//! Metadata that identifies the circuit of a proving key file.
+//!
+//! # Specification
+//!
+//! A key file with metadata is
+//!
+//! ```text
+//! magic || version || length || metadata || key,
+//! ```
+//!
+//! where `magic` is `b"g16k"`, `version` is `1` as a little-endian `u32`, `metadata` is the
+//! compressed encoding of a [`KeyMetadata`] and `length` its length as a little-endian
+//! `u64`, and `key` is the [`ProvingKey`] as written by `CanonicalSerialize`. The metadata
+//! comes first so that [`read_key_metadata`] can identify a key without reading the
+//! points.
+//!
+//! The circuit hash of [`KeyMetadata::for_circuit`] is `SHA-256(domain || matrices)`, where
+//! `matrices` are the number of instance and witness variables and the matrices `A`, `B`
+//! and `C` of the circuit, synthesized for setup, with every length and index a
+//! little-endian `u64` and every coefficient compressed.
+use crate::ProvingKey;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate, Write,
+};
+use ark_std::{string::String, vec, vec::Vec};
+use sha2::{Digest, Sha256};
+
+/// The magic bytes that start a key file with metadata.
+const KEY_FILE_MAGIC: &[u8; 4] = b"g16k";
+
+/// The version of the key file format.
+const KEY_FILE_VERSION: u32 = 1;
+
+/// The largest metadata block that is read, so that a corrupted length cannot exhaust
+/// memory.
+const MAX_METADATA_LENGTH: u64 = 1 << 16;
+
+/// Domain separator for the hash of a circuit.
+const CIRCUIT_HASH_DOMAIN: &[u8] = b"ark-groth16/metadata/circuit/v1";
+
+/// A description of the circuit and the setup that a proving key belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KeyMetadata {
+    /// A hash of the constraint matrices of the circuit.
+    pub circuit_hash: [u8; 32],
+    /// The number of constraints of the circuit.
+    pub num_constraints: u64,
+    /// The number of public inputs of the circuit.
+    pub num_public_inputs: u64,
+    /// A name of the curve, such as `"bls12-381"`.
+    pub curve_id: String,
+    /// The version of the generator that produced the key.
+    pub generator_version: String,
+}
+
+impl KeyMetadata {
+    /// Describes `circuit` on the curve `curve_id`, for keys generated by this version of
+    /// the crate.
+    pub fn for_circuit<F, C>(circuit: C, curve_id: &str) -> Result<Self, SynthesisError>
+    where
+        F: PrimeField,
+        C: ConstraintSynthesizer<F>,
+    {
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+
+        let mut hasher = Sha256::new()
+            .chain_update(CIRCUIT_HASH_DOMAIN)
+            .chain_update((matrices.num_instance_variables as u64).to_le_bytes())
+            .chain_update((matrices.num_witness_variables as u64).to_le_bytes());
+        let mut coefficient = Vec::new();
+        for matrix in [&matrices.a, &matrices.b, &matrices.c] {
+            hasher.update((matrix.len() as u64).to_le_bytes());
+            for row in matrix {
+                hasher.update((row.len() as u64).to_le_bytes());
+                for (value, index) in row {
+                    coefficient.clear();
+                    value
+                        .serialize_compressed(&mut coefficient)
+                        .expect("serializing into a `Vec` cannot fail");
+                    hasher.update(&coefficient);
+                    hasher.update((*index as u64).to_le_bytes());
+                }
+            }
+        }
+
+        Ok(Self {
+            circuit_hash: hasher.finalize().into(),
+            num_constraints: matrices.num_constraints as u64,
+            num_public_inputs: matrices.num_instance_variables.saturating_sub(1) as u64,
+            curve_id: curve_id.into(),
+            generator_version: env!("CARGO_PKG_VERSION").into(),
+        })
+    }
+}
+
+/// Reads the magic, the version and the metadata of a key file written by
+/// [`ProvingKey::serialize_with_metadata`], leaving `reader` at the start of the key.
+pub fn read_key_metadata<R: Read>(mut reader: R) -> Result<KeyMetadata, SerializationError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != KEY_FILE_MAGIC || u32::deserialize_compressed(&mut reader)? != KEY_FILE_VERSION {
+        return Err(SerializationError::InvalidData);
+    }
+    let length = u64::deserialize_compressed(&mut reader)?;
+    if length > MAX_METADATA_LENGTH {
+        return Err(SerializationError::InvalidData);
+    }
+    let mut block = vec![0u8; length as usize];
+    reader.read_exact(&mut block)?;
+    let mut block = &block[..];
+    let metadata = KeyMetadata::deserialize_compressed(&mut block)?;
+    if !block.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(metadata)
+}
+
+impl<E: Pairing> ProvingKey<E> {
+    /// Writes `self` to `writer` with `compress`, preceded by `metadata`, so that the circuit
+    /// of the key file can be identified with [`read_key_metadata`].
+    pub fn serialize_with_metadata<W: Write>(
+        &self,
+        metadata: &KeyMetadata,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        writer.write_all(KEY_FILE_MAGIC)?;
+        KEY_FILE_VERSION.serialize_compressed(&mut writer)?;
+        (metadata.compressed_size() as u64).serialize_compressed(&mut writer)?;
+        metadata.serialize_compressed(&mut writer)?;
+        self.serialize_with_mode(writer, compress)
+    }
+
+    /// Reads a key file written by [`ProvingKey::serialize_with_metadata`] with `compress`,
+    /// and returns the key and its metadata.
+    pub fn deserialize_with_metadata<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<(Self, KeyMetadata), SerializationError> {
+        let metadata = read_key_metadata(&mut reader)?;
+        let pk = Self::deserialize_with_mode(reader, compress, validate)?;
+        Ok((pk, metadata))
+    }
+}
@@ -0,0 +1,257 @@
+use crate::precompute::window;
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    Group, VariableBaseMSM,
+};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_std::{cfg_into_iter, vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The big-integer representation of a scalar of `E`.
+pub type ScalarBigInt<E> = <<E as Pairing>::ScalarField as PrimeField>::BigInt;
+
+/// A query of the proving key that the prover computes a multi-scalar multiplication with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MsmQuery {
+    /// `h_query`, with the QAP witness `h`.
+    H,
+    /// `l_query`, with the witness assignment.
+    L,
+    /// `a_query`, with the full assignment.
+    A,
+    /// `b_g1_query`, with the full assignment.
+    BG1,
+    /// `b_g2_query`, with the full assignment.
+    BG2,
+}
+
+/// A backend that computes the variable-base multi-scalar multiplications
+/// performed by the prover.
+///
+/// Accelerated implementations (for example on GPUs) are usually specific to a
+/// curve, so the trait is parameterized by the pairing engine rather than by the group.
+/// The prover may call the backend from several threads at once, and tells it which
+/// query of the proving key `bases` is, without its first point for `a_query` and the
+/// `b_*_query`s.
+pub trait MsmBackend<E: Pairing>: Sync {
+    /// Computes `sum_i scalars[i] * bases[i]` in `E::G1`.
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1;
+
+    /// Computes `sum_i scalars[i] * bases[i]` in `E::G2`.
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2;
+}
+
+/// The default backend, using the Pippenger implementation of `ark-ec`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuBackend;
+
+impl<E: Pairing> MsmBackend<E> for CpuBackend {
+    fn msm_g1(&self, _: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        E::G1::msm_bigint(bases, scalars)
+    }
+
+    fn msm_g2(&self, _: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        E::G2::msm_bigint(bases, scalars)
+    }
+}
+
+/// The number of bucket additions that share one field inversion in
+/// [`BatchAffineBackend`].
+const BATCH_SIZE: usize = 1 << 10;
+
+/// A backend for curves in short Weierstrass form, which accumulates the buckets of the
+/// Pippenger multi-scalar multiplication in affine coordinates.
+///
+/// An affine addition needs the inverse of the difference of the `x`-coordinates. The
+/// backend adds up to `BATCH_SIZE` bases to distinct buckets at once and computes all
+/// their inverses with a single inversion, so that an addition costs about two thirds of
+/// the mixed addition of [`CpuBackend`]. Multi-scalar multiplications with too few bases
+/// to fill the batches, such as those over `l_query` of small circuits, fall back to
+/// [`CpuBackend`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BatchAffineBackend;
+
+impl<E, P1, P2> MsmBackend<E> for BatchAffineBackend
+where
+    E: Pairing<G1Affine = Affine<P1>, G1 = Projective<P1>>,
+    E: Pairing<G2Affine = Affine<P2>, G2 = Projective<P2>>,
+    P1: SWCurveConfig<ScalarField = E::ScalarField>,
+    P2: SWCurveConfig<ScalarField = E::ScalarField>,
+{
+    fn msm_g1(&self, _: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        batch_affine_msm(bases, scalars)
+    }
+
+    fn msm_g2(&self, _: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        batch_affine_msm(bases, scalars)
+    }
+}
+
+/// The Pippenger multi-scalar multiplication with buckets in affine coordinates.
+fn batch_affine_msm<P: SWCurveConfig>(
+    bases: &[Affine<P>],
+    scalars: &[<P::ScalarField as PrimeField>::BigInt],
+) -> Projective<P> {
+    let len = bases.len().min(scalars.len());
+    let (bases, scalars) = (&bases[..len], &scalars[..len]);
+    // The window size of `ark-ec`.
+    let window_size = (ark_std::log2(len) as usize * 69 / 100) + 2;
+    if len < 4 * BATCH_SIZE || (1 << window_size) < 4 * BATCH_SIZE {
+        return Projective::<P>::msm_bigint(bases, scalars);
+    }
+    let num_bits = P::ScalarField::MODULUS_BIT_SIZE as usize;
+
+    let window_sums = cfg_into_iter!((0..num_bits).step_by(window_size).collect::<Vec<_>>())
+        .map(|offset| {
+            let mut buckets = Buckets::<P>::new((1 << window_size) - 1);
+            for (base, scalar) in bases.iter().zip(scalars) {
+                let digit = window(scalar, offset, window_size);
+                if digit != 0 && !base.infinity {
+                    buckets.add(digit - 1, *base);
+                }
+            }
+            let (buckets, overflow) = buckets.finish();
+
+            // sum_k (k + 1) * buckets[k], via running sums of mixed additions.
+            let mut running_sum = Projective::<P>::zero();
+            let mut acc = Projective::<P>::zero();
+            for (k, bucket) in buckets.iter().enumerate().rev() {
+                running_sum += bucket;
+                if let Some(overflow) = overflow.get(k) {
+                    running_sum += overflow;
+                }
+                acc += running_sum;
+            }
+            acc
+        })
+        .collect::<Vec<_>>();
+
+    let mut acc = Projective::<P>::zero();
+    for window_sum in window_sums.into_iter().rev() {
+        for _ in 0..window_size {
+            acc.double_in_place();
+        }
+        acc += window_sum;
+    }
+    acc
+}
+
+/// Affine buckets with a batch of pending additions, each to a different bucket.
+struct Buckets<P: SWCurveConfig> {
+    buckets: Vec<Affine<P>>,
+    /// The pending additions, as a bucket index and a point.
+    batch: Vec<(usize, Affine<P>)>,
+    /// Projective buckets for the points whose bucket already has an addition in the
+    /// batch, allocated at the first such point. Frequent digits, such as those of
+    /// boolean witness values, therefore cost mixed additions instead of stalling the
+    /// batch.
+    overflow: Vec<Projective<P>>,
+    /// Whether each bucket has an addition in the batch.
+    scheduled: Vec<bool>,
+    denominators: Vec<P::BaseField>,
+}
+
+impl<P: SWCurveConfig> Buckets<P> {
+    fn new(num_buckets: usize) -> Self {
+        Self {
+            buckets: vec![Affine::identity(); num_buckets],
+            batch: Vec::with_capacity(BATCH_SIZE),
+            overflow: Vec::new(),
+            scheduled: vec![false; num_buckets],
+            denominators: Vec::with_capacity(BATCH_SIZE),
+        }
+    }
+
+    /// Schedules the addition of `point` to the bucket `index`.
+    fn add(&mut self, index: usize, point: Affine<P>) {
+        if self.scheduled[index] {
+            if self.overflow.is_empty() {
+                self.overflow = vec![Projective::zero(); self.buckets.len()];
+            }
+            self.overflow[index] += &point;
+        } else {
+            self.scheduled[index] = true;
+            self.batch.push((index, point));
+        }
+        if self.batch.len() == BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    /// Performs the pending additions.
+    fn flush(&mut self) {
+        // The slope of each addition is `numerator / denominator`, where the denominator
+        // is zero for the additions that need no inversion.
+        self.denominators.clear();
+        self.denominators
+            .extend(self.batch.iter().map(|(index, point)| {
+                let bucket = &self.buckets[*index];
+                if bucket.infinity {
+                    P::BaseField::zero()
+                } else if bucket.x == point.x {
+                    // Doubling, or adding the negation.
+                    if bucket.y == point.y {
+                        point.y.double()
+                    } else {
+                        P::BaseField::zero()
+                    }
+                } else {
+                    point.x - bucket.x
+                }
+            }));
+        batch_inversion(&mut self.denominators);
+
+        for ((index, point), inverse) in self.batch.drain(..).zip(&self.denominators) {
+            self.scheduled[index] = false;
+            let bucket = &mut self.buckets[index];
+            if bucket.infinity {
+                *bucket = point;
+            } else if inverse.is_zero() {
+                *bucket = Affine::identity();
+            } else {
+                let lambda = if bucket.x == point.x {
+                    let x_squared = point.x.square();
+                    (x_squared.double() + x_squared + P::COEFF_A) * inverse
+                } else {
+                    (point.y - bucket.y) * inverse
+                };
+                let x = lambda.square() - bucket.x - point.x;
+                let y = lambda * (bucket.x - x) - bucket.y;
+                *bucket = Affine::new_unchecked(x, y);
+            }
+        }
+    }
+
+    /// Performs the pending additions and returns the affine buckets and the overflow
+    /// buckets, which are empty if no point overflowed.
+    fn finish(mut self) -> (Vec<Affine<P>>, Vec<Projective<P>>) {
+        self.flush();
+        (self.buckets, self.overflow)
+    }
+}
+
+/// Inverts the nonzero entries of `v` with a single field inversion.
+fn batch_inversion<F: Field>(v: &mut [F]) {
+    let mut products = Vec::with_capacity(v.len());
+    let mut acc = F::one();
+    for x in v.iter().filter(|x| !x.is_zero()) {
+        products.push(acc);
+        acc *= x;
+    }
+    let mut inverse = acc
+        .inverse()
+        .expect("the product of nonzero elements is nonzero");
+    for (x, product) in v
+        .iter_mut()
+        .rev()
+        .filter(|x| !x.is_zero())
+        .zip(products.into_iter().rev())
+    {
+        let next = inverse * *x;
+        *x = inverse * product;
+        inverse = next;
+    }
+}
@@ -0,0 +1,268 @@
+//! Well-formedness checks of parameters against a circuit.
+//!
+//! # Specification
+//!
+//! [`Groth16::verify_parameters`] synthesizes the circuit, evaluates its QAP polynomials
+//! `u_j`, `v_j` and `w_j` at a point `t` derived from the parameters, and checks that
+//!
+//! - every query vector has the length the generator gives it for the circuit;
+//! - `alpha_g1`, `beta_g1`, `beta_g2`, `gamma_g2`, `delta_g1`, `delta_g2` and every point
+//!   of `h_query` are not the identity;
+//! - `e(beta_g1, delta_g2) = e(delta_g1, beta_g2)`;
+//! - `a_query[j]`, `b_g1_query[j]` and `b_g2_query[j]` are the identity exactly when
+//!   `u_j(t) = 0`, respectively `v_j(t) = 0`;
+//! - `e(Σ ρ_j · b_g1_query[j], delta_g2) = e(delta_g1, Σ ρ_j · b_g2_query[j])`;
+//! - for the variables with `w_j(t) = 0`, with `k_j` the point of `gamma_abc_g1` or
+//!   `l_query` of the variable and `ε_j` the matching `gamma_g2` or `delta_g2`,
+//!
+//! ```text
+//! Σ e(ρ_j · k_j, ε_j) = e(Σ ρ_j · a_query[j], beta_g2) + e(alpha_g1, Σ ρ_j · b_g2_query[j]),
+//! ```
+//!
+//! with 128-bit challenges `ρ_j` derived by SHA-256 from the parameters. A Groth16 key
+//! does not contain the powers of the secret `τ`, so the relations of the points that
+//! depend on `w_j(τ)` and of `h_query` cannot be checked with pairings. Parameters from a
+//! ceremony should also be checked with [`Groth16::verify_contribution`].
+use crate::{r1cs_to_qap::R1CSToQAP, Groth16, ProvingKey, Vec};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::GeneralEvaluationDomain;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::CanonicalSerialize;
+use ark_std::fmt;
+use sha2::{Digest, Sha256};
+
+/// Domain separator for the challenges of parameter verification.
+const CHALLENGE_DOMAIN: &[u8] = b"ark-groth16/parameters/challenges/v1";
+
+/// A reason why parameters are not well-formed for a circuit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParameterError {
+    /// Synthesizing the circuit failed.
+    Synthesis(SynthesisError),
+    /// A query vector has the wrong length for the circuit.
+    LengthMismatch {
+        /// The name of the query vector.
+        query: &'static str,
+        /// The length for the circuit.
+        expected: usize,
+        /// The length in the parameters.
+        found: usize,
+    },
+    /// An element that must not be the identity is the identity.
+    UnexpectedIdentity {
+        /// The name of the element or query vector.
+        element: &'static str,
+    },
+    /// A point of a query vector is the identity although the polynomial of its
+    /// variable is not zero, or the other way around.
+    UnexpectedZero {
+        /// The name of the query vector.
+        query: &'static str,
+        /// The index of the point in the query vector.
+        index: usize,
+    },
+    /// A pairing relation between the elements does not hold.
+    RelationFailed {
+        /// The elements of the relation.
+        relation: &'static str,
+    },
+}
+
+impl From<SynthesisError> for ParameterError {
+    fn from(e: SynthesisError) -> Self {
+        ParameterError::Synthesis(e)
+    }
+}
+
+impl ark_std::error::Error for ParameterError {}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterError::Synthesis(e) => write!(f, "{}", e),
+            ParameterError::LengthMismatch {
+                query,
+                expected,
+                found,
+            } => write!(f, "{} has {} points, expected {}", query, found, expected),
+            ParameterError::UnexpectedIdentity { element } => {
+                write!(f, "{} contains the identity", element)
+            },
+            ParameterError::UnexpectedZero { query, index } => {
+                write!(f, "{}[{}] does not match the circuit", query, index)
+            },
+            ParameterError::RelationFailed { relation } => {
+                write!(f, "the relation between {} does not hold", relation)
+            },
+        }
+    }
+}
+
+fn check_length(query: &'static str, expected: usize, found: usize) -> Result<(), ParameterError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(ParameterError::LengthMismatch {
+            query,
+            expected,
+            found,
+        })
+    }
+}
+
+fn check_zeros<A: AffineRepr, F: Zero>(
+    query: &'static str,
+    points: &[A],
+    evaluations: &[F],
+) -> Result<(), ParameterError> {
+    match points
+        .iter()
+        .zip(evaluations)
+        .position(|(point, evaluation)| point.is_zero() != evaluation.is_zero())
+    {
+        Some(index) => Err(ParameterError::UnexpectedZero { query, index }),
+        None => Ok(()),
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Check that the parameters `pk` are well-formed for `circuit`, to detect corrupted or
+    /// subverted parameters before proving with them.
+    ///
+    /// See the [module documentation](crate::parameters) for the relations that are
+    /// checked.
+    pub fn verify_parameters<C>(pk: &ProvingKey<E>, circuit: C) -> Result<(), ParameterError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        type D<F> = GeneralEvaluationDomain<F>;
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let num_instance_variables = cs.num_instance_variables();
+
+        let mut key = Vec::new();
+        pk.serialize_compressed(&mut key)
+            .expect("serializing into a `Vec` cannot fail");
+        let hasher = Sha256::new()
+            .chain_update(CHALLENGE_DOMAIN)
+            .chain_update(Sha256::digest(&key));
+        let challenge = |i: u64| {
+            let digest = hasher.clone().chain_update(i.to_le_bytes()).finalize();
+            E::ScalarField::from_le_bytes_mod_order(&digest[..16])
+        };
+
+        // The evaluation point need not be secret: it only separates the zero polynomials of
+        // the circuit from the others.
+        let t =
+            E::ScalarField::from_le_bytes_mod_order(&hasher.clone().chain_update(b"t").finalize());
+        let (u, v, w, _, qap_num_variables, domain_size) =
+            QAP::instance_map_with_evaluation::<E::ScalarField, D<E::ScalarField>>(cs, &t)?;
+        let num_variables = qap_num_variables + 1;
+
+        check_length("a_query", num_variables, pk.a_query.len())?;
+        check_length("b_g1_query", num_variables, pk.b_g1_query.len())?;
+        check_length("b_g2_query", num_variables, pk.b_g2_query.len())?;
+        check_length("h_query", domain_size - 1, pk.h_query.len())?;
+        check_length(
+            "l_query",
+            num_variables - num_instance_variables,
+            pk.l_query.len(),
+        )?;
+        check_length(
+            "gamma_abc_g1",
+            num_instance_variables,
+            pk.vk.gamma_abc_g1.len(),
+        )?;
+
+        for (element, is_zero) in [
+            ("alpha_g1", pk.vk.alpha_g1.is_zero()),
+            ("beta_g1", pk.beta_g1.is_zero()),
+            ("beta_g2", pk.vk.beta_g2.is_zero()),
+            ("gamma_g2", pk.vk.gamma_g2.is_zero()),
+            ("delta_g1", pk.delta_g1.is_zero()),
+            ("delta_g2", pk.vk.delta_g2.is_zero()),
+            ("h_query", pk.h_query.iter().any(|h| h.is_zero())),
+        ] {
+            if is_zero {
+                return Err(ParameterError::UnexpectedIdentity { element });
+            }
+        }
+
+        if !E::multi_pairing(
+            [pk.beta_g1.into_group(), -pk.delta_g1.into_group()],
+            [pk.vk.delta_g2, pk.vk.beta_g2],
+        )
+        .is_zero()
+        {
+            return Err(ParameterError::RelationFailed {
+                relation: "beta and delta",
+            });
+        }
+
+        check_zeros("a_query", &pk.a_query, &u)?;
+        check_zeros("b_g1_query", &pk.b_g1_query, &v)?;
+        check_zeros("b_g2_query", &pk.b_g2_query, &v)?;
+
+        let rho = (0..num_variables as u64).map(challenge).collect::<Vec<_>>();
+        if !E::multi_pairing(
+            [
+                E::G1::msm_unchecked(&pk.b_g1_query, &rho),
+                -pk.delta_g1.into_group(),
+            ],
+            [
+                pk.vk.delta_g2.into_group(),
+                E::G2::msm_unchecked(&pk.b_g2_query, &rho),
+            ],
+        )
+        .is_zero()
+        {
+            return Err(ParameterError::RelationFailed {
+                relation: "b_g1_query and b_g2_query",
+            });
+        }
+
+        // Only the variables without a `w_j` take part in the relation of `alpha` and
+        // `beta` with `gamma_abc_g1` and `l_query`.
+        let rho = rho
+            .iter()
+            .zip(&w)
+            .map(|(rho, w)| {
+                if w.is_zero() {
+                    *rho
+                } else {
+                    E::ScalarField::zero()
+                }
+            })
+            .collect::<Vec<_>>();
+        let (rho_abc, rho_l) = rho.split_at(num_instance_variables);
+        if !E::multi_pairing(
+            [
+                E::G1::msm_unchecked(&pk.vk.gamma_abc_g1, rho_abc),
+                E::G1::msm_unchecked(&pk.l_query, rho_l),
+                -E::G1::msm_unchecked(&pk.a_query, &rho),
+                -pk.vk.alpha_g1.into_group(),
+            ],
+            [
+                pk.vk.gamma_g2.into_group(),
+                pk.vk.delta_g2.into_group(),
+                pk.vk.beta_g2.into_group(),
+                E::G2::msm_unchecked(&pk.b_g2_query, &rho),
+            ],
+        )
+        .is_zero()
+        {
+            return Err(ParameterError::RelationFailed {
+                relation: "alpha, beta, gamma_abc_g1 and l_query",
+            });
+        }
+
+        Ok(())
+    }
+}
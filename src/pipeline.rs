@@ -0,0 +1,116 @@
+use crate::{
+    msm::CpuBackend, prover::Synthesized, r1cs_to_qap::R1CSToQAP, Groth16, Proof, ProvingKey,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{PrimeField, UniformRand};
+use ark_poly::GeneralEvaluationDomain;
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult, SynthesisError};
+use ark_std::rand::Rng;
+use std::{sync::mpsc, thread, vec::Vec};
+use zeroize::Zeroizing;
+
+type D<F> = GeneralEvaluationDomain<F>;
+
+/// A job that has left the QAP witness map stage.
+struct Reduced<F: PrimeField> {
+    r: Zeroizing<F>,
+    s: Zeroizing<F>,
+    num_inputs: usize,
+    full_assignment: Zeroizing<Vec<F>>,
+    h: Zeroizing<Vec<F>>,
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create zero-knowledge proofs for a sequence of instances of the circuit that `pk`
+    /// was generated for.
+    ///
+    /// Witness generation, the R1CS-to-QAP witness map, and the multi-scalar
+    /// multiplications run as three stages on separate threads, connected by channels
+    /// that buffer at most `capacity` jobs. The stages of consecutive proofs therefore
+    /// overlap. Constraint matrices are only built for the first instance and reused
+    /// for the rest; an instance whose number of inputs, witnesses or constraints
+    /// differs from that first instance fails with [`SynthesisError::Unsatisfiable`].
+    ///
+    /// The `i`-th result corresponds to the `i`-th circuit.
+    pub fn prove_pipelined<C, I, R>(
+        pk: &ProvingKey<E>,
+        circuits: I,
+        capacity: usize,
+        rng: &mut R,
+    ) -> Vec<R1CSResult<Proof<E>>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        I: IntoIterator<Item = C>,
+        I::IntoIter: Send,
+        R: Rng + Send,
+    {
+        let circuits = circuits.into_iter();
+        let (synthesized_tx, synthesized_rx) = mpsc::sync_channel(capacity);
+        let (reduced_tx, reduced_rx) = mpsc::sync_channel(capacity);
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                let mut have_matrices = false;
+                for circuit in circuits {
+                    let r = Zeroizing::new(E::ScalarField::rand(rng));
+                    let s = Zeroizing::new(E::ScalarField::rand(rng));
+                    let job = Self::synthesize_job(circuit, r, s, !have_matrices);
+                    if let Ok(job) = &job {
+                        have_matrices |= job.matrices.is_some();
+                    }
+                    if synthesized_tx.send(job).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            scope.spawn(move || {
+                let mut matrices = None;
+                for job in synthesized_rx {
+                    let job = job.and_then(|mut job: Synthesized<E::ScalarField>| {
+                        if job.matrices.is_some() {
+                            matrices = job.matrices.take();
+                        }
+                        let matrices = matrices.as_ref().expect("first job carries the matrices");
+                        if !job.matches(matrices) {
+                            return Err(SynthesisError::Unsatisfiable);
+                        }
+                        let h =
+                            Zeroizing::new(QAP::witness_map_from_matrices::<E::ScalarField, D<_>>(
+                                matrices,
+                                job.num_inputs,
+                                job.num_constraints,
+                                &job.full_assignment,
+                            )?);
+                        Ok(Reduced {
+                            r: job.r,
+                            s: job.s,
+                            num_inputs: job.num_inputs,
+                            full_assignment: job.full_assignment,
+                            h,
+                        })
+                    });
+                    if reduced_tx.send(job).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            reduced_rx
+                .into_iter()
+                .map(|job| {
+                    let job = job?;
+                    Self::create_proof_with_assignment(
+                        pk.into(),
+                        &job.r,
+                        &job.s,
+                        &job.h,
+                        &job.full_assignment[1..job.num_inputs],
+                        &job.full_assignment[job.num_inputs..],
+                        &CpuBackend,
+                    )
+                })
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,327 @@
+//! Import of phase-1 Powers of Tau ceremonies.
+//!
+//! # Specification
+//!
+//! A phase-1 accumulator of size `2^k` for secrets `τ`, `α` and `β` consists of
+//!
+//! ```text
+//! tau_powers_g1        [τ^i]_1      for i < 2^(k+1) - 1,
+//! tau_powers_g2        [τ^i]_2      for i < 2^k,
+//! alpha_tau_powers_g1  [α · τ^i]_1  for i < 2^k,
+//! beta_tau_powers_g1   [β · τ^i]_1  for i < 2^k,
+//! beta_g2              [β]_2,
+//! ```
+//!
+//! in that order. A challenge file is a 64-byte hash of the previous response followed by
+//! the accumulator with uncompressed points; a response file is a 64-byte hash of the
+//! challenge followed by the accumulator with compressed points and the public key of the
+//! contribution. Points use the canonical encoding of the curve, which for BLS12-381 is the
+//! encoding of the `powersoftau` ceremony. Neither the hashes nor the public key are
+//! checked: the transcript of the ceremony should be verified with its own tools.
+//!
+//! [`PowersOfTau::read_ptau`] reads the same accumulator from the `.ptau` files of
+//! snarkjs, whose sections 2 to 6 hold the five parts above with uncompressed points in
+//! little-endian Montgomery form, and the identity as zero coordinates.
+//!
+//! For a circuit whose QAP domain has `n ≤ 2^k` points, the Lagrange basis `[L_i(τ)]` of
+//! the domain is the inverse FFT of `[τ^i]` for `i < n`, in each group. The parameters of
+//! [`Groth16::generate_parameters_from_powers_of_tau`] follow the libsnark reduction with
+//! `γ = δ = 1`, and `h_i = [τ^(i + n) - τ^i]_1` for `i < n - 1`.
+use crate::{
+    circom::{read_field_header, read_sections, read_u32, read_u64, section},
+    r1cs_to_qap::LibsnarkReduction,
+    Groth16, ProvingKey, Vec, VerifyingKey,
+};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, Result as R1CSResult,
+    SynthesisError, SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, Compress, Read, SerializationError, Validate};
+use ark_std::{cfg_iter, vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Length of the hash that starts challenge and response files.
+const HASH_LENGTH: usize = 64;
+
+/// The magic number of snarkjs `.ptau` files.
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+
+/// The largest power of a `.ptau` file, as in snarkjs.
+const MAX_PTAU_POWER: u32 = 28;
+
+/// The accumulator of a phase-1 Powers of Tau ceremony.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowersOfTau<E: Pairing> {
+    /// The points `[τ^i]_1` for `i < 2^(k+1) - 1`.
+    pub tau_powers_g1: Vec<E::G1Affine>,
+    /// The points `[τ^i]_2` for `i < 2^k`.
+    pub tau_powers_g2: Vec<E::G2Affine>,
+    /// The points `[α · τ^i]_1` for `i < 2^k`.
+    pub alpha_tau_powers_g1: Vec<E::G1Affine>,
+    /// The points `[β · τ^i]_1` for `i < 2^k`.
+    pub beta_tau_powers_g1: Vec<E::G1Affine>,
+    /// The point `[β]_2`.
+    pub beta_g2: E::G2Affine,
+}
+
+fn read_points<G: CanonicalDeserialize, R: Read>(
+    mut reader: R,
+    count: usize,
+    compress: Compress,
+) -> Result<Vec<G>, SerializationError> {
+    (0..count)
+        .map(|_| G::deserialize_with_mode(&mut reader, compress, Validate::Yes))
+        .collect()
+}
+
+impl<E: Pairing> PowersOfTau<E> {
+    /// Read the accumulator of size `2^power` from a challenge file.
+    pub fn read_challenge<R: Read>(reader: R, power: u32) -> Result<Self, SerializationError> {
+        Self::read(reader, power, Compress::No)
+    }
+
+    /// Read the accumulator of size `2^power` from a response file. The public key after
+    /// the accumulator is not read.
+    pub fn read_response<R: Read>(reader: R, power: u32) -> Result<Self, SerializationError> {
+        Self::read(reader, power, Compress::Yes)
+    }
+
+    fn read<R: Read>(
+        mut reader: R,
+        power: u32,
+        compress: Compress,
+    ) -> Result<Self, SerializationError> {
+        let size = 1usize
+            .checked_shl(power)
+            .filter(|size| size.checked_mul(2).is_some())
+            .ok_or(SerializationError::InvalidData)?;
+
+        let mut hash = [0u8; HASH_LENGTH];
+        reader.read_exact(&mut hash)?;
+
+        let tau_powers_g1 = read_points(&mut reader, 2 * size - 1, compress)?;
+        let tau_powers_g2 = read_points(&mut reader, size, compress)?;
+        let alpha_tau_powers_g1 = read_points(&mut reader, size, compress)?;
+        let beta_tau_powers_g1 = read_points(&mut reader, size, compress)?;
+        let beta_g2 = E::G2Affine::deserialize_with_mode(&mut reader, compress, Validate::Yes)?;
+
+        Ok(Self {
+            tau_powers_g1,
+            tau_powers_g2,
+            alpha_tau_powers_g1,
+            beta_tau_powers_g1,
+            beta_g2,
+        })
+    }
+
+    /// The largest QAP domain the accumulator supports.
+    pub fn max_domain_size(&self) -> usize {
+        self.tau_powers_g2
+            .len()
+            .min(self.alpha_tau_powers_g1.len())
+            .min(self.beta_tau_powers_g1.len())
+            .min(self.tau_powers_g1.len().div_ceil(2))
+    }
+}
+
+/// Reads a little-endian field element of `n8` bytes in Montgomery form, multiplying it by
+/// `r_inverse`, the inverse of the Montgomery factor.
+fn read_montgomery<F: PrimeField>(
+    reader: &mut &[u8],
+    n8: usize,
+    r_inverse: F,
+) -> Result<F, SerializationError> {
+    let mut bigint = F::BigInt::default();
+    for limb in bigint.as_mut().iter_mut().take(n8 / 8) {
+        *limb = read_u64(&mut *reader)?;
+    }
+    Ok(F::from_bigint(bigint).ok_or(SerializationError::InvalidData)? * r_inverse)
+}
+
+/// Reads `count` uncompressed points of a `.ptau` file from `reader`.
+fn read_ptau_points<P: SWCurveConfig>(
+    mut reader: &[u8],
+    count: usize,
+    n8: usize,
+) -> Result<Vec<Affine<P>>, SerializationError> {
+    type Base<P> = <<P as ark_ec::CurveConfig>::BaseField as Field>::BasePrimeField;
+
+    let degree = P::BaseField::extension_degree() as usize;
+    if reader.len() != count * 2 * degree * n8 {
+        return Err(SerializationError::InvalidData);
+    }
+    let r_inverse = Base::<P>::from(2u64)
+        .pow([8 * n8 as u64])
+        .inverse()
+        .ok_or(SerializationError::InvalidData)?;
+    let coordinate = |reader: &mut &[u8]| {
+        let elements = (0..degree)
+            .map(|_| read_montgomery(reader, n8, r_inverse))
+            .collect::<Result<Vec<_>, _>>()?;
+        P::BaseField::from_base_prime_field_elems(&elements).ok_or(SerializationError::InvalidData)
+    };
+    (0..count)
+        .map(|_| {
+            let x = coordinate(&mut reader)?;
+            let y = coordinate(&mut reader)?;
+            if x.is_zero() && y.is_zero() {
+                return Ok(Affine::identity());
+            }
+            let point = Affine::new_unchecked(x, y);
+            if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+                Ok(point)
+            } else {
+                Err(SerializationError::InvalidData)
+            }
+        })
+        .collect()
+}
+
+impl<P1: SWCurveConfig, P2: SWCurveConfig, E> PowersOfTau<E>
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+{
+    /// Read the accumulator from a snarkjs `.ptau` file, of the size the file declares.
+    ///
+    /// The prime of the file must be the modulus of the base field of `E::G1`. The
+    /// contributions and the Lagrange bases that snarkjs stores after the accumulator are
+    /// not read.
+    pub fn read_ptau<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        let sections = read_sections(reader, PTAU_MAGIC)?;
+
+        let mut header = section(&sections, 1)?;
+        let n8 = read_field_header::<<P1::BaseField as Field>::BasePrimeField, _>(&mut header)?;
+        let power = read_u32(&mut header)?;
+        if power > MAX_PTAU_POWER {
+            return Err(SerializationError::InvalidData);
+        }
+        let size = 1usize << power;
+
+        Ok(Self {
+            tau_powers_g1: read_ptau_points(section(&sections, 2)?, 2 * size - 1, n8)?,
+            tau_powers_g2: read_ptau_points(section(&sections, 3)?, size, n8)?,
+            alpha_tau_powers_g1: read_ptau_points(section(&sections, 4)?, size, n8)?,
+            beta_tau_powers_g1: read_ptau_points(section(&sections, 5)?, size, n8)?,
+            beta_g2: read_ptau_points(section(&sections, 6)?, 1, n8)?[0],
+        })
+    }
+}
+
+/// Adds `Σ_i rows[i][j] · bases[i]` to `sums[j]` for every column `j` of the constraint
+/// matrix `rows`.
+fn accumulate<G: CurveGroup>(sums: &mut [G], rows: &[Vec<(G::ScalarField, usize)>], bases: &[G]) {
+    for (row, base) in rows.iter().zip(bases) {
+        for (coeff, index) in row {
+            sums[*index] += *base * coeff;
+        }
+    }
+}
+
+impl<E: Pairing> Groth16<E, LibsnarkReduction> {
+    /// Create parameters for a circuit from the accumulator `powers` of a phase-1 Powers of
+    /// Tau ceremony.
+    ///
+    /// The parameters have `γ = δ = 1` and must not be used before at least one phase-2
+    /// contribution, for example with [`Groth16::contribute`], rerandomizes `δ`.
+    pub fn generate_parameters_from_powers_of_tau<C>(
+        circuit: C,
+        powers: &PowersOfTau<E>,
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        type D<F> = GeneralEvaluationDomain<F>;
+
+        let setup_time = start_timer!(|| "Groth16::Generator from Powers of Tau");
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+
+        let num_instance_variables = cs.num_instance_variables();
+        let num_constraints = cs.num_constraints();
+        let num_variables = num_instance_variables + cs.num_witness_variables();
+        let domain = D::<E::ScalarField>::new(num_constraints + num_instance_variables)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let n = domain.size();
+        if n > powers.max_domain_size() {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
+        let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+
+        let lagrange_time = start_timer!(|| "Compute Lagrange bases");
+        let lagrange = |points: &[E::G1Affine]| {
+            domain.ifft(
+                &cfg_iter!(points[..n])
+                    .map(|p| p.into_group())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let tau_g1 = lagrange(&powers.tau_powers_g1);
+        let alpha_tau_g1 = lagrange(&powers.alpha_tau_powers_g1);
+        let beta_tau_g1 = lagrange(&powers.beta_tau_powers_g1);
+        let tau_g2 = domain.ifft(
+            &cfg_iter!(powers.tau_powers_g2[..n])
+                .map(|p| p.into_group())
+                .collect::<Vec<_>>(),
+        );
+        end_timer!(lagrange_time);
+
+        let query_time = start_timer!(|| "Compute queries");
+        let mut a = vec![E::G1::zero(); num_variables];
+        let mut b_g1 = vec![E::G1::zero(); num_variables];
+        let mut b_g2 = vec![E::G2::zero(); num_variables];
+        let mut abc = vec![E::G1::zero(); num_variables];
+        // The libsnark reduction adds a constraint `x_i * 1 = 0` for every input.
+        a[..num_instance_variables]
+            .copy_from_slice(&tau_g1[num_constraints..num_constraints + num_instance_variables]);
+        abc[..num_instance_variables].copy_from_slice(
+            &beta_tau_g1[num_constraints..num_constraints + num_instance_variables],
+        );
+        accumulate(&mut a, &matrices.a, &tau_g1);
+        accumulate(&mut b_g1, &matrices.b, &tau_g1);
+        accumulate(&mut b_g2, &matrices.b, &tau_g2);
+        accumulate(&mut abc, &matrices.a, &beta_tau_g1);
+        accumulate(&mut abc, &matrices.b, &alpha_tau_g1);
+        accumulate(&mut abc, &matrices.c, &tau_g1);
+
+        let h_query = cfg_iter!(powers.tau_powers_g1[n..2 * n - 1])
+            .zip(&powers.tau_powers_g1[..n - 1])
+            .map(|(high, low)| high.into_group() - low)
+            .collect::<Vec<_>>();
+        end_timer!(query_time);
+
+        let g1_generator = powers.tau_powers_g1[0];
+        let g2_generator = powers.tau_powers_g2[0];
+        let vk = VerifyingKey::<E> {
+            alpha_g1: powers.alpha_tau_powers_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: g2_generator,
+            delta_g2: g2_generator,
+            gamma_abc_g1: E::G1::normalize_batch(&abc[..num_instance_variables]),
+        };
+        let pk = ProvingKey {
+            vk,
+            beta_g1: powers.beta_tau_powers_g1[0],
+            delta_g1: g1_generator,
+            a_query: E::G1::normalize_batch(&a),
+            b_g1_query: E::G1::normalize_batch(&b_g1),
+            b_g2_query: E::G2::normalize_batch(&b_g2),
+            h_query: E::G1::normalize_batch(&h_query),
+            l_query: E::G1::normalize_batch(&abc[num_instance_variables..]),
+        };
+        end_timer!(setup_time);
+
+        Ok(pk)
+    }
+}
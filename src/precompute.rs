@@ -0,0 +1,366 @@
+#[cfg(feature = "prover")]
+use crate::{
+    msm::{CpuBackend, MsmBackend, MsmQuery, ScalarBigInt},
+    ProverError, ProvingKey,
+};
+use crate::{
+    prepare_verifying_key, r1cs_to_qap::R1CSToQAP, Groth16, PreparedInputs, PreparedVerifyingKey,
+    Proof, VerifyingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
+#[cfg(feature = "prover")]
+use ark_ff::UniformRand;
+use ark_ff::{BigInteger, PrimeField, Zero};
+#[cfg(feature = "prover")]
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+#[cfg(feature = "prover")]
+use ark_std::rand::Rng;
+use ark_std::{cfg_chunks, cfg_iter, vec, vec::Vec};
+#[cfg(feature = "prover")]
+use zeroize::Zeroizing;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The largest window size in bits of a multi-scalar multiplication, which bounds the
+/// memory of its buckets.
+pub const MAX_WINDOW_SIZE: usize = 24;
+
+/// Precomputed multiples of a fixed set of bases, for fast repeated multi-scalar
+/// multiplications with them.
+///
+/// For every base `P` and every window `j`, the table stores `2^(window_size * j) * P`.
+/// A multi-scalar multiplication then needs a single bucket-accumulation pass and no
+/// doublings, at the cost of storing `ceil(MODULUS_BIT_SIZE / window_size)` points per base.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FixedBaseTable<G: AffineRepr> {
+    window_size: usize,
+    num_windows: usize,
+    table: Vec<G>,
+}
+
+impl<G: AffineRepr> FixedBaseTable<G> {
+    /// Precompute the table for `bases` with windows of `window_size` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is zero or larger than [`MAX_WINDOW_SIZE`].
+    pub fn new(bases: &[G], window_size: usize) -> Self {
+        assert!(
+            (1..=MAX_WINDOW_SIZE).contains(&window_size),
+            "window size must be between 1 and {} bits",
+            MAX_WINDOW_SIZE
+        );
+        let num_windows = (G::ScalarField::MODULUS_BIT_SIZE as usize).div_ceil(window_size);
+
+        let table = cfg_iter!(bases)
+            .flat_map(|base| {
+                let mut multiple = base.into_group();
+                (0..num_windows)
+                    .map(|_| {
+                        let current = multiple;
+                        for _ in 0..window_size {
+                            multiple.double_in_place();
+                        }
+                        current
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            window_size,
+            num_windows,
+            table: G::Group::normalize_batch(&table),
+        }
+    }
+
+    /// The number of bases in the table.
+    pub fn len(&self) -> usize {
+        self.table.len() / self.num_windows
+    }
+
+    /// Whether the table has no bases.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// The window size in bits.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Computes `sum_i scalars[i] * bases[i]`. Extra scalars or bases are ignored.
+    pub fn msm(&self, scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G::Group {
+        let scalars = &scalars[..scalars.len().min(self.len())];
+        let chunk_size = chunk_size(scalars.len());
+
+        cfg_chunks!(scalars, chunk_size)
+            .enumerate()
+            .map(|(chunk, scalars)| {
+                let offset = chunk * chunk_size;
+                let mut buckets = vec![G::Group::zero(); (1 << self.window_size) - 1];
+                for (i, scalar) in scalars.iter().enumerate() {
+                    let multiples = &self.table[(offset + i) * self.num_windows..];
+                    for (j, multiple) in multiples[..self.num_windows].iter().enumerate() {
+                        let digit = window(scalar, j * self.window_size, self.window_size);
+                        if digit != 0 {
+                            buckets[digit - 1] += multiple;
+                        }
+                    }
+                }
+
+                // sum_k (k + 1) * buckets[k], via running sums.
+                let mut running_sum = G::Group::zero();
+                let mut acc = G::Group::zero();
+                for bucket in buckets.into_iter().rev() {
+                    running_sum += bucket;
+                    acc += running_sum;
+                }
+                acc
+            })
+            .sum()
+    }
+}
+
+/// Splits the scalars across threads when the `parallel` feature is enabled.
+fn chunk_size(len: usize) -> usize {
+    #[cfg(feature = "parallel")]
+    let threads = rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    let threads = 1;
+    len.div_ceil(threads).max(1)
+}
+
+/// Extracts the `width` bits of `scalar` starting at bit `offset`.
+pub(crate) fn window<B: BigInteger>(scalar: &B, offset: usize, width: usize) -> usize {
+    let limbs = scalar.as_ref();
+    let (limb, shift) = (offset / 64, offset % 64);
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut bits = limbs[limb] >> shift;
+    if shift + width > 64 && limb + 1 < limbs.len() {
+        bits |= limbs[limb + 1] << (64 - shift);
+    }
+    (bits & ((1 << width) - 1)) as usize
+}
+
+#[cfg(feature = "prover")]
+/// A [`ProvingKey`] together with fixed-base tables for its `a_query`, `b_g1_query`,
+/// `b_g2_query`, and `l_query`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecomputedProvingKey<E: Pairing> {
+    pk: ProvingKey<E>,
+    a_query: FixedBaseTable<E::G1Affine>,
+    b_g1_query: FixedBaseTable<E::G1Affine>,
+    b_g2_query: FixedBaseTable<E::G2Affine>,
+    l_query: FixedBaseTable<E::G1Affine>,
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing> PrecomputedProvingKey<E> {
+    /// Precompute fixed-base tables for `pk` with windows of `window_size` bits.
+    /// The tables take about `ceil(MODULUS_BIT_SIZE / window_size)` times the memory
+    /// of the queries they cover.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if `a_query` and the `b_*_query`s of
+    /// `pk` do not have one point for each variable, including the constant, or if
+    /// `l_query` has more points than there are witness variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is zero or larger than [`MAX_WINDOW_SIZE`].
+    pub fn new(pk: ProvingKey<E>, window_size: usize) -> Result<Self, ProverError> {
+        let num_variables = pk.a_query.len();
+        if num_variables == 0
+            || pk.b_g1_query.len() != num_variables
+            || pk.b_g2_query.len() != num_variables
+            || pk.l_query.len() >= num_variables
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+        Ok(Self {
+            a_query: FixedBaseTable::new(&pk.a_query[1..], window_size),
+            b_g1_query: FixedBaseTable::new(&pk.b_g1_query[1..], window_size),
+            b_g2_query: FixedBaseTable::new(&pk.b_g2_query[1..], window_size),
+            l_query: FixedBaseTable::new(&pk.l_query, window_size),
+            pk,
+        })
+    }
+
+    /// The underlying proving key.
+    pub fn pk(&self) -> &ProvingKey<E> {
+        &self.pk
+    }
+
+    /// Returns the underlying proving key, dropping its tables.
+    pub fn into_inner(self) -> ProvingKey<E> {
+        self.pk
+    }
+}
+
+#[cfg(feature = "prover")]
+/// Answers the MSMs over the precomputed queries from their tables, and all others
+/// with [`CpuBackend`].
+struct PrecomputedBackend<'a, E: Pairing>(&'a PrecomputedProvingKey<E>);
+
+#[cfg(feature = "prover")]
+impl<E: Pairing> MsmBackend<E> for PrecomputedBackend<'_, E> {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        let ppk = self.0;
+        match query {
+            MsmQuery::A => ppk.a_query.msm(scalars),
+            MsmQuery::BG1 => ppk.b_g1_query.msm(scalars),
+            MsmQuery::L => ppk.l_query.msm(scalars),
+            MsmQuery::H | MsmQuery::BG2 => {
+                MsmBackend::<E>::msm_g1(&CpuBackend, query, bases, scalars)
+            },
+        }
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        match query {
+            MsmQuery::BG2 => self.0.b_g2_query.msm(scalars),
+            _ => MsmBackend::<E>::msm_g2(&CpuBackend, query, bases, scalars),
+        }
+    }
+}
+
+#[cfg(feature = "prover")]
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction and the fixed-base tables of `ppk`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_precomputation<C>(
+        circuit: C,
+        ppk: &PrecomputedProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::prove_with_backend(circuit, (&ppk.pk).into(), &r, &s, &PrecomputedBackend(ppk))
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s`, the provided
+    /// R1CS-to-QAP reduction, and the fixed-base tables of `ppk`.
+    pub fn create_proof_with_precomputation<C>(
+        circuit: C,
+        ppk: &PrecomputedProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        Self::create_proof_with_reduction_and_backend(
+            circuit,
+            &ppk.pk,
+            r,
+            s,
+            &PrecomputedBackend(ppk),
+        )
+    }
+}
+
+/// A [`PreparedVerifyingKey`] together with an optional fixed-base table for its
+/// `gamma_abc_g1`, built with [`PreparedVerifyingKeyBuilder`].
+#[derive(Clone, Debug)]
+pub struct PrecomputedVerifyingKey<E: Pairing> {
+    /// The underlying prepared verifying key.
+    pub pvk: PreparedVerifyingKey<E>,
+    gamma_abc_g1: Option<FixedBaseTable<E::G1Affine>>,
+}
+
+impl<E: Pairing> PrecomputedVerifyingKey<E> {
+    /// Prepare the instance `public_inputs` like [`Groth16::prepare_public_inputs`], with the
+    /// table for `gamma_abc_g1` if there is one.
+    pub fn prepare_public_inputs(
+        &self,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<PreparedInputs<E>> {
+        let table = match &self.gamma_abc_g1 {
+            Some(table) => table,
+            None => return Groth16::<E>::prepare_public_inputs(&self.pvk, public_inputs),
+        };
+        let ic_0 = self
+            .pvk
+            .vk
+            .gamma_abc_g1
+            .first()
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        if public_inputs.len() != self.pvk.vk.num_public_inputs() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        let scalars = cfg_iter!(public_inputs)
+            .map(|input| input.into_bigint())
+            .collect::<Vec<_>>();
+        Ok(PreparedInputs {
+            prepared_inputs: (table.msm(&scalars) + ic_0).into_affine(),
+        })
+    }
+}
+
+/// Builds a [`PrecomputedVerifyingKey`] from a [`VerifyingKey`].
+///
+/// Without options this is [`prepare_verifying_key`]. A table for `gamma_abc_g1` speeds
+/// up the input MSM of circuits with many public inputs, and takes about
+/// `ceil(MODULUS_BIT_SIZE / window_size)` times the memory of `gamma_abc_g1`.
+#[derive(Clone, Copy, Debug)]
+pub struct PreparedVerifyingKeyBuilder<'a, E: Pairing> {
+    vk: &'a VerifyingKey<E>,
+    gamma_abc_window_size: Option<usize>,
+}
+
+impl<'a, E: Pairing> PreparedVerifyingKeyBuilder<'a, E> {
+    /// Start building a prepared key for `vk`.
+    pub fn new(vk: &'a VerifyingKey<E>) -> Self {
+        Self {
+            vk,
+            gamma_abc_window_size: None,
+        }
+    }
+
+    /// Precompute a table for `gamma_abc_g1` with windows of `window_size` bits.
+    pub fn gamma_abc_table(mut self, window_size: usize) -> Self {
+        self.gamma_abc_window_size = Some(window_size);
+        self
+    }
+
+    /// Prepare the key and precompute the requested tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window size of a table is zero or larger than [`MAX_WINDOW_SIZE`].
+    pub fn build(self) -> PrecomputedVerifyingKey<E> {
+        let gamma_abc_g1 = self.gamma_abc_window_size.map(|window_size| {
+            FixedBaseTable::new(
+                self.vk.gamma_abc_g1.get(1..).unwrap_or_default(),
+                window_size,
+            )
+        });
+        PrecomputedVerifyingKey {
+            pvk: prepare_verifying_key(self.vk),
+            gamma_abc_g1,
+        }
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify a Groth16 proof `proof` like [`verify_proof`], with the tables of `ppvk`.
+    ///
+    /// [`verify_proof`]: Self::verify_proof
+    pub fn verify_proof_with_precomputed_key(
+        ppvk: &PrecomputedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        let prepared_inputs = ppvk.prepare_public_inputs(public_inputs)?;
+        Self::verify_proof_with_prepared_public_inputs(&ppvk.pvk, proof, &prepared_inputs)
+    }
+}
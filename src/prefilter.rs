@@ -0,0 +1,147 @@
+use crate::{
+    r1cs_to_qap::R1CSToQAP, verifier::check_num_public_inputs, Groth16, PreparedVerifyingKey,
+    Proof, VerificationError,
+};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Domain separator for the proof-of-work hash.
+const POW_DOMAIN: &[u8] = b"ark-groth16/proof-of-work/v1";
+
+/// Deployment-specific configuration of the cheap checks that
+/// [`Groth16::prefilter_proof`] runs before any pairing is computed.
+///
+/// The proof of work is bound to `pow_challenge`, which the verifier chooses. A solved
+/// nonce stays valid for as long as the challenge does, so a verifier that uses the proof
+/// of work for rate limiting must replace the challenge regularly, for example with a
+/// random value or a counter per time epoch, and must reject a `(pow_challenge, nonce)`
+/// pair that it has already accepted. The prefilter itself keeps no state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrefilterConfig {
+    /// Check that `a`, `b` and `c` are non-identity and lie in the prime-order subgroup.
+    pub check_subgroups: bool,
+    /// If set, require a proof-of-work nonce such that the tag hash has at
+    /// least this many leading zero bits.
+    pub pow_difficulty: Option<u32>,
+    /// The verifier-chosen challenge that the proof-of-work tag hash covers.
+    pub pow_challenge: [u8; 32],
+}
+
+impl Default for PrefilterConfig {
+    fn default() -> Self {
+        Self {
+            check_subgroups: true,
+            pow_difficulty: None,
+            pow_challenge: [0; 32],
+        }
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Run the checks enabled in `config` against `proof` and `public_inputs`, cheapest first.
+    /// The number of public inputs is always checked. No pairing is computed, so this can be
+    /// used to shed garbage proofs before they reach [`Groth16::verify_proof`].
+    pub fn prefilter_proof(
+        config: &PrefilterConfig,
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+        pow_nonce: Option<u64>,
+    ) -> Result<(), VerificationError> {
+        check_num_public_inputs(pvk, public_inputs.len())?;
+
+        if let Some(difficulty) = config.pow_difficulty {
+            let nonce = pow_nonce.ok_or(VerificationError::InsufficientProofOfWork)?;
+            if leading_zeros(
+                pow_hasher(&config.pow_challenge, proof, public_inputs),
+                nonce,
+            ) < difficulty
+            {
+                return Err(VerificationError::InsufficientProofOfWork);
+            }
+        }
+
+        let points_ok = || {
+            is_valid_proof_point(&proof.a)
+                && is_valid_proof_point(&proof.b)
+                && is_valid_proof_point(&proof.c)
+        };
+        if config.check_subgroups && !points_ok() {
+            return Err(VerificationError::MalformedProofPoint);
+        }
+
+        Ok(())
+    }
+
+    /// Verify `proof` with [`Groth16::verify_proof`], but only after it has passed
+    /// [`Groth16::prefilter_proof`].
+    pub fn verify_proof_with_prefilter(
+        config: &PrefilterConfig,
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+        pow_nonce: Option<u64>,
+    ) -> Result<bool, VerificationError> {
+        Self::prefilter_proof(config, pvk, proof, public_inputs, pow_nonce)?;
+        Ok(Self::verify_proof(pvk, proof, public_inputs)?)
+    }
+
+    /// Find the smallest nonce whose proof-of-work tag for the verifier's `challenge`,
+    /// `proof` and `public_inputs` has at least `difficulty` leading zero bits. This is
+    /// meant to be run by the party submitting the proof.
+    pub fn solve_proof_of_work(
+        challenge: &[u8; 32],
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+        difficulty: u32,
+    ) -> u64 {
+        let hasher = pow_hasher(challenge, proof, public_inputs);
+        (0..=u64::MAX)
+            .find(|nonce| leading_zeros(hasher.clone(), *nonce) >= difficulty)
+            .expect("exhausted the nonce space")
+    }
+}
+
+/// Checks that `p` is not the identity and lies in the prime-order subgroup.
+pub(crate) fn is_valid_proof_point<G: AffineRepr>(p: &G) -> bool {
+    !p.is_zero()
+        && p.mul_bigint(<G::ScalarField as PrimeField>::MODULUS)
+            .is_zero()
+}
+
+/// Absorbs `POW_DOMAIN || challenge || proof || public_inputs` into a fresh SHA-256 state.
+fn pow_hasher<E: Pairing>(
+    challenge: &[u8; 32],
+    proof: &Proof<E>,
+    public_inputs: &[E::ScalarField],
+) -> Sha256 {
+    let mut bytes = Vec::new();
+    proof
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    public_inputs
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+
+    Sha256::new()
+        .chain_update(POW_DOMAIN)
+        .chain_update(challenge)
+        .chain_update(&bytes)
+}
+
+/// Computes the number of leading zero bits of the tag hash after absorbing `nonce`.
+fn leading_zeros(hasher: Sha256, nonce: u64) -> u32 {
+    let digest = hasher.chain_update(nonce.to_le_bytes()).finalize();
+
+    let mut zeros = 0;
+    for byte in digest.iter() {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
@@ -0,0 +1,19 @@
+//! Re-exports of the items needed to set up, prove, and verify with [`Groth16`].
+//!
+//! ```
+//! use ark_groth16::prelude::*;
+//! ```
+
+#[cfg(feature = "prover")]
+pub use crate::ProverError;
+pub use crate::{
+    prepare_verifying_key,
+    r1cs_to_qap::{LibsnarkReduction, R1CSToQAP},
+    Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerificationError, VerifyingKey,
+};
+pub use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+pub use ark_ec::pairing::Pairing;
+pub use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+pub use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate,
+};
@@ -0,0 +1,228 @@
+//! A preprocessed in-memory form of proving keys.
+//!
+//! The queries of a proving key usually contain many identity points: a variable that
+//! does not occur in the `A` or `B` side of any constraint has the identity as its point
+//! in `a_query` or in both `b_*_query`s. The prover would still read its scalar and point
+//! in every window of the Pippenger multi-scalar multiplication. A
+//! [`PreprocessedProvingKey`] stores each of these queries without its identity points,
+//! together with the indices of the remaining ones, and fixes the window size of every
+//! multi-scalar multiplication once.
+//!
+//! The preprocessed form only lives in memory. To store a key, serialize the
+//! [`ProvingKey`] it wraps and preprocess it again after loading it.
+use crate::{
+    config::{num_threads, pippenger, WindowTuning},
+    msm::{MsmBackend, MsmQuery, ScalarBigInt},
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProverError, ProvingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand};
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult};
+use ark_std::{rand::Rng, vec::Vec};
+use zeroize::Zeroizing;
+
+/// A query without its identity points, with the window size of its multi-scalar
+/// multiplications.
+#[derive(Clone, Debug, PartialEq)]
+struct CompactQuery<G: AffineRepr> {
+    bases: Vec<G>,
+    /// The index in the query of each base, or `None` if the query had no identity
+    /// points.
+    indices: Option<Vec<usize>>,
+    window_size: Option<usize>,
+}
+
+impl<G> CompactQuery<G>
+where
+    G: AffineRepr,
+    G::Group: VariableBaseMSM<MulBase = G>,
+{
+    fn new(query: &[G], compact: bool) -> Self {
+        if !compact || query.iter().all(|base| !base.is_zero()) {
+            return Self {
+                bases: query.to_vec(),
+                indices: None,
+                window_size: None,
+            };
+        }
+        let (indices, bases) = query
+            .iter()
+            .enumerate()
+            .filter(|(_, base)| !base.is_zero())
+            .unzip();
+        Self {
+            bases,
+            indices: Some(indices),
+            window_size: None,
+        }
+    }
+
+    /// The number of identity points removed from a query of `len` points.
+    fn num_removed(&self, len: usize) -> usize {
+        len - self.bases.len()
+    }
+
+    fn msm(&self, scalars: &[<G::ScalarField as PrimeField>::BigInt]) -> G::Group {
+        let gathered;
+        let scalars = match &self.indices {
+            Some(indices) => {
+                gathered = indices
+                    .iter()
+                    .map_while(|i| scalars.get(*i).copied())
+                    .collect::<Vec<_>>();
+                &gathered[..]
+            },
+            None => scalars,
+        };
+        let len = self.bases.len().min(scalars.len());
+        let (bases, scalars) = (&self.bases[..len], &scalars[..len]);
+        match self.window_size {
+            Some(window_size) => pippenger(bases, scalars, window_size),
+            None => G::Group::msm_bigint(bases, scalars),
+        }
+    }
+}
+
+/// A [`ProvingKey`] together with its queries in the form that the prover reads fastest.
+///
+/// Preprocessing copies the queries once, so it takes up to twice the memory of the key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreprocessedProvingKey<E: Pairing> {
+    pk: ProvingKey<E>,
+    a_query: CompactQuery<E::G1Affine>,
+    b_g1_query: CompactQuery<E::G1Affine>,
+    b_g2_query: CompactQuery<E::G2Affine>,
+    h_query: CompactQuery<E::G1Affine>,
+    l_query: CompactQuery<E::G1Affine>,
+}
+
+impl<E: Pairing> PreprocessedProvingKey<E> {
+    /// Preprocess `pk`, leaving the window sizes to `ark-ec`.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if `a_query` and the `b_*_query`s of
+    /// `pk` do not have one point for each variable, including the constant, or if
+    /// `l_query` has more points than there are witness variables.
+    pub fn new(pk: ProvingKey<E>) -> Result<Self, ProverError> {
+        let num_variables = pk.a_query.len();
+        if num_variables == 0
+            || pk.b_g1_query.len() != num_variables
+            || pk.b_g2_query.len() != num_variables
+            || pk.l_query.len() >= num_variables
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+        Ok(Self {
+            a_query: CompactQuery::new(&pk.a_query[1..], true),
+            b_g1_query: CompactQuery::new(&pk.b_g1_query[1..], true),
+            b_g2_query: CompactQuery::new(&pk.b_g2_query[1..], true),
+            // The points of `h_query` are powers of a secret and never the identity.
+            h_query: CompactQuery::new(&pk.h_query, false),
+            l_query: CompactQuery::new(&pk.l_query, true),
+            pk,
+        })
+    }
+
+    /// Preprocess `pk`, and choose the window size of each query from the number of its
+    /// remaining points with `tuning`, for as many threads as the current pool has.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] under the same conditions as
+    /// [`PreprocessedProvingKey::new`].
+    pub fn with_tuning(pk: ProvingKey<E>, tuning: &WindowTuning) -> Result<Self, ProverError> {
+        let mut ppk = Self::new(pk)?;
+        let num_bits = E::ScalarField::MODULUS_BIT_SIZE as usize;
+        let num_threads = num_threads();
+        let g1_windows = |len: usize| Some(tuning.g1.window_size(len, num_bits, num_threads));
+        ppk.a_query.window_size = g1_windows(ppk.a_query.bases.len());
+        ppk.b_g1_query.window_size = g1_windows(ppk.b_g1_query.bases.len());
+        ppk.h_query.window_size = g1_windows(ppk.h_query.bases.len());
+        ppk.l_query.window_size = g1_windows(ppk.l_query.bases.len());
+        ppk.b_g2_query.window_size = Some(tuning.g2.window_size(
+            ppk.b_g2_query.bases.len(),
+            num_bits,
+            num_threads,
+        ));
+        Ok(ppk)
+    }
+
+    /// The underlying proving key.
+    pub fn pk(&self) -> &ProvingKey<E> {
+        &self.pk
+    }
+
+    /// Returns the underlying proving key, dropping its preprocessed queries.
+    pub fn into_inner(self) -> ProvingKey<E> {
+        self.pk
+    }
+
+    /// The number of identity points that preprocessing removed from the queries.
+    pub fn num_removed_points(&self) -> usize {
+        // `new` checked that `a_query` and the `b_*_query`s are not empty.
+        self.a_query.num_removed(self.pk.a_query.len() - 1)
+            + self.b_g1_query.num_removed(self.pk.b_g1_query.len() - 1)
+            + self.b_g2_query.num_removed(self.pk.b_g2_query.len() - 1)
+            + self.l_query.num_removed(self.pk.l_query.len())
+    }
+}
+
+/// Answers the MSMs over the queries of the key from their preprocessed form.
+struct PreprocessedBackend<'a, E: Pairing>(&'a PreprocessedProvingKey<E>);
+
+impl<E: Pairing> MsmBackend<E> for PreprocessedBackend<'_, E> {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        let ppk = self.0;
+        match query {
+            MsmQuery::A => ppk.a_query.msm(scalars),
+            MsmQuery::BG1 => ppk.b_g1_query.msm(scalars),
+            MsmQuery::H => ppk.h_query.msm(scalars),
+            MsmQuery::L => ppk.l_query.msm(scalars),
+            MsmQuery::BG2 => E::G1::msm_bigint(bases, scalars),
+        }
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        match query {
+            MsmQuery::BG2 => self.0.b_g2_query.msm(scalars),
+            _ => E::G2::msm_bigint(bases, scalars),
+        }
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction and the preprocessed key `ppk`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_preprocessed_key<C>(
+        circuit: C,
+        ppk: &PreprocessedProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::prove_with_backend(circuit, (&ppk.pk).into(), &r, &s, &PreprocessedBackend(ppk))
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s`, the provided
+    /// R1CS-to-QAP reduction, and the preprocessed key `ppk`.
+    pub fn create_proof_with_preprocessed_key<C>(
+        circuit: C,
+        ppk: &PreprocessedProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        Self::create_proof_with_reduction_and_backend(
+            circuit,
+            &ppk.pk,
+            r,
+            s,
+            &PreprocessedBackend(ppk),
+        )
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    generator::SecretSetupRandomness,
+    msm::{CpuBackend, MsmBackend, MsmQuery, ScalarBigInt},
+    r1cs_to_qap::R1CSToQAP,
+    Groth16, Proof, ProvingKey,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{UniformRand, Zero};
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult};
+use ark_std::rand::Rng;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use zeroize::Zeroizing;
+
+/// A stage of proof generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProvingStage {
+    /// Generating the witness by synthesizing the circuit.
+    Synthesis,
+    /// Computing the QAP witness `h` from the assignment.
+    WitnessMap,
+    /// Computing the multi-scalar multiplications of the proof.
+    Msm,
+}
+
+/// Receives progress reports from the prover.
+///
+/// Each stage is reported at 0 percent when it starts and at 100 percent when it ends,
+/// and the [`Msm`](ProvingStage::Msm) stage additionally after each multi-scalar
+/// multiplication. Reports may come from several threads at once.
+pub trait ProgressObserver: Sync {
+    /// Called with the current stage and the percentage of it that is done.
+    fn on_progress(&self, stage: ProvingStage, percent: u8);
+}
+
+impl ProgressObserver for () {
+    fn on_progress(&self, _: ProvingStage, _: u8) {}
+}
+
+impl<F: Fn(ProvingStage, u8) + Sync> ProgressObserver for F {
+    fn on_progress(&self, stage: ProvingStage, percent: u8) {
+        self(stage, percent)
+    }
+}
+
+/// A stage of parameter generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SetupStage {
+    /// Synthesizing the constraints of the circuit.
+    Synthesis,
+    /// Evaluating the QAP polynomials of the circuit at the secret point.
+    InstanceMap,
+    /// Computing `a_query`.
+    AQuery,
+    /// Computing `b_g1_query`.
+    BG1Query,
+    /// Computing `b_g2_query`.
+    BG2Query,
+    /// Computing `h_query`.
+    HQuery,
+    /// Computing `l_query`.
+    LQuery,
+    /// Computing `gamma_abc_g1` of the verifying key.
+    GammaAbcQuery,
+}
+
+/// Receives progress reports from parameter generation.
+///
+/// Each stage is reported at 0 percent when it starts and at 100 percent when it ends,
+/// and the stages of the query vectors additionally after each percent or so of their
+/// points. `b_g2_query` is computed concurrently with other stages, so reports may come
+/// from several threads at once.
+pub trait SetupObserver: Sync {
+    /// Called with the current stage and the percentage of it that is done.
+    fn on_progress(&self, stage: SetupStage, percent: u8);
+}
+
+impl SetupObserver for () {
+    fn on_progress(&self, _: SetupStage, _: u8) {}
+}
+
+impl<F: Fn(SetupStage, u8) + Sync> SetupObserver for F {
+    fn on_progress(&self, stage: SetupStage, percent: u8) {
+        self(stage, percent)
+    }
+}
+
+/// Reports the progress of the [`Msm`](ProvingStage::Msm) stage as the MSMs of
+/// `backend` complete.
+struct ProgressBackend<'a, B, O> {
+    backend: &'a B,
+    observer: &'a O,
+    done: AtomicUsize,
+    total: usize,
+}
+
+impl<B, O: ProgressObserver> ProgressBackend<'_, B, O> {
+    fn report(&self) {
+        let done = self.done.fetch_add(1, Ordering::SeqCst) + 1;
+        let percent = (done * 100 / self.total).min(100) as u8;
+        self.observer.on_progress(ProvingStage::Msm, percent);
+    }
+}
+
+impl<E: Pairing, B: MsmBackend<E>, O: ProgressObserver> MsmBackend<E>
+    for ProgressBackend<'_, B, O>
+{
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        let acc = self.backend.msm_g1(query, bases, scalars);
+        self.report();
+        acc
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        let acc = self.backend.msm_g2(query, bases, scalars);
+        self.report();
+        acc
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Generates a random common reference string for a circuit using the provided
+    /// R1CS-to-QAP reduction, reporting progress to `observer`.
+    pub fn generate_random_parameters_with_progress<C, O>(
+        circuit: C,
+        observer: &O,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<ProvingKey<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: SetupObserver,
+    {
+        let secret = SecretSetupRandomness::sample(rng);
+
+        let g1_generator = E::G1::rand(rng);
+        let g2_generator = E::G2::rand(rng);
+
+        Self::generate_parameters_with_observer(
+            circuit,
+            &secret,
+            g1_generator,
+            g2_generator,
+            observer,
+            rng,
+        )
+    }
+
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, reporting progress to `observer`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_progress<C, O>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        observer: &O,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: ProgressObserver,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_with_progress(circuit, pk, *r, *s, observer)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided
+    /// R1CS-to-QAP reduction, reporting progress to `observer`.
+    pub fn create_proof_with_progress<C, O>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+        observer: &O,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: ProgressObserver,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let (cs, h) = Self::synthesize_and_reduce(circuit, observer)?;
+
+        observer.on_progress(ProvingStage::Msm, 0);
+        let backend = ProgressBackend {
+            backend: &CpuBackend,
+            observer,
+            done: AtomicUsize::new(0),
+            // The MSM for B in G1 is skipped if `r` is zero.
+            total: if r.is_zero() { 4 } else { 5 },
+        };
+        let prover = cs.borrow().unwrap();
+        Self::create_proof_with_assignment(
+            pk.into(),
+            &r,
+            &s,
+            &h,
+            &prover.instance_assignment[1..],
+            &prover.witness_assignment,
+            &backend,
+        )
+    }
+}
@@ -1,23 +1,146 @@
-use crate::{r1cs_to_qap::R1CSToQAP, Groth16, Proof, ProvingKey, VerifyingKey};
-use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group, VariableBaseMSM};
-use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use crate::{
+    hash_to_field,
+    msm::{CpuBackend, MsmBackend, MsmQuery},
+    progress::{ProgressObserver, ProvingStage},
+    r1cs_to_qap::{QapDomain, R1CSToQAP},
+    Groth16, Proof, ProverAssignment, ProvingKey, ProvingKeyRef, VerifyingKey, VerifyingKeyRef,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand, Zero};
 use ark_poly::GeneralEvaluationDomain;
 use ark_relations::r1cs::{
-    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal,
-    Result as R1CSResult,
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef,
+    OptimizationGoal, Result as R1CSResult, SynthesisError, SynthesisMode,
 };
+use ark_serialize::{CanonicalSerialize, SerializationError};
 use ark_std::rand::Rng;
 use ark_std::{
-    cfg_into_iter, cfg_iter,
-    ops::{AddAssign, Mul},
+    cfg_into_iter, cfg_iter, fmt,
+    ops::{AddAssign, Deref, Mul},
     vec::Vec,
 };
 
+use zeroize::{Zeroize, Zeroizing};
+
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 type D<F> = GeneralEvaluationDomain<F>;
 
+/// Domain separator for the hash that derives deterministic blinding factors.
+const DETERMINISTIC_DOMAIN: &[u8] = b"ark-groth16/deterministic-prover/v1";
+
+/// An error that can occur while creating a proof.
+#[derive(Debug)]
+pub enum ProverError {
+    /// Synthesizing the circuit or reducing it to a QAP failed.
+    Synthesis(SynthesisError),
+    /// Reading the proving key failed.
+    Serialization(SerializationError),
+    /// The proving key does not match the circuit being proven.
+    MalformedProvingKey,
+    /// Proving was aborted by the caller.
+    Cancelled,
+    /// A proof assembled from results computed elsewhere does not verify.
+    InvalidProof,
+    /// A message of another party of a multi-party proof is malformed.
+    MalformedMessage,
+    /// A setting of the [`ProverConfig`](crate::config::ProverConfig) is out of range.
+    InvalidConfig,
+    /// The messages of a multi-party proof do not come from each party exactly once.
+    UnexpectedParties,
+}
+
+impl From<SynthesisError> for ProverError {
+    fn from(e: SynthesisError) -> Self {
+        ProverError::Synthesis(e)
+    }
+}
+
+impl From<SerializationError> for ProverError {
+    fn from(e: SerializationError) -> Self {
+        ProverError::Serialization(e)
+    }
+}
+
+impl ark_std::error::Error for ProverError {}
+
+impl fmt::Display for ProverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProverError::Synthesis(e) => write!(f, "{}", e),
+            ProverError::Serialization(e) => write!(f, "{}", e),
+            ProverError::MalformedProvingKey => {
+                write!(f, "the proving key does not match the circuit")
+            },
+            ProverError::Cancelled => write!(f, "proving was cancelled"),
+            ProverError::InvalidProof => write!(f, "the assembled proof does not verify"),
+            ProverError::MalformedMessage => write!(f, "a message of another party is malformed"),
+            ProverError::InvalidConfig => {
+                write!(f, "a prover configuration setting is out of range")
+            },
+            ProverError::UnexpectedParties => {
+                write!(f, "the messages do not come from each party exactly once")
+            },
+        }
+    }
+}
+
+/// The multi-scalar multiplications of an assignment with the queries of a proving key,
+/// each including the point of its query for the constant variable.
+pub(crate) struct QueryAccumulators<E: Pairing> {
+    pub(crate) a: E::G1,
+    /// Unused if `r` is zero.
+    pub(crate) b_g1: E::G1,
+    pub(crate) b_g2: E::G2,
+    pub(crate) h: E::G1,
+    pub(crate) l: E::G1,
+}
+
+/// A circuit instance that has been synthesized for proving, together with the
+/// randomness of its proof.
+pub(crate) struct Synthesized<F: PrimeField> {
+    pub(crate) r: Zeroizing<F>,
+    pub(crate) s: Zeroizing<F>,
+    pub(crate) num_inputs: usize,
+    pub(crate) num_constraints: usize,
+    pub(crate) full_assignment: Zeroizing<Vec<F>>,
+    /// Only set if the matrices were requested.
+    pub(crate) matrices: Option<ConstraintMatrices<F>>,
+}
+
+impl<F: PrimeField> Synthesized<F> {
+    /// Whether this instance has the shape that `matrices` were built for. Instances
+    /// that share a shape can still come from different circuits; only the sizes are
+    /// compared.
+    pub(crate) fn matches(&self, matrices: &ConstraintMatrices<F>) -> bool {
+        self.num_inputs == matrices.num_instance_variables
+            && self.num_constraints == matrices.num_constraints
+            && self.full_assignment.len()
+                == matrices.num_instance_variables + matrices.num_witness_variables
+    }
+}
+
+/// A constraint system synthesized for proving, whose witness assignment is wiped
+/// when it is dropped.
+pub(crate) struct WitnessGuard<F: Field>(ConstraintSystemRef<F>);
+
+impl<F: Field> Deref for WitnessGuard<F> {
+    type Target = ConstraintSystemRef<F>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: Field> Drop for WitnessGuard<F> {
+    fn drop(&mut self) {
+        if let Some(mut cs) = self.0.borrow_mut() {
+            cs.witness_assignment.zeroize();
+        }
+    }
+}
+
 impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     /// Create a Groth16 proof using randomness `r` and `s` and
     /// the provided R1CS-to-QAP reduction, using the provided
@@ -31,108 +154,197 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         num_inputs: usize,
         num_constraints: usize,
         full_assignment: &[E::ScalarField],
+    ) -> R1CSResult<Proof<E>> {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let domain = QapDomain::new(num_constraints, num_inputs)?;
+        Self::prove_with_matrices(
+            pk.into(),
+            &r,
+            &s,
+            &domain,
+            matrices,
+            num_inputs,
+            num_constraints,
+            full_assignment,
+        )
+    }
+
+    /// Proves with the evaluation `domain` of the circuit built by the caller, so that
+    /// proving many instances of one circuit builds it once.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_with_matrices(
+        pk: ProvingKeyRef<'_, E>,
+        r: &E::ScalarField,
+        s: &E::ScalarField,
+        domain: &QapDomain<E::ScalarField, D<E::ScalarField>>,
+        matrices: &ConstraintMatrices<E::ScalarField>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[E::ScalarField],
     ) -> R1CSResult<Proof<E>> {
         let prover_time = start_timer!(|| "Groth16::Prover");
         let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
-        let h = QAP::witness_map_from_matrices::<E::ScalarField, D<E::ScalarField>>(
+        let h = Zeroizing::new(QAP::witness_map_with_domain(
+            domain,
             matrices,
             num_inputs,
             num_constraints,
             full_assignment,
-        )?;
+        )?);
         end_timer!(witness_map_time);
         let input_assignment = &full_assignment[1..num_inputs];
         let aux_assignment = &full_assignment[num_inputs..];
-        let proof =
-            Self::create_proof_with_assignment(pk, r, s, &h, input_assignment, aux_assignment)?;
+        let proof = Self::create_proof_with_assignment(
+            pk,
+            r,
+            s,
+            &h,
+            input_assignment,
+            aux_assignment,
+            &CpuBackend,
+        )?;
         end_timer!(prover_time);
 
         Ok(proof)
     }
 
     #[inline]
-    fn create_proof_with_assignment(
-        pk: &ProvingKey<E>,
-        r: E::ScalarField,
-        s: E::ScalarField,
+    pub(crate) fn create_proof_with_assignment<B: MsmBackend<E>>(
+        pk: ProvingKeyRef<'_, E>,
+        r: &E::ScalarField,
+        s: &E::ScalarField,
         h: &[E::ScalarField],
         input_assignment: &[E::ScalarField],
         aux_assignment: &[E::ScalarField],
+        backend: &B,
     ) -> R1CSResult<Proof<E>> {
-        let c_acc_time = start_timer!(|| "Compute C");
-        let h_assignment = cfg_into_iter!(h)
-            .map(|s| s.into_bigint())
-            .collect::<Vec<_>>();
-        let h_acc = E::G1::msm_bigint(&pk.h_query, &h_assignment);
+        let h_assignment = Zeroizing::new(
+            cfg_into_iter!(h)
+                .map(|s| s.into_bigint())
+                .collect::<Vec<_>>(),
+        );
+        let assignment = Zeroizing::new(
+            cfg_iter!(input_assignment)
+                .chain(cfg_iter!(aux_assignment))
+                .map(|s| s.into_bigint())
+                .collect::<Vec<_>>(),
+        );
+        let aux_assignment = &assignment[input_assignment.len()..];
+
+        // The five MSMs below are independent of each other, so with the
+        // `parallel` feature they are computed concurrently.
+        let ((h, l), (a, (b_g1, b_g2))) = crate::join(
+            || {
+                crate::join(
+                    || {
+                        let h_acc_time = start_timer!(|| "Compute H");
+                        let h_acc = backend.msm_g1(MsmQuery::H, pk.h_query, &h_assignment);
+                        end_timer!(h_acc_time);
+                        h_acc
+                    },
+                    || {
+                        let l_acc_time = start_timer!(|| "Compute L");
+                        let l_aux_acc = backend.msm_g1(MsmQuery::L, pk.l_query, aux_assignment);
+                        end_timer!(l_acc_time);
+                        l_aux_acc
+                    },
+                )
+            },
+            || {
+                crate::join(
+                    || {
+                        // Compute A
+                        let a_acc_time = start_timer!(|| "Compute A");
+                        let acc = backend.msm_g1(MsmQuery::A, &pk.a_query[1..], &assignment);
+                        end_timer!(a_acc_time);
+                        acc + pk.a_query[0]
+                    },
+                    || {
+                        crate::join(
+                            || {
+                                // Compute B in G1 if needed
+                                if r.is_zero() {
+                                    return E::G1::zero();
+                                }
+                                let b_g1_acc_time = start_timer!(|| "Compute B in G1");
+                                let acc =
+                                    backend.msm_g1(MsmQuery::BG1, &pk.b_g1_query[1..], &assignment);
+                                end_timer!(b_g1_acc_time);
+                                acc + pk.b_g1_query[0]
+                            },
+                            || {
+                                // Compute B in G2
+                                let b_g2_acc_time = start_timer!(|| "Compute B in G2");
+                                let acc =
+                                    backend.msm_g2(MsmQuery::BG2, &pk.b_g2_query[1..], &assignment);
+                                end_timer!(b_g2_acc_time);
+                                acc + pk.b_g2_query[0]
+                            },
+                        )
+                    },
+                )
+            },
+        );
         drop(h_assignment);
+        drop(assignment);
 
-        // Compute C
-        let aux_assignment = cfg_iter!(aux_assignment)
-            .map(|s| s.into_bigint())
-            .collect::<Vec<_>>();
-
-        let l_aux_acc = E::G1::msm_bigint(&pk.l_query, &aux_assignment);
-
-        let r_s_delta_g1 = pk
-            .delta_g1
-            .into_group()
-            .mul_bigint(&r.into_bigint())
-            .mul_bigint(&s.into_bigint());
-
-        end_timer!(c_acc_time);
-
-        let input_assignment = input_assignment
-            .iter()
-            .map(|s| s.into_bigint())
-            .collect::<Vec<_>>();
-
-        let assignment = [&input_assignment[..], &aux_assignment[..]].concat();
-        drop(aux_assignment);
-
-        // Compute A
-        let a_acc_time = start_timer!(|| "Compute A");
-        let r_g1 = pk.delta_g1.mul(r);
-
-        let g_a = Self::calculate_coeff(r_g1, &pk.a_query, pk.vk.alpha_g1, &assignment);
-
-        let s_g_a = g_a.mul_bigint(&s.into_bigint());
-        end_timer!(a_acc_time);
-
-        // Compute B in G1 if needed
-        let g1_b = if !r.is_zero() {
-            let b_g1_acc_time = start_timer!(|| "Compute B in G1");
-            let s_g1 = pk.delta_g1.mul(s);
-            let g1_b = Self::calculate_coeff(s_g1, &pk.b_g1_query, pk.beta_g1, &assignment);
-
-            end_timer!(b_g1_acc_time);
+        let acc = QueryAccumulators {
+            a,
+            b_g1,
+            b_g2,
+            h,
+            l,
+        };
+        Ok(Self::assemble_proof(
+            pk.vk,
+            pk.beta_g1,
+            pk.delta_g1,
+            r,
+            s,
+            acc,
+        ))
+    }
 
-            g1_b
-        } else {
+    /// Assembles the proof with randomness `r` and `s` from the multi-scalar
+    /// multiplications `acc` of an assignment with the queries of a proving key, whose
+    /// other elements are `vk`, `beta_g1` and `delta_g1`.
+    pub(crate) fn assemble_proof(
+        vk: VerifyingKeyRef<'_, E>,
+        beta_g1: E::G1Affine,
+        delta_g1: E::G1Affine,
+        r: &E::ScalarField,
+        s: &E::ScalarField,
+        acc: QueryAccumulators<E>,
+    ) -> Proof<E> {
+        let c_time = start_timer!(|| "Finish C");
+        let r = Zeroizing::new(r.into_bigint());
+        let s = Zeroizing::new(s.into_bigint());
+        let (r, s) = (&*r, &*s);
+
+        let r_delta_g1 = delta_g1.mul_bigint(r);
+        let g_a = r_delta_g1 + acc.a + vk.alpha_g1;
+        // B in G1 only enters C multiplied by `r`.
+        let g1_b = if r.is_zero() {
             E::G1::zero()
+        } else {
+            delta_g1.mul_bigint(s) + acc.b_g1 + beta_g1
         };
+        let g2_b = vk.delta_g2.mul_bigint(s) + acc.b_g2 + vk.beta_g2;
 
-        // Compute B in G2
-        let b_g2_acc_time = start_timer!(|| "Compute B in G2");
-        let s_g2 = pk.vk.delta_g2.mul(s);
-        let g2_b = Self::calculate_coeff(s_g2, &pk.b_g2_query, pk.vk.beta_g2, &assignment);
-        let r_g1_b = g1_b.mul_bigint(&r.into_bigint());
-        drop(assignment);
-
-        end_timer!(b_g2_acc_time);
-
-        let c_time = start_timer!(|| "Finish C");
-        let mut g_c = s_g_a;
-        g_c += &r_g1_b;
-        g_c -= &r_s_delta_g1;
-        g_c += &l_aux_acc;
-        g_c += &h_acc;
+        let mut g_c = g_a.mul_bigint(s);
+        g_c += &g1_b.mul_bigint(r);
+        g_c -= &r_delta_g1.mul_bigint(s);
+        g_c += &acc.l;
+        g_c += &acc.h;
         end_timer!(c_time);
 
-        Ok(Proof {
-            a: g_a.into_affine(),
+        // `A` and `C` share one inversion.
+        let g1 = E::G1::normalize_batch(&[g_a, g_c]);
+        Proof {
+            a: g1[0],
             b: g2_b.into_affine(),
-            c: g_c.into_affine(),
-        })
+            c: g1[1],
+        }
     }
 
     /// Create a Groth16 proof that is zero-knowledge using the provided
@@ -147,10 +359,42 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     where
         C: ConstraintSynthesizer<E::ScalarField>,
     {
-        let r = E::ScalarField::rand(rng);
-        let s = E::ScalarField::rand(rng);
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
 
-        Self::create_proof_with_reduction(circuit, pk, r, s)
+        Self::prove_with_backend(circuit, pk.into(), &r, &s, &CpuBackend)
+    }
+
+    /// Create a Groth16 proof that is zero-knowledge like
+    /// [`create_random_proof_with_reduction`](Self::create_random_proof_with_reduction), but
+    /// return [`SynthesisError::Unsatisfiable`] instead of a proof that does not verify if
+    /// the assignment of `circuit` does not satisfy its constraints.
+    ///
+    /// The check evaluates every constraint once more, on the constraint system that the
+    /// proof is computed from.
+    pub fn create_random_proof_checked<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        let cs = Self::synthesize_with_check(circuit, &(), true)?;
+        let h = Self::reduce(cs.clone(), &())?;
+        let prover = cs.borrow().unwrap();
+        Self::create_proof_with_assignment(
+            pk.into(),
+            &r,
+            &s,
+            &h,
+            &prover.instance_assignment[1..],
+            &prover.witness_assignment,
+            &CpuBackend,
+        )
     }
 
     /// Create a Groth16 proof that is *not* zero-knowledge with the provided
@@ -173,6 +417,10 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
 
     /// Create a Groth16 proof using randomness `r` and `s` and the provided
     /// R1CS-to-QAP reduction.
+    ///
+    /// The proof is a deterministic function of its inputs, so fixed `r` and `s` yield
+    /// known-answer proofs that can be cross-checked against other implementations. It
+    /// is only zero-knowledge if `r` and `s` are secret and uniformly random.
     #[inline]
     pub fn create_proof_with_reduction<C>(
         circuit: C,
@@ -185,25 +433,100 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         C: ConstraintSynthesizer<E::ScalarField>,
         QAP: R1CSToQAP,
     {
-        let prover_time = start_timer!(|| "Groth16::Prover");
-        let cs = ConstraintSystem::new_ref();
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        Self::prove_with_backend(circuit, pk.into(), &r, &s, &CpuBackend)
+    }
 
-        // Set the optimization goal
-        cs.set_optimization_goal(OptimizationGoal::Constraints);
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, computing all multi-scalar multiplications with `backend`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    #[inline]
+    pub fn create_random_proof_with_backend<C, B>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        backend: &B,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        B: MsmBackend<E>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
 
-        // Synthesize the circuit.
-        let synthesis_time = start_timer!(|| "Constraint synthesis");
-        circuit.generate_constraints(cs.clone())?;
-        debug_assert!(cs.is_satisfied().unwrap());
-        end_timer!(synthesis_time);
+        Self::prove_with_backend(circuit, pk.into(), &r, &s, backend)
+    }
 
-        let lc_time = start_timer!(|| "Inlining LCs");
-        cs.finalize();
-        end_timer!(lc_time);
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided
+    /// R1CS-to-QAP reduction, computing all multi-scalar multiplications with `backend`.
+    #[inline]
+    pub fn create_proof_with_reduction_and_backend<C, B>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+        backend: &B,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        B: MsmBackend<E>,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        Self::prove_with_backend(circuit, pk.into(), &r, &s, backend)
+    }
 
-        let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
-        let h = QAP::witness_map::<E::ScalarField, D<E::ScalarField>>(cs.clone())?;
-        end_timer!(witness_map_time);
+    /// Create a Groth16 proof that is zero-knowledge using the provided R1CS-to-QAP
+    /// reduction and the borrowed proving key `pk`.
+    /// This method samples randomness for zero knowledges via `rng`.
+    #[inline]
+    pub fn create_random_proof_with_key_ref<C>(
+        circuit: C,
+        pk: ProvingKeyRef<'_, E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::prove_with_backend(circuit, pk, &r, &s, &CpuBackend)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s`, the provided R1CS-to-QAP
+    /// reduction and the borrowed proving key `pk`, computing all multi-scalar
+    /// multiplications with `backend`.
+    #[inline]
+    pub fn create_proof_with_key_ref_and_backend<C, B>(
+        circuit: C,
+        pk: ProvingKeyRef<'_, E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+        backend: &B,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        B: MsmBackend<E>,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        Self::prove_with_backend(circuit, pk, &r, &s, backend)
+    }
+
+    /// The prover behind the `create_*proof*` methods, which borrows `r` and `s` so that
+    /// the callers' [`Zeroizing`] copies are the only ones.
+    pub(crate) fn prove_with_backend<C, B>(
+        circuit: C,
+        pk: ProvingKeyRef<'_, E>,
+        r: &E::ScalarField,
+        s: &E::ScalarField,
+        backend: &B,
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        B: MsmBackend<E>,
+    {
+        let prover_time = start_timer!(|| "Groth16::Prover");
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
 
         let prover = cs.borrow().unwrap();
         let proof = Self::create_proof_with_assignment(
@@ -213,6 +536,7 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
             &h,
             &prover.instance_assignment[1..],
             &prover.witness_assignment,
+            backend,
         )?;
 
         end_timer!(prover_time);
@@ -220,6 +544,296 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         Ok(proof)
     }
 
+    /// Create a Groth16 proof whose randomness `r` and `s` is derived from `key`, the
+    /// verifying key, and the full assignment of `circuit`, using the provided R1CS-to-QAP
+    /// reduction. Identical inputs therefore yield identical proofs.
+    ///
+    /// The proof is only zero-knowledge if `key` is secret and uniformly random: anyone who
+    /// knows `key` can test guesses of the witness against the proof.
+    pub fn create_deterministic_proof<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        key: &[u8],
+    ) -> R1CSResult<Proof<E>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let prover_time = start_timer!(|| "Groth16::Prover (deterministic)");
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
+
+        let prover = cs.borrow().unwrap();
+        let input_assignment = &prover.instance_assignment[1..];
+        let aux_assignment = &prover.witness_assignment;
+        let (r, s) = deterministic_blinding(key, &pk.vk, input_assignment, aux_assignment);
+        let proof = Self::create_proof_with_assignment(
+            pk.into(),
+            &r,
+            &s,
+            &h,
+            input_assignment,
+            aux_assignment,
+            &CpuBackend,
+        )?;
+
+        end_timer!(prover_time);
+
+        Ok(proof)
+    }
+
+    /// Synthesize `circuit` and compute its QAP witness with the provided R1CS-to-QAP
+    /// reduction, which is the first phase of proving. The second phase,
+    /// [`create_proof_from_assignment`], only needs the result and the proving key, so the
+    /// two can run in different processes.
+    ///
+    /// [`create_proof_from_assignment`]: Self::create_proof_from_assignment
+    pub fn synthesize_assignment<C>(circuit: C) -> R1CSResult<ProverAssignment<E::ScalarField>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+    {
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
+        let prover = cs.borrow().unwrap();
+
+        Ok(ProverAssignment {
+            input_assignment: prover.instance_assignment[1..].to_vec(),
+            aux_assignment: prover.witness_assignment.clone(),
+            h: h.to_vec(),
+        })
+    }
+
+    /// Create a Groth16 proof that is zero-knowledge from an `assignment` produced by
+    /// [`synthesize_assignment`](Self::synthesize_assignment).
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_from_assignment(
+        pk: &ProvingKey<E>,
+        assignment: &ProverAssignment<E::ScalarField>,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError> {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::prove_from_assignment(pk, assignment, &r, &s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` from an `assignment` produced
+    /// by [`synthesize_assignment`](Self::synthesize_assignment).
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if the numbers of variables of
+    /// `assignment` do not match `pk`.
+    pub fn create_proof_from_assignment(
+        pk: &ProvingKey<E>,
+        assignment: &ProverAssignment<E::ScalarField>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> Result<Proof<E>, ProverError> {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        Self::prove_from_assignment(pk, assignment, &r, &s)
+    }
+
+    fn prove_from_assignment(
+        pk: &ProvingKey<E>,
+        assignment: &ProverAssignment<E::ScalarField>,
+        r: &E::ScalarField,
+        s: &E::ScalarField,
+    ) -> Result<Proof<E>, ProverError> {
+        let num_variables = 1 + assignment.input_assignment.len() + assignment.aux_assignment.len();
+        if num_variables != pk.a_query.len() || assignment.aux_assignment.len() != pk.l_query.len()
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+
+        let prover_time = start_timer!(|| "Groth16::Prover (from assignment)");
+        let proof = Self::create_proof_with_assignment(
+            pk.into(),
+            r,
+            s,
+            &assignment.h,
+            &assignment.input_assignment,
+            &assignment.aux_assignment,
+            &CpuBackend,
+        )?;
+        end_timer!(prover_time);
+
+        Ok(proof)
+    }
+
+    /// Create zero-knowledge proofs for a sequence of instances of the circuit that `pk`
+    /// was generated for. This method samples randomness for zero knowledges via `rng`.
+    ///
+    /// Constraint matrices are only built for the first instance that synthesizes
+    /// successfully. They are reused for the witness maps of the rest, together with the
+    /// evaluation domain of the circuit and the borrowed form of `pk`. An instance whose
+    /// number of inputs, witnesses or constraints differs from that first instance
+    /// fails with [`SynthesisError::Unsatisfiable`].
+    ///
+    /// The `i`-th result corresponds to the `i`-th circuit.
+    pub fn prove_batch<C, I>(
+        pk: &ProvingKey<E>,
+        circuits: I,
+        rng: &mut impl Rng,
+    ) -> Vec<R1CSResult<Proof<E>>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        I: IntoIterator<Item = C>,
+    {
+        let pk = ProvingKeyRef::from(pk);
+        let mut matrices = None;
+        let mut domain = None;
+        circuits
+            .into_iter()
+            .map(|circuit| {
+                let r = Zeroizing::new(E::ScalarField::rand(rng));
+                let s = Zeroizing::new(E::ScalarField::rand(rng));
+                let mut job = Self::synthesize_job(circuit, r, s, matrices.is_none())?;
+                if job.matrices.is_some() {
+                    matrices = job.matrices.take();
+                }
+                let matrices = matrices.as_ref().expect("first job carries the matrices");
+                if !job.matches(matrices) {
+                    return Err(SynthesisError::Unsatisfiable);
+                }
+                if domain.is_none() {
+                    domain = Some(QapDomain::new(job.num_constraints, job.num_inputs)?);
+                }
+                Self::prove_with_matrices(
+                    pk,
+                    &job.r,
+                    &job.s,
+                    domain.as_ref().unwrap(),
+                    matrices,
+                    job.num_inputs,
+                    job.num_constraints,
+                    &job.full_assignment,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn synthesize_job<C: ConstraintSynthesizer<E::ScalarField>>(
+        circuit: C,
+        r: Zeroizing<E::ScalarField>,
+        s: Zeroizing<E::ScalarField>,
+        construct_matrices: bool,
+    ) -> R1CSResult<Synthesized<E::ScalarField>> {
+        let cs = WitnessGuard(ConstraintSystem::new_ref());
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Prove { construct_matrices });
+
+        let synthesis_time = start_timer!(|| "Constraint synthesis");
+        circuit.generate_constraints(cs.clone())?;
+        // Satisfiability can only be checked when the matrices are available.
+        debug_assert!(!construct_matrices || cs.is_satisfied().unwrap());
+        end_timer!(synthesis_time);
+
+        let lc_time = start_timer!(|| "Inlining LCs");
+        cs.finalize();
+        end_timer!(lc_time);
+
+        let matrices = if construct_matrices {
+            cs.to_matrices()
+        } else {
+            None
+        };
+        let num_inputs = cs.num_instance_variables();
+        let num_constraints = cs.num_constraints();
+        let prover = cs.borrow().unwrap();
+        let full_assignment = Zeroizing::new(
+            [
+                prover.instance_assignment.as_slice(),
+                prover.witness_assignment.as_slice(),
+            ]
+            .concat(),
+        );
+
+        Ok(Synthesized {
+            r,
+            s,
+            num_inputs,
+            num_constraints,
+            full_assignment,
+            matrices,
+        })
+    }
+
+    /// Synthesizes `circuit` in proving mode and computes the QAP witness `h`
+    /// of its assignment, reporting both stages to `observer`.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn synthesize_and_reduce<C, O>(
+        circuit: C,
+        observer: &O,
+    ) -> R1CSResult<(WitnessGuard<E::ScalarField>, Zeroizing<Vec<E::ScalarField>>)>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: ProgressObserver,
+    {
+        let cs = Self::synthesize(circuit, observer)?;
+        let h = Self::reduce(cs.clone(), observer)?;
+
+        Ok((cs, h))
+    }
+
+    /// Synthesizes `circuit` in proving mode, reporting the stage to `observer`.
+    pub(crate) fn synthesize<C, O>(
+        circuit: C,
+        observer: &O,
+    ) -> R1CSResult<WitnessGuard<E::ScalarField>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: ProgressObserver,
+    {
+        Self::synthesize_with_check(circuit, observer, false)
+    }
+
+    /// Synthesizes `circuit` like [`Self::synthesize`], and if `check` is set returns
+    /// [`SynthesisError::Unsatisfiable`] if its assignment does not satisfy it.
+    fn synthesize_with_check<C, O>(
+        circuit: C,
+        observer: &O,
+        check: bool,
+    ) -> R1CSResult<WitnessGuard<E::ScalarField>>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        O: ProgressObserver,
+    {
+        let cs = WitnessGuard(ConstraintSystem::new_ref());
+
+        // Set the optimization goal
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+
+        // Synthesize the circuit.
+        observer.on_progress(ProvingStage::Synthesis, 0);
+        let synthesis_time = start_timer!(|| "Constraint synthesis");
+        circuit.generate_constraints(cs.clone())?;
+        if check {
+            if !cs.is_satisfied()? {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+        } else {
+            debug_assert!(cs.is_satisfied().unwrap());
+        }
+        end_timer!(synthesis_time);
+
+        let lc_time = start_timer!(|| "Inlining LCs");
+        cs.finalize();
+        end_timer!(lc_time);
+        observer.on_progress(ProvingStage::Synthesis, 100);
+
+        Ok(cs)
+    }
+
+    /// Computes the QAP witness `h` of the synthesized `cs`, reporting the stage to `observer`.
+    pub(crate) fn reduce<O: ProgressObserver>(
+        cs: ConstraintSystemRef<E::ScalarField>,
+        observer: &O,
+    ) -> R1CSResult<Zeroizing<Vec<E::ScalarField>>> {
+        observer.on_progress(ProvingStage::WitnessMap, 0);
+        let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
+        let h = Zeroizing::new(QAP::witness_map::<E::ScalarField, D<E::ScalarField>>(cs)?);
+        end_timer!(witness_map_time);
+        observer.on_progress(ProvingStage::WitnessMap, 100);
+
+        Ok(h)
+    }
+
     /// Given a Groth16 proof, returns a fresh proof of the same statement. For a proof π of a
     /// statement S, the output of the non-deterministic procedure `rerandomize_proof(π)` is
     /// statistically indistinguishable from a fresh honest proof of S. For more info, see theorem 3 of
@@ -253,17 +867,15 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         }
     }
 
-    fn calculate_coeff<G: AffineRepr>(
+    /// Computes `initial + query[0] + acc + vk_param`, where `acc` is the MSM of
+    /// `query[1..]` with the assignment.
+    pub(crate) fn calculate_coeff<G: AffineRepr>(
         initial: G::Group,
         query: &[G],
         vk_param: G,
-        assignment: &[<G::ScalarField as PrimeField>::BigInt],
-    ) -> G::Group
-    where
-        G::Group: VariableBaseMSM<MulBase = G>,
-    {
+        acc: G::Group,
+    ) -> G::Group {
         let el = query[0];
-        let acc = G::Group::msm_bigint(&query[1..], assignment);
 
         let mut res = initial;
         res.add_assign(&el);
@@ -273,3 +885,39 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         res
     }
 }
+
+/// Derives the blinding factors `r` and `s` of [`Groth16::create_deterministic_proof`].
+/// Each factor is reduced from 512 bits of hash output, so that its bias is negligible.
+fn deterministic_blinding<E: Pairing>(
+    key: &[u8],
+    vk: &VerifyingKey<E>,
+    input_assignment: &[E::ScalarField],
+    aux_assignment: &[E::ScalarField],
+) -> (Zeroizing<E::ScalarField>, Zeroizing<E::ScalarField>) {
+    // The buffer holds the key and the witness, so it is wiped as well. It is allocated
+    // once, with room for the label, so that growing it leaves no copies behind.
+    let len = key.compressed_size()
+        + vk.compressed_size()
+        + input_assignment.compressed_size()
+        + aux_assignment.compressed_size();
+    let mut bytes = Zeroizing::new(Vec::with_capacity(len + 1));
+    key.serialize_compressed(&mut *bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    vk.serialize_compressed(&mut *bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    input_assignment
+        .serialize_compressed(&mut *bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    aux_assignment
+        .serialize_compressed(&mut *bytes)
+        .expect("serializing into a `Vec` cannot fail");
+
+    // The label of each factor is the last byte of its input.
+    let mut derive = |label: u8| {
+        bytes.push(label);
+        let factor = hash_to_field(DETERMINISTIC_DOMAIN, &bytes);
+        bytes.pop();
+        Zeroizing::new(factor)
+    };
+    (derive(b'r'), derive(b's'))
+}
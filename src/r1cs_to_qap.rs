@@ -1,12 +1,18 @@
-use ark_ff::{One, PrimeField, Zero};
-use ark_poly::EvaluationDomain;
-use ark_std::{cfg_iter, cfg_iter_mut, vec};
-
-use crate::Vec;
-use ark_relations::r1cs::{
-    ConstraintMatrices, ConstraintSystemRef, Result as R1CSResult, SynthesisError,
+use ark_ff::{One, Zero};
+use ark_std::cfg_iter;
+use core::ops::AddAssign;
+#[cfg(feature = "prover")]
+use {
+    crate::Vec,
+    ark_ff::PrimeField,
+    ark_poly::EvaluationDomain,
+    ark_relations::r1cs::{
+        ConstraintMatrices, ConstraintSystemRef, Result as R1CSResult, SynthesisError,
+    },
+    ark_std::{cfg_chunks_mut, cfg_iter_mut, vec},
+    core::ops::Deref,
+    zeroize::Zeroizing,
 };
-use core::ops::{AddAssign, Deref};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -44,15 +50,57 @@ where
     return res;
 }
 
+/// The evaluation domain of the QAP of a circuit, with the coset that the witness map
+/// evaluates on. It only depends on the size of the circuit, so proving many instances
+/// of one circuit can build it once and pass it to
+/// [`R1CSToQAP::witness_map_with_domain`].
+#[cfg(feature = "prover")]
+pub struct QapDomain<F: PrimeField, D: EvaluationDomain<F>> {
+    domain: D,
+    coset_domain: D,
+    /// The inverse of the vanishing polynomial of `domain` at the coset offset.
+    vanishing_polynomial_over_coset: F,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField, D: EvaluationDomain<F>> QapDomain<F, D> {
+    /// Builds the domain of a circuit with `num_constraints` constraints and
+    /// `num_inputs` instance variables, including the constant.
+    pub fn new(num_constraints: usize, num_inputs: usize) -> R1CSResult<Self> {
+        let domain =
+            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let coset_domain = domain.get_coset(F::GENERATOR).unwrap();
+        let vanishing_polynomial_over_coset = domain
+            .evaluate_vanishing_polynomial(F::GENERATOR)
+            .inverse()
+            .unwrap();
+        Ok(Self {
+            domain,
+            coset_domain,
+            vanishing_polynomial_over_coset,
+        })
+    }
+
+    /// The number of points of the domain.
+    pub fn size(&self) -> usize {
+        self.domain.size()
+    }
+}
+
 /// Computes instance and witness reductions from R1CS to
 /// Quadratic Arithmetic Programs (QAPs).
+///
+/// Without the `prover` feature the reduction is not needed and the trait has no methods.
 pub trait R1CSToQAP {
+    #[cfg(feature = "prover")]
     /// Computes a QAP instance corresponding to the R1CS instance defined by `cs`.
+    #[allow(clippy::type_complexity)]
     fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
         cs: ConstraintSystemRef<F>,
         t: &F,
     ) -> Result<(Vec<F>, Vec<F>, Vec<F>, F, usize, usize), SynthesisError>;
 
+    #[cfg(feature = "prover")]
     #[inline]
     /// Computes a QAP witness corresponding to the R1CS witness defined by `cs`.
     fn witness_map<F: PrimeField, D: EvaluationDomain<F>>(
@@ -79,6 +127,7 @@ pub trait R1CSToQAP {
         )
     }
 
+    #[cfg(feature = "prover")]
     /// Computes a QAP witness corresponding to the R1CS witness defined by `cs`.
     fn witness_map_from_matrices<F: PrimeField, D: EvaluationDomain<F>>(
         matrices: &ConstraintMatrices<F>,
@@ -87,6 +136,28 @@ pub trait R1CSToQAP {
         full_assignment: &[F],
     ) -> R1CSResult<Vec<F>>;
 
+    #[cfg(feature = "prover")]
+    /// Computes a QAP witness like [`R1CSToQAP::witness_map_from_matrices`], over a
+    /// `domain` that the caller built once for all instances of the circuit.
+    ///
+    /// The default implementation ignores `domain`.
+    fn witness_map_with_domain<F: PrimeField, D: EvaluationDomain<F>>(
+        domain: &QapDomain<F, D>,
+        matrices: &ConstraintMatrices<F>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[F],
+    ) -> R1CSResult<Vec<F>> {
+        let _ = domain;
+        Self::witness_map_from_matrices::<F, D>(
+            matrices,
+            num_inputs,
+            num_constraints,
+            full_assignment,
+        )
+    }
+
+    #[cfg(feature = "prover")]
     /// Computes the exponents that the generator uses to calculate base
     /// elements which the prover later uses to compute `h(x)t(x)/delta`.
     fn h_query_scalars<F: PrimeField, D: EvaluationDomain<F>>(
@@ -101,6 +172,7 @@ pub trait R1CSToQAP {
 pub struct LibsnarkReduction;
 
 impl R1CSToQAP for LibsnarkReduction {
+    #[cfg(feature = "prover")]
     #[inline]
     #[allow(clippy::type_complexity)]
     fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
@@ -116,14 +188,14 @@ impl R1CSToQAP for LibsnarkReduction {
 
         // Evaluate all Lagrange polynomials
         let coefficients_time = start_timer!(|| "Evaluate Lagrange coefficients");
-        let u = domain.evaluate_all_lagrange_coefficients(*t);
+        let u = Zeroizing::new(domain.evaluate_all_lagrange_coefficients(*t));
         end_timer!(coefficients_time);
 
         let qap_num_variables = (cs.num_instance_variables() - 1) + cs.num_witness_variables();
 
         let mut a = vec![F::zero(); qap_num_variables + 1];
-        let mut b = vec![F::zero(); qap_num_variables + 1];
-        let mut c = vec![F::zero(); qap_num_variables + 1];
+        let b = vec![F::zero(); qap_num_variables + 1];
+        let c = vec![F::zero(); qap_num_variables + 1];
 
         {
             let start = 0;
@@ -132,30 +204,56 @@ impl R1CSToQAP for LibsnarkReduction {
             a[start..end].copy_from_slice(&u[(start + num_constraints)..(end + num_constraints)]);
         }
 
-        for (i, u_i) in u.iter().enumerate().take(cs.num_constraints()) {
-            for &(ref coeff, index) in &matrices.a[i] {
-                a[index] += &(*u_i * coeff);
-            }
-            for &(ref coeff, index) in &matrices.b[i] {
-                b[index] += &(*u_i * coeff);
+        // The three matrices are independent, so they are evaluated concurrently.
+        let evaluate = |matrix: &[Vec<(F, usize)>], mut evaluations: Vec<F>| {
+            for (u_i, row) in u.iter().zip(matrix) {
+                for &(ref coeff, index) in row {
+                    evaluations[index] += &(*u_i * coeff);
+                }
             }
-            for &(ref coeff, index) in &matrices.c[i] {
-                c[index] += &(*u_i * coeff);
-            }
-        }
+            evaluations
+        };
+        let (a, (b, c)) = crate::join(
+            || evaluate(&matrices.a, a),
+            || crate::join(|| evaluate(&matrices.b, b), || evaluate(&matrices.c, c)),
+        );
 
         Ok((a, b, c, zt, qap_num_variables, domain_size))
     }
 
+    #[cfg(feature = "prover")]
     fn witness_map_from_matrices<F: PrimeField, D: EvaluationDomain<F>>(
         matrices: &ConstraintMatrices<F>,
         num_inputs: usize,
         num_constraints: usize,
         full_assignment: &[F],
     ) -> R1CSResult<Vec<F>> {
-        let domain =
-            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        Self::witness_map_with_domain(
+            &QapDomain::<F, D>::new(num_constraints, num_inputs)?,
+            matrices,
+            num_inputs,
+            num_constraints,
+            full_assignment,
+        )
+    }
+
+    #[cfg(feature = "prover")]
+    fn witness_map_with_domain<F: PrimeField, D: EvaluationDomain<F>>(
+        qap_domain: &QapDomain<F, D>,
+        matrices: &ConstraintMatrices<F>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[F],
+    ) -> R1CSResult<Vec<F>> {
+        let QapDomain {
+            domain,
+            coset_domain,
+            vanishing_polynomial_over_coset,
+        } = qap_domain;
         let domain_size = domain.size();
+        if domain_size < num_constraints + num_inputs {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
         let zero = F::zero();
 
         let mut a = vec![zero; domain_size];
@@ -166,8 +264,8 @@ impl R1CSToQAP for LibsnarkReduction {
             .zip(cfg_iter!(&matrices.a))
             .zip(cfg_iter!(&matrices.b))
             .for_each(|(((a, b), at_i), bt_i)| {
-                *a = evaluate_constraint(&at_i, &full_assignment);
-                *b = evaluate_constraint(&bt_i, &full_assignment);
+                *a = evaluate_constraint(at_i, full_assignment);
+                *b = evaluate_constraint(bt_i, full_assignment);
             });
 
         {
@@ -179,8 +277,6 @@ impl R1CSToQAP for LibsnarkReduction {
         domain.ifft_in_place(&mut a);
         domain.ifft_in_place(&mut b);
 
-        let coset_domain = domain.get_coset(F::GENERATOR).unwrap();
-
         coset_domain.fft_in_place(&mut a);
         coset_domain.fft_in_place(&mut b);
 
@@ -192,19 +288,15 @@ impl R1CSToQAP for LibsnarkReduction {
         cfg_iter_mut!(c[..num_constraints])
             .enumerate()
             .for_each(|(i, c)| {
-                *c = evaluate_constraint(&matrices.c[i], &full_assignment);
+                *c = evaluate_constraint(&matrices.c[i], full_assignment);
             });
 
         domain.ifft_in_place(&mut c);
         coset_domain.fft_in_place(&mut c);
 
-        let vanishing_polynomial_over_coset = domain
-            .evaluate_vanishing_polynomial(F::GENERATOR)
-            .inverse()
-            .unwrap();
         cfg_iter_mut!(ab).zip(c).for_each(|(ab_i, c_i)| {
             *ab_i -= &c_i;
-            *ab_i *= &vanishing_polynomial_over_coset;
+            *ab_i *= vanishing_polynomial_over_coset;
         });
 
         coset_domain.ifft_in_place(&mut ab);
@@ -212,15 +304,26 @@ impl R1CSToQAP for LibsnarkReduction {
         Ok(ab)
     }
 
+    #[cfg(feature = "prover")]
     fn h_query_scalars<F: PrimeField, D: EvaluationDomain<F>>(
         max_power: usize,
         t: F,
         zt: F,
         delta_inverse: F,
     ) -> Result<Vec<F>, SynthesisError> {
-        let scalars = cfg_into_iter!(0..max_power)
-            .map(|i| zt * &delta_inverse * &t.pow([i as u64]))
-            .collect::<Vec<_>>();
+        // Each chunk starts from one exponentiation and multiplies by `t` from there.
+        const CHUNK_SIZE: usize = 1 << 12;
+        let zt_delta_inverse = Zeroizing::new(zt * &delta_inverse);
+        let mut scalars = vec![F::zero(); max_power];
+        cfg_chunks_mut!(scalars, CHUNK_SIZE)
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                let mut scalar = *zt_delta_inverse * &t.pow([(i * CHUNK_SIZE) as u64]);
+                for s in chunk {
+                    *s = scalar;
+                    scalar *= &t;
+                }
+            });
         Ok(scalars)
     }
 }
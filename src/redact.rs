@@ -0,0 +1,191 @@
+use crate::{r1cs_to_qap::R1CSToQAP, Groth16};
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal,
+    Result as R1CSResult, SynthesisMode,
+};
+use ark_std::{fmt, ops::Range, string::String, vec::Vec};
+use zeroize::Zeroize;
+
+/// The placeholder printed in place of a sensitive witness value.
+pub const REDACTED: &str = "<redacted>";
+
+/// Marks regions of a circuit's witness assignment as sensitive.
+///
+/// Indices refer to positions in the witness assignment, i.e. the order in which the
+/// circuit allocates witness variables. Instance variables are public and never redacted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WitnessClassification {
+    sensitive: Vec<Range<usize>>,
+}
+
+impl WitnessClassification {
+    /// A classification with no sensitive witness variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A classification that treats every witness variable as sensitive.
+    pub fn all_sensitive() -> Self {
+        let mut classification = Self::new();
+        classification.mark_sensitive(0..usize::MAX);
+        classification
+    }
+
+    /// Marks the witness variables with indices in `range` as sensitive.
+    pub fn mark_sensitive(&mut self, range: Range<usize>) -> &mut Self {
+        if !range.is_empty() {
+            self.sensitive.push(range);
+        }
+        self
+    }
+
+    /// Whether the witness variable at `index` is sensitive.
+    pub fn is_sensitive(&self, index: usize) -> bool {
+        self.sensitive.iter().any(|range| range.contains(&index))
+    }
+}
+
+/// A circuit that marks which of its witness variables are sensitive.
+///
+/// The prover's diagnostics that show witness values, such as [`AssignmentReport`] and
+/// the [`ProvingReport`](crate::report::ProvingReport), only accept such circuits. A
+/// circuit without its own classification can be wrapped in [`Classified`].
+pub trait ClassifiedCircuit<F: Field>: ConstraintSynthesizer<F> {
+    /// The sensitive regions of the witness assignment that the circuit allocates.
+    fn witness_classification(&self) -> WitnessClassification;
+}
+
+/// A circuit together with the classification of its witness.
+#[derive(Clone, Debug)]
+pub struct Classified<C> {
+    /// The circuit.
+    pub circuit: C,
+    /// The classification of the witness variables of `circuit`.
+    pub classification: WitnessClassification,
+}
+
+impl<C> Classified<C> {
+    /// Treats every witness variable of `circuit` as sensitive.
+    pub fn all_sensitive(circuit: C) -> Self {
+        Self {
+            circuit,
+            classification: WitnessClassification::all_sensitive(),
+        }
+    }
+}
+
+impl<F: Field, C: ConstraintSynthesizer<F>> ConstraintSynthesizer<F> for Classified<C> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> R1CSResult<()> {
+        self.circuit.generate_constraints(cs)
+    }
+}
+
+impl<F: Field, C: ConstraintSynthesizer<F>> ClassifiedCircuit<F> for Classified<C> {
+    fn witness_classification(&self) -> WitnessClassification {
+        self.classification.clone()
+    }
+}
+
+/// Diagnostics about a synthesized circuit whose `Debug` output redacts sensitive witness
+/// values, so that it can be logged without leaking secrets.
+///
+/// Sensitive values are not copied into the report at all.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AssignmentReport<F: Field> {
+    /// The number of constraints of the circuit.
+    pub num_constraints: usize,
+    /// The instance assignment, starting with the constant `1`.
+    pub instance_assignment: Vec<F>,
+    /// The name of the first unsatisfied constraint, if any.
+    pub unsatisfied: Option<String>,
+    /// The witness assignment, with `None` for the sensitive values.
+    witness_assignment: Vec<Option<F>>,
+}
+
+impl<F: Field> AssignmentReport<F> {
+    /// Reports on the assignment of `cs`, which must have been synthesized in proving
+    /// mode.
+    pub(crate) fn new(
+        cs: &ConstraintSystemRef<F>,
+        classification: &WitnessClassification,
+    ) -> R1CSResult<Self> {
+        let unsatisfied = cs.which_is_unsatisfied()?;
+        let num_constraints = cs.num_constraints();
+        let prover = cs.borrow().unwrap();
+        let witness_assignment = prover
+            .witness_assignment
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (!classification.is_sensitive(i)).then_some(*value))
+            .collect();
+        Ok(Self {
+            num_constraints,
+            instance_assignment: prover.instance_assignment.clone(),
+            unsatisfied,
+            witness_assignment,
+        })
+    }
+
+    /// The number of witness variables of the circuit.
+    pub fn num_witness_variables(&self) -> usize {
+        self.witness_assignment.len()
+    }
+
+    /// The value of the witness variable at `index`, or `None` if it is sensitive or out
+    /// of range.
+    pub fn witness_value(&self, index: usize) -> Option<&F> {
+        self.witness_assignment.get(index)?.as_ref()
+    }
+}
+
+impl<F: Field> fmt::Debug for AssignmentReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let witness = self
+            .witness_assignment
+            .iter()
+            .map(|value| RedactedValue(value.as_ref()))
+            .collect::<Vec<_>>();
+        f.debug_struct("AssignmentReport")
+            .field("num_constraints", &self.num_constraints)
+            .field("instance_assignment", &self.instance_assignment)
+            .field("witness_assignment", &witness)
+            .field("unsatisfied", &self.unsatisfied)
+            .finish()
+    }
+}
+
+struct RedactedValue<'a, F>(Option<&'a F>);
+
+impl<F: fmt::Debug> fmt::Debug for RedactedValue<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str(REDACTED),
+        }
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Synthesize `circuit` as the prover would and report on its assignment, redacting
+    /// the witness variables that the circuit marks as sensitive.
+    pub fn assignment_report<C>(circuit: C) -> R1CSResult<AssignmentReport<E::ScalarField>>
+    where
+        C: ClassifiedCircuit<E::ScalarField>,
+    {
+        let classification = circuit.witness_classification();
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Prove {
+            construct_matrices: true,
+        });
+        let report = circuit
+            .generate_constraints(cs.clone())
+            .and_then(|()| AssignmentReport::new(&cs, &classification));
+        if let Some(mut cs) = cs.borrow_mut() {
+            cs.witness_assignment.zeroize();
+        }
+        report
+    }
+}
@@ -0,0 +1,160 @@
+use crate::ProvingKey;
+use ark_ec::pairing::Pairing;
+use ark_std::{borrow::Borrow, fmt, marker::PhantomData, mem, rand::Rng};
+
+/// The query vectors of a [`ProvingKey`], in serialization order.
+const QUERIES: [&str; 5] = ["a_query", "b_g1_query", "b_g2_query", "h_query", "l_query"];
+
+/// A difference found between the two copies of a [`RedundantProvingKey`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyIntegrityError {
+    /// The verifying keys, `beta_g1` or `delta_g1` differ.
+    HeaderMismatch,
+    /// A query vector has different lengths in the two copies.
+    LengthMismatch {
+        /// The name of the query vector.
+        query: &'static str,
+    },
+    /// A point of a query vector differs between the two copies.
+    PointMismatch {
+        /// The name of the query vector.
+        query: &'static str,
+        /// The index of the point in the query vector.
+        index: usize,
+    },
+}
+
+impl ark_std::error::Error for KeyIntegrityError {}
+
+impl fmt::Display for KeyIntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyIntegrityError::HeaderMismatch => {
+                write!(f, "the verifying keys, beta_g1 or delta_g1 differ")
+            },
+            KeyIntegrityError::LengthMismatch { query } => {
+                write!(f, "{} has different lengths", query)
+            },
+            KeyIntegrityError::PointMismatch { query, index } => {
+                write!(f, "{}[{}] differs", query, index)
+            },
+        }
+    }
+}
+
+/// Two copies of the same [`ProvingKey`], for example one memory-mapped and one in RAM,
+/// that can be cross-checked to detect silent corruption of either before a long job.
+pub struct RedundantProvingKey<E: Pairing, K: Borrow<ProvingKey<E>> = ProvingKey<E>> {
+    primary: K,
+    replica: K,
+    _p: PhantomData<E>,
+}
+
+impl<E: Pairing, K: Borrow<ProvingKey<E>>> RedundantProvingKey<E, K> {
+    /// Wrap two copies of the same proving key.
+    pub fn new(primary: K, replica: K) -> Self {
+        Self {
+            primary,
+            replica,
+            _p: PhantomData,
+        }
+    }
+
+    /// The copy that should be used for proving.
+    pub fn primary(&self) -> &ProvingKey<E> {
+        self.primary.borrow()
+    }
+
+    /// The copy that [`check_integrity`](Self::check_integrity) compares the primary against.
+    pub fn replica(&self) -> &ProvingKey<E> {
+        self.replica.borrow()
+    }
+
+    /// Make the replica the primary copy and vice versa, for example after the
+    /// primary has failed to produce a valid proof.
+    pub fn failover(&mut self) {
+        mem::swap(&mut self.primary, &mut self.replica);
+    }
+
+    /// Unwrap the two copies, primary first.
+    pub fn into_inner(self) -> (K, K) {
+        (self.primary, self.replica)
+    }
+
+    /// Compare the two copies. The verifying keys, `beta_g1`, `delta_g1`, and the
+    /// lengths of all query vectors are always compared; then `samples` query points
+    /// chosen uniformly at random are compared. If `samples` is at least the total
+    /// number of query points, every point is compared.
+    pub fn check_integrity<R: Rng>(
+        &self,
+        samples: usize,
+        rng: &mut R,
+    ) -> Result<(), KeyIntegrityError> {
+        let (primary, replica) = (self.primary(), self.replica());
+        if primary.vk != replica.vk
+            || primary.beta_g1 != replica.beta_g1
+            || primary.delta_g1 != replica.delta_g1
+        {
+            return Err(KeyIntegrityError::HeaderMismatch);
+        }
+
+        let lengths = query_lengths(primary);
+        for (query, (len, other)) in QUERIES
+            .iter()
+            .zip(lengths.iter().zip(query_lengths(replica)))
+        {
+            if *len != other {
+                return Err(KeyIntegrityError::LengthMismatch { query });
+            }
+        }
+
+        let total = lengths.iter().sum::<usize>();
+        if samples >= total {
+            (0..total).try_for_each(|i| compare_point(primary, replica, &lengths, i))
+        } else {
+            (0..samples).try_for_each(|_| {
+                compare_point(primary, replica, &lengths, rng.gen_range(0..total))
+            })
+        }
+    }
+}
+
+fn query_lengths<E: Pairing>(pk: &ProvingKey<E>) -> [usize; 5] {
+    [
+        pk.a_query.len(),
+        pk.b_g1_query.len(),
+        pk.b_g2_query.len(),
+        pk.h_query.len(),
+        pk.l_query.len(),
+    ]
+}
+
+/// Compares the `index`-th point of the concatenation of all query vectors.
+fn compare_point<E: Pairing>(
+    primary: &ProvingKey<E>,
+    replica: &ProvingKey<E>,
+    lengths: &[usize; 5],
+    mut index: usize,
+) -> Result<(), KeyIntegrityError> {
+    let mut query = 0;
+    while index >= lengths[query] {
+        index -= lengths[query];
+        query += 1;
+    }
+
+    let equal = match query {
+        0 => primary.a_query[index] == replica.a_query[index],
+        1 => primary.b_g1_query[index] == replica.b_g1_query[index],
+        2 => primary.b_g2_query[index] == replica.b_g2_query[index],
+        3 => primary.h_query[index] == replica.h_query[index],
+        _ => primary.l_query[index] == replica.l_query[index],
+    };
+    if equal {
+        Ok(())
+    } else {
+        Err(KeyIntegrityError::PointMismatch {
+            query: QUERIES[query],
+            index,
+        })
+    }
+}
@@ -0,0 +1,180 @@
+pub use crate::msm::MsmQuery;
+
+use crate::{
+    msm::{CpuBackend, MsmBackend, ScalarBigInt},
+    r1cs_to_qap::R1CSToQAP,
+    redact::{AssignmentReport, ClassifiedCircuit},
+    Groth16, Proof, ProvingKey,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, UniformRand};
+use ark_relations::r1cs::Result as R1CSResult;
+use ark_std::rand::Rng;
+use std::{
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+use zeroize::Zeroizing;
+
+/// The timing of one multi-scalar multiplication of the prover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsmTiming {
+    /// The query of the proving key.
+    pub query: MsmQuery,
+    /// The number of bases.
+    pub size: usize,
+    /// The time from start to end.
+    pub duration: Duration,
+}
+
+/// Where the time of one proof went, as returned by [`Groth16::create_proof_with_report`].
+///
+/// The multi-scalar multiplications run concurrently when the `parallel` feature is
+/// enabled, so their durations overlap and may add up to more than `total`.
+///
+/// The report includes the assignment of the circuit, whose sensitive witness values
+/// are redacted as the circuit classifies them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvingReport<F: Field> {
+    /// The number of constraints of the circuit.
+    pub num_constraints: usize,
+    /// The number of instance variables, including the constant `1`.
+    pub num_instance_variables: usize,
+    /// The number of witness variables.
+    pub num_witness_variables: usize,
+    /// The time to synthesize the circuit and inline its linear combinations.
+    pub synthesis: Duration,
+    /// The time to compute the QAP witness `h`, which is dominated by FFTs.
+    pub witness_map: Duration,
+    /// The multi-scalar multiplications, in the order in which they completed.
+    pub msms: Vec<MsmTiming>,
+    /// The time of the whole proof.
+    pub total: Duration,
+    /// The assignment, without its sensitive witness values.
+    pub assignment: AssignmentReport<F>,
+}
+
+impl<F: Field> fmt::Display for ProvingReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} constraints, {} instance and {} witness variables",
+            self.num_constraints, self.num_instance_variables, self.num_witness_variables
+        )?;
+        writeln!(f, "synthesis: {:?}", self.synthesis)?;
+        writeln!(f, "witness map: {:?}", self.witness_map)?;
+        for msm in &self.msms {
+            writeln!(
+                f,
+                "{:?} MSM ({} bases): {:?}",
+                msm.query, msm.size, msm.duration
+            )?;
+        }
+        write!(f, "total: {:?}", self.total)
+    }
+}
+
+/// Records the timing of each MSM of `backend` under the query that the prover names.
+struct TimingBackend<'a, B> {
+    backend: &'a B,
+    msms: Mutex<Vec<MsmTiming>>,
+}
+
+impl<B> TimingBackend<'_, B> {
+    fn record(&self, query: MsmQuery, size: usize, start: Instant) {
+        let timing = MsmTiming {
+            query,
+            size,
+            duration: start.elapsed(),
+        };
+        self.msms.lock().unwrap().push(timing);
+    }
+}
+
+impl<E: Pairing, B: MsmBackend<E>> MsmBackend<E> for TimingBackend<'_, B> {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        let start = Instant::now();
+        let acc = self.backend.msm_g1(query, bases, scalars);
+        self.record(query, bases.len().min(scalars.len()), start);
+        acc
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        let start = Instant::now();
+        let acc = self.backend.msm_g2(query, bases, scalars);
+        self.record(query, bases.len().min(scalars.len()), start);
+        acc
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge using the provided
+    /// R1CS-to-QAP reduction, together with a report of where its time went.
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_with_report<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        rng: &mut impl Rng,
+    ) -> R1CSResult<(Proof<E>, ProvingReport<E::ScalarField>)>
+    where
+        C: ClassifiedCircuit<E::ScalarField>,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_with_report(circuit, pk, *r, *s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s` and the provided
+    /// R1CS-to-QAP reduction, together with a report of where its time went.
+    pub fn create_proof_with_report<C>(
+        circuit: C,
+        pk: &ProvingKey<E>,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> R1CSResult<(Proof<E>, ProvingReport<E::ScalarField>)>
+    where
+        C: ClassifiedCircuit<E::ScalarField>,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let classification = circuit.witness_classification();
+        let start = Instant::now();
+        let cs = Self::synthesize(circuit, &())?;
+        let synthesis = start.elapsed();
+        let assignment = AssignmentReport::new(&cs, &classification)?;
+        add_to_trace!(|| "Assignment", || format!("{:?}", assignment));
+
+        let witness_map_start = Instant::now();
+        let h = Self::reduce(cs.clone(), &())?;
+        let witness_map = witness_map_start.elapsed();
+
+        let backend = TimingBackend {
+            backend: &CpuBackend,
+            msms: Mutex::new(Vec::new()),
+        };
+        let prover = cs.borrow().unwrap();
+        let proof = Self::create_proof_with_assignment(
+            pk.into(),
+            &r,
+            &s,
+            &h,
+            &prover.instance_assignment[1..],
+            &prover.witness_assignment,
+            &backend,
+        )?;
+
+        let report = ProvingReport {
+            num_constraints: prover.num_constraints,
+            num_instance_variables: prover.num_instance_variables,
+            num_witness_variables: prover.num_witness_variables,
+            synthesis,
+            witness_map,
+            msms: backend.msms.into_inner().unwrap(),
+            total: start.elapsed(),
+            assignment,
+        };
+        Ok((proof, report))
+    }
+}
@@ -0,0 +1,234 @@
+//! Verification with fixed-size encodings and bounded memory, for blockchain runtimes such
+//! as Substrate pallets, runtime host functions and CosmWasm contracts.
+//!
+//! # Specification
+//!
+//! A proof is the `P`-byte array of [`Proof::to_array`], a public input is the `S`-byte
+//! little-endian canonical encoding of a scalar, and a verifying key is its compressed
+//! encoding, which is [`verifying_key_size`] bytes long. These are the SCALE encodings of
+//! `[u8; P]`, `Vec<[u8; S]>` and `Vec<u8>`, so a pallet can decode its call arguments with
+//! `parity-scale-codec` and pass them on unchanged.
+//!
+//! A [`StoredVerifyingKey`] is the compressed verifying key followed by the compressed
+//! pairing `e(alpha * G, beta * H)`, which is [`stored_verifying_key_size`] bytes long.
+//! Contracts that keep a key in storage and verify against it on every call, as CosmWasm
+//! contracts do, save recomputing the pairing each time.
+//!
+//! [`Groth16::verify_runtime`] and [`Groth16::verify_stored`] never panic on untrusted
+//! input, and every length is checked before any point is decoded, so that allocations are
+//! bounded by [`MAX_RUNTIME_PUBLIC_INPUTS`]. Verification samples no randomness and uses
+//! no threads without the `parallel` feature, so its result and its cost are
+//! deterministic.
+//!
+//! Smart contracts, such as ink! contracts, can pass a [`PairingBackend`] to
+//! [`Groth16::verify_runtime_with_backend`] that checks the pairings through a chain
+//! extension or precompile, so that neither the pairing code nor its execution cost is in
+//! the contract.
+use crate::{
+    equation, prepare_verifying_key, r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey, Proof,
+    VerificationError, VerifyingKey,
+};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::One;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+/// The largest number of public inputs that [`Groth16::verify_runtime`] and
+/// [`StoredVerifyingKey::from_bytes`] accept.
+pub const MAX_RUNTIME_PUBLIC_INPUTS: usize = 256;
+
+/// The size in bytes of the compressed verifying key for a circuit with
+/// `num_public_inputs` public inputs.
+pub fn verifying_key_size<E: Pairing>(num_public_inputs: usize) -> usize {
+    let g1 = E::G1Affine::zero().compressed_size();
+    let g2 = E::G2Affine::zero().compressed_size();
+    // `alpha_g1`, `beta_g2`, `gamma_g2`, `delta_g2`, and the length-prefixed `gamma_abc_g1`.
+    g1 + 3 * g2 + 8 + (num_public_inputs + 1) * g1
+}
+
+/// The size in bytes of a [`StoredVerifyingKey`] for a circuit with `num_public_inputs`
+/// public inputs.
+pub fn stored_verifying_key_size<E: Pairing>(num_public_inputs: usize) -> usize {
+    verifying_key_size::<E>(num_public_inputs) + E::TargetField::default().compressed_size()
+}
+
+/// A verifying key with the pairing `e(alpha * G, beta * H)` precomputed, in the compact
+/// encoding of the [module documentation](self) for contract storage.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct StoredVerifyingKey<E: Pairing> {
+    /// The verifying key.
+    pub vk: VerifyingKey<E>,
+    /// The element `e(alpha * G, beta * H)` in `E::GT`.
+    pub alpha_g1_beta_g2: E::TargetField,
+}
+
+impl<E: Pairing> From<&VerifyingKey<E>> for StoredVerifyingKey<E> {
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        Self {
+            vk: vk.clone(),
+            alpha_g1_beta_g2: equation::alpha_g1_beta_g2::<E>(vk.alpha_g1, vk.beta_g2),
+        }
+    }
+}
+
+impl<E: Pairing> StoredVerifyingKey<E> {
+    /// Returns the encoding of `self` for storage.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.compressed_size());
+        self.serialize_compressed(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        bytes
+    }
+
+    /// Parses the encoding of [`StoredVerifyingKey::to_bytes`], checking its length before
+    /// any point is decoded.
+    ///
+    /// The points of the verifying key are checked to be in the prime-order subgroups, but
+    /// the pairing is not recomputed, so `bytes` must come from storage that only the
+    /// contract writes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VerificationError> {
+        let g1 = E::G1Affine::zero().compressed_size();
+        let num_public_inputs = bytes
+            .len()
+            .checked_sub(stored_verifying_key_size::<E>(0))
+            .filter(|extra| extra % g1 == 0)
+            .map(|extra| extra / g1);
+        if !matches!(num_public_inputs, Some(n) if n <= MAX_RUNTIME_PUBLIC_INPUTS) {
+            return Err(VerificationError::InvalidEncoding);
+        }
+        Ok(Self::deserialize_compressed(bytes)?)
+    }
+
+    /// Prepares `self` for verification without computing a pairing.
+    pub fn prepare(&self) -> PreparedVerifyingKey<E> {
+        PreparedVerifyingKey {
+            vk: self.vk.clone(),
+            alpha_g1_beta_g2: self.alpha_g1_beta_g2,
+            gamma_g2_neg_pc: equation::negate_and_prepare::<E>(self.vk.gamma_g2),
+            delta_g2_neg_pc: equation::negate_and_prepare::<E>(self.vk.delta_g2),
+        }
+    }
+}
+
+/// A backend that checks whether a product of pairings is the identity, the check that
+/// pairing chain extensions and precompiles typically expose.
+pub trait PairingBackend<E: Pairing> {
+    /// Returns whether `prod_i e(g1[i], g2[i])` is the identity of `E::GT`, or `None` if
+    /// the check cannot be computed. `g1` and `g2` have the same length.
+    fn pairing_check(&self, g1: &[E::G1Affine], g2: &[E::G2Affine]) -> Option<bool>;
+}
+
+/// The default backend, using the pairing of `ark-ec`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuPairing;
+
+impl<E: Pairing> PairingBackend<E> for CpuPairing {
+    fn pairing_check(&self, g1: &[E::G1Affine], g2: &[E::G2Affine]) -> Option<bool> {
+        let PairingOutput(product) =
+            E::final_exponentiation(E::multi_miller_loop(g1.iter().copied(), g2.iter().copied()))?;
+        Some(product.is_one())
+    }
+}
+
+/// Decodes `S`-byte public inputs, checking their number and size.
+fn decode_public_inputs<E: Pairing, const S: usize>(
+    public_inputs: &[[u8; S]],
+) -> Result<Vec<E::ScalarField>, VerificationError> {
+    if public_inputs.len() > MAX_RUNTIME_PUBLIC_INPUTS
+        || E::ScalarField::default().compressed_size() != S
+    {
+        return Err(VerificationError::InvalidEncoding);
+    }
+    public_inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            E::ScalarField::deserialize_compressed(&input[..])
+                .map_err(|_| VerificationError::InputNotInField { index })
+        })
+        .collect()
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify the proof `proof` against the compressed verifying key `vk` and the public
+    /// inputs `public_inputs`, in the encodings of the [module documentation](self).
+    ///
+    /// `P` must be the compressed size of a proof and `S` the size of a scalar for `E`,
+    /// which are 192 and 32 for BLS12-381 and BLS12-377; otherwise this returns
+    /// [`VerificationError::InvalidEncoding`].
+    pub fn verify_runtime<const P: usize, const S: usize>(
+        vk: &[u8],
+        proof: &[u8; P],
+        public_inputs: &[[u8; S]],
+    ) -> Result<bool, VerificationError> {
+        if vk.len() != verifying_key_size::<E>(public_inputs.len()) {
+            return Err(VerificationError::InvalidEncoding);
+        }
+        let public_inputs = decode_public_inputs::<E, S>(public_inputs)?;
+        let proof = Proof::<E>::from_array(proof)?;
+        let vk = VerifyingKey::<E>::deserialize_compressed(vk)?;
+
+        Ok(Self::verify_proof(
+            &prepare_verifying_key(&vk),
+            &proof,
+            &public_inputs,
+        )?)
+    }
+
+    /// Verify the proof `proof` against `vk` and the public inputs `public_inputs`, in the
+    /// encodings of [`Groth16::verify_runtime`], without computing the pairing
+    /// `e(alpha * G, beta * H)`.
+    pub fn verify_stored<const P: usize, const S: usize>(
+        vk: &StoredVerifyingKey<E>,
+        proof: &[u8; P],
+        public_inputs: &[[u8; S]],
+    ) -> Result<bool, VerificationError> {
+        let public_inputs = decode_public_inputs::<E, S>(public_inputs)?;
+        let proof = Proof::<E>::from_array(proof)?;
+        Ok(Self::verify_proof(&vk.prepare(), &proof, &public_inputs)?)
+    }
+
+    /// Verify like [`Groth16::verify_runtime`], but check the pairings with `backend`, as
+    /// `e(A, B) * e(IC(x), -gamma) * e(C, -delta) * e(-alpha, beta) = 1` in the notation of
+    /// [`equation`].
+    pub fn verify_runtime_with_backend<const P: usize, const S: usize, B>(
+        vk: &[u8],
+        proof: &[u8; P],
+        public_inputs: &[[u8; S]],
+        backend: &B,
+    ) -> Result<bool, VerificationError>
+    where
+        B: PairingBackend<E> + ?Sized,
+    {
+        if vk.len() != verifying_key_size::<E>(public_inputs.len()) {
+            return Err(VerificationError::InvalidEncoding);
+        }
+        let public_inputs = decode_public_inputs::<E, S>(public_inputs)?;
+        let proof = Proof::<E>::from_array(proof)?;
+        let vk = VerifyingKey::<E>::deserialize_compressed(vk)?;
+
+        let prepared_inputs = equation::prepared_inputs::<E>(&vk.gamma_abc_g1, &public_inputs)
+            .ok_or(VerificationError::WrongNumberOfPublicInputs {
+                expected: vk.num_public_inputs(),
+                found: public_inputs.len(),
+            })?;
+        let g1 = [
+            proof.a,
+            prepared_inputs.into_affine(),
+            proof.c,
+            (-vk.alpha_g1.into_group()).into_affine(),
+        ];
+        let g2 = [
+            proof.b,
+            (-vk.gamma_g2.into_group()).into_affine(),
+            (-vk.delta_g2.into_group()).into_affine(),
+            vk.beta_g2,
+        ];
+        backend
+            .pairing_check(&g1, &g2)
+            .ok_or(VerificationError::PairingCheckFailed)
+    }
+}
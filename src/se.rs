@@ -0,0 +1,190 @@
+//! A simulation-extractable variant of Groth16, after
+//! [[BKSV21]](https://eprint.iacr.org/2020/811.pdf).
+//!
+//! Groth16 proofs are malleable: anyone can rerandomize a proof into a different proof of
+//! the same statement. [BKSV21] show that this is the only malleability, and that signing
+//! every proof with a fresh one-time key that is bound to the statement makes Groth16
+//! strongly simulation-extractable.
+//!
+//! # Specification
+//!
+//! The circuit of an [`SeProof`] has one more public input than the circuit it wraps, the
+//! *binding* `b = H(domain || pk)` of the public key `pk = sk · G` of a Schnorr key pair in
+//! `G1`, as the last public input, constrained by `b · 1 = b`. A proof is a Groth16 proof `π` for the wrapped statement
+//! and `b`, `pk`, and a Schnorr signature `(R, s)` on `π` with
+//!
+//! ```text
+//! R = k · G,  e = H(domain' || pk || R || π),  s = k + e · sk,
+//! ```
+//!
+//! where both hashes are SHA-256 reduced to the scalar field and `π` is compressed. The
+//! verifier checks `π` for the public inputs followed by `b`, and `s · G = R + e · pk`.
+//! Rerandomizing `π` invalidates the signature, and replacing the key changes `b`.
+use crate::{hash_to_field, r1cs_to_qap::R1CSToQAP, Groth16, PreparedVerifyingKey, Proof};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::PrimeField;
+use ark_relations::r1cs::Result as R1CSResult;
+use ark_serialize::*;
+use ark_std::{ops::Mul, vec::Vec};
+
+#[cfg(feature = "prover")]
+pub(crate) use self::proving::BoundCircuit;
+
+/// The domain separator of the binding of a public key.
+const BINDING_DOMAIN: &[u8] = b"ark-groth16/se/binding/v1";
+
+/// The domain separator of the Schnorr challenge.
+const SIGNATURE_DOMAIN: &[u8] = b"ark-groth16/se/signature/v1";
+
+/// A simulation-extractable proof: a Groth16 proof signed with a one-time key that is
+/// bound to its statement.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SeProof<E: Pairing> {
+    /// The Groth16 proof of the wrapped statement and the binding of `public_key`.
+    pub proof: Proof<E>,
+    /// The one-time public key.
+    pub public_key: E::G1Affine,
+    /// The commitment `R` of the signature.
+    pub signature_r: E::G1Affine,
+    /// The response `s` of the signature.
+    pub signature_s: E::ScalarField,
+}
+
+/// Hashes the compressed `value` with `domain` to a scalar.
+fn hash_to_scalar<F: PrimeField>(domain: &[u8], value: impl CanonicalSerialize) -> F {
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    hash_to_field(domain, &bytes)
+}
+
+/// The binding of `public_key`, the public input that ties a proof to it.
+fn binding<E: Pairing>(public_key: &E::G1Affine) -> E::ScalarField {
+    hash_to_scalar(BINDING_DOMAIN, *public_key)
+}
+
+/// The Schnorr challenge for a signature with commitment `r` on `proof`.
+fn challenge<E: Pairing>(
+    public_key: &E::G1Affine,
+    r: &E::G1Affine,
+    proof: &Proof<E>,
+) -> E::ScalarField {
+    hash_to_scalar(SIGNATURE_DOMAIN, (*public_key, *r, proof.clone()))
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify a simulation-extractable proof `proof` against the prepared verification
+    /// key `pvk`, with respect to the instance `public_inputs` of the wrapped circuit.
+    pub fn verify_se_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &SeProof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        if proof.public_key.is_zero() {
+            return Ok(false);
+        }
+        let e = challenge::<E>(&proof.public_key, &proof.signature_r, &proof.proof);
+        let signed = E::G1Affine::generator().mul(proof.signature_s)
+            == proof.signature_r.into_group() + proof.public_key.mul(e);
+        if !signed {
+            return Ok(false);
+        }
+
+        let public_inputs = [public_inputs, &[binding::<E>(&proof.public_key)]].concat();
+        Self::verify_proof(pvk, &proof.proof, &public_inputs)
+    }
+}
+
+/// Parameter generation and proving, which need the `prover` feature.
+#[cfg(feature = "prover")]
+mod proving {
+    use super::{binding, challenge, SeProof};
+    use crate::{r1cs_to_qap::R1CSToQAP, Groth16, ProvingKey};
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_relations::{
+        lc,
+        r1cs::{
+            ConstraintSynthesizer, ConstraintSystemRef, Result as R1CSResult, SynthesisError,
+            Variable,
+        },
+    };
+    use ark_std::{ops::Mul, rand::Rng};
+    use zeroize::Zeroizing;
+
+    /// `circuit` with a binding, such as that of a public key, as an additional, last public
+    /// input.
+    pub(crate) struct BoundCircuit<C, F> {
+        pub(crate) circuit: C,
+        pub(crate) binding: Option<F>,
+    }
+
+    impl<C, F> ConstraintSynthesizer<F> for BoundCircuit<C, F>
+    where
+        C: ConstraintSynthesizer<F>,
+        F: PrimeField,
+    {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> R1CSResult<()> {
+            self.circuit.generate_constraints(cs.clone())?;
+            // `LibsnarkReduction` adds a constraint for every public input, but another
+            // `R1CSToQAP` need not, and an input in no constraint drops out of the
+            // verification equation. `b · 1 = b` keeps the binding in it under any
+            // reduction.
+            let binding =
+                cs.new_input_variable(|| self.binding.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(lc!() + binding, lc!() + Variable::One, lc!() + binding)?;
+            Ok(())
+        }
+    }
+
+    impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+        /// Generates a proving key for simulation-extractable proofs of `circuit`, whose
+        /// verifying key has one more public input than `circuit`.
+        pub fn generate_random_se_parameters<C>(
+            circuit: C,
+            rng: &mut impl Rng,
+        ) -> R1CSResult<ProvingKey<E>>
+        where
+            C: ConstraintSynthesizer<E::ScalarField>,
+        {
+            let circuit = BoundCircuit {
+                circuit,
+                binding: None,
+            };
+            Self::generate_random_parameters_with_reduction(circuit, rng)
+        }
+
+        /// Create a simulation-extractable proof for `circuit` with a proving key from
+        /// [`Groth16::generate_random_se_parameters`].
+        /// This method samples the one-time key and randomness for zero knowledge via `rng`.
+        pub fn create_random_se_proof<C>(
+            circuit: C,
+            pk: &ProvingKey<E>,
+            rng: &mut impl Rng,
+        ) -> R1CSResult<SeProof<E>>
+        where
+            C: ConstraintSynthesizer<E::ScalarField>,
+        {
+            let generator = E::G1Affine::generator();
+            let secret_key = Zeroizing::new(E::ScalarField::rand(rng));
+            let public_key = generator.mul(*secret_key).into_affine();
+
+            let circuit = BoundCircuit {
+                circuit,
+                binding: Some(binding::<E>(&public_key)),
+            };
+            let proof = Self::create_random_proof_with_reduction(circuit, pk, rng)?;
+
+            let k = Zeroizing::new(E::ScalarField::rand(rng));
+            let signature_r = generator.mul(*k).into_affine();
+            let e = challenge::<E>(&public_key, &signature_r, &proof);
+            Ok(SeProof {
+                proof,
+                public_key,
+                signature_r,
+                signature_s: *k + e * *secret_key,
+            })
+        }
+    }
+}
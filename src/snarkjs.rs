@@ -0,0 +1,378 @@
+//! Verification of proofs in the JSON formats of snarkjs.
+//!
+//! snarkjs writes verifying keys (`verification_key.json`), proofs (`proof.json`), and
+//! public inputs (`public.json`) as JSON with every number as a decimal string:
+//!
+//! ```text
+//! G1 point:  ["x", "y", "z"]
+//! G2 point:  [["x.c0", "x.c1"], ["y.c0", "y.c1"], ["z.c0", "z.c1"]]
+//! vk:        { "vk_alpha_1": G1, "vk_beta_2": G2, "vk_gamma_2": G2, "vk_delta_2": G2,
+//!              "IC": [G1, ...], "nPublic": n, ... }
+//! proof:     { "pi_a": G1, "pi_b": G2, "pi_c": G1, ... }
+//! public:    ["x_1", ..., "x_n"]
+//! ```
+//!
+//! Points are affine, so `z` is one, or zero for the point at infinity. Other members,
+//! such as `curve` and `vk_alphabeta_12`, are ignored; a `protocol` other than `groth16`
+//! is rejected.
+use crate::{
+    prepare_verifying_key, r1cs_to_qap::R1CSToQAP, verifier::check_num_public_inputs, Groth16,
+    Proof, VerificationError, VerifyingKey,
+};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_std::{string::String, vec::Vec};
+
+/// The deepest nesting of arrays and objects that is parsed. snarkjs files nest at most
+/// three levels deep, so this only guards the recursion of the parser.
+const MAX_DEPTH: usize = 16;
+
+/// A parsed JSON value. Numbers are kept as their source text.
+pub(crate) enum Json<'a> {
+    /// `null`, `true`, or `false`.
+    Literal,
+    Number(&'a str),
+    String(String),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(String, Json<'a>)>),
+}
+
+impl<'a> Json<'a> {
+    /// Parses `input`, which must hold exactly one JSON value.
+    pub(crate) fn parse(input: &'a str) -> Option<Self> {
+        let mut parser = Parser { input, pos: 0 };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        (parser.pos == input.len()).then_some(value)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&Json<'a>> {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Json<'a>]> {
+        match self {
+            Json::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.eat(byte).then_some(())
+    }
+
+    fn literal(&mut self, literal: &str) -> Option<Json<'a>> {
+        self.input[self.pos..].starts_with(literal).then(|| {
+            self.pos += literal.len();
+            Json::Literal
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Option<Json<'a>> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'n' => self.literal("null"),
+            b't' => self.literal("true"),
+            b'f' => self.literal("false"),
+            b'"' => self.string().map(Json::String),
+            b'[' if depth < MAX_DEPTH => {
+                self.pos += 1;
+                let mut elements = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        elements.push(self.value(depth + 1)?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Some(Json::Array(elements))
+            },
+            b'{' if depth < MAX_DEPTH => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        members.push((key, self.value(depth + 1)?));
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Some(Json::Object(members))
+            },
+            b'-' | b'0'..=b'9' => {
+                let start = self.pos;
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                ) {
+                    self.pos += 1;
+                }
+                Some(Json::Number(&self.input[start..self.pos]))
+            },
+            _ => None,
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect(b'"')?;
+        let mut s = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        loop {
+            let (i, c) = chars.next()?;
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Some(s);
+                },
+                '\\' => s.push(match chars.next()?.1 {
+                    '"' => '"',
+                    '\\' => '\\',
+                    '/' => '/',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let hex = (0..4).map(|_| chars.next().map(|(_, c)| c));
+                        let code = hex.collect::<Option<String>>()?;
+                        char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+                    },
+                    _ => return None,
+                }),
+                c if c < ' ' => return None,
+                c => s.push(c),
+            }
+        }
+    }
+}
+
+/// Why a string is not an encoded field element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IntegerError {
+    /// The string is not an integer in the expected encoding.
+    Malformed,
+    /// The integer is not below the modulus.
+    TooLarge,
+}
+
+impl IntegerError {
+    /// The error for the public input at `index`.
+    pub(crate) fn input_error(self, index: usize) -> VerificationError {
+        match self {
+            IntegerError::Malformed => VerificationError::InvalidEncoding,
+            IntegerError::TooLarge => VerificationError::InputNotInField { index },
+        }
+    }
+}
+
+/// Parses a decimal integer into a field element, rejecting values that are not below
+/// the modulus.
+fn parse_decimal<F: PrimeField>(digits: &str) -> Result<F, IntegerError> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(IntegerError::Malformed);
+    }
+    let mut bigint = F::BigInt::default();
+    for digit in digits.bytes() {
+        let mut carry = u128::from(digit - b'0');
+        for limb in bigint.as_mut() {
+            let wide = u128::from(*limb) * 10 + carry;
+            *limb = wide as u64;
+            carry = wide >> 64;
+        }
+        if carry != 0 {
+            return Err(IntegerError::TooLarge);
+        }
+    }
+    F::from_bigint(bigint).ok_or(IntegerError::TooLarge)
+}
+
+/// Parses a coordinate: a decimal string, or an array of decimal strings for a field
+/// extension.
+fn parse_coordinate<F: Field>(json: &Json<'_>) -> Option<F> {
+    let elements = match json {
+        Json::Array(elements) => elements
+            .iter()
+            .map(|e| parse_decimal(e.as_str()?).ok())
+            .collect::<Option<Vec<_>>>()?,
+        json => ark_std::vec![parse_decimal(json.as_str()?).ok()?],
+    };
+    F::from_base_prime_field_elems(&elements)
+}
+
+/// Parses a point, returning `Err(VerificationError::InvalidEncoding)` if the coordinates
+/// cannot be read and `Err(invalid_point)` if they are not a point of the prime-order
+/// subgroup.
+fn parse_point<P: SWCurveConfig>(
+    json: Option<&Json<'_>>,
+    invalid_point: VerificationError,
+) -> Result<Affine<P>, VerificationError> {
+    let coordinates = json
+        .and_then(Json::as_array)
+        .filter(|c| c.len() == 3)
+        .and_then(|c| {
+            c.iter()
+                .map(parse_coordinate::<P::BaseField>)
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or(VerificationError::InvalidEncoding)?;
+    let point = match coordinates[2] {
+        z if z.is_zero() => return Ok(Affine::identity()),
+        z if z.is_one() => Affine::new_unchecked(coordinates[0], coordinates[1]),
+        _ => return Err(VerificationError::InvalidEncoding),
+    };
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(invalid_point)
+    }
+}
+
+/// Parses a JSON object and rejects it if its `protocol` is present but not `groth16`.
+fn parse_object(json: &str) -> Result<Json<'_>, VerificationError> {
+    let json = Json::parse(json)
+        .filter(|json| matches!(json, Json::Object(_)))
+        .ok_or(VerificationError::InvalidEncoding)?;
+    match json.get("protocol") {
+        None => Ok(json),
+        Some(protocol) if protocol.as_str() == Some("groth16") => Ok(json),
+        Some(_) => Err(VerificationError::InvalidEncoding),
+    }
+}
+
+fn parse_verifying_key<E, P1, P2>(json: &str) -> Result<VerifyingKey<E>, VerificationError>
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    let json = parse_object(json)?;
+    let g1 = |key| parse_point::<P1>(json.get(key), VerificationError::InvalidEncoding);
+    let g2 = |key| parse_point::<P2>(json.get(key), VerificationError::InvalidEncoding);
+    let gamma_abc_g1 = json
+        .get("IC")
+        .and_then(Json::as_array)
+        .ok_or(VerificationError::InvalidEncoding)?
+        .iter()
+        .map(|point| parse_point::<P1>(Some(point), VerificationError::InvalidEncoding))
+        .collect::<Result<Vec<_>, _>>()?;
+    if gamma_abc_g1.is_empty() {
+        return Err(VerificationError::InvalidEncoding);
+    }
+    if let Some(num_public) = json.get("nPublic") {
+        match num_public {
+            Json::Number(n) if n.parse() == Ok(gamma_abc_g1.len() - 1) => {},
+            _ => return Err(VerificationError::InvalidEncoding),
+        }
+    }
+
+    Ok(VerifyingKey {
+        alpha_g1: g1("vk_alpha_1")?,
+        beta_g2: g2("vk_beta_2")?,
+        gamma_g2: g2("vk_gamma_2")?,
+        delta_g2: g2("vk_delta_2")?,
+        gamma_abc_g1,
+    })
+}
+
+fn parse_proof<E, P1, P2>(json: &str) -> Result<Proof<E>, VerificationError>
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    let json = parse_object(json)?;
+    Ok(Proof {
+        a: parse_point::<P1>(json.get("pi_a"), VerificationError::MalformedProofPoint)?,
+        b: parse_point::<P2>(json.get("pi_b"), VerificationError::MalformedProofPoint)?,
+        c: parse_point::<P1>(json.get("pi_c"), VerificationError::MalformedProofPoint)?,
+    })
+}
+
+fn parse_public_inputs<F: PrimeField>(json: &str) -> Result<Vec<F>, VerificationError> {
+    Json::parse(json)
+        .as_ref()
+        .and_then(Json::as_array)
+        .ok_or(VerificationError::InvalidEncoding)?
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            let input = input.as_str().ok_or(VerificationError::InvalidEncoding)?;
+            parse_decimal(input).map_err(|e| e.input_error(index))
+        })
+        .collect()
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Verify a proof produced by snarkjs, given the contents of its `proof.json` in
+    /// `proof_json`, against the verifying key in `vk_json` (`verification_key.json`) and
+    /// the public inputs in `public_json` (`public.json`).
+    ///
+    /// Coordinates and inputs are checked to be canonical field elements and points of the
+    /// prime-order subgroups. The curve is that of `E`; a key or proof for another curve
+    /// is rejected because its points do not lie on the curve.
+    pub fn verify_snarkjs<P1, P2>(
+        vk_json: &str,
+        proof_json: &str,
+        public_json: &str,
+    ) -> Result<bool, VerificationError>
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        let vk = parse_verifying_key::<E, P1, P2>(vk_json)?;
+        let proof = parse_proof::<E, P1, P2>(proof_json)?;
+        let public_inputs = parse_public_inputs::<E::ScalarField>(public_json)?;
+
+        let pvk = prepare_verifying_key(&vk);
+        check_num_public_inputs(&pvk, public_inputs.len())?;
+        Ok(Self::verify_proof(&pvk, &proof, &public_inputs)?)
+    }
+}
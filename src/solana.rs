@@ -0,0 +1,164 @@
+//! Encoding of verifying keys, proofs and public inputs in the byte format of the
+//! `alt_bn128` syscalls of Solana, for verifying proofs of `Bn254` in Solana programs.
+//!
+//! # Specification
+//!
+//! Every base field element is big-endian and padded to the byte length of the modulus,
+//! 32 bytes for `Bn254`. A `G1` point is `x || y` and a `G2` point is
+//! `x.c1 || x.c0 || y.c1 || y.c0`, so 64 and 128 bytes for `Bn254`, and the point at
+//! infinity is all zeros. A public input is its scalar, big-endian and padded the same way.
+//! The `A` of a [`SolanaProof`] is negated, so that a program accepts the proof if the
+//! pairing syscall reports that
+//!
+//! ```text
+//! e(-A, B) · e(α, β) · e(IC(x), γ) · e(C, δ) = 1,
+//! ```
+//!
+//! in the notation of [`equation`](crate::equation). [`Groth16::solana_pairing_input`] is
+//! the input of the pairing syscall for this check.
+//!
+//! A verifier program accumulates `IC(x)` from the verifying key with the `alt_bn128`
+//! multiplication and addition syscalls, then checks the pairings:
+//!
+//! ```ignore
+//! use solana_program::alt_bn128::prelude::*;
+//!
+//! fn verify(vk: &SolanaVerifyingKey, proof: &SolanaProof, inputs: &[Vec<u8>]) -> bool {
+//!     let mut ic = vk.gamma_abc_g1[0].clone();
+//!     for (input, point) in inputs.iter().zip(&vk.gamma_abc_g1[1..]) {
+//!         let term = alt_bn128_multiplication(&[&point[..], input].concat()).unwrap();
+//!         ic = alt_bn128_addition(&[ic, term].concat()).unwrap();
+//!     }
+//!     let input = [
+//!         &proof.a[..], &proof.b, &vk.alpha_g1, &vk.beta_g2,
+//!         &ic, &vk.gamma_g2, &proof.c, &vk.delta_g2,
+//!     ]
+//!     .concat();
+//!     alt_bn128_pairing(&input).unwrap()[31] == 1
+//! }
+//! ```
+use crate::{equation, r1cs_to_qap::R1CSToQAP, Groth16, Proof, VerificationError, VerifyingKey};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_std::vec::Vec;
+
+/// Appends `f` to `bytes`, with the coefficients of a field extension in reverse order.
+fn push_field<F: Field>(bytes: &mut Vec<u8>, f: &F) {
+    let elements = f.to_base_prime_field_elements().collect::<Vec<_>>();
+    for element in elements.iter().rev() {
+        bytes.extend(element.into_bigint().to_bytes_be());
+    }
+}
+
+/// Encodes a point of `G1` or `G2`.
+pub fn encode_point<P: SWCurveConfig>(point: &Affine<P>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if point.infinity {
+        push_field(&mut bytes, &P::BaseField::ZERO);
+        bytes.resize(2 * bytes.len(), 0);
+    } else {
+        push_field(&mut bytes, &point.x);
+        push_field(&mut bytes, &point.y);
+    }
+    bytes
+}
+
+/// Encodes a public input.
+pub fn encode_scalar<F: PrimeField>(scalar: &F) -> Vec<u8> {
+    scalar.into_bigint().to_bytes_be()
+}
+
+/// A proof in the format of the [module documentation](self), with `A` negated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolanaProof {
+    /// The encoding of `-A`.
+    pub a: Vec<u8>,
+    /// The encoding of `B`.
+    pub b: Vec<u8>,
+    /// The encoding of `C`.
+    pub c: Vec<u8>,
+}
+
+/// A verifying key in the format of the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolanaVerifyingKey {
+    /// The encoding of `alpha * G`.
+    pub alpha_g1: Vec<u8>,
+    /// The encoding of `beta * H`.
+    pub beta_g2: Vec<u8>,
+    /// The encoding of `gamma * H`.
+    pub gamma_g2: Vec<u8>,
+    /// The encoding of `delta * H`.
+    pub delta_g2: Vec<u8>,
+    /// The encodings of the points `IC_0, …, IC_ℓ`.
+    pub gamma_abc_g1: Vec<Vec<u8>>,
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Encode `proof` for a Solana program.
+    pub fn proof_to_solana<P1, P2>(proof: &Proof<E>) -> SolanaProof
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        SolanaProof {
+            a: encode_point(&-proof.a),
+            b: encode_point(&proof.b),
+            c: encode_point(&proof.c),
+        }
+    }
+
+    /// Encode `vk` for a Solana program.
+    pub fn verifying_key_to_solana<P1, P2>(vk: &VerifyingKey<E>) -> SolanaVerifyingKey
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        SolanaVerifyingKey {
+            alpha_g1: encode_point(&vk.alpha_g1),
+            beta_g2: encode_point(&vk.beta_g2),
+            gamma_g2: encode_point(&vk.gamma_g2),
+            delta_g2: encode_point(&vk.delta_g2),
+            gamma_abc_g1: vk.gamma_abc_g1.iter().map(encode_point).collect(),
+        }
+    }
+
+    /// Returns the input of the pairing syscall that checks `proof` against `vk` and
+    /// `public_inputs`, for programs that receive `IC(x)` precomputed.
+    pub fn solana_pairing_input<P1, P2>(
+        vk: &VerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<Vec<u8>, VerificationError>
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        let prepared_inputs = equation::prepared_inputs::<E>(&vk.gamma_abc_g1, public_inputs)
+            .ok_or(VerificationError::WrongNumberOfPublicInputs {
+                expected: vk.num_public_inputs(),
+                found: public_inputs.len(),
+            })?
+            .into_affine();
+        let proof = Self::proof_to_solana(proof);
+        let vk = Self::verifying_key_to_solana(vk);
+        Ok([
+            proof.a,
+            proof.b,
+            vk.alpha_g1,
+            vk.beta_g2,
+            encode_point(&prepared_inputs),
+            vk.gamma_g2,
+            proof.c,
+            vk.delta_g2,
+        ]
+        .concat())
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    prover::QueryAccumulators, r1cs_to_qap::R1CSToQAP, Groth16, Proof, ProverError, VerifyingKey,
+};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_serialize::{CanonicalDeserialize, Compress, Read, Validate};
+use ark_std::{cfg_iter, rand::Rng, vec::Vec};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Create a Groth16 proof that is zero-knowledge, reading the proving key from `reader`
+    /// instead of requiring it in memory. See [`Groth16::create_proof_from_reader`].
+    /// This method samples randomness for zero knowledges via `rng`.
+    pub fn create_random_proof_from_reader<C, R>(
+        circuit: C,
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+        chunk_size: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        R: Read,
+    {
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+
+        Self::create_proof_from_reader(circuit, reader, compress, validate, chunk_size, *r, *s)
+    }
+
+    /// Create a Groth16 proof using randomness `r` and `s`, reading a [`ProvingKey`]
+    /// serialized with `compress` from `reader`.
+    ///
+    /// The key is consumed in a single pass: each query vector is deserialized at most
+    /// `chunk_size` points at a time and folded into a running multi-scalar multiplication,
+    /// so only the witness and one chunk of points are held in memory at once.
+    ///
+    /// [`ProvingKey`]: crate::ProvingKey
+    pub fn create_proof_from_reader<C, R>(
+        circuit: C,
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+        chunk_size: usize,
+        r: E::ScalarField,
+        s: E::ScalarField,
+    ) -> Result<Proof<E>, ProverError>
+    where
+        C: ConstraintSynthesizer<E::ScalarField>,
+        R: Read,
+    {
+        let (r, s) = (Zeroizing::new(r), Zeroizing::new(s));
+        let prover_time = start_timer!(|| "Groth16::Prover (streaming)");
+        let (cs, h) = Self::synthesize_and_reduce(circuit, &())?;
+        let h_assignment =
+            Zeroizing::new(cfg_iter!(h).map(|s| s.into_bigint()).collect::<Vec<_>>());
+        drop(h);
+
+        let prover = cs.borrow().unwrap();
+        let assignment = Zeroizing::new(
+            cfg_iter!(prover.instance_assignment[1..])
+                .chain(cfg_iter!(prover.witness_assignment))
+                .map(|s| s.into_bigint())
+                .collect::<Vec<_>>(),
+        );
+        let aux_assignment = &assignment[(prover.instance_assignment.len() - 1)..];
+        drop(prover);
+
+        let chunk_size = chunk_size.max(1);
+        let vk = VerifyingKey::<E>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let beta_g1 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        let delta_g1 = E::G1Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        let stream_time = start_timer!(|| "Stream queries");
+        let a_acc = stream_msm::<E::G1Affine, _>(
+            &mut reader,
+            compress,
+            validate,
+            &assignment,
+            true,
+            chunk_size,
+        )?;
+        let b_g1_acc = stream_msm::<E::G1Affine, _>(
+            &mut reader,
+            compress,
+            validate,
+            &assignment,
+            true,
+            chunk_size,
+        )?;
+        let b_g2_acc = stream_msm::<E::G2Affine, _>(
+            &mut reader,
+            compress,
+            validate,
+            &assignment,
+            true,
+            chunk_size,
+        )?;
+        let h_acc = stream_msm::<E::G1Affine, _>(
+            &mut reader,
+            compress,
+            validate,
+            // `h_query` has no base for the top coefficient of `h`, which is always zero.
+            &h_assignment[..h_assignment.len().saturating_sub(1)],
+            false,
+            chunk_size,
+        )?;
+        let l_aux_acc = stream_msm::<E::G1Affine, _>(
+            &mut reader,
+            compress,
+            validate,
+            aux_assignment,
+            false,
+            chunk_size,
+        )?;
+        end_timer!(stream_time);
+
+        let acc = QueryAccumulators {
+            a: a_acc,
+            b_g1: b_g1_acc,
+            b_g2: b_g2_acc,
+            h: h_acc,
+            l: l_aux_acc,
+        };
+        let proof = Self::assemble_proof((&vk).into(), beta_g1, delta_g1, &r, &s, acc);
+        end_timer!(prover_time);
+
+        Ok(proof)
+    }
+}
+
+/// Reads a serialized `Vec<G>` from `reader` in chunks of `chunk_size` points and returns its
+/// multi-scalar multiplication with `scalars`. If `with_constant`, the vector has one extra
+/// leading point which is added to the result without a scalar.
+fn stream_msm<G, R>(
+    reader: &mut R,
+    compress: Compress,
+    validate: Validate,
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+    with_constant: bool,
+    chunk_size: usize,
+) -> Result<G::Group, ProverError>
+where
+    G: AffineRepr,
+    G::Group: VariableBaseMSM<MulBase = G>,
+    R: Read,
+{
+    let len = u64::deserialize_with_mode(&mut *reader, compress, validate)?;
+    if len != (scalars.len() + usize::from(with_constant)) as u64 {
+        return Err(ProverError::MalformedProvingKey);
+    }
+
+    let mut acc = G::Group::zero();
+    if with_constant {
+        acc += G::deserialize_with_mode(&mut *reader, compress, validate)?;
+    }
+
+    let mut chunk = Vec::with_capacity(chunk_size.min(scalars.len()));
+    for scalars in scalars.chunks(chunk_size) {
+        chunk.clear();
+        for _ in 0..scalars.len() {
+            chunk.push(G::deserialize_with_mode(&mut *reader, compress, validate)?);
+        }
+        acc += G::Group::msm_bigint(&chunk, scalars);
+    }
+
+    Ok(acc)
+}
@@ -1,15 +1,43 @@
-use crate::{prepare_verifying_key, Groth16};
+use crate::{
+    aggregation::AggregateProof,
+    blob,
+    cache::PvkCache,
+    circom, codegen,
+    config::{OperationCosts, ProverConfig, WindowTuning, MAX_WINDOW_SIZE},
+    msm::{BatchAffineBackend, CpuBackend, MsmBackend, MsmQuery, ScalarBigInt},
+    precompute::{FixedBaseTable, PrecomputedProvingKey},
+    prefilter::PrefilterConfig,
+    prepare_verifying_key,
+    progress::ProvingStage,
+    redact::{self, Classified, WitnessClassification},
+    redundant::{KeyIntegrityError, RedundantProvingKey},
+    runtime, se, solana, Groth16, KeySize, PreparedInputs, PreparedProof, PreparedVerifyingKey,
+    Proof, ProverAssignment, ProverError, ProvingKey, PublicInputAccumulator, VerificationError,
+    VerifyingKey,
+};
 use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
-use ark_ec::pairing::Pairing;
-use ark_ff::Field;
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
 use ark_relations::{
     lc,
-    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+    r1cs::{
+        ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode,
+    },
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 use ark_std::{
+    format,
     rand::{RngCore, SeedableRng},
-    test_rng, UniformRand,
+    string::{String, ToString},
+    test_rng, vec,
+    vec::Vec,
+    UniformRand,
 };
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 struct MySillyCircuit<F: Field> {
     a: Option<F>,
@@ -117,33 +145,4807 @@ where
     }
 }
 
-mod bls12_377 {
-    use super::{test_prove_and_verify, test_rerandomize};
-    use ark_bls12_377::Bls12_377;
+fn test_serialization<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
 
-    #[test]
-    fn prove_and_verify() {
-        test_prove_and_verify::<Bls12_377>(100);
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    for compress in [Compress::Yes, Compress::No] {
+        let mut bytes = Vec::new();
+        proof.serialize_with_mode(&mut bytes, compress).unwrap();
+        assert_eq!(bytes.len(), proof.serialized_size(compress));
+        let proof2 =
+            Proof::<E>::deserialize_with_mode(&bytes[..], compress, Validate::Yes).unwrap();
+        assert_eq!(proof, proof2);
+
+        let mut bytes = Vec::new();
+        pk.serialize_with_mode(&mut bytes, compress).unwrap();
+        let pk2 =
+            ProvingKey::<E>::deserialize_with_mode(&bytes[..], compress, Validate::Yes).unwrap();
+        assert_eq!(pk, pk2);
+
+        let mut bytes = Vec::new();
+        pvk.serialize_with_mode(&mut bytes, compress).unwrap();
+        let pvk2 =
+            PreparedVerifyingKey::<E>::deserialize_with_mode(&bytes[..], compress, Validate::Yes)
+                .unwrap();
+        assert_eq!(pvk.vk, pvk2.vk);
+        assert_eq!(pvk.alpha_g1_beta_g2, pvk2.alpha_g1_beta_g2);
     }
+}
 
-    #[test]
-    fn rerandomize() {
-        test_rerandomize::<Bls12_377>();
+fn test_prepared_statement<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let statement = Groth16::<E>::prepare_statement(&pvk, &[a * b]).unwrap();
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_proof_with_prepared_statement(&statement, &proof).unwrap());
+
+    let other = Groth16::<E>::prepare_statement(&pvk, &[a]).unwrap();
+    assert!(!Groth16::<E>::verify_proof_with_prepared_statement(&other, &proof).unwrap());
+    assert!(Groth16::<E>::prepare_statement(&pvk, &[]).is_err());
+}
+
+fn test_prepared_inputs<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    // Different witnesses for the same public input `a * b`.
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let prepared_inputs = Groth16::<E>::prepare_public_inputs(&pvk, &[a * b]).unwrap();
+    for k in [E::ScalarField::one(), a, b] {
+        let circuit = MySillyCircuit {
+            a: Some(a * k),
+            b: Some(b / k),
+        };
+        let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+        assert!(Groth16::<E>::verify_proof_with_prepared_public_inputs(
+            &pvk,
+            &proof,
+            &prepared_inputs
+        )
+        .unwrap());
     }
+
+    let mut bytes = Vec::new();
+    prepared_inputs.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        PreparedInputs::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        prepared_inputs
+    );
+    assert!(Groth16::<E>::prepare_public_inputs(&pvk, &[]).is_err());
+
+    // The projective form of the same element.
+    let g_ic = Groth16::<E>::prepare_inputs(&pvk, &[a * b]).unwrap();
+    assert_eq!(g_ic.into_affine(), prepared_inputs.prepared_inputs);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_proof_with_prepared_inputs(&pvk, &proof, &g_ic).unwrap());
 }
 
-mod cp6_782 {
-    use super::{test_prove_and_verify, test_rerandomize};
+fn test_prepared_proof<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
 
-    use ark_cp6_782::CP6_782;
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
 
-    #[test]
-    fn prove_and_verify() {
-        test_prove_and_verify::<CP6_782>(1);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    let prepared = Groth16::<E>::prepare_proof(&proof);
+
+    assert!(Groth16::<E>::verify_prepared_proof(&pvk, &prepared, &[a * b]).unwrap());
+    assert!(!Groth16::<E>::verify_prepared_proof(&pvk, &prepared, &[a]).unwrap());
+    let prepared_inputs = Groth16::<E>::prepare_public_inputs(&pvk, &[a * b]).unwrap();
+    assert!(Groth16::<E>::verify_prepared_proof_with_prepared_inputs(
+        &pvk,
+        &prepared,
+        &prepared_inputs
+    )
+    .unwrap());
+
+    let mut bytes = Vec::new();
+    prepared.serialize_compressed(&mut bytes).unwrap();
+    let deserialized = PreparedProof::<E>::deserialize_compressed(&bytes[..]).unwrap();
+    assert_eq!(deserialized.proof, proof);
+    assert!(Groth16::<E>::verify_prepared_proof(&pvk, &deserialized, &[a * b]).unwrap());
+}
+
+fn test_public_input_accumulator<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let vk = VerifyingKey::<E> {
+        gamma_abc_g1: (0..6)
+            .map(|_| E::G1::rand(&mut rng).into_affine())
+            .collect(),
+        ..Default::default()
+    };
+    let pvk = PreparedVerifyingKey::<E> {
+        vk,
+        ..Default::default()
+    };
+    let inputs = (0..5)
+        .map(|_| E::ScalarField::rand(&mut rng))
+        .collect::<Vec<_>>();
+
+    let mut acc = PublicInputAccumulator::new(&pvk).unwrap();
+    acc.push(inputs[0]).unwrap();
+    acc.extend_from_slice(&inputs[1..3]).unwrap();
+    acc.extend_from_slice(&[]).unwrap();
+    acc.push(inputs[3]).unwrap();
+    assert_eq!(acc.num_inputs(), 4);
+    assert!(acc.clone().finalize().is_err());
+    assert!(acc.extend_from_slice(&inputs[3..]).is_err());
+    assert_eq!(acc.num_inputs(), 4);
+    acc.push(inputs[4]).unwrap();
+    assert!(acc.push(inputs[4]).is_err());
+    assert_eq!(
+        acc.finalize().unwrap(),
+        Groth16::<E>::prepare_public_inputs(&pvk, &inputs).unwrap()
+    );
+}
+
+fn test_verify_from_reader<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let encode = |inputs: &[E::ScalarField]| {
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        inputs.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    };
+
+    let mut bytes = encode(&[a * b]);
+    assert_eq!(Groth16::<E>::verify_from_reader(&pvk, &bytes[..]), Ok(true));
+    // Trailing data is left in the reader.
+    bytes.push(0);
+    assert_eq!(Groth16::<E>::verify_from_reader(&pvk, &bytes[..]), Ok(true));
+    assert_eq!(
+        Groth16::<E>::verify_from_reader(&pvk, &encode(&[a])[..]),
+        Ok(false)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_from_reader(&pvk, &encode(&[a, b])[..]),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2
+        })
+    );
+    let bytes = encode(&[a * b]);
+    assert_eq!(
+        Groth16::<E>::verify_from_reader(&pvk, &bytes[..bytes.len() - 1]),
+        Err(VerificationError::InvalidEncoding)
+    );
+    let mut bytes = encode(&[a * b]);
+    let input_size = a.compressed_size();
+    let len = bytes.len();
+    bytes[len - input_size..].fill(0xff);
+    assert_eq!(
+        Groth16::<E>::verify_from_reader(&pvk, &bytes[..]),
+        Err(VerificationError::InputNotInField { index: 0 })
+    );
+}
+
+fn test_verify_proof_detailed<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    assert_eq!(vk.num_public_inputs(), 1);
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b, a * b]).is_err());
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &proof, &[a * b]),
+        Ok(())
+    );
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &proof, &[a * b, a * b]),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &proof, &[a]),
+        Err(VerificationError::PairingCheckFailed)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &proof, &[]),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 0
+        })
+    );
+    let malformed = Proof {
+        c: E::G1Affine::zero(),
+        ..proof
+    };
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &malformed, &[a * b]),
+        Err(VerificationError::MalformedProofPoint)
+    );
+}
+
+fn test_subgroup_checks<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    assert_eq!(
+        Groth16::<E>::verify_proof_with_subgroup_checks(&pvk, &proof, &[a * b]),
+        Ok(())
+    );
+
+    // A point on the curve, but outside the prime-order subgroup.
+    let outside = loop {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        if let Some(p) = E::G1Affine::from_random_bytes(&bytes) {
+            if !p
+                .mul_bigint(E::ScalarField::MODULUS)
+                .into_affine()
+                .is_zero()
+            {
+                break p;
+            }
+        }
+    };
+    let malformed = Proof {
+        a: outside,
+        ..proof
+    };
+    assert_eq!(
+        Groth16::<E>::verify_proof_detailed(&pvk, &malformed, &[a * b]),
+        Err(VerificationError::PairingCheckFailed)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_proof_with_subgroup_checks(&pvk, &malformed, &[a * b]),
+        Err(VerificationError::MalformedProofPoint)
+    );
+}
+
+fn test_verify_proof_constant_time<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    for _ in 0..3 {
+        let a = E::ScalarField::rand(&mut rng);
+        let b = E::ScalarField::rand(&mut rng);
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+        assert_eq!(
+            Groth16::<E>::verify_proof_constant_time(&pvk, &proof, &[a * b]),
+            Ok(true)
+        );
+        assert_eq!(
+            Groth16::<E>::verify_proof_constant_time(&pvk, &proof, &[a]),
+            Ok(false)
+        );
+        let malformed = Proof {
+            a: E::G1Affine::zero(),
+            ..proof.clone()
+        };
+        assert_eq!(
+            Groth16::<E>::verify_proof_constant_time(&pvk, &malformed, &[a * b]),
+            Ok(false)
+        );
+        assert_eq!(
+            Groth16::<E>::verify_proof_constant_time(&pvk, &proof, &[a, b]),
+            Err(VerificationError::WrongNumberOfPublicInputs {
+                expected: 1,
+                found: 2
+            })
+        );
     }
+}
 
-    #[test]
-    fn rerandomize() {
-        test_rerandomize::<CP6_782>();
+fn snarkjs_coordinate<F: Field>(f: &F) -> String {
+    let elements = f
+        .to_base_prime_field_elements()
+        .map(|e| format!("\"{}\"", e.into_bigint()))
+        .collect::<Vec<_>>();
+    match elements.len() {
+        1 => elements[0].clone(),
+        _ => format!("[{}]", elements.join(", ")),
+    }
+}
+
+fn snarkjs_point<P: SWCurveConfig>(point: &Affine<P>) -> String {
+    let (x, y, z) = if point.infinity {
+        (
+            P::BaseField::zero(),
+            P::BaseField::one(),
+            P::BaseField::zero(),
+        )
+    } else {
+        (point.x, point.y, P::BaseField::one())
+    };
+    format!(
+        "[{}, {}, {}]",
+        snarkjs_coordinate(&x),
+        snarkjs_coordinate(&y),
+        snarkjs_coordinate(&z)
+    )
+}
+
+fn test_verify_snarkjs<E, P1, P2>()
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let ic = vk
+        .gamma_abc_g1
+        .iter()
+        .map(snarkjs_point)
+        .collect::<Vec<_>>();
+    let vk_json = format!(
+        "{{\n \"protocol\": \"groth16\",\n \"curve\": \"bls12377\",\n \"nPublic\": 1,\n \
+         \"vk_alpha_1\": {},\n \"vk_beta_2\": {},\n \"vk_gamma_2\": {},\n \
+         \"vk_delta_2\": {},\n \"vk_alphabeta_12\": [],\n \"IC\": [{}]\n}}",
+        snarkjs_point(&vk.alpha_g1),
+        snarkjs_point(&vk.beta_g2),
+        snarkjs_point(&vk.gamma_g2),
+        snarkjs_point(&vk.delta_g2),
+        ic.join(", ")
+    );
+    let proof_json = |a: &str| {
+        format!(
+            "{{\"pi_a\": {}, \"pi_b\": {}, \"pi_c\": {}, \"protocol\": \"groth16\"}}",
+            a,
+            snarkjs_point(&proof.b),
+            snarkjs_point(&proof.c)
+        )
+    };
+    let valid_proof = proof_json(&snarkjs_point(&proof.a));
+    let public = |inputs: &[&str]| {
+        let inputs = inputs
+            .iter()
+            .map(|i| format!("\"{}\"", i))
+            .collect::<Vec<_>>();
+        format!("[{}]", inputs.join(","))
+    };
+    let c = (a * b).into_bigint().to_string();
+    let verify = |proof: &str, public: &str| Groth16::<E>::verify_snarkjs(&vk_json, proof, public);
+
+    assert_eq!(verify(&valid_proof, &public(&[&c])), Ok(true));
+    assert_eq!(
+        verify(&valid_proof, &public(&[&a.into_bigint().to_string()])),
+        Ok(false)
+    );
+    assert_eq!(
+        verify(
+            &valid_proof,
+            &public(&[&E::ScalarField::MODULUS.to_string()])
+        ),
+        Err(VerificationError::InputNotInField { index: 0 })
+    );
+    assert_eq!(
+        verify(&valid_proof, &public(&[&c, &c])),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(
+        verify(&valid_proof, "[\"0x1\"]"),
+        Err(VerificationError::InvalidEncoding)
+    );
+    assert_eq!(
+        verify(&valid_proof[..valid_proof.len() - 1], &public(&[&c])),
+        Err(VerificationError::InvalidEncoding)
+    );
+
+    let off_curve = Affine::<P1>::new_unchecked(proof.a.x, proof.a.y + P1::BaseField::one());
+    assert_eq!(
+        verify(&proof_json(&snarkjs_point(&off_curve)), &public(&[&c])),
+        Err(VerificationError::MalformedProofPoint)
+    );
+    assert_eq!(
+        verify(&valid_proof.replace("groth16", "plonk"), &public(&[&c])),
+        Err(VerificationError::InvalidEncoding)
+    );
+}
+
+fn zokrates_coordinate<F: Field>(f: &F) -> String {
+    let elements = f
+        .to_base_prime_field_elements()
+        .map(|e| {
+            let hex = e
+                .into_bigint()
+                .to_bytes_be()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>();
+            format!("\"0x{}\"", hex)
+        })
+        .collect::<Vec<_>>();
+    match elements.len() {
+        1 => elements[0].clone(),
+        _ => format!("[{}]", elements.join(", ")),
+    }
+}
+
+fn zokrates_point<P: SWCurveConfig>(point: &Affine<P>) -> String {
+    format!(
+        "[{}, {}]",
+        zokrates_coordinate(&point.x),
+        zokrates_coordinate(&point.y)
+    )
+}
+
+fn test_verify_zokrates<E, P1, P2>()
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let gamma_abc = vk
+        .gamma_abc_g1
+        .iter()
+        .map(zokrates_point)
+        .collect::<Vec<_>>();
+    let vk_json = format!(
+        "{{\n  \"scheme\": \"g16\",\n  \"curve\": \"bls12_377\",\n  \"alpha\": {},\n  \
+         \"beta\": {},\n  \"gamma\": {},\n  \"delta\": {},\n  \"gamma_abc\": [{}]\n}}",
+        zokrates_point(&vk.alpha_g1),
+        zokrates_point(&vk.beta_g2),
+        zokrates_point(&vk.gamma_g2),
+        zokrates_point(&vk.delta_g2),
+        gamma_abc.join(", ")
+    );
+    assert_eq!(
+        Groth16::<E>::verifying_key_from_zokrates(&vk_json),
+        Ok(vk.clone())
+    );
+    let proof_json = |a: &str, inputs: &[E::ScalarField]| {
+        let inputs = inputs.iter().map(zokrates_coordinate).collect::<Vec<_>>();
+        format!(
+            "{{\"scheme\": \"g16\", \"curve\": \"bls12_377\", \"proof\": {{\"a\": {}, \"b\": {}, \
+             \"c\": {}}}, \"inputs\": [{}]}}",
+            a,
+            zokrates_point(&proof.b),
+            zokrates_point(&proof.c),
+            inputs.join(", ")
+        )
+    };
+    let valid_a = zokrates_point(&proof.a);
+    let verify = |proof: &str| Groth16::<E>::verify_zokrates(&vk_json, proof);
+
+    assert_eq!(verify(&proof_json(&valid_a, &[a * b])), Ok(true));
+    assert_eq!(verify(&proof_json(&valid_a, &[a])), Ok(false));
+    assert_eq!(
+        verify(&proof_json(&valid_a, &[a, b])),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Groth16::<E>::proof_from_zokrates(&proof_json(&valid_a, &[a * b])),
+        Ok((proof.clone(), vec![a * b]))
+    );
+
+    let mut modulus = String::from("\"0x");
+    for byte in E::ScalarField::MODULUS.to_bytes_be() {
+        modulus.push_str(&format!("{:02x}", byte));
+    }
+    let too_large = proof_json(&valid_a, &[]).replace("[]", &format!("[{}\"]", modulus));
+    assert_eq!(
+        verify(&too_large),
+        Err(VerificationError::InputNotInField { index: 0 })
+    );
+    let off_curve = Affine::<P1>::new_unchecked(proof.a.x, proof.a.y + P1::BaseField::one());
+    assert_eq!(
+        verify(&proof_json(&zokrates_point(&off_curve), &[a * b])),
+        Err(VerificationError::MalformedProofPoint)
+    );
+    assert_eq!(
+        verify(&proof_json(&valid_a, &[a * b]).replace("g16", "gm17")),
+        Err(VerificationError::InvalidEncoding)
+    );
+
+    let mut pk_file = Vec::new();
+    pk.serialize_uncompressed(&mut pk_file).unwrap();
+    assert_eq!(
+        Groth16::<E>::proving_key_from_zokrates(&pk_file[..]).unwrap(),
+        pk
+    );
+}
+
+fn test_solana_encoding<E, P1, P2>()
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let n = <P1::BaseField as Field>::BasePrimeField::MODULUS
+        .to_bytes_be()
+        .len();
+    let g1 = solana::encode_point(&proof.c);
+    assert_eq!(g1.len(), 2 * n);
+    let x = proof.c.x.to_base_prime_field_elements().collect::<Vec<_>>();
+    assert_eq!(&g1[..n], &x[0].into_bigint().to_bytes_be()[..]);
+    assert_eq!(
+        solana::encode_point(&Affine::<P1>::identity()),
+        vec![0; 2 * n]
+    );
+    let g2 = solana::encode_point(&proof.b);
+    let x = proof.b.x.to_base_prime_field_elements().collect::<Vec<_>>();
+    assert_eq!(g2.len(), 4 * n);
+    assert_eq!(&g2[..n], &x[1].into_bigint().to_bytes_be()[..]);
+    assert_eq!(&g2[n..2 * n], &x[0].into_bigint().to_bytes_be()[..]);
+
+    // Decode the pairing input and check that it is accepted exactly for the statement.
+    let decode_g1 = |bytes: &[u8]| {
+        let field = |bytes: &[u8]| {
+            P1::BaseField::from_base_prime_field_elems(&[PrimeField::from_be_bytes_mod_order(
+                bytes,
+            )])
+            .unwrap()
+        };
+        Affine::<P1>::new(field(&bytes[..n]), field(&bytes[n..]))
+    };
+    let decode_g2 = |bytes: &[u8]| {
+        let field = <P2::BaseField as Field>::BasePrimeField::from_be_bytes_mod_order;
+        let fq2 = |bytes: &[u8]| {
+            P2::BaseField::from_base_prime_field_elems(&[field(&bytes[n..]), field(&bytes[..n])])
+                .unwrap()
+        };
+        Affine::<P2>::new(fq2(&bytes[..2 * n]), fq2(&bytes[2 * n..]))
+    };
+    let accepts = |public_inputs: &[E::ScalarField]| {
+        let input = Groth16::<E>::solana_pairing_input(&vk, &proof, public_inputs).unwrap();
+        assert_eq!(input.len(), 4 * 6 * n);
+        let (g1, g2): (Vec<_>, Vec<_>) = input
+            .chunks(6 * n)
+            .map(|pair| (decode_g1(&pair[..2 * n]), decode_g2(&pair[2 * n..])))
+            .unzip();
+        E::multi_pairing(g1, g2).0.is_one()
+    };
+    assert!(accepts(&[a * b]));
+    assert!(!accepts(&[a]));
+    assert_eq!(
+        Groth16::<E>::solana_pairing_input(&vk, &proof, &[]),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 0
+        })
+    );
+    assert_eq!(
+        solana::encode_scalar(&a).len(),
+        E::ScalarField::MODULUS.to_bytes_be().len()
+    );
+}
+
+fn test_pvk_cache<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let keys = (0..2)
+        .map(|_| Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap())
+        .collect::<Vec<_>>();
+    let fingerprints = keys
+        .iter()
+        .map(|(_, vk)| vk.fingerprint())
+        .collect::<Vec<_>>();
+    assert_ne!(fingerprints[0], fingerprints[1]);
+
+    let mut cache = PvkCache::<E>::new();
+    assert!(cache.is_empty());
+    for _ in 0..2 {
+        for (pk, vk) in &keys {
+            let a = E::ScalarField::rand(&mut rng);
+            let b = E::ScalarField::rand(&mut rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            let proof = Groth16::<E>::prove(pk, circuit, &mut rng).unwrap();
+
+            let pvk = cache.get_or_prepare(vk);
+            assert_eq!(pvk.vk, *vk);
+            assert!(Groth16::<E>::verify_proof(pvk, &proof, &[a * b]).unwrap());
+        }
+    }
+    assert_eq!(cache.len(), 2);
+
+    let removed = cache.remove(&fingerprints[0]).unwrap();
+    assert!(cache.get(&fingerprints[0]).is_none());
+    assert_eq!(cache.insert(removed), fingerprints[0]);
+    assert_eq!(cache.get(&fingerprints[0]).unwrap().vk, keys[0].1);
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+/// Exposes the product and the sum of two witnesses as public inputs, in that order.
+struct ProductAndSumCircuit<F: Field> {
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for ProductAndSumCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let value = |v: Option<F>| v.ok_or(SynthesisError::AssignmentMissing);
+        let a = cs.new_witness_variable(|| value(self.a))?;
+        let b = cs.new_witness_variable(|| value(self.b))?;
+        let product = cs.new_input_variable(|| Ok(value(self.a)? * value(self.b)?))?;
+        let sum = cs.new_input_variable(|| Ok(value(self.a)? + value(self.b)?))?;
+
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+        cs.enforce_constraint(
+            lc!() + a + b,
+            lc!() + ark_relations::r1cs::Variable::One,
+            lc!() + sum,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+fn test_labeled_inputs<E>()
+where
+    E: Pairing,
+{
+    use crate::LabeledVerifyingKey;
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = ProductAndSumCircuit { a: None, b: None };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let labels = |labels: &[(&str, usize)]| {
+        labels
+            .iter()
+            .map(|(name, index)| (name.to_string(), *index))
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    for invalid in [
+        labels(&[("product", 0)]),
+        labels(&[("product", 0), ("sum", 0)]),
+        labels(&[("product", 0), ("sum", 2)]),
+    ] {
+        assert_eq!(
+            LabeledVerifyingKey::new(vk.clone(), invalid),
+            Err(SynthesisError::MalformedVerifyingKey)
+        );
+    }
+    let labeled_vk =
+        LabeledVerifyingKey::new(vk.clone(), labels(&[("product", 0), ("sum", 1)])).unwrap();
+
+    let mut bytes = Vec::new();
+    labeled_vk.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        LabeledVerifyingKey::deserialize_compressed(&*bytes).unwrap(),
+        labeled_vk
+    );
+    assert_eq!(
+        VerifyingKey::<E>::deserialize_compressed(&*bytes).unwrap(),
+        vk
+    );
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = ProductAndSumCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    let inputs = |inputs: &[(&str, E::ScalarField)]| {
+        inputs
+            .iter()
+            .map(|(name, input)| (name.to_string(), *input))
+            .collect::<HashMap<_, _>>()
+    };
+    let verify =
+        |inputs| Groth16::<E>::verify_proof_with_labeled_inputs(&pvk, &labeled_vk, &proof, &inputs);
+
+    assert_eq!(
+        verify(inputs(&[("sum", a + b), ("product", a * b)])),
+        Ok(true)
+    );
+    assert_eq!(
+        verify(inputs(&[("sum", a * b), ("product", a + b)])),
+        Ok(false)
+    );
+    assert_eq!(
+        verify(inputs(&[("sum", a + b), ("difference", a - b)])),
+        Err(VerificationError::UnknownInputLabel)
+    );
+    assert_eq!(
+        verify(inputs(&[("sum", a + b)])),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 2,
+            found: 1
+        })
+    );
+}
+
+fn test_verify_with_input_iter<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = ProductAndSumCircuit { a: None, b: None };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = ProductAndSumCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    let inputs = [a * b, a + b];
+
+    for chunk_size in [0, 1, 2, 5] {
+        let verify = |inputs: &[E::ScalarField]| {
+            Groth16::<E>::verify_proof_with_input_iter(
+                &pvk,
+                &proof,
+                inputs.iter().copied(),
+                chunk_size,
+            )
+        };
+        assert_eq!(verify(&inputs), Ok(true));
+        assert_eq!(verify(&[a + b, a * b]), Ok(false));
+        assert_eq!(
+            verify(&inputs[..1]),
+            Err(VerificationError::WrongNumberOfPublicInputs {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        for compress in [Compress::Yes, Compress::No] {
+            let mut bytes = Vec::new();
+            vk.serialize_with_mode(&mut bytes, compress).unwrap();
+            let verify = |bytes: &[u8], inputs: &[E::ScalarField]| {
+                Groth16::<E>::verify_proof_with_streamed_key(
+                    bytes,
+                    compress,
+                    Validate::Yes,
+                    &proof,
+                    inputs.iter().copied(),
+                    chunk_size,
+                )
+            };
+            assert_eq!(verify(&bytes, &inputs), Ok(true));
+            assert_eq!(verify(&bytes, &[a + b, a * b]), Ok(false));
+            assert_eq!(
+                verify(&bytes, &[a * b, a + b, a]),
+                Err(VerificationError::WrongNumberOfPublicInputs {
+                    expected: 2,
+                    found: 3
+                })
+            );
+            assert_eq!(
+                verify(&bytes[..bytes.len() - 1], &inputs),
+                Err(VerificationError::InvalidEncoding)
+            );
+        }
+    }
+}
+
+fn test_ceremony<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (initial, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let (first, first_proof) = Groth16::<E>::contribute(&initial, &mut rng);
+    let (second, second_proof) = Groth16::<E>::contribute(&first, &mut rng);
+    assert!(Groth16::<E>::verify_contribution(
+        &initial,
+        &first,
+        &first_proof
+    ));
+    assert!(Groth16::<E>::verify_contribution(
+        &first,
+        &second,
+        &second_proof
+    ));
+    assert_ne!(initial.delta_g1, second.delta_g1);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&second, circuit, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&second.vk, &[a * b], &proof).unwrap());
+
+    // Contributions cannot be reordered, skipped or verified with another proof.
+    assert!(!Groth16::<E>::verify_contribution(
+        &initial,
+        &second,
+        &second_proof
+    ));
+    assert!(!Groth16::<E>::verify_contribution(
+        &first,
+        &second,
+        &first_proof
+    ));
+    assert!(!Groth16::<E>::verify_contribution(
+        &second,
+        &first,
+        &second_proof
+    ));
+
+    let mut tampered = second.clone();
+    tampered.vk.alpha_g1 = (tampered.vk.alpha_g1 + E::G1Affine::generator()).into_affine();
+    assert!(!Groth16::<E>::verify_contribution(
+        &first,
+        &tampered,
+        &second_proof
+    ));
+
+    let mut tampered = second.clone();
+    tampered.h_query[0] = (tampered.h_query[0] + E::G1Affine::generator()).into_affine();
+    assert!(!Groth16::<E>::verify_contribution(
+        &first,
+        &tampered,
+        &second_proof
+    ));
+
+    let mut tampered = second.clone();
+    tampered.l_query[0] = (tampered.l_query[0] * E::ScalarField::from(2u64)).into_affine();
+    assert!(!Groth16::<E>::verify_contribution(
+        &first,
+        &tampered,
+        &second_proof
+    ));
+}
+
+fn test_powers_of_tau<E>()
+where
+    E: Pairing,
+{
+    use crate::powers_of_tau::PowersOfTau;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let power = 4;
+    let size = 1 << power;
+    let tau = E::ScalarField::rand(&mut rng);
+    let alpha = E::ScalarField::rand(&mut rng);
+    let beta = E::ScalarField::rand(&mut rng);
+    let powers = |n: usize, scale: E::ScalarField| {
+        (0..n as u64)
+            .map(|i| scale * tau.pow([i]))
+            .collect::<Vec<_>>()
+    };
+    let g1 = |scalars: Vec<E::ScalarField>| {
+        scalars
+            .iter()
+            .map(|s| (E::G1Affine::generator() * s).into_affine())
+            .collect::<Vec<_>>()
+    };
+    let accumulator = PowersOfTau::<E> {
+        tau_powers_g1: g1(powers(2 * size - 1, E::ScalarField::one())),
+        tau_powers_g2: powers(size, E::ScalarField::one())
+            .iter()
+            .map(|s| (E::G2Affine::generator() * s).into_affine())
+            .collect(),
+        alpha_tau_powers_g1: g1(powers(size, alpha)),
+        beta_tau_powers_g1: g1(powers(size, beta)),
+        beta_g2: (E::G2Affine::generator() * beta).into_affine(),
+    };
+
+    for compress in [Compress::No, Compress::Yes] {
+        let mut bytes = vec![0u8; 64];
+        for p in &accumulator.tau_powers_g1 {
+            p.serialize_with_mode(&mut bytes, compress).unwrap();
+        }
+        for p in &accumulator.tau_powers_g2 {
+            p.serialize_with_mode(&mut bytes, compress).unwrap();
+        }
+        for p in accumulator
+            .alpha_tau_powers_g1
+            .iter()
+            .chain(&accumulator.beta_tau_powers_g1)
+        {
+            p.serialize_with_mode(&mut bytes, compress).unwrap();
+        }
+        accumulator
+            .beta_g2
+            .serialize_with_mode(&mut bytes, compress)
+            .unwrap();
+
+        let read = |bytes: &[u8], power| match compress {
+            Compress::No => PowersOfTau::<E>::read_challenge(bytes, power),
+            Compress::Yes => PowersOfTau::<E>::read_response(bytes, power),
+        };
+        assert_eq!(read(&bytes, power).unwrap(), accumulator);
+        assert!(read(&bytes[..bytes.len() - 1], power).is_err());
+        assert!(read(&bytes, power + 1).is_err());
+    }
+    assert_eq!(accumulator.max_domain_size(), size);
+
+    let circuit = MySillyCircuit { a: None, b: None };
+    let initial =
+        Groth16::<E>::generate_parameters_from_powers_of_tau(circuit, &accumulator).unwrap();
+    assert_eq!(initial.vk.alpha_g1, accumulator.alpha_tau_powers_g1[0]);
+    assert_eq!(initial.vk.delta_g2, E::G2Affine::generator());
+
+    let (pk, contribution) = Groth16::<E>::contribute(&initial, &mut rng);
+    assert!(Groth16::<E>::verify_contribution(
+        &initial,
+        &pk,
+        &contribution
+    ));
+    for _ in 0..3 {
+        let a = E::ScalarField::rand(&mut rng);
+        let b = E::ScalarField::rand(&mut rng);
+        let circuit = MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+        assert!(Groth16::<E>::verify(&pk.vk, &[a * b], &proof).unwrap());
+        assert!(!Groth16::<E>::verify(&pk.vk, &[a], &proof).unwrap());
+    }
+
+    let small = PowersOfTau::<E> {
+        tau_powers_g1: accumulator.tau_powers_g1[..3].to_vec(),
+        tau_powers_g2: accumulator.tau_powers_g2[..2].to_vec(),
+        alpha_tau_powers_g1: accumulator.alpha_tau_powers_g1[..2].to_vec(),
+        beta_tau_powers_g1: accumulator.beta_tau_powers_g1[..2].to_vec(),
+        beta_g2: accumulator.beta_g2,
+    };
+    let circuit = MySillyCircuit { a: None, b: None };
+    assert!(matches!(
+        Groth16::<E>::generate_parameters_from_powers_of_tau(circuit, &small),
+        Err(SynthesisError::PolynomialDegreeTooLarge)
+    ));
+}
+
+fn test_rerandomize_delta<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let (new_pk, new_vk) = Groth16::<E>::rerandomize_delta(&pk, &mut rng);
+    assert_eq!(new_pk.vk, new_vk);
+    assert_ne!(new_pk.delta_g1, pk.delta_g1);
+    assert_ne!(new_vk.delta_g2, vk.delta_g2);
+    assert_eq!(new_vk.gamma_abc_g1, vk.gamma_abc_g1);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let old_proof = Groth16::<E>::prove(&pk, circuit(), &mut rng).unwrap();
+    let new_proof = Groth16::<E>::prove(&new_pk, circuit(), &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&new_vk, &[a * b], &new_proof).unwrap());
+    assert!(!Groth16::<E>::verify(&new_vk, &[a * b], &old_proof).unwrap());
+    assert!(!Groth16::<E>::verify(&vk, &[a * b], &new_proof).unwrap());
+}
+
+fn test_verify_parameters<E>()
+where
+    E: Pairing,
+{
+    use crate::parameters::ParameterError;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = || MySillyCircuit::<E::ScalarField> { a: None, b: None };
+    let (pk, _) = Groth16::<E>::setup(circuit(), &mut rng).unwrap();
+    let verify = |pk: &ProvingKey<E>| Groth16::<E>::verify_parameters(pk, circuit());
+    assert_eq!(verify(&pk), Ok(()));
+    let (contributed, _) = Groth16::<E>::contribute(&pk, &mut rng);
+    assert_eq!(verify(&contributed), Ok(()));
+
+    assert!(matches!(
+        Groth16::<E>::verify_parameters(&pk, ProductAndSumCircuit { a: None, b: None }),
+        Err(ParameterError::LengthMismatch { .. })
+    ));
+
+    let double = |p: E::G1Affine| (p + p).into_affine();
+    let tamper = |f: &dyn Fn(&mut ProvingKey<E>)| {
+        let mut tampered = pk.clone();
+        f(&mut tampered);
+        verify(&tampered)
+    };
+    // The variable `b` does not appear in `A`.
+    assert_eq!(
+        tamper(&|pk| pk.a_query[3] = E::G1Affine::generator()),
+        Err(ParameterError::UnexpectedZero {
+            query: "a_query",
+            index: 3
+        })
+    );
+    assert_eq!(
+        tamper(&|pk| pk.h_query[1] = E::G1Affine::zero()),
+        Err(ParameterError::UnexpectedIdentity { element: "h_query" })
+    );
+    assert_eq!(
+        tamper(&|pk| pk.vk.delta_g2 = E::G2Affine::zero()),
+        Err(ParameterError::UnexpectedIdentity {
+            element: "delta_g2"
+        })
+    );
+    assert_eq!(
+        tamper(&|pk| pk.beta_g1 = double(pk.beta_g1)),
+        Err(ParameterError::RelationFailed {
+            relation: "beta and delta"
+        })
+    );
+    assert_eq!(
+        tamper(&|pk| pk.b_g1_query[3] = double(pk.b_g1_query[3])),
+        Err(ParameterError::RelationFailed {
+            relation: "b_g1_query and b_g2_query"
+        })
+    );
+    for tampered in [
+        tamper(&|pk| pk.vk.alpha_g1 = double(pk.vk.alpha_g1)),
+        tamper(&|pk| pk.vk.gamma_abc_g1[0] = double(pk.vk.gamma_abc_g1[0])),
+        tamper(&|pk| pk.l_query[0] = double(pk.l_query[0])),
+        tamper(&|pk| pk.a_query[2] = double(pk.a_query[2])),
+    ] {
+        assert_eq!(
+            tampered,
+            Err(ParameterError::RelationFailed {
+                relation: "alpha, beta, gamma_abc_g1 and l_query"
+            })
+        );
+    }
+}
+
+fn test_deterministic_parameters<E>(fingerprint: &str)
+where
+    E: Pairing,
+{
+    let circuit = || MySillyCircuit::<E::ScalarField> { a: None, b: None };
+    let pk = Groth16::<E>::generate_parameters_deterministic(circuit(), [7; 32]).unwrap();
+    assert_eq!(
+        pk,
+        Groth16::<E>::generate_parameters_deterministic(circuit(), [7; 32]).unwrap()
+    );
+    assert_ne!(
+        pk,
+        Groth16::<E>::generate_parameters_deterministic(circuit(), [8; 32]).unwrap()
+    );
+    let found = pk
+        .vk
+        .fingerprint()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    assert_eq!(found, fingerprint);
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&pk.vk, &[a * b], &proof).unwrap());
+}
+
+fn test_ceremony_beacon<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (initial, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let (contributed, _) = Groth16::<E>::contribute(&initial, &mut rng);
+    let beacon = b"block 0000000000000000000a1b2c3d";
+    let (closed, proof) = Groth16::<E>::apply_beacon(&contributed, beacon, 1 << 10);
+    assert_ne!(closed, contributed);
+    assert!(Groth16::<E>::verify_contribution(
+        &contributed,
+        &closed,
+        &proof
+    ));
+    assert!(Groth16::<E>::verify_beacon(
+        &contributed,
+        &closed,
+        &proof,
+        beacon,
+        1 << 10
+    ));
+    assert!(!Groth16::<E>::verify_beacon(
+        &contributed,
+        &closed,
+        &proof,
+        beacon,
+        1 << 9
+    ));
+    assert!(!Groth16::<E>::verify_beacon(
+        &contributed,
+        &closed,
+        &proof,
+        b"other",
+        1 << 10
+    ));
+    assert!(!Groth16::<E>::verify_beacon(
+        &initial,
+        &closed,
+        &proof,
+        beacon,
+        1 << 10
+    ));
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&closed, circuit, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&closed.vk, &[a * b], &proof).unwrap());
+}
+
+fn test_check_consistency<E>()
+where
+    E: Pairing,
+{
+    use crate::KeyConsistencyError;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    assert_eq!(pk.check_consistency(), Ok(()));
+
+    let check = |f: &dyn Fn(&mut ProvingKey<E>)| {
+        let mut pk = pk.clone();
+        f(&mut pk);
+        pk.check_consistency()
+    };
+    assert_eq!(
+        check(&|pk| pk.vk.gamma_abc_g1.clear()),
+        Err(KeyConsistencyError::EmptyGammaAbc)
+    );
+    assert_eq!(
+        check(&|pk| {
+            pk.b_g2_query.pop();
+        }),
+        Err(KeyConsistencyError::LengthMismatch {
+            query: "b_g2_query",
+            expected: 4,
+            found: 3
+        })
+    );
+    assert_eq!(
+        check(&|pk| {
+            pk.l_query.pop();
+        }),
+        Err(KeyConsistencyError::LengthMismatch {
+            query: "l_query",
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(
+        check(&|pk| pk.h_query.clear()),
+        Err(KeyConsistencyError::HQueryTooShort {
+            minimum: 1,
+            found: 0
+        })
+    );
+}
+
+fn test_secret_setup_randomness<E>()
+where
+    E: Pairing,
+{
+    use crate::generator::SecretSetupRandomness;
+    use zeroize::Zeroize;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let mut secret = SecretSetupRandomness::<E::ScalarField>::sample(&mut rng);
+    assert_eq!(format!("{:?}", secret), "SecretSetupRandomness { .. }");
+    let (alpha, beta, gamma, delta) = (secret.alpha, secret.beta, secret.gamma, secret.delta);
+    secret.zeroize();
+    assert!(secret.alpha.is_zero() && secret.beta.is_zero());
+    assert!(secret.gamma.is_zero() && secret.delta.is_zero());
+
+    let g1_generator = E::G1::rand(&mut rng);
+    let g2_generator = E::G2::rand(&mut rng);
+    let seed = rng.next_u64();
+    let pk = Groth16::<E>::generate_parameters_with_secret(
+        MySillyCircuit { a: None, b: None },
+        SecretSetupRandomness::new(alpha, beta, gamma, delta),
+        g1_generator,
+        g2_generator,
+        &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed),
+    )
+    .unwrap();
+    let expected = Groth16::<E>::generate_parameters_with_qap(
+        MySillyCircuit { a: None, b: None },
+        alpha,
+        beta,
+        gamma,
+        delta,
+        g1_generator,
+        g2_generator,
+        &mut ark_std::rand::rngs::StdRng::seed_from_u64(seed),
+    )
+    .unwrap();
+    assert_eq!(pk, expected);
+    assert_eq!(pk.vk.alpha_g1, (g1_generator * alpha).into_affine());
+}
+
+fn test_entropy_source<E>()
+where
+    E: Pairing,
+{
+    use crate::entropy::{EntropyError, EntropySource, SetupError, ENTROPY_LENGTH};
+
+    /// A transcript of dice rolls, one roll per byte.
+    struct Dice(Vec<u8>);
+
+    impl EntropySource for Dice {
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+            if self.0.len() < dest.len() {
+                return Err(EntropyError::Exhausted);
+            }
+            dest.copy_from_slice(&self.0[..dest.len()]);
+            self.0.drain(..dest.len());
+            Ok(())
+        }
+    }
+
+    let rolls = (0..2 * ENTROPY_LENGTH)
+        .map(|i| (i * 7 % 6 + 1) as u8)
+        .collect::<Vec<_>>();
+    let circuit = || MySillyCircuit::<E::ScalarField> { a: None, b: None };
+    let mut dice = Dice(rolls.clone());
+    let pk = Groth16::<E>::generate_parameters_with_entropy(circuit(), &mut dice).unwrap();
+    let (contributed, proof) = Groth16::<E>::contribute_with_entropy(&pk, &mut dice).unwrap();
+    assert!(Groth16::<E>::verify_contribution(&pk, &contributed, &proof));
+    assert_eq!(
+        Groth16::<E>::contribute_with_entropy(&pk, &mut dice).unwrap_err(),
+        EntropyError::Exhausted
+    );
+    assert_eq!(
+        Groth16::<E>::generate_parameters_with_entropy(circuit(), &mut dice).unwrap_err(),
+        SetupError::Entropy(EntropyError::Exhausted)
+    );
+
+    // The same transcript gives the same parameters.
+    let mut dice = Dice(rolls);
+    assert_eq!(
+        Groth16::<E>::generate_parameters_with_entropy(circuit(), &mut dice).unwrap(),
+        pk
+    );
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&contributed, circuit, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&contributed.vk, &[a * b], &proof).unwrap());
+}
+
+/// Encodes points as in the sections of a snarkjs `.ptau` file.
+fn ptau_points<P: SWCurveConfig>(n8: usize, points: &[Affine<P>]) -> Vec<u8> {
+    let r = <P::BaseField as Field>::BasePrimeField::from(2u64).pow([8 * n8 as u64]);
+    let mut bytes = Vec::new();
+    for point in points {
+        let (x, y) = point
+            .xy()
+            .map_or((Zero::zero(), Zero::zero()), |(x, y)| (*x, *y));
+        for element in x
+            .to_base_prime_field_elements()
+            .chain(y.to_base_prime_field_elements())
+        {
+            let mut element = (element * r).into_bigint().to_bytes_le();
+            element.resize(n8, 0);
+            bytes.extend(element);
+        }
+    }
+    bytes
+}
+
+fn test_read_ptau<E, P1, P2>()
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig,
+{
+    use crate::powers_of_tau::PowersOfTau;
+    use ark_ec::Group;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let power = 3u32;
+    let size = 1usize << power;
+    let tau = E::ScalarField::rand(&mut rng);
+    let alpha = E::ScalarField::rand(&mut rng);
+    let beta = E::ScalarField::rand(&mut rng);
+    let g1 = |scale: E::ScalarField, n: usize| {
+        (0..n as u64)
+            .map(|i| (E::G1::generator() * (scale * tau.pow([i]))).into_affine())
+            .collect::<Vec<_>>()
+    };
+    let mut accumulator = PowersOfTau::<E> {
+        tau_powers_g1: g1(E::ScalarField::one(), 2 * size - 1),
+        tau_powers_g2: (0..size as u64)
+            .map(|i| (E::G2::generator() * tau.pow([i])).into_affine())
+            .collect(),
+        alpha_tau_powers_g1: g1(alpha, size),
+        beta_tau_powers_g1: g1(beta, size),
+        beta_g2: (E::G2::generator() * beta).into_affine(),
+    };
+    // The identity is encoded as zero coordinates.
+    accumulator.beta_tau_powers_g1[size - 1] = E::G1Affine::zero();
+
+    let modulus = <<P1::BaseField as Field>::BasePrimeField as PrimeField>::MODULUS;
+    let modulus = modulus.to_bytes_le();
+    let n8 = modulus.len();
+    let mut header = (n8 as u32).to_le_bytes().to_vec();
+    header.extend(&modulus);
+    header.extend(power.to_le_bytes());
+    header.extend(power.to_le_bytes());
+    let sections = [
+        (1u32, header),
+        (2, ptau_points(n8, &accumulator.tau_powers_g1)),
+        (3, ptau_points(n8, &accumulator.tau_powers_g2)),
+        (4, ptau_points(n8, &accumulator.alpha_tau_powers_g1)),
+        (5, ptau_points(n8, &accumulator.beta_tau_powers_g1)),
+        (6, ptau_points(n8, &[accumulator.beta_g2])),
+    ];
+    let mut ptau = b"ptau".to_vec();
+    ptau.extend(1u32.to_le_bytes());
+    ptau.extend((sections.len() as u32).to_le_bytes());
+    for (section_type, contents) in &sections {
+        ptau.extend(section_type.to_le_bytes());
+        ptau.extend((contents.len() as u64).to_le_bytes());
+        ptau.extend(contents);
+    }
+
+    assert_eq!(PowersOfTau::<E>::read_ptau(&ptau[..]).unwrap(), accumulator);
+    assert!(PowersOfTau::<E>::read_ptau(&ptau[..ptau.len() - 1]).is_err());
+    let mut corrupted = ptau.clone();
+    // The first coordinate of the first point of section 2.
+    let offset = 12 + 12 + sections[0].1.len() + 12;
+    corrupted[offset] ^= 1;
+    assert!(PowersOfTau::<E>::read_ptau(&corrupted[..]).is_err());
+}
+
+fn test_ceremony_transcript<E>()
+where
+    E: Pairing,
+{
+    use crate::ceremony::{BeaconContribution, Contribution, Transcript, TranscriptError};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (initial, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let mut transcript = Transcript::new(initial);
+    assert_eq!(transcript.final_parameters(), &transcript.initial);
+    for _ in 0..2 {
+        let (params, proof) = Groth16::<E>::contribute(transcript.final_parameters(), &mut rng);
+        transcript
+            .contributions
+            .push(Contribution { params, proof });
+    }
+    let beacon = b"block 0000000000000000000a1b2c3d".to_vec();
+    let (params, proof) = Groth16::<E>::apply_beacon(transcript.final_parameters(), &beacon, 16);
+    transcript.beacon = Some(BeaconContribution {
+        beacon,
+        iterations: 16,
+        params: params.clone(),
+        proof,
+    });
+    assert_eq!(transcript.final_parameters(), &params);
+    assert_eq!(Groth16::<E>::verify_transcript(&transcript), Ok(()));
+
+    let mut bytes = Vec::new();
+    transcript.serialize_compressed(&mut bytes).unwrap();
+    let deserialized = Transcript::<E>::deserialize_compressed(&bytes[..]).unwrap();
+    assert_eq!(deserialized, transcript);
+
+    let mut reordered = transcript.clone();
+    reordered.contributions.swap(0, 1);
+    assert_eq!(
+        Groth16::<E>::verify_transcript(&reordered),
+        Err(TranscriptError::InvalidContribution(0))
+    );
+
+    let mut tampered = transcript.clone();
+    tampered.contributions[1].proof = transcript.contributions[0].proof.clone();
+    assert_eq!(
+        Groth16::<E>::verify_transcript(&tampered),
+        Err(TranscriptError::InvalidContribution(1))
+    );
+
+    let mut wrong_beacon = transcript;
+    wrong_beacon.beacon.as_mut().unwrap().iterations = 15;
+    assert_eq!(
+        Groth16::<E>::verify_transcript(&wrong_beacon),
+        Err(TranscriptError::InvalidBeacon)
+    );
+}
+
+fn test_proof_array<E, const N: usize>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let bytes = proof.to_array::<N>().unwrap();
+    let mut compressed = Vec::new();
+    proof.serialize_compressed(&mut compressed).unwrap();
+    assert_eq!(&bytes[..], &compressed[..]);
+    assert_eq!(Proof::<E>::from_array(&bytes).unwrap(), proof);
+
+    assert!(proof.to_array::<1>().is_err());
+    assert!(Proof::<E>::from_array(&[0u8; 1]).is_err());
+    let mut corrupted = bytes;
+    corrupted[0] ^= 1;
+    assert!(Proof::<E>::from_array(&corrupted).is_err());
+}
+
+fn test_precomputed_verifying_key<E>()
+where
+    E: Pairing,
+{
+    use crate::precompute::PreparedVerifyingKeyBuilder;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = |a, b| ProductAndSumCircuit { a, b };
+    let (pk, vk) = Groth16::<E>::setup(circuit(None, None), &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(&pk, circuit(Some(a), Some(b)), &mut rng).unwrap();
+    let inputs = [a * b, a + b];
+
+    let pvk = prepare_verifying_key(&vk);
+    let expected = Groth16::<E>::prepare_public_inputs(&pvk, &inputs).unwrap();
+    for ppvk in [
+        PreparedVerifyingKeyBuilder::new(&vk).build(),
+        PreparedVerifyingKeyBuilder::new(&vk)
+            .gamma_abc_table(4)
+            .build(),
+    ] {
+        assert_eq!(ppvk.pvk.vk, vk);
+        assert_eq!(ppvk.prepare_public_inputs(&inputs).unwrap(), expected);
+        assert!(Groth16::<E>::verify_proof_with_precomputed_key(&ppvk, &proof, &inputs).unwrap());
+        assert!(!Groth16::<E>::verify_proof_with_precomputed_key(&ppvk, &proof, &[a, b]).unwrap());
+        assert!(Groth16::<E>::verify_proof_with_precomputed_key(&ppvk, &proof, &[a]).is_err());
+    }
+}
+
+fn test_byte_conversions<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(E::ScalarField::rand(&mut rng)),
+            b: Some(E::ScalarField::rand(&mut rng)),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let proof_bytes = Vec::from(&proof);
+    let mut compressed = Vec::new();
+    proof.serialize_compressed(&mut compressed).unwrap();
+    assert_eq!(proof_bytes, compressed);
+    assert_eq!(Proof::<E>::try_from(&proof_bytes[..]).unwrap(), proof);
+    assert!(Proof::<E>::try_from(&proof_bytes[1..]).is_err());
+    let mut extended = proof_bytes.clone();
+    extended.push(0);
+    assert!(Proof::<E>::try_from(&extended[..]).is_err());
+
+    let vk_bytes = Vec::from(&vk);
+    assert_eq!(VerifyingKey::<E>::try_from(&vk_bytes[..]).unwrap(), vk);
+    assert!(VerifyingKey::<E>::try_from(&vk_bytes[..vk_bytes.len() - 1]).is_err());
+    let mut extended = vk_bytes;
+    extended.push(0);
+    assert!(VerifyingKey::<E>::try_from(&extended[..]).is_err());
+}
+
+fn test_key_metadata<E>()
+where
+    E: Pairing,
+{
+    use crate::metadata::{read_key_metadata, KeyMetadata};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let metadata = KeyMetadata::for_circuit(
+        MySillyCircuit::<E::ScalarField> { a: None, b: None },
+        "test",
+    )
+    .unwrap();
+    assert_eq!(metadata.num_constraints, 6);
+    assert_eq!(metadata.num_public_inputs, 1);
+    assert_eq!(metadata.curve_id, "test");
+    assert_eq!(
+        metadata,
+        KeyMetadata::for_circuit(
+            MySillyCircuit::<E::ScalarField> {
+                a: Some(One::one()),
+                b: Some(One::one()),
+            },
+            "test"
+        )
+        .unwrap()
+    );
+    let other = KeyMetadata::for_circuit(
+        ProductAndSumCircuit::<E::ScalarField> { a: None, b: None },
+        "test",
+    )
+    .unwrap();
+    assert_ne!(other.circuit_hash, metadata.circuit_hash);
+
+    let mut file = Vec::new();
+    pk.serialize_with_metadata(&metadata, &mut file, Compress::Yes)
+        .unwrap();
+    let header_len = file.len() - pk.compressed_size();
+    assert_eq!(read_key_metadata(&file[..header_len]).unwrap(), metadata);
+    assert_eq!(
+        ProvingKey::<E>::deserialize_with_metadata(&file[..], Compress::Yes, Validate::Yes)
+            .unwrap(),
+        (pk.clone(), metadata)
+    );
+
+    let mut corrupted = file.clone();
+    corrupted[0] ^= 1;
+    assert!(read_key_metadata(&corrupted[..]).is_err());
+    let mut plain = Vec::new();
+    pk.serialize_compressed(&mut plain).unwrap();
+    assert!(read_key_metadata(&plain[..]).is_err());
+    assert!(read_key_metadata(&file[..header_len - 1]).is_err());
+}
+
+#[cfg(feature = "std")]
+fn test_proof_ordering<E>()
+where
+    E: Pairing,
+{
+    use std::collections::{BTreeSet, HashSet};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let proofs = (0..4)
+        .map(|_| {
+            Groth16::<E>::prove(
+                &pk,
+                MySillyCircuit {
+                    a: Some(E::ScalarField::rand(&mut rng)),
+                    b: Some(E::ScalarField::rand(&mut rng)),
+                },
+                &mut rng,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let duplicated = proofs.iter().chain(&proofs).cloned().collect::<Vec<_>>();
+    assert_eq!(
+        duplicated.iter().collect::<HashSet<_>>().len(),
+        proofs.len()
+    );
+    let ordered = duplicated.into_iter().collect::<BTreeSet<_>>();
+    assert_eq!(ordered.len(), proofs.len());
+    let encodings = ordered.iter().map(Vec::from).collect::<Vec<_>>();
+    assert!(encodings.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+fn test_proving_key_ref<E>()
+where
+    E: Pairing,
+{
+    use crate::{msm::CpuBackend, ProvingKeyRef};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pk_ref = ProvingKeyRef::from(&pk);
+    assert_eq!(pk_ref.to_owned(), pk);
+    assert_eq!(pk_ref.vk.to_owned(), vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    assert_eq!(
+        Groth16::<E>::create_proof_with_key_ref_and_backend(circuit(), pk_ref, r, s, &CpuBackend)
+            .unwrap(),
+        Groth16::<E>::create_proof_with_reduction(circuit(), &pk, r, s).unwrap()
+    );
+
+    let proof =
+        Groth16::<E>::create_random_proof_with_key_ref(circuit(), pk_ref, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&vk);
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+}
+
+fn test_formatting<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(E::ScalarField::rand(&mut rng)),
+            b: Some(E::ScalarField::rand(&mut rng)),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    };
+    assert_eq!(
+        format!("{}", proof),
+        format!("0x{}", hex(&Vec::from(&proof)))
+    );
+    let mut a = Vec::new();
+    proof.a.serialize_compressed(&mut a).unwrap();
+    assert!(format!("{:?}", proof).starts_with(&format!("Proof {{ a: 0x{}, b: 0x", hex(&a))));
+
+    assert_eq!(
+        format!("{}", vk),
+        format!(
+            "verifying key (public inputs: 1, compressed size: {} bytes, fingerprint: 0x{})",
+            vk.compressed_size(),
+            hex(&vk.fingerprint())
+        )
+    );
+    let debug = format!("{:?}", vk);
+    assert!(debug.starts_with("VerifyingKey { alpha_g1: 0x"));
+    assert!(debug.contains("gamma_abc_g1: [0x"));
+
+    assert_eq!(
+        format!("{}", pk.key_size()),
+        format!(
+            "public inputs: 1, G1 elements: {}, G2 elements: {}",
+            pk.key_size().num_g1_elements(),
+            pk.key_size().num_g2_elements()
+        )
+    );
+}
+
+fn test_verify_runtime<E, const P: usize, const S: usize>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng)
+        .unwrap()
+        .to_array::<P>()
+        .unwrap();
+    let vk = Vec::from(&vk);
+    assert_eq!(vk.len(), runtime::verifying_key_size::<E>(1));
+    let scalar = |x: E::ScalarField| -> [u8; S] {
+        let mut bytes = [0u8; S];
+        x.serialize_compressed(&mut bytes[..]).unwrap();
+        bytes
+    };
+
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &proof, &[scalar(a * b)]),
+        Ok(true)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &proof, &[scalar(a)]),
+        Ok(false)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &proof, &[scalar(a), scalar(b)]),
+        Err(VerificationError::InvalidEncoding)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk[1..], &proof, &[scalar(a * b)]),
+        Err(VerificationError::InvalidEncoding)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &proof, &[[0xff; S]]),
+        Err(VerificationError::InputNotInField { index: 0 })
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &[0xff; P], &[scalar(a * b)]),
+        Err(VerificationError::InvalidEncoding)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime(&vk, &proof, &[[0u8; 31]]),
+        Err(VerificationError::InvalidEncoding)
+    );
+
+    let stored = runtime::StoredVerifyingKey::from(&VerifyingKey::<E>::try_from(&vk[..]).unwrap());
+    let bytes = stored.to_bytes();
+    assert_eq!(bytes.len(), runtime::stored_verifying_key_size::<E>(1));
+    let stored = runtime::StoredVerifyingKey::<E>::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        Groth16::<E>::verify_stored(&stored, &proof, &[scalar(a * b)]),
+        Ok(true)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_stored(&stored, &proof, &[scalar(a)]),
+        Ok(false)
+    );
+    assert_eq!(
+        runtime::StoredVerifyingKey::<E>::from_bytes(&bytes[1..]),
+        Err(VerificationError::InvalidEncoding)
+    );
+
+    let cpu = runtime::CpuPairing;
+    assert_eq!(
+        Groth16::<E>::verify_runtime_with_backend(&vk, &proof, &[scalar(a * b)], &cpu),
+        Ok(true)
+    );
+    assert_eq!(
+        Groth16::<E>::verify_runtime_with_backend(&vk, &proof, &[scalar(a)], &cpu),
+        Ok(false)
+    );
+    struct Unavailable;
+    impl<E: Pairing> runtime::PairingBackend<E> for Unavailable {
+        fn pairing_check(&self, g1: &[E::G1Affine], g2: &[E::G2Affine]) -> Option<bool> {
+            assert_eq!(g1.len(), g2.len());
+            None
+        }
+    }
+    assert_eq!(
+        Groth16::<E>::verify_runtime_with_backend(&vk, &proof, &[scalar(a * b)], &Unavailable),
+        Err(VerificationError::PairingCheckFailed)
+    );
+}
+
+fn test_sui_move_verifier<E: Pairing>(curve: codegen::MoveCurve, other: codegen::MoveCurve) {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (_, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let source = codegen::sui_move_verifier(&vk, curve, "verifier", "silly").unwrap();
+    let vk_hex = Vec::from(&vk)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    assert!(source.contains("module verifier::silly {"));
+    assert!(source.contains(&format!(
+        "const VERIFYING_KEY: vector<u8> = x\"{}\";",
+        vk_hex
+    )));
+    assert!(source.contains("const NUM_PUBLIC_INPUTS: u64 = 1;"));
+    assert_eq!(source.matches('{').count(), source.matches('}').count());
+
+    assert_eq!(
+        codegen::sui_move_verifier(&vk, curve, "verifier", "1silly"),
+        Err(codegen::CodegenError::InvalidIdentifier)
+    );
+    assert_eq!(
+        codegen::sui_move_verifier(&vk, curve, "0x1::evil", "silly"),
+        Err(codegen::CodegenError::InvalidIdentifier)
+    );
+    assert_eq!(
+        codegen::sui_move_verifier(&vk, other, "verifier", "silly"),
+        Err(codegen::CodegenError::CurveMismatch)
+    );
+}
+
+fn test_aptos_move_verifier<E: Pairing>() {
+    fn compressed_hex(point: impl CanonicalSerialize) -> String {
+        let mut bytes = Vec::new();
+        point.serialize_compressed(&mut bytes).unwrap();
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (_, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let source = codegen::aptos_move_verifier(&vk, "verifier", "silly").unwrap();
+    assert!(source.contains("module verifier::silly {"));
+    assert!(source.contains(&format!(
+        "const ALPHA_G1: vector<u8> = x\"{}\";",
+        compressed_hex(vk.alpha_g1)
+    )));
+    assert!(source.contains(&format!(
+        "const DELTA_G2: vector<u8> = x\"{}\";",
+        compressed_hex(vk.delta_g2)
+    )));
+    assert!(source.contains(&format!(
+        "const GAMMA_ABC_G1: vector<vector<u8>> = vector[x\"{}\", x\"{}\"];",
+        compressed_hex(vk.gamma_abc_g1[0]),
+        compressed_hex(vk.gamma_abc_g1[1])
+    )));
+    assert!(source.contains("const NUM_PUBLIC_INPUTS: u64 = 1;"));
+    assert_eq!(source.matches('{').count(), source.matches('}').count());
+    assert_eq!(source.matches('(').count(), source.matches(')').count());
+
+    assert_eq!(
+        codegen::aptos_move_verifier(&vk, "verifier", "1silly"),
+        Err(codegen::CodegenError::InvalidIdentifier)
+    );
+}
+
+fn test_aptos_move_verifier_curve_mismatch<E: Pairing>() {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (_, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    assert_eq!(
+        codegen::aptos_move_verifier(&vk, "verifier", "silly"),
+        Err(codegen::CodegenError::CurveMismatch)
+    );
+}
+
+fn test_se_proof<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let pk =
+        Groth16::<E>::generate_random_se_parameters(MySillyCircuit { a: None, b: None }, &mut rng)
+            .unwrap();
+    assert_eq!(pk.vk.num_public_inputs(), 2);
+    let pvk = prepare_verifying_key::<E>(&pk.vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::create_random_se_proof(circuit, &pk, &mut rng).unwrap();
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    assert!(Groth16::<E>::verify_se_proof(&pvk, &proof, &[a * b]).unwrap());
+    assert!(!Groth16::<E>::verify_se_proof(&pvk, &proof, &[a]).unwrap());
+
+    // The inner proof is still a valid Groth16 proof, but a rerandomized one is no longer
+    // signed.
+    let rerandomized = se::SeProof {
+        proof: Groth16::<E>::rerandomize_proof(&pk.vk, &proof.proof, &mut rng),
+        ..proof.clone()
+    };
+    assert!(!Groth16::<E>::verify_se_proof(&pvk, &rerandomized, &[a * b]).unwrap());
+
+    // Re-signing the proof under another key changes the binding.
+    let other = Groth16::<E>::create_random_se_proof(circuit, &pk, &mut rng).unwrap();
+    let swapped = se::SeProof {
+        proof: proof.proof.clone(),
+        ..other.clone()
+    };
+    assert!(!Groth16::<E>::verify_se_proof(&pvk, &swapped, &[a * b]).unwrap());
+    let unsigned = se::SeProof {
+        public_key: E::G1Affine::zero(),
+        ..proof.clone()
+    };
+    assert!(!Groth16::<E>::verify_se_proof(&pvk, &unsigned, &[a * b]).unwrap());
+
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        se::SeProof::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        proof
+    );
+
+    // The binding is constrained by the circuit itself, not only by the reduction.
+    let cs = ConstraintSystem::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    se::BoundCircuit {
+        circuit: MySillyCircuit::<E::ScalarField> { a: None, b: None },
+        binding: None,
+    }
+    .generate_constraints(cs.clone())
+    .unwrap();
+    cs.finalize();
+    let matrices = cs.to_matrices().unwrap();
+    let binding = matrices.num_instance_variables - 1;
+    assert!(matrices
+        .a
+        .iter()
+        .any(|row| row.iter().any(|(_, i)| *i == binding)));
+}
+
+fn test_gm17<E>()
+where
+    E: Pairing,
+{
+    use crate::gm17::{Gm17, Gm17Proof};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Gm17::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    assert_eq!(vk.num_public_inputs(), 1);
+    let pvk = Gm17::<E>::process_vk(&vk).unwrap();
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Gm17::<E>::prove(&pk, circuit(), &mut rng).unwrap();
+    assert!(Gm17::<E>::verify_with_processed_vk(&pvk, &[a * b], &proof).unwrap());
+    assert!(!Gm17::<E>::verify_with_processed_vk(&pvk, &[a], &proof).unwrap());
+    assert!(Gm17::<E>::verify(&vk, &[a * b], &proof).unwrap());
+    assert!(Gm17::<E>::verify_proof(&pvk, &proof, &[a * b, a]).is_err());
+
+    // A proof whose `A` and `B` have different exponents fails the second check.
+    let other = Gm17::<E>::prove(&pk, circuit(), &mut rng).unwrap();
+    assert_ne!(other, proof);
+    let mixed = Gm17Proof {
+        b: other.b,
+        ..proof.clone()
+    };
+    assert!(!Gm17::<E>::verify_with_processed_vk(&pvk, &[a * b], &mixed).unwrap());
+
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        Gm17Proof::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        proof
+    );
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        crate::gm17::Gm17ProvingKey::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        pk
+    );
+}
+
+fn test_designated_proof<E>()
+where
+    E: Pairing,
+{
+    use crate::designated::{DesignatedProof, DesignatedVerifierKey};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let key = DesignatedVerifierKey::sample(&mut rng);
+    let verifier = key.public_key::<E>();
+    let other_verifier = DesignatedVerifierKey::sample(&mut rng).public_key::<E>();
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let designated =
+        Groth16::<E>::create_designated_proof(&pvk, &proof, &[a * b], &verifier, &mut rng).unwrap();
+    assert_ne!(designated.a, proof.a);
+    assert!(Groth16::<E>::verify_designated_proof(&pvk, &verifier, &designated, &[a * b]).unwrap());
+    assert!(!Groth16::<E>::verify_designated_proof(&pvk, &verifier, &designated, &[a]).unwrap());
+    assert!(
+        !Groth16::<E>::verify_designated_proof(&pvk, &other_verifier, &designated, &[a * b])
+            .unwrap()
+    );
+
+    // An invalid proof does not yield a valid designated proof.
+    let forged =
+        Groth16::<E>::create_designated_proof(&pvk, &proof, &[a], &verifier, &mut rng).unwrap();
+    assert!(!Groth16::<E>::verify_designated_proof(&pvk, &verifier, &forged, &[a]).unwrap());
+
+    // The designated verifier can simulate proofs of false statements, but only for
+    // their own key.
+    let simulated = Groth16::<E>::simulate_designated_proof(&pvk, &key, &[a], &mut rng).unwrap();
+    assert!(Groth16::<E>::verify_designated_proof(&pvk, &verifier, &simulated, &[a]).unwrap());
+    assert!(
+        !Groth16::<E>::verify_designated_proof(&pvk, &other_verifier, &simulated, &[a]).unwrap()
+    );
+
+    let mut bytes = Vec::new();
+    designated.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        DesignatedProof::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        designated
+    );
+    assert_eq!(format!("{:?}", key), "DesignatedVerifierKey { .. }");
+}
+
+fn test_delegated_proof<E>()
+where
+    E: Pairing,
+{
+    use crate::{delegated::DelegationMask, msm::CpuBackend};
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let assignment = Groth16::<E>::synthesize_assignment(MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    })
+    .unwrap();
+
+    let mask = Groth16::<E>::generate_delegation_mask(&pk, &mut rng).unwrap();
+    let mut bytes = Vec::new();
+    mask.serialize_compressed(&mut bytes).unwrap();
+    let mask_copy = DelegationMask::<E>::deserialize_compressed(&bytes[..]).unwrap();
+    assert_eq!(format!("{:?}", mask), "DelegationMask { .. }");
+
+    let (blinded, token) = Groth16::<E>::blind_assignment(&pk, &assignment, mask).unwrap();
+    assert_eq!(format!("{:?}", token), "DelegationToken { .. }");
+    assert!(!blinded.assignment.contains(&a));
+    assert!(!blinded.assignment.contains(&b));
+    let evaluation = Groth16::<E>::evaluate_blinded_assignment(&pk, &blinded, &CpuBackend).unwrap();
+    let proof =
+        Groth16::<E>::finish_delegated_proof(&pk, &assignment, token, &evaluation, &mut rng)
+            .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    // A server that answers incorrectly is detected.
+    let (_, token) = Groth16::<E>::blind_assignment(&pk, &assignment, mask_copy).unwrap();
+    let wrong = crate::delegated::BlindedEvaluation {
+        h_g1: evaluation.a_g1,
+        ..evaluation.clone()
+    };
+    assert!(matches!(
+        Groth16::<E>::finish_delegated_proof(&pk, &assignment, token, &wrong, &mut rng),
+        Err(ProverError::InvalidProof)
+    ));
+
+    let truncated = crate::delegated::BlindedAssignment {
+        assignment: blinded.assignment[1..].to_vec(),
+        ..blinded
+    };
+    assert!(matches!(
+        Groth16::<E>::evaluate_blinded_assignment(&pk, &truncated, &CpuBackend),
+        Err(ProverError::MalformedProvingKey)
+    ));
+}
+
+#[cfg(feature = "r1cs")]
+fn test_input_hash<E>()
+where
+    E: Pairing,
+{
+    use crate::input_hash::{enforce_input_hash, hash_public_inputs};
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+
+    /// Proves knowledge of `a` and `b` with `a * b = c`, with `a + b` and `c` public.
+    struct HashedCircuit<F: PrimeField> {
+        a: Option<F>,
+        b: Option<F>,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for HashedCircuit<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let a = FpVar::new_witness(cs.clone(), || {
+                self.a.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let b = FpVar::new_witness(cs.clone(), || {
+                self.b.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let _ = enforce_input_hash(cs, &[&a + &b, &a * &b])?;
+            Ok(())
+        }
+    }
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(HashedCircuit { a: None, b: None }, &mut rng).unwrap();
+    assert_eq!(vk.num_public_inputs(), 1);
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        HashedCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+    let values = [a + b, a * b];
+    assert!(Groth16::<E>::verify_proof_with_input_hash(&pvk, &proof, &values).unwrap());
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[hash_public_inputs(&values)]).unwrap());
+    assert!(!Groth16::<E>::verify_proof_with_input_hash(&pvk, &proof, &[a * b, a + b]).unwrap());
+    assert!(!Groth16::<E>::verify_proof_with_input_hash(&pvk, &proof, &values[..1]).unwrap());
+
+    assert_ne!(
+        hash_public_inputs(&values),
+        hash_public_inputs(&[values[0], values[1], E::ScalarField::zero()])
+    );
+
+    let (_, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let mut two_inputs = prepare_verifying_key::<E>(&vk);
+    two_inputs.vk.gamma_abc_g1.push(vk.gamma_abc_g1[1]);
+    assert!(Groth16::<E>::verify_proof_with_input_hash(&two_inputs, &proof, &values).is_err());
+}
+
+fn test_context_proof<E>()
+where
+    E: Pairing,
+{
+    use crate::context::context_input;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let pk = Groth16::<E>::generate_random_context_parameters(
+        MySillyCircuit { a: None, b: None },
+        &mut rng,
+    )
+    .unwrap();
+    assert_eq!(pk.vk.num_public_inputs(), 2);
+    let pvk = prepare_verifying_key::<E>(&pk.vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let context = b"example.com/login/session-42";
+    let proof =
+        Groth16::<E>::create_random_proof_with_context(circuit, &pk, context, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify_proof_with_context(&pvk, &proof, &[a * b], context).unwrap());
+    assert!(!Groth16::<E>::verify_proof_with_context(&pvk, &proof, &[a], context).unwrap());
+    assert!(!Groth16::<E>::verify_proof_with_context(
+        &pvk,
+        &proof,
+        &[a * b],
+        b"example.com/login/session-43"
+    )
+    .unwrap());
+    assert!(!Groth16::<E>::verify_proof_with_context(&pvk, &proof, &[a * b], b"").unwrap());
+
+    // The context input is an ordinary public input, which rerandomization preserves.
+    let context_input = context_input::<E::ScalarField>(context);
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b, context_input]).unwrap());
+    let rerandomized = Groth16::<E>::rerandomize_proof(&pk.vk, &proof, &mut rng);
+    assert!(
+        Groth16::<E>::verify_proof_with_context(&pvk, &rerandomized, &[a * b], context).unwrap()
+    );
+}
+
+fn test_threshold_proof<E>()
+where
+    E: Pairing,
+{
+    use crate::threshold::AssignmentShare;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let cs = ConstraintSystem::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    MySillyCircuit::<E::ScalarField> { a: None, b: None }
+        .generate_constraints(cs.clone())
+        .unwrap();
+    cs.finalize();
+    let matrices = cs.to_matrices().unwrap();
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let num_parties = 3;
+    let shares = AssignmentShare::split(&[a * b], &[a, b], num_parties, &mut rng);
+    assert_eq!(format!("{:?}", shares[0]), "AssignmentShare { .. }");
+    assert!(shares
+        .iter()
+        .all(|share| !share.aux_assignment.contains(&a)));
+
+    let count = Groth16::<E>::threshold_triples_needed(&matrices).unwrap();
+    let triples = Groth16::<E>::deal_beaver_triples(count, num_parties, &mut rng);
+
+    let (provers, masked): (Vec<_>, Vec<_>) = shares
+        .iter()
+        .zip(triples)
+        .map(|(share, triples)| {
+            Groth16::<E>::start_threshold_proof(
+                &pk,
+                &matrices,
+                share,
+                num_parties,
+                triples,
+                &mut rng,
+            )
+            .unwrap()
+        })
+        .unzip();
+    assert!(matches!(
+        Groth16::<E>::start_threshold_proof(
+            &pk,
+            &matrices,
+            &shares[0],
+            num_parties,
+            vec![],
+            &mut rng
+        ),
+        Err(ProverError::MalformedMessage)
+    ));
+
+    let (opened, partials): (Vec<_>, Vec<_>) = provers
+        .into_iter()
+        .map(|prover| prover.open_products(&pk, &masked).unwrap())
+        .unzip();
+    let c_shares = opened
+        .into_iter()
+        .map(|opened| opened.share_c(&partials).unwrap())
+        .collect::<Vec<_>>();
+
+    let proof = Groth16::<E>::combine_threshold_proof(&pk, &[a * b], &partials, &c_shares).unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    assert!(matches!(
+        Groth16::<E>::combine_threshold_proof(&pk, &[a], &partials, &c_shares),
+        Err(ProverError::InvalidProof)
+    ));
+    assert!(matches!(
+        Groth16::<E>::combine_threshold_proof(&pk, &[a * b], &partials, &c_shares[1..]),
+        Err(ProverError::UnexpectedParties)
+    ));
+
+    // A party that is counted twice is detected, even if the count matches.
+    let mut repeated = c_shares.clone();
+    repeated[1] = repeated[0].clone();
+    assert!(matches!(
+        Groth16::<E>::combine_threshold_proof(&pk, &[a * b], &partials, &repeated),
+        Err(ProverError::UnexpectedParties)
+    ));
+    let mut repeated = partials.clone();
+    repeated[2] = repeated[1].clone();
+    assert!(matches!(
+        Groth16::<E>::combine_threshold_proof(&pk, &[a * b], &repeated, &c_shares),
+        Err(ProverError::UnexpectedParties)
+    ));
+    // Dropping a party from both rounds changes the number of parties.
+    assert!(matches!(
+        Groth16::<E>::combine_threshold_proof(&pk, &[a * b], &partials[1..], &c_shares[1..]),
+        Err(ProverError::UnexpectedParties)
+    ));
+}
+
+fn test_batch_affine_backend<E>()
+where
+    E: Pairing,
+    BatchAffineBackend: MsmBackend<E>,
+{
+    // Enough distinct bases for the batches to be used, followed by a repeated base, a
+    // negated base and the identity, whose scalars put them into occupied buckets.
+    fn bases<G: CurveGroup>(n: usize) -> Vec<G::Affine> {
+        let mut acc = G::generator();
+        let mut points = (0..n)
+            .map(|_| {
+                acc += G::generator();
+                acc
+            })
+            .collect::<Vec<_>>();
+        points.extend([points[0], -points[1], G::zero()]);
+        G::normalize_batch(&points)
+    }
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let n = (1 << 14) + 1;
+    let mut scalars = (0..n)
+        .map(|_| E::ScalarField::rand(&mut rng))
+        .collect::<Vec<_>>();
+    scalars.extend([scalars[0], scalars[1], scalars[2]]);
+    // Boolean values, which all fall into the same bucket.
+    scalars[3..1000].fill(E::ScalarField::one());
+    let scalars = scalars.iter().map(|s| s.into_bigint()).collect::<Vec<_>>();
+
+    let g1 = bases::<E::G1>(n);
+    assert_eq!(
+        MsmBackend::<E>::msm_g1(&BatchAffineBackend, MsmQuery::A, &g1, &scalars),
+        E::G1::msm_bigint(&g1, &scalars)
+    );
+    let g2 = bases::<E::G2>(n);
+    assert_eq!(
+        MsmBackend::<E>::msm_g2(&BatchAffineBackend, MsmQuery::BG2, &g2, &scalars),
+        E::G2::msm_bigint(&g2, &scalars)
+    );
+    // Small multi-scalar multiplications fall back to `CpuBackend`.
+    assert_eq!(
+        MsmBackend::<E>::msm_g1(&BatchAffineBackend, MsmQuery::A, &g1[..20], &scalars),
+        E::G1::msm_bigint(&g1[..20], &scalars[..20])
+    );
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::create_proof_with_reduction_and_backend(
+        circuit,
+        &pk,
+        r,
+        s,
+        &BatchAffineBackend,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+}
+
+fn test_preprocessed_proving_key<E>()
+where
+    E: Pairing,
+{
+    use crate::preprocess::PreprocessedProvingKey;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = |a, b| ProductAndSumCircuit { a, b };
+    let (pk, vk) = Groth16::<E>::setup(circuit(None, None), &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let costs = OperationCosts {
+        mixed_addition: 100,
+        addition: 120,
+        doubling: 100,
+    };
+    let tuning = WindowTuning {
+        g1: costs,
+        g2: costs,
+    };
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    let expected =
+        Groth16::<E>::create_proof_with_reduction(circuit(Some(a), Some(b)), &pk, r, s).unwrap();
+    for ppk in [
+        PreprocessedProvingKey::new(pk.clone()).unwrap(),
+        PreprocessedProvingKey::with_tuning(pk.clone(), &tuning).unwrap(),
+    ] {
+        // The public inputs occur in no `B`, so their points in the `B` queries are the
+        // identity.
+        assert!(ppk.num_removed_points() >= 4);
+        assert_eq!(ppk.pk(), &pk);
+        let mut malformed = pk.clone();
+        malformed.a_query.clear();
+        assert!(matches!(
+            PrecomputedProvingKey::new(malformed, 4),
+            Err(ProverError::MalformedProvingKey)
+        ));
+        let proof =
+            Groth16::<E>::create_proof_with_preprocessed_key(circuit(Some(a), Some(b)), &ppk, r, s)
+                .unwrap();
+        assert_eq!(proof, expected);
+
+        let proof = Groth16::<E>::create_random_proof_with_preprocessed_key(
+            circuit(Some(a), Some(b)),
+            &ppk,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b, a + b]).unwrap());
+        assert_eq!(ppk.into_inner(), pk);
+    }
+
+    // Keys whose queries do not have one point per variable are rejected.
+    let mut malformed = pk.clone();
+    malformed.a_query.clear();
+    assert!(matches!(
+        PreprocessedProvingKey::new(malformed),
+        Err(ProverError::MalformedProvingKey)
+    ));
+    let mut malformed = pk;
+    malformed.b_g2_query.pop();
+    assert!(matches!(
+        PreprocessedProvingKey::with_tuning(malformed, &tuning),
+        Err(ProverError::MalformedProvingKey)
+    ));
+}
+
+fn test_prefilter<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let c = a * b;
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let config = PrefilterConfig::default();
+    assert!(Groth16::<E>::verify_proof_with_prefilter(&config, &pvk, &proof, &[c], None).unwrap());
+    assert_eq!(
+        Groth16::<E>::prefilter_proof(&config, &pvk, &proof, &[c, c], None),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Groth16::<E>::prefilter_proof(&config, &pvk, &Proof::default(), &[c], None),
+        Err(VerificationError::MalformedProofPoint)
+    );
+
+    let config = PrefilterConfig {
+        pow_difficulty: Some(8),
+        pow_challenge: [7; 32],
+        ..PrefilterConfig::default()
+    };
+    let nonce = Groth16::<E>::solve_proof_of_work(&config.pow_challenge, &proof, &[c], 8);
+    assert!(Groth16::<E>::prefilter_proof(&config, &pvk, &proof, &[c], Some(nonce)).is_ok());
+    // The nonce is bound to the challenge, so it does not carry over to a fresh one.
+    let stale = (0..=u8::MAX)
+        .map(|i| PrefilterConfig {
+            pow_challenge: [i; 32],
+            ..config
+        })
+        .find(|fresh| {
+            Groth16::<E>::prefilter_proof(fresh, &pvk, &proof, &[c], Some(nonce)).is_err()
+        });
+    assert!(stale.is_some());
+    assert_eq!(
+        Groth16::<E>::prefilter_proof(&config, &pvk, &proof, &[c], None),
+        Err(VerificationError::InsufficientProofOfWork)
+    );
+    let bad_nonce = (0..)
+        .find(|n| Groth16::<E>::prefilter_proof(&config, &pvk, &proof, &[c], Some(*n)).is_err())
+        .unwrap();
+    assert_eq!(
+        Groth16::<E>::prefilter_proof(&config, &pvk, &proof, &[c], Some(bad_nonce)),
+        Err(VerificationError::InsufficientProofOfWork)
+    );
+}
+
+/// Forwards to [`CpuBackend`] while counting the MSMs it is asked to compute.
+#[derive(Default)]
+struct CountingBackend {
+    g1: AtomicUsize,
+    g2: AtomicUsize,
+}
+
+impl<E: Pairing> MsmBackend<E> for CountingBackend {
+    fn msm_g1(&self, query: MsmQuery, bases: &[E::G1Affine], scalars: &[ScalarBigInt<E>]) -> E::G1 {
+        self.g1.fetch_add(1, Ordering::SeqCst);
+        MsmBackend::<E>::msm_g1(&CpuBackend, query, bases, scalars)
+    }
+
+    fn msm_g2(&self, query: MsmQuery, bases: &[E::G2Affine], scalars: &[ScalarBigInt<E>]) -> E::G2 {
+        self.g2.fetch_add(1, Ordering::SeqCst);
+        MsmBackend::<E>::msm_g2(&CpuBackend, query, bases, scalars)
+    }
+}
+
+fn test_msm_backend<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let backend = CountingBackend::default();
+    let proof = Groth16::<E>::create_random_proof_with_backend(
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &pk,
+        &backend,
+        &mut rng,
+    )
+    .unwrap();
+
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    // H, L, A and B in G1, plus B in G2.
+    assert_eq!(backend.g1.load(Ordering::SeqCst), 4);
+    assert_eq!(backend.g2.load(Ordering::SeqCst), 1);
+}
+
+fn test_prove_batch<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    // The first job fails synthesis, so the matrices must come from the second one.
+    let mut values = vec![None];
+    for _ in 0..3 {
+        let a = E::ScalarField::rand(&mut rng);
+        let b = E::ScalarField::rand(&mut rng);
+        values.push(Some((a, b)));
+    }
+    let circuits = values.iter().map(|v| MySillyCircuit {
+        a: v.map(|(a, _)| a),
+        b: v.map(|(_, b)| b),
+    });
+
+    let proofs = Groth16::<E>::prove_batch(&pk, circuits, &mut rng);
+    assert_eq!(proofs.len(), values.len());
+    assert_eq!(proofs[0], Err(SynthesisError::AssignmentMissing));
+    for (proof, (a, b)) in proofs[1..].iter().zip(values[1..].iter().flatten()) {
+        let proof = proof.as_ref().unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, &[*a * b]).unwrap());
+    }
+}
+
+/// [`MySillyCircuit`] with `padding` extra copies of its constraint.
+struct PaddedCircuit<F: Field> {
+    inner: MySillyCircuit<F>,
+    padding: usize,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for PaddedCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.inner.generate_constraints(cs.clone())?;
+        for _ in 0..self.padding {
+            cs.enforce_constraint(lc!(), lc!(), lc!())?;
+        }
+        Ok(())
+    }
+}
+
+fn test_prove_batch_shape_mismatch<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = |padding| PaddedCircuit {
+        inner: MySillyCircuit { a: None, b: None },
+        padding,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit(0), &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuits = [0, 1, 0].into_iter().map(|padding| PaddedCircuit {
+        inner: MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        padding,
+    });
+
+    let proofs = Groth16::<E>::prove_batch(&pk, circuits, &mut rng);
+    assert_eq!(proofs[1], Err(SynthesisError::Unsatisfiable));
+    for proof in [&proofs[0], &proofs[2]] {
+        let proof = proof.as_ref().unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, &[a * b]).unwrap());
+    }
+}
+
+#[cfg(feature = "std")]
+fn test_prove_pipelined_shape_mismatch<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let circuit = |padding| PaddedCircuit {
+        inner: MySillyCircuit { a: None, b: None },
+        padding,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit(0), &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuits = [0, 1, 0].into_iter().map(|padding| PaddedCircuit {
+        inner: MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        padding,
+    });
+
+    let proofs = Groth16::<E>::prove_pipelined(&pk, circuits, 1, &mut rng);
+    assert_eq!(proofs[1], Err(SynthesisError::Unsatisfiable));
+    for proof in [&proofs[0], &proofs[2]] {
+        let proof = proof.as_ref().unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, &[a * b]).unwrap());
+    }
+}
+
+#[cfg(feature = "std")]
+fn test_prove_pipelined<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    // The first job fails synthesis, so the matrices must come from the second one.
+    let mut values = vec![None];
+    for _ in 0..4 {
+        let a = E::ScalarField::rand(&mut rng);
+        let b = E::ScalarField::rand(&mut rng);
+        values.push(Some((a, b)));
+    }
+    let circuits = values.clone().into_iter().map(|v| MySillyCircuit {
+        a: v.map(|(a, _)| a),
+        b: v.map(|(_, b)| b),
+    });
+
+    let proofs = Groth16::<E>::prove_pipelined(&pk, circuits, 1, &mut rng);
+    assert_eq!(proofs.len(), values.len());
+    assert_eq!(proofs[0], Err(SynthesisError::AssignmentMissing));
+    for (proof, (a, b)) in proofs[1..].iter().zip(values[1..].iter().flatten()) {
+        let proof = proof.as_ref().unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, &[*a * b]).unwrap());
+    }
+}
+
+fn test_blob_encoding<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let proof = Groth16::<E>::prove(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &mut rng,
+    )
+    .unwrap();
+
+    let elements = blob::encode_proof(&proof, &[a * b]);
+    assert!(elements.iter().all(|e| e[0] == 0));
+    let (proof2, inputs) = blob::decode_proof::<E>(&elements).unwrap();
+    assert_eq!(proof, proof2);
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof2, &inputs).unwrap());
+
+    let mut bad = elements.clone();
+    bad[0][0] = 1;
+    assert!(blob::decode_proof::<E>(&bad).is_err());
+    let mut bad = elements.clone();
+    bad.push([0u8; blob::BYTES_PER_FIELD_ELEMENT]);
+    assert!(blob::decode_proof::<E>(&bad).is_err());
+    assert!(blob::decode_proof::<E>(&elements[..elements.len() - 1]).is_err());
+}
+
+fn test_key_size<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let size = pk.key_size();
+    // `MySillyCircuit` has six constraints, two instance and two witness variables.
+    assert_eq!(Some(size), KeySize::for_circuit::<E::ScalarField>(6, 2, 2));
+    assert_eq!(size.num_public_inputs(), 1);
+
+    for compress in [Compress::Yes, Compress::No] {
+        assert_eq!(
+            size.proving_key_bytes::<E>(compress),
+            pk.serialized_size(compress)
+        );
+        assert_eq!(
+            size.verifying_key_bytes::<E>(compress),
+            vk.serialized_size(compress)
+        );
+        assert_eq!(
+            KeySize::proof_bytes::<E>(compress),
+            Proof::<E>::default().serialized_size(compress)
+        );
+    }
+}
+
+fn test_prove_from_reader<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    let mut bytes = Vec::new();
+    pk.serialize_compressed(&mut bytes).unwrap();
+    let proof = Groth16::<E>::create_proof_from_reader(
+        circuit(),
+        &bytes[..],
+        Compress::Yes,
+        Validate::Yes,
+        2,
+        r,
+        s,
+    )
+    .unwrap();
+    assert_eq!(
+        proof,
+        Groth16::<E>::create_proof_with_reduction(circuit(), &pk, r, s).unwrap()
+    );
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(Groth16::<E>::create_random_proof_from_reader(
+        circuit(),
+        truncated,
+        Compress::Yes,
+        Validate::Yes,
+        2,
+        &mut rng,
+    )
+    .is_err());
+    assert!(Groth16::<E>::create_random_proof_from_reader(
+        circuit(),
+        &bytes[..],
+        Compress::No,
+        Validate::Yes,
+        2,
+        &mut rng,
+    )
+    .is_err());
+}
+
+fn test_assignment_report<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+
+    let mut classification = WitnessClassification::new();
+    classification.mark_sensitive(0..1);
+    let report = Groth16::<E>::assignment_report(Classified {
+        circuit: MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        classification,
+    })
+    .unwrap();
+    assert_eq!(report.num_constraints, 6);
+    assert_eq!(report.unsatisfied, None);
+    assert_eq!(report.witness_value(0), None);
+    assert_eq!(report.witness_value(1), Some(&b));
+
+    let output = format!("{:?}", report);
+    assert!(!output.contains(&format!("{:?}", a)));
+    assert!(output.contains(&format!("{:?}", b)));
+    assert!(output.contains(redact::REDACTED));
+
+    let report = Groth16::<E>::assignment_report(Classified::all_sensitive(MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    }))
+    .unwrap();
+    assert!(!format!("{:?}", report).contains(&format!("{:?}", b)));
+
+    // The assignment that is handed between the proving phases redacts the witness and
+    // the QAP witness, which is derived from it.
+    let assignment = Groth16::<E>::synthesize_assignment(MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    })
+    .unwrap();
+    let output = format!("{:?}", assignment);
+    assert!(output.contains(&format!("{:?}", a * b)));
+    assert!(!output.contains(&format!("{:?}", a)));
+    assert!(!output.contains(&format!("{:?}", b)));
+}
+
+fn test_deterministic_proof<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    let proof = Groth16::<E>::create_deterministic_proof(circuit(), &pk, b"key").unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    assert_eq!(
+        proof,
+        Groth16::<E>::create_deterministic_proof(circuit(), &pk, b"key").unwrap()
+    );
+    assert_ne!(
+        proof,
+        Groth16::<E>::create_deterministic_proof(circuit(), &pk, b"other key").unwrap()
+    );
+    // Same statement, different witness.
+    let swapped = MySillyCircuit {
+        a: Some(b),
+        b: Some(a),
+    };
+    let proof2 = Groth16::<E>::create_deterministic_proof(swapped, &pk, b"key").unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof2, &[a * b]).unwrap());
+    assert_ne!(proof, proof2);
+}
+
+fn test_redundant_proving_key<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let key = RedundantProvingKey::new(&pk, &pk);
+    assert_eq!(key.check_integrity(usize::MAX, &mut rng), Ok(()));
+
+    let mut corrupted = pk.clone();
+    corrupted.h_query[2] = corrupted.h_query[1];
+    let mut key = RedundantProvingKey::new(&pk, &corrupted);
+    assert_eq!(
+        key.check_integrity(usize::MAX, &mut rng),
+        Err(KeyIntegrityError::PointMismatch {
+            query: "h_query",
+            index: 2
+        })
+    );
+    key.failover();
+    assert_eq!(key.primary(), &corrupted);
+
+    corrupted.l_query.pop();
+    let key = RedundantProvingKey::new(&pk, &corrupted);
+    assert_eq!(
+        key.check_integrity(0, &mut rng),
+        Err(KeyIntegrityError::LengthMismatch { query: "l_query" })
+    );
+}
+
+fn test_progress<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let reports = AtomicUsize::new(0);
+    let msm_percent = AtomicUsize::new(0);
+    let observer = |stage, percent| {
+        reports.fetch_add(1, Ordering::SeqCst);
+        if stage == ProvingStage::Msm {
+            msm_percent.fetch_max(percent as usize, Ordering::SeqCst);
+        }
+    };
+    let proof = Groth16::<E>::create_random_proof_with_progress(
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &pk,
+        &observer,
+        &mut rng,
+    )
+    .unwrap();
+
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    // Start and end of synthesis and the witness map, then the start of the MSMs
+    // and one report per MSM.
+    assert_eq!(reports.load(Ordering::SeqCst), 10);
+    assert_eq!(msm_percent.load(Ordering::SeqCst), 100);
+}
+
+fn test_setup_progress<E>()
+where
+    E: Pairing,
+{
+    use crate::progress::SetupStage;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let stages = [
+        SetupStage::Synthesis,
+        SetupStage::InstanceMap,
+        SetupStage::AQuery,
+        SetupStage::BG1Query,
+        SetupStage::BG2Query,
+        SetupStage::HQuery,
+        SetupStage::LQuery,
+        SetupStage::GammaAbcQuery,
+    ];
+    let reports = AtomicUsize::new(0);
+    let finished = stages.map(|_| AtomicUsize::new(0));
+    let observer = |stage, percent| {
+        reports.fetch_add(1, Ordering::SeqCst);
+        if percent == 100 {
+            let i = stages.iter().position(|s| *s == stage).unwrap();
+            finished[i].fetch_add(1, Ordering::SeqCst);
+        }
+    };
+    let pk = Groth16::<E>::generate_random_parameters_with_progress(
+        MySillyCircuit { a: None, b: None },
+        &observer,
+        &mut rng,
+    )
+    .unwrap();
+
+    // Every stage finishes once. Synthesis and the instance map report their start and
+    // end, and the queries have fewer points than chunks, so they report their start and
+    // then once per point.
+    assert!(finished.iter().all(|f| f.load(Ordering::SeqCst) == 1));
+    let points = 3 * pk.a_query.len() + pk.h_query.len() + pk.l_query.len();
+    let points = points + pk.vk.gamma_abc_g1.len();
+    assert_eq!(reports.load(Ordering::SeqCst), 2 * 2 + 6 + points);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify(&pk.vk, &[a * b], &proof).unwrap());
+}
+
+fn test_cancellation<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    let proof =
+        Groth16::<E>::create_random_proof_with_cancellation(circuit(), &pk, &|| false, &mut rng)
+            .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    // Abort after synthesis, after the witness map, and during the MSMs.
+    for checks in 0..3 {
+        let calls = AtomicUsize::new(0);
+        let should_abort = || calls.fetch_add(1, Ordering::SeqCst) >= checks;
+        let result = Groth16::<E>::create_random_proof_with_cancellation(
+            circuit(),
+            &pk,
+            &should_abort,
+            &mut rng,
+        );
+        assert!(matches!(result, Err(ProverError::Cancelled)));
+        assert!(calls.load(Ordering::SeqCst) > checks);
+    }
+}
+
+/// Polls `future` to completion on the current thread, returning its output and the
+/// number of polls it took.
+#[cfg(feature = "std")]
+fn block_on<F: core::future::Future>(future: F) -> (F::Output, usize) {
+    use core::task::{Context, Poll};
+    use std::{sync::Arc, task::Wake};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Arc::new(NoopWaker).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = core::pin::pin!(future);
+    let mut polls = 0;
+    loop {
+        polls += 1;
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return (output, polls);
+        }
+        std::thread::yield_now();
+    }
+}
+
+#[cfg(feature = "std")]
+fn test_prove_async<E>()
+where
+    E: Pairing,
+{
+    fn assert_send<T: Send>(t: T) -> T {
+        t
+    }
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let future = assert_send(Groth16::<E>::prove_async(
+        &pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        1,
+        &mut rng,
+    ));
+    let (proof, polls) = block_on(future);
+
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof.unwrap(), &[a * b]).unwrap());
+    assert!(polls > 5);
+
+    fn assert_static<T: Send + 'static>(t: T) -> T {
+        t
+    }
+    let pk = std::sync::Arc::new(pk);
+    let futures = (0..2)
+        .map(|i| {
+            assert_static(Groth16::<E>::prove_async(
+                pk.clone(),
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                1 << 10,
+                ark_std::rand::rngs::StdRng::seed_from_u64(i),
+            ))
+        })
+        .collect::<Vec<_>>();
+    for future in futures {
+        let (proof, _) = block_on(future);
+        assert!(Groth16::<E>::verify_proof(&pvk, &proof.unwrap(), &[a * b]).unwrap());
+    }
+
+    let future = assert_static(Groth16::<E>::prove_on_thread(
+        pk,
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        ark_std::rand::rngs::StdRng::seed_from_u64(2),
+    ));
+    let (proof, _) = block_on(future);
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof.unwrap(), &[a * b]).unwrap());
+}
+
+fn test_precomputation<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let bases = (0..10)
+        .map(|_| E::G1::rand(&mut rng).into_affine())
+        .collect::<Vec<_>>();
+    let scalars = (0..10)
+        .map(|_| E::ScalarField::rand(&mut rng).into_bigint())
+        .collect::<Vec<_>>();
+    for window_size in [1, 5, 8] {
+        let table = FixedBaseTable::new(&bases, window_size);
+        assert_eq!(table.len(), bases.len());
+        assert_eq!(table.msm(&scalars), E::G1::msm_bigint(&bases, &scalars));
+    }
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let ppk = PrecomputedProvingKey::new(pk.clone(), 4).unwrap();
+    assert_eq!(ppk.pk(), &pk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let proof = Groth16::<E>::create_proof_with_precomputation(circuit(), &ppk, r, s).unwrap();
+    assert_eq!(
+        proof,
+        Groth16::<E>::create_proof_with_reduction(circuit(), &pk, r, s).unwrap()
+    );
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+}
+
+fn test_two_phase_proving<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let r = E::ScalarField::rand(&mut rng);
+    let s = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    let assignment = Groth16::<E>::synthesize_assignment(circuit()).unwrap();
+    assert_eq!(assignment.input_assignment, vec![a * b]);
+
+    // The assignment can be moved to another process in serialized form.
+    let mut bytes = Vec::new();
+    assignment.serialize_compressed(&mut bytes).unwrap();
+    let assignment = ProverAssignment::deserialize_compressed(&bytes[..]).unwrap();
+
+    let proof = Groth16::<E>::create_proof_from_assignment(&pk, &assignment, r, s).unwrap();
+    assert_eq!(
+        proof,
+        Groth16::<E>::create_proof_with_reduction(circuit(), &pk, r, s).unwrap()
+    );
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    let mut truncated = assignment.clone();
+    truncated.aux_assignment.pop();
+    assert!(matches!(
+        Groth16::<E>::create_random_proof_from_assignment(&pk, &truncated, &mut rng),
+        Err(ProverError::MalformedProvingKey)
+    ));
+}
+
+/// Serializes `assignment` in the format of circom's `.wtns` files.
+fn write_wtns<F: PrimeField>(assignment: &[F]) -> Vec<u8> {
+    let n8 = F::MODULUS.to_bytes_le().len();
+    let mut bytes = b"wtns".to_vec();
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(((4 + n8 + 4) as u64).to_le_bytes());
+    bytes.extend((n8 as u32).to_le_bytes());
+    bytes.extend(F::MODULUS.to_bytes_le());
+    bytes.extend((assignment.len() as u32).to_le_bytes());
+
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(((assignment.len() * n8) as u64).to_le_bytes());
+    for value in assignment {
+        bytes.extend(value.into_bigint().to_bytes_le());
+    }
+    bytes
+}
+
+fn test_prove_from_wtns<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let cs = ConstraintSystem::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    MySillyCircuit::<E::ScalarField> { a: None, b: None }
+        .generate_constraints(cs.clone())
+        .unwrap();
+    cs.finalize();
+    let matrices = cs.to_matrices().unwrap();
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let wtns = write_wtns(&[E::ScalarField::one(), a * b, a, b]);
+    let assignment = circom::read_wtns::<E::ScalarField, _>(&wtns[..]).unwrap();
+    assert_eq!(assignment, vec![E::ScalarField::one(), a * b, a, b]);
+
+    let proof =
+        Groth16::<E>::create_random_proof_from_wtns(&pk, &matrices, &wtns[..], &mut rng).unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    let short = write_wtns(&[E::ScalarField::one(), a * b, a]);
+    assert!(matches!(
+        Groth16::<E>::create_random_proof_from_wtns(&pk, &matrices, &short[..], &mut rng),
+        Err(ProverError::MalformedProvingKey)
+    ));
+    let mut bad_prime = wtns.clone();
+    bad_prime[24] ^= 1;
+    assert!(circom::read_wtns::<E::ScalarField, _>(&bad_prime[..]).is_err());
+    assert!(circom::read_wtns::<E::ScalarField, _>(&wtns[..wtns.len() - 1]).is_err());
+    let mut bad_count = wtns.clone();
+    let n8 = E::ScalarField::MODULUS.to_bytes_le().len();
+    bad_count[28 + n8..32 + n8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(circom::read_wtns::<E::ScalarField, _>(&bad_count[..]).is_err());
+}
+
+fn write_r1cs<F: PrimeField>(
+    num_wires: u32,
+    num_public_outputs: u32,
+    constraints: &[[Vec<(u32, F)>; 3]],
+) -> Vec<u8> {
+    let n8 = F::MODULUS.to_bytes_le().len();
+    let mut bytes = b"r1cs".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(3u32.to_le_bytes());
+
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(((4 + n8 + 4 * 4 + 8 + 4) as u64).to_le_bytes());
+    bytes.extend((n8 as u32).to_le_bytes());
+    bytes.extend(F::MODULUS.to_bytes_le());
+    bytes.extend(num_wires.to_le_bytes());
+    bytes.extend(num_public_outputs.to_le_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend((num_wires - 1 - num_public_outputs).to_le_bytes());
+    bytes.extend(u64::from(num_wires).to_le_bytes());
+    bytes.extend((constraints.len() as u32).to_le_bytes());
+
+    let mut body = Vec::new();
+    for combination in constraints.iter().flatten() {
+        body.extend((combination.len() as u32).to_le_bytes());
+        for (wire, coeff) in combination {
+            body.extend(wire.to_le_bytes());
+            body.extend(coeff.into_bigint().to_bytes_le());
+        }
+    }
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend((body.len() as u64).to_le_bytes());
+    bytes.extend(body);
+
+    bytes.extend(3u32.to_le_bytes());
+    bytes.extend((u64::from(num_wires) * 8).to_le_bytes());
+    for label in 0..u64::from(num_wires) {
+        bytes.extend(label.to_le_bytes());
+    }
+    bytes
+}
+
+fn test_circom_r1cs<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let one = E::ScalarField::one();
+    let two = one + one;
+
+    // Wires [1, c, a, b] with c = a * b and (a + 2) * 1 = a + 2.
+    let r1cs_file = write_r1cs(
+        4,
+        1,
+        &[
+            [vec![(2, one)], vec![(3, one)], vec![(1, one)]],
+            [
+                vec![(2, one), (0, two)],
+                vec![(0, one)],
+                vec![(0, two), (2, one)],
+            ],
+        ],
+    );
+    let r1cs = circom::read_r1cs::<E::ScalarField, _>(&r1cs_file[..]).unwrap();
+    assert_eq!(r1cs.num_public, 2);
+    assert_eq!(r1cs.num_wires, 4);
+    assert_eq!(r1cs.constraints.len(), 2);
+    assert_eq!(r1cs.wire_labels, Some(vec![0, 1, 2, 3]));
+
+    let circuit = |witness| circom::CircomCircuit {
+        r1cs: r1cs.clone(),
+        witness,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit(None), &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let wtns = write_wtns(&[one, a * b, a, b]);
+    let witness = circom::read_wtns::<E::ScalarField, _>(&wtns[..]).unwrap();
+    let proof =
+        Groth16::<E>::create_random_proof_with_reduction(circuit(Some(witness)), &pk, &mut rng)
+            .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    assert!(!Groth16::<E>::verify_proof(&pvk, &proof, &[a]).unwrap());
+
+    let cs = ConstraintSystem::new_ref();
+    circuit(Some(vec![one, a, a, b]))
+        .generate_constraints(cs.clone())
+        .unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+    assert!(circuit(Some(vec![one, a * b, a]))
+        .generate_constraints(ConstraintSystem::new_ref())
+        .is_err());
+
+    assert!(circom::read_r1cs::<E::ScalarField, _>(&wtns[..]).is_err());
+    assert!(circom::read_r1cs::<E::ScalarField, _>(&r1cs_file[..r1cs_file.len() - 1]).is_err());
+}
+
+fn test_prover_config<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let g1 = (0..20)
+        .map(|_| E::G1::rand(&mut rng).into_affine())
+        .collect::<Vec<_>>();
+    let g2 = (0..20)
+        .map(|_| E::G2::rand(&mut rng).into_affine())
+        .collect::<Vec<_>>();
+    let scalars = (0..20)
+        .map(|_| E::ScalarField::rand(&mut rng).into_bigint())
+        .collect::<Vec<_>>();
+    let costs = OperationCosts {
+        mixed_addition: 100,
+        addition: 120,
+        doubling: 100,
+    };
+    let tuning = WindowTuning {
+        g1: costs,
+        g2: costs,
+    };
+    for window_size in [None, Some(1), Some(5)] {
+        for msm_chunk_size in [None, Some(1), Some(7)] {
+            for window_tuning in [None, Some(tuning)] {
+                let config = ProverConfig {
+                    msm_chunk_size,
+                    num_threads: None,
+                    window_size,
+                    window_tuning,
+                };
+                assert_eq!(
+                    MsmBackend::<E>::msm_g1(&config, MsmQuery::A, &g1, &scalars),
+                    E::G1::msm_bigint(&g1, &scalars)
+                );
+                assert_eq!(
+                    MsmBackend::<E>::msm_g2(&config, MsmQuery::BG2, &g2, &scalars[..13]),
+                    E::G2::msm_bigint(&g2[..13], &scalars[..13])
+                );
+            }
+        }
+    }
+
+    // Larger multi-scalar multiplications get larger windows, and more threads smaller
+    // ones.
+    let small = costs.window_size(1 << 8, 255, 1);
+    let large = costs.window_size(1 << 22, 255, 1);
+    assert!(small < large);
+    assert!(costs.window_size(1 << 22, 255, 64) <= large);
+
+    #[cfg(all(
+        feature = "std",
+        not(all(target_arch = "wasm32", target_os = "unknown"))
+    ))]
+    {
+        let tuned = ProverConfig::tuned::<E>().window_tuning.unwrap();
+        assert_eq!(WindowTuning::cached::<E>(), tuned);
+        let mut bytes = Vec::new();
+        tuned.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            WindowTuning::deserialize_compressed(&bytes[..]).unwrap(),
+            tuned
+        );
+    }
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let config = ProverConfig {
+        msm_chunk_size: Some(2),
+        num_threads: Some(2),
+        window_size: Some(3),
+        window_tuning: None,
+    };
+    for invalid in [
+        ProverConfig {
+            window_size: Some(0),
+            ..config
+        },
+        ProverConfig {
+            window_size: Some(MAX_WINDOW_SIZE + 1),
+            ..config
+        },
+        ProverConfig {
+            msm_chunk_size: Some(0),
+            ..config
+        },
+    ] {
+        let result = Groth16::<E>::create_random_proof_with_config(
+            MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            &pk,
+            &invalid,
+            &mut rng,
+        );
+        assert!(matches!(result, Err(ProverError::InvalidConfig)));
+        assert_eq!(
+            MsmBackend::<E>::msm_g1(&invalid, MsmQuery::A, &g1, &scalars),
+            E::G1::msm_bigint(&g1, &scalars)
+        );
+        assert_eq!(
+            MsmBackend::<E>::msm_g2(&invalid, MsmQuery::BG2, &g2, &scalars),
+            E::G2::msm_bigint(&g2, &scalars)
+        );
+    }
+    let proof = Groth16::<E>::create_random_proof_with_config(
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &pk,
+        &config,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+}
+
+#[cfg(feature = "std")]
+fn test_proving_report<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let mut classification = WitnessClassification::new();
+    classification.mark_sensitive(0..1);
+    let (proof, report) = Groth16::<E>::create_random_proof_with_report(
+        Classified {
+            circuit: MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            },
+            classification,
+        },
+        &pk,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+
+    assert_eq!(report.num_instance_variables, 2);
+    assert_eq!(report.num_witness_variables, 2);
+    assert!(report.num_constraints > 0);
+    assert!(report.synthesis + report.witness_map <= report.total);
+    let mut queries = report.msms.iter().map(|m| m.query).collect::<Vec<_>>();
+    queries.sort_by_key(|q| *q as u8);
+    assert_eq!(
+        queries,
+        [
+            MsmQuery::H,
+            MsmQuery::L,
+            MsmQuery::A,
+            MsmQuery::BG1,
+            MsmQuery::BG2
+        ]
+    );
+    for msm in &report.msms {
+        let expected = match msm.query {
+            MsmQuery::H => pk.h_query.len(),
+            MsmQuery::L => 2,
+            _ => 3,
+        };
+        assert_eq!(msm.size, expected);
+    }
+    assert!(format!("{}", report).contains("BG2 MSM (3 bases)"));
+
+    assert_eq!(report.assignment.witness_value(0), None);
+    assert_eq!(report.assignment.witness_value(1), Some(&b));
+    let output = format!("{:?}", report);
+    assert!(!output.contains(&format!("{:?}", a)));
+    assert!(output.contains(redact::REDACTED));
+}
+
+fn test_lego_proof<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    // Commit to `a`, the first witness variable of `MySillyCircuit`.
+    let pk = Groth16::<E>::generate_random_lego_parameters(
+        MySillyCircuit { a: None, b: None },
+        1,
+        &mut rng,
+    )
+    .unwrap();
+    let vk = pk.verifying_key();
+    assert_eq!(vk.commitment_key().unwrap().0.len(), 1);
+
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+    let (proof, v) = Groth16::<E>::create_random_lego_proof(circuit(), &pk, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify_lego_proof(&vk, &proof, &[a * b]).unwrap());
+    assert!(!Groth16::<E>::verify_lego_proof(&vk, &proof, &[a]).unwrap());
+    assert!(Groth16::<E>::verify_lego_commitment(&vk, &proof, &[a], v));
+    assert!(!Groth16::<E>::verify_lego_commitment(&vk, &proof, &[b], v));
+    assert!(!Groth16::<E>::verify_lego_commitment(
+        &vk,
+        &proof,
+        &[a],
+        v + v
+    ));
+
+    // The commitment cannot be swapped without invalidating the proof.
+    let (other, _) = Groth16::<E>::create_random_lego_proof(circuit(), &pk, &mut rng).unwrap();
+    assert_ne!(other.d, proof.d);
+    let swapped = crate::lego::LegoProof {
+        proof: proof.proof.clone(),
+        d: other.d,
+    };
+    assert!(!Groth16::<E>::verify_lego_proof(&vk, &swapped, &[a * b]).unwrap());
+
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        crate::lego::LegoProof::<E>::deserialize_compressed(&bytes[..]).unwrap(),
+        proof
+    );
+
+    assert!(Groth16::<E>::generate_random_lego_parameters(
+        MySillyCircuit { a: None, b: None },
+        3,
+        &mut rng,
+    )
+    .is_err());
+}
+
+fn test_lego_link<E>()
+where
+    E: Pairing,
+{
+    use crate::lego::PedersenCommitmentKey;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let pk = Groth16::<E>::generate_random_lego_parameters(
+        MySillyCircuit { a: None, b: None },
+        2,
+        &mut rng,
+    )
+    .unwrap();
+    let vk = pk.verifying_key();
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let (proof, v) = Groth16::<E>::create_random_lego_proof(
+        MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        },
+        &pk,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_lego_proof(&vk, &proof, &[a * b]).unwrap());
+
+    let ck = PedersenCommitmentKey::<E> {
+        bases: vec![
+            E::G1::rand(&mut rng).into_affine(),
+            E::G1::rand(&mut rng).into_affine(),
+        ],
+        blinding_base: E::G1::rand(&mut rng).into_affine(),
+    };
+    let blinding = E::ScalarField::rand(&mut rng);
+    let commitment = ck.commit(&[a, b], blinding).unwrap();
+    let link = Groth16::<E>::prove_link(
+        &vk,
+        &proof,
+        &ck,
+        &commitment,
+        &[a, b],
+        v,
+        blinding,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(Groth16::<E>::verify_link(
+        &vk,
+        &proof,
+        &ck,
+        &commitment,
+        &link
+    ));
+
+    // A commitment to other values cannot be linked.
+    let other = ck.commit(&[b, a], blinding).unwrap();
+    let bad_link =
+        Groth16::<E>::prove_link(&vk, &proof, &ck, &other, &[b, a], v, blinding, &mut rng).unwrap();
+    assert!(!Groth16::<E>::verify_link(
+        &vk, &proof, &ck, &other, &bad_link
+    ));
+    assert!(!Groth16::<E>::verify_link(&vk, &proof, &ck, &other, &link));
+    assert!(
+        Groth16::<E>::prove_link(&vk, &proof, &ck, &commitment, &[a], v, blinding, &mut rng)
+            .is_none()
+    );
+
+    // A key that claims more committed variables than it has points is rejected.
+    let malformed = crate::lego::LegoVerifyingKey {
+        commit_witness_count: vk.pvk.vk.gamma_abc_g1.len() + 1,
+        ..vk.clone()
+    };
+    assert!(malformed.commitment_key().is_none());
+    assert!(!Groth16::<E>::verify_link(
+        &malformed,
+        &proof,
+        &ck,
+        &commitment,
+        &link
+    ));
+    assert!(!Groth16::<E>::verify_lego_commitment(
+        &malformed,
+        &proof,
+        &[a, b],
+        v
+    ));
+}
+
+fn test_zeroize_witness<E>()
+where
+    E: Pairing,
+{
+    use zeroize::Zeroize;
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    let (cs, h) = Groth16::<E>::synthesize_and_reduce(circuit(), &()).unwrap();
+    let inner = ConstraintSystemRef::clone(&cs);
+    assert_eq!(inner.borrow().unwrap().witness_assignment, vec![a, b]);
+    drop((cs, h));
+    assert!(inner.borrow().unwrap().witness_assignment.is_empty());
+
+    let mut assignment = Groth16::<E>::synthesize_assignment(circuit()).unwrap();
+    assignment.zeroize();
+    assert!(assignment.aux_assignment.is_empty() && assignment.h.is_empty());
+}
+
+#[cfg(feature = "std")]
+fn test_distributed_prover<E>()
+where
+    E: Pairing,
+{
+    use crate::distributed::{serve_msm_worker, DistributedBackend};
+    use std::{
+        net::{Shutdown, TcpListener, TcpStream},
+        thread,
+    };
+
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let a = E::ScalarField::rand(&mut rng);
+    let b = E::ScalarField::rand(&mut rng);
+    let circuit = || MySillyCircuit {
+        a: Some(a),
+        b: Some(b),
+    };
+
+    // Workers normally run on machines of their own. Here they get their own thread
+    // pool, so that they do not wait for the blocked threads of the coordinator.
+    let serve_worker = |pk: &ProvingKey<E>, connection: TcpStream| {
+        #[cfg(feature = "parallel")]
+        return rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| serve_msm_worker(pk, connection));
+        #[cfg(not(feature = "parallel"))]
+        serve_msm_worker(pk, connection)
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::scope(|scope| {
+        let workers = (0..2)
+            .map(|_| {
+                let stream = TcpStream::connect(address).unwrap();
+                let (connection, _) = listener.accept().unwrap();
+                let pk = &pk;
+                scope.spawn(move || serve_worker(pk, connection));
+                stream
+            })
+            .collect::<Vec<_>>();
+
+        let backend = DistributedBackend::new(&pk, workers);
+        let proof =
+            Groth16::<E>::create_random_distributed_proof(circuit(), &backend, &mut rng).unwrap();
+        assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+        assert_eq!(backend.failures(), 0);
+        assert_eq!(backend.live_workers(), 2);
+    });
+
+    // A worker with another proving key answers with wrong partial sums.
+    let (other_pk, _) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    thread::scope(|scope| {
+        let stream = TcpStream::connect(address).unwrap();
+        let (connection, _) = listener.accept().unwrap();
+        let other_pk = &other_pk;
+        scope.spawn(move || serve_worker(other_pk, connection));
+        let backend = DistributedBackend::new(&pk, vec![stream]);
+        assert!(matches!(
+            Groth16::<E>::create_random_distributed_proof(circuit(), &backend, &mut rng),
+            Err(ProverError::InvalidProof)
+        ));
+    });
+
+    // Shards of a worker that has gone away are computed locally, and the worker is not
+    // asked again.
+    let stream = TcpStream::connect(address).unwrap();
+    drop(listener.accept().unwrap());
+    stream.shutdown(Shutdown::Both).unwrap();
+    let backend = DistributedBackend::new(&pk, vec![stream]);
+    let proof =
+        Groth16::<E>::create_random_distributed_proof(circuit(), &backend, &mut rng).unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &[a * b]).unwrap());
+    assert!(backend.failures() > 0);
+    assert_eq!(backend.live_workers(), 0);
+}
+
+fn test_batch_verification<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let mut batch = (0..4)
+        .map(|_| {
+            let a = E::ScalarField::rand(&mut rng);
+            let b = E::ScalarField::rand(&mut rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            (
+                Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap(),
+                vec![a * b],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert!(Groth16::<E>::verify_proofs_batch(&pvk, &batch).unwrap());
+    assert!(Groth16::<E>::verify_proofs_batch::<Vec<_>>(&pvk, &[]).unwrap());
+
+    // Swapping the inputs of two proofs invalidates both.
+    let inputs = batch[0].1.clone();
+    batch[0].1 = batch[1].1.clone();
+    batch[1].1 = inputs;
+    assert!(!Groth16::<E>::verify_proofs_batch(&pvk, &batch).unwrap());
+    // A single proof for the wrong statement invalidates the batch.
+    batch.swap(0, 1);
+    batch[0].0 = batch[1].0.clone();
+    assert!(!Groth16::<E>::verify_proofs_batch(&pvk, &batch).unwrap());
+
+    batch[0].1.push(E::ScalarField::one());
+    assert!(Groth16::<E>::verify_proofs_batch(&pvk, &batch).is_err());
+}
+
+fn test_batch_verification_detailed<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+
+    let mut batch = (0..7)
+        .map(|_| {
+            let a = E::ScalarField::rand(&mut rng);
+            let b = E::ScalarField::rand(&mut rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            (
+                Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap(),
+                vec![a * b],
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        Groth16::<E>::verify_proofs_batch_detailed(&pvk, &batch),
+        vec![Ok(()); 7]
+    );
+    assert!(Groth16::<E>::verify_proofs_batch_detailed::<Vec<_>>(&pvk, &[]).is_empty());
+
+    let mut expected = vec![Ok(()); 7];
+    batch[1].1[0] += E::ScalarField::one();
+    expected[1] = Err(VerificationError::PairingCheckFailed);
+    batch[4].0 = batch[5].0.clone();
+    expected[4] = Err(VerificationError::PairingCheckFailed);
+    batch[5].1.push(E::ScalarField::one());
+    expected[5] = Err(VerificationError::WrongNumberOfPublicInputs {
+        expected: 1,
+        found: 2,
+    });
+    batch[6].0.c = E::G1Affine::zero();
+    expected[6] = Err(VerificationError::MalformedProofPoint);
+    assert_eq!(
+        Groth16::<E>::verify_proofs_batch_detailed(&pvk, &batch),
+        expected
+    );
+}
+
+fn test_proof_aggregation<E>()
+where
+    E: Pairing,
+{
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+
+    let (pk, vk) = Groth16::<E>::setup(MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&vk);
+    let srs = Groth16::<E>::generate_aggregation_srs(8, &mut rng).unwrap();
+    let avk = srs.verifier_key();
+
+    let mut proofs = (0..8)
+        .map(|_| {
+            let a = E::ScalarField::rand(&mut rng);
+            let b = E::ScalarField::rand(&mut rng);
+            let circuit = MySillyCircuit {
+                a: Some(a),
+                b: Some(b),
+            };
+            (
+                Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap(),
+                vec![a * b],
+            )
+        })
+        .collect::<Vec<_>>();
+    let inputs = |proofs: &[(Proof<E>, Vec<E::ScalarField>)]| {
+        proofs.iter().map(|(_, x)| x.clone()).collect::<Vec<_>>()
+    };
+
+    for n in [2, 8] {
+        let aggregate = Groth16::<E>::aggregate_proofs(&srs, &proofs[..n]).unwrap();
+        assert_eq!(aggregate.rounds.len(), n.trailing_zeros() as usize);
+        let public_inputs = inputs(&proofs[..n]);
+        assert!(
+            Groth16::<E>::verify_aggregate_proof(&avk, &pvk, &public_inputs, &aggregate).unwrap()
+        );
+
+        let mut bytes = Vec::new();
+        aggregate.serialize_compressed(&mut bytes).unwrap();
+        let deserialized = AggregateProof::<E>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(deserialized, aggregate);
+
+        let mut tampered = aggregate.clone();
+        tampered.opening_w.1 = tampered.final_w.1;
+        assert!(
+            !Groth16::<E>::verify_aggregate_proof(&avk, &pvk, &public_inputs, &tampered).unwrap()
+        );
+
+        let mut wrong_inputs = public_inputs.clone();
+        wrong_inputs.swap(0, 1);
+        assert!(
+            !Groth16::<E>::verify_aggregate_proof(&avk, &pvk, &wrong_inputs, &aggregate).unwrap()
+        );
+    }
+    assert!(Groth16::<E>::aggregate_proofs(&srs, &proofs[..3]).is_err());
+    assert!(Groth16::<E>::aggregate_proofs(&srs, &proofs[..1]).is_err());
+
+    // An aggregate that includes an invalid proof does not verify.
+    proofs[3].1[0] += E::ScalarField::one();
+    let aggregate = Groth16::<E>::aggregate_proofs(&srs, &proofs[..4]).unwrap();
+    assert!(
+        !Groth16::<E>::verify_aggregate_proof(&avk, &pvk, &inputs(&proofs[..4]), &aggregate)
+            .unwrap()
+    );
+    let aggregate = Groth16::<E>::aggregate_proofs(&srs, &proofs[4..]).unwrap();
+    assert!(
+        !Groth16::<E>::verify_aggregate_proof(&avk, &pvk, &inputs(&proofs[..4]), &aggregate)
+            .unwrap()
+    );
+}
+
+mod bls12_377 {
+    use super::{
+        test_msm_backend, test_prefilter, test_prove_and_verify, test_rerandomize,
+        test_serialization,
+    };
+    use ark_bls12_377::Bls12_377;
+
+    #[test]
+    fn prove_and_verify() {
+        test_prove_and_verify::<Bls12_377>(100);
+    }
+
+    #[test]
+    fn rerandomize() {
+        test_rerandomize::<Bls12_377>();
+    }
+
+    #[test]
+    fn serialization() {
+        test_serialization::<Bls12_377>();
+    }
+
+    #[test]
+    fn prepared_statement() {
+        super::test_prepared_statement::<Bls12_377>();
+    }
+
+    #[test]
+    fn prepared_inputs() {
+        super::test_prepared_inputs::<Bls12_377>();
+    }
+
+    #[test]
+    fn prepared_proof() {
+        super::test_prepared_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn public_input_accumulator() {
+        super::test_public_input_accumulator::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_proof_detailed() {
+        super::test_verify_proof_detailed::<Bls12_377>();
+    }
+
+    #[test]
+    fn subgroup_checks() {
+        super::test_subgroup_checks::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_proof_constant_time() {
+        super::test_verify_proof_constant_time::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_snarkjs() {
+        super::test_verify_snarkjs::<Bls12_377, _, _>();
+    }
+
+    #[test]
+    fn solana_encoding() {
+        super::test_solana_encoding::<Bls12_377, _, _>();
+    }
+
+    #[test]
+    fn verify_zokrates() {
+        super::test_verify_zokrates::<Bls12_377, _, _>();
+    }
+
+    #[test]
+    fn pvk_cache() {
+        super::test_pvk_cache::<Bls12_377>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn labeled_inputs() {
+        super::test_labeled_inputs::<Bls12_377>();
+    }
+
+    #[test]
+    fn batch_verification_detailed() {
+        super::test_batch_verification_detailed::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_with_input_iter() {
+        super::test_verify_with_input_iter::<Bls12_377>();
+    }
+
+    #[test]
+    fn ceremony() {
+        super::test_ceremony::<Bls12_377>();
+    }
+
+    #[test]
+    fn powers_of_tau() {
+        super::test_powers_of_tau::<Bls12_377>();
+    }
+
+    #[test]
+    fn rerandomize_delta() {
+        super::test_rerandomize_delta::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_parameters() {
+        super::test_verify_parameters::<Bls12_377>();
+    }
+
+    #[test]
+    fn deterministic_parameters() {
+        super::test_deterministic_parameters::<Bls12_377>(
+            "7cf8c355566ee3c6dea5771e01d9e9ed1bc2f2596647173bc5c1fcd8e28c3fa8",
+        );
+    }
+
+    #[test]
+    fn ceremony_beacon() {
+        super::test_ceremony_beacon::<Bls12_377>();
+    }
+
+    #[test]
+    fn check_consistency() {
+        super::test_check_consistency::<Bls12_377>();
+    }
+
+    #[test]
+    fn secret_setup_randomness() {
+        super::test_secret_setup_randomness::<Bls12_377>();
+    }
+
+    #[test]
+    fn entropy_source() {
+        super::test_entropy_source::<Bls12_377>();
+    }
+
+    #[test]
+    fn read_ptau() {
+        super::test_read_ptau::<Bls12_377, _, _>();
+    }
+
+    #[test]
+    fn ceremony_transcript() {
+        super::test_ceremony_transcript::<Bls12_377>();
+    }
+
+    #[test]
+    fn proof_array() {
+        super::test_proof_array::<Bls12_377, 192>();
+    }
+
+    #[test]
+    fn precomputed_verifying_key() {
+        super::test_precomputed_verifying_key::<Bls12_377>();
+    }
+
+    #[test]
+    fn byte_conversions() {
+        super::test_byte_conversions::<Bls12_377>();
+    }
+
+    #[test]
+    fn key_metadata() {
+        super::test_key_metadata::<Bls12_377>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn proof_ordering() {
+        super::test_proof_ordering::<Bls12_377>();
+    }
+
+    #[test]
+    fn proving_key_ref() {
+        super::test_proving_key_ref::<Bls12_377>();
+    }
+
+    #[test]
+    fn formatting() {
+        super::test_formatting::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_runtime() {
+        super::test_verify_runtime::<Bls12_377, 192, 32>();
+    }
+
+    #[test]
+    fn se_proof() {
+        super::test_se_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn gm17() {
+        super::test_gm17::<Bls12_377>();
+    }
+
+    #[test]
+    fn designated_proof() {
+        super::test_designated_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn delegated_proof() {
+        super::test_delegated_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn context_proof() {
+        super::test_context_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn threshold_proof() {
+        super::test_threshold_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn batch_affine_backend() {
+        super::test_batch_affine_backend::<Bls12_377>();
+    }
+
+    #[test]
+    fn preprocessed_proving_key() {
+        super::test_preprocessed_proving_key::<Bls12_377>();
+    }
+
+    #[cfg(feature = "r1cs")]
+    #[test]
+    fn input_hash() {
+        super::test_input_hash::<Bls12_377>();
+    }
+
+    #[test]
+    fn verify_from_reader() {
+        super::test_verify_from_reader::<Bls12_377>();
+    }
+
+    #[test]
+    fn prefilter() {
+        test_prefilter::<Bls12_377>();
+    }
+
+    #[test]
+    fn prover_config() {
+        super::test_prover_config::<Bls12_377>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn proving_report() {
+        super::test_proving_report::<Bls12_377>();
+    }
+
+    #[test]
+    fn lego_proof() {
+        super::test_lego_proof::<Bls12_377>();
+    }
+
+    #[test]
+    fn lego_link() {
+        super::test_lego_link::<Bls12_377>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn distributed_prover() {
+        super::test_distributed_prover::<Bls12_377>();
+    }
+
+    #[test]
+    fn batch_verification() {
+        super::test_batch_verification::<Bls12_377>();
+    }
+
+    #[test]
+    fn proof_aggregation() {
+        super::test_proof_aggregation::<Bls12_377>();
+    }
+
+    #[test]
+    fn msm_backend() {
+        test_msm_backend::<Bls12_377>();
+    }
+
+    #[test]
+    fn key_size() {
+        super::test_key_size::<Bls12_377>();
+    }
+
+    #[test]
+    fn blob_encoding() {
+        super::test_blob_encoding::<Bls12_377>();
+    }
+
+    #[test]
+    fn deterministic_proof() {
+        super::test_deterministic_proof::<Bls12_377>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prove_async() {
+        super::test_prove_async::<Bls12_377>();
+    }
+
+    #[test]
+    fn prove_from_wtns() {
+        super::test_prove_from_wtns::<Bls12_377>();
+    }
+
+    #[test]
+    fn circom_r1cs() {
+        super::test_circom_r1cs::<Bls12_377>();
+    }
+
+    #[test]
+    fn zeroize_witness() {
+        super::test_zeroize_witness::<Bls12_377>();
+    }
+
+    #[test]
+    fn two_phase_proving() {
+        super::test_two_phase_proving::<Bls12_377>();
+    }
+
+    #[test]
+    fn precomputation() {
+        super::test_precomputation::<Bls12_377>();
+    }
+
+    #[test]
+    fn cancellation() {
+        super::test_cancellation::<Bls12_377>();
+    }
+
+    #[test]
+    fn progress() {
+        super::test_progress::<Bls12_377>();
+    }
+
+    #[test]
+    fn setup_progress() {
+        super::test_setup_progress::<Bls12_377>();
+    }
+
+    #[test]
+    fn redundant_proving_key() {
+        super::test_redundant_proving_key::<Bls12_377>();
+    }
+
+    #[test]
+    fn assignment_report() {
+        super::test_assignment_report::<Bls12_377>();
+    }
+
+    #[test]
+    fn prove_from_reader() {
+        super::test_prove_from_reader::<Bls12_377>();
+    }
+
+    #[test]
+    fn prove_batch() {
+        super::test_prove_batch::<Bls12_377>();
+    }
+
+    #[test]
+    fn prove_batch_shape_mismatch() {
+        super::test_prove_batch_shape_mismatch::<Bls12_377>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prove_pipelined() {
+        super::test_prove_pipelined::<Bls12_377>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prove_pipelined_shape_mismatch() {
+        super::test_prove_pipelined_shape_mismatch::<Bls12_377>();
+    }
+}
+
+mod bls12_381 {
+    use ark_bls12_381::Bls12_381;
+
+    use crate::codegen::MoveCurve;
+
+    #[test]
+    fn sui_move_verifier() {
+        super::test_sui_move_verifier::<Bls12_381>(MoveCurve::Bls12381, MoveCurve::Bn254);
+    }
+
+    #[test]
+    fn aptos_move_verifier_curve_mismatch() {
+        super::test_aptos_move_verifier_curve_mismatch::<Bls12_381>();
+    }
+}
+
+mod bn254 {
+    use ark_bn254::Bn254;
+
+    use crate::codegen::MoveCurve;
+
+    #[test]
+    fn sui_move_verifier() {
+        super::test_sui_move_verifier::<Bn254>(MoveCurve::Bn254, MoveCurve::Bls12381);
+    }
+
+    #[test]
+    fn aptos_move_verifier() {
+        super::test_aptos_move_verifier::<Bn254>();
+    }
+}
+
+mod bw6_761 {
+    use super::{test_prove_and_verify, test_rerandomize};
+
+    use ark_bw6_761::BW6_761;
+
+    #[test]
+    fn prove_and_verify() {
+        test_prove_and_verify::<BW6_761>(1);
+    }
+
+    #[test]
+    fn rerandomize() {
+        test_rerandomize::<BW6_761>();
+    }
+}
+
+mod cp6_782 {
+    use super::{test_prove_and_verify, test_rerandomize};
+
+    use ark_cp6_782::CP6_782;
+
+    #[test]
+    fn prove_and_verify() {
+        test_prove_and_verify::<CP6_782>(1);
+    }
+
+    #[test]
+    fn rerandomize() {
+        test_rerandomize::<CP6_782>();
+    }
+}
+
+mod curve_macro {
+    crate::groth16_for_curve!(ark_bls12_377::Bls12_377);
+
+    #[test]
+    fn prove_and_verify() {
+        let mut rng = ark_std::test_rng();
+        let (pk, vk) = setup(super::MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+        let pk = proving_key_from_bytes(&proving_key_to_bytes(&pk)).unwrap();
+        let vk = verifying_key_from_bytes(&verifying_key_to_bytes(&vk)).unwrap();
+        let pvk = prepare_verifying_key(&vk);
+
+        let a = Fr::from(3u64);
+        let b = Fr::from(5u64);
+        let circuit = super::MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = prove(&pk, circuit, &mut rng).unwrap();
+        let proof = proof_from_bytes(&proof_to_bytes(&proof)).unwrap();
+        assert!(verify(&pvk, &proof, &[a * b]).unwrap());
+        assert!(!verify(&pvk, &proof, &[a]).unwrap());
+
+        let proof2 = rerandomize(&vk, &proof, &mut rng);
+        assert_ne!(proof, proof2);
+        assert!(verify(&pvk, &proof2, &[a * b]).unwrap());
+    }
+
+    #[test]
+    fn verify_from_bytes() {
+        use crate::VerificationError;
+        use ark_ff::{BigInteger, PrimeField};
+        use ark_std::string::String;
+
+        let mut rng = ark_std::test_rng();
+        let (pk, vk) = setup(super::MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+        let (a, b) = (Fr::from(3u64), Fr::from(5u64));
+        let circuit = super::MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+        let proof = prove(&pk, circuit, &mut rng).unwrap();
+        let vk_bytes = verifying_key_to_bytes(&vk);
+        let proof_bytes = proof_to_bytes(&proof);
+
+        assert_eq!(verify_bytes(&vk_bytes, &proof_bytes, "0xf"), Ok(true));
+        assert_eq!(verify_bytes(&vk_bytes, &proof_bytes, " 000F,"), Ok(true));
+        assert_eq!(verify_bytes(&vk_bytes, &proof_bytes, "0x10"), Ok(false));
+        assert_eq!(
+            verify_bytes(&vk_bytes, &proof_bytes, "0xf, 0x1"),
+            Err(VerificationError::WrongNumberOfPublicInputs {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            verify_bytes(&vk_bytes, &proof_bytes, "0xg"),
+            Err(VerificationError::InvalidEncoding)
+        );
+        let modulus: String = Fr::MODULUS
+            .to_bytes_be()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(
+            verify_bytes(&vk_bytes, &proof_bytes, &modulus),
+            Err(VerificationError::InputNotInField { index: 0 })
+        );
+        assert_eq!(
+            verify_bytes(&vk_bytes, &proof_bytes[1..], "0xf"),
+            Err(VerificationError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn known_answer_proof() {
+        use ark_ec::CurveGroup;
+        use ark_ff::Zero;
+
+        let mut rng = ark_std::test_rng();
+        let (pk, vk) = setup(super::MySillyCircuit { a: None, b: None }, &mut rng).unwrap();
+        let pvk = prepare_verifying_key(&vk);
+        let (a, b) = (Fr::from(3u64), Fr::from(5u64));
+        let circuit = || super::MySillyCircuit {
+            a: Some(a),
+            b: Some(b),
+        };
+
+        let (r, s) = (Fr::from(7u64), Fr::from(11u64));
+        let proof = prove_with_randomness(&pk, circuit(), r, s).unwrap();
+        assert_eq!(proof, prove_with_randomness(&pk, circuit(), r, s).unwrap());
+        assert!(verify(&pvk, &proof, &[a * b]).unwrap());
+
+        // `A` and `B` are shifted by `r * delta` and `s * delta` respectively.
+        let no_zk = prove_with_randomness(&pk, circuit(), Fr::zero(), Fr::zero()).unwrap();
+        assert_eq!(proof.a, (no_zk.a + pk.delta_g1 * r).into_affine());
+        assert_eq!(proof.b, (no_zk.b + vk.delta_g2 * s).into_affine());
+    }
+}
+
+#[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
+mod ffi {
+    use crate::ffi::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::{BigInteger, One, PrimeField};
+    use ark_std::{ptr, slice, vec, vec::Vec};
+
+    unsafe fn take(buffer: Groth16Buffer) -> Vec<u8> {
+        let bytes = slice::from_raw_parts(buffer.data, buffer.len).to_vec();
+        groth16_buffer_free(buffer);
+        bytes
+    }
+
+    #[test]
+    fn setup_prove_and_verify() {
+        let one = Fr::one();
+        // Wires [1, c, a, b] with c = a * b.
+        let r1cs = super::write_r1cs(4, 1, &[[vec![(2, one)], vec![(3, one)], vec![(1, one)]]]);
+        let (a, b) = (Fr::from(3u64), Fr::from(5u64));
+        let wtns = super::write_wtns(&[one, a * b, a, b]);
+        let mut inputs = (a * b).into_bigint().to_bytes_le();
+        let seed = [7u8; 32];
+
+        unsafe {
+            let mut pk = ptr::null_mut();
+            assert_eq!(
+                groth16_setup(r1cs.as_ptr(), r1cs.len(), seed.as_ptr(), &mut pk),
+                GROTH16_OK
+            );
+            let mut vk = Groth16Buffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(groth16_verifying_key(pk, &mut vk), GROTH16_OK);
+            let vk = take(vk);
+            let mut proof = Groth16Buffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(
+                groth16_prove(pk, wtns.as_ptr(), wtns.len(), seed.as_ptr(), &mut proof),
+                GROTH16_OK
+            );
+            let proof = take(proof);
+
+            let verify = |inputs: &[u8]| {
+                groth16_verify(
+                    vk.as_ptr(),
+                    vk.len(),
+                    proof.as_ptr(),
+                    proof.len(),
+                    inputs.as_ptr(),
+                    inputs.len(),
+                )
+            };
+            assert_eq!(verify(&inputs), GROTH16_OK);
+            assert_eq!(verify(&inputs[..31]), GROTH16_INVALID_ENCODING);
+            assert_eq!(verify(&[]), GROTH16_WRONG_NUMBER_OF_INPUTS);
+            inputs[0] ^= 1;
+            assert_eq!(verify(&inputs), GROTH16_INVALID_PROOF);
+
+            let bad_wtns = super::write_wtns(&[one, a, a, b]);
+            let mut proof = Groth16Buffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(
+                groth16_prove(
+                    pk,
+                    bad_wtns.as_ptr(),
+                    bad_wtns.len(),
+                    seed.as_ptr(),
+                    &mut proof
+                ),
+                GROTH16_SYNTHESIS_FAILED
+            );
+            assert_eq!(
+                groth16_prove(pk, wtns.as_ptr(), wtns.len(), ptr::null(), &mut proof),
+                GROTH16_NULL_POINTER
+            );
+            assert_eq!(
+                groth16_setup(r1cs.as_ptr(), 3, seed.as_ptr(), &mut ptr::null_mut()),
+                GROTH16_INVALID_ENCODING
+            );
+            groth16_proving_key_free(pk);
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        assert_eq!(catch_panic(|| GROTH16_OK), GROTH16_OK);
+        assert_eq!(catch_panic(|| panic!("unwound")), GROTH16_PANIC);
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm {
+    use crate::{wasm::verify, Groth16};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::vec::Vec;
+
+    #[test]
+    fn verify_from_javascript_arguments() {
+        let mut rng = ark_std::test_rng();
+        let pk = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            super::MySillyCircuit { a: None, b: None },
+            &mut rng,
+        )
+        .unwrap();
+        let circuit = super::MySillyCircuit {
+            a: Some(Fr::from(3u64)),
+            b: Some(Fr::from(5u64)),
+        };
+        let proof =
+            Groth16::<Bls12_381>::create_random_proof_with_reduction(circuit, &pk, &mut rng)
+                .unwrap();
+        let mut vk_bytes = Vec::new();
+        pk.vk.serialize_compressed(&mut vk_bytes).unwrap();
+        let mut proof_bytes = Vec::new();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+
+        // The error path builds a JavaScript `Error`, which only exists on wasm32.
+        assert!(verify(&vk_bytes, &proof_bytes, "0xf").unwrap());
+        assert!(!verify(&vk_bytes, &proof_bytes, "0x10").unwrap());
     }
 }
@@ -0,0 +1,384 @@
+//! Known-answer test vectors, for checking that other implementations, and other versions
+//! of this one, agree with this crate on keys, proofs, and verification outcomes.
+//!
+//! Every vector is for the [`MultiplicationCircuit`]: its proving and verifying keys, the
+//! witness `a, b`, the prover randomness `r, s`, the proof, the public input, and the
+//! outcome of [`Groth16::verify_bytes`]. Keys and proofs are hexadecimal encodings of the
+//! compressed `ark-serialize` format; the proof of a valid vector is exactly the one that
+//! [`Groth16::create_proof_with_reduction`] computes from the key, the witness, `r`, and `s`
+//! with the default [`LibsnarkReduction`](crate::r1cs_to_qap::LibsnarkReduction).
+//!
+//! Vectors are provided for BLS12-381 and BN254, with keys generated independently for
+//! each curve.
+//!
+//! [`Groth16::create_proof_with_reduction`]: crate::Groth16
+use crate::{Groth16, VerificationError};
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use ark_std::vec::Vec;
+
+/// The circuit of the test vectors: it allocates the witnesses `a` and `b`, then the public
+/// input `c`, and enforces the single constraint `a * b = c`.
+#[derive(Clone, Copy, Debug)]
+pub struct MultiplicationCircuit<F: Field> {
+    /// The first factor, or `None` for setup.
+    pub a: Option<F>,
+    /// The second factor, or `None` for setup.
+    pub b: Option<F>,
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for MultiplicationCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let value = |v: Option<F>| v.ok_or(SynthesisError::AssignmentMissing);
+        let a = cs.new_witness_variable(|| value(self.a))?;
+        let b = cs.new_witness_variable(|| value(self.b))?;
+        let c = cs.new_input_variable(|| Ok(value(self.a)? * value(self.b)?))?;
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + c)
+    }
+}
+
+/// A known-answer test vector for the [`MultiplicationCircuit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    /// A short description of what the vector checks.
+    pub name: &'static str,
+    /// The compressed proving key, in hexadecimal.
+    pub proving_key: &'static str,
+    /// The compressed verifying key, in hexadecimal.
+    pub verifying_key: &'static str,
+    /// The witness `a` of the proof.
+    pub a: u64,
+    /// The witness `b` of the proof.
+    pub b: u64,
+    /// The prover randomness `r`.
+    pub r: u64,
+    /// The prover randomness `s`.
+    pub s: u64,
+    /// The compressed proof, in hexadecimal.
+    pub proof: &'static str,
+    /// The public input, in the format of [`Groth16::verify_bytes`].
+    pub public_inputs: &'static str,
+    /// The result of verifying `proof` against `verifying_key` and `public_inputs`.
+    pub expected: Result<bool, VerificationError>,
+}
+
+impl TestVector {
+    /// Verifies the proof of `self` with [`Groth16::verify_bytes`]; the result should be
+    /// `self.expected`.
+    pub fn verify<E: Pairing>(&self) -> Result<bool, VerificationError> {
+        let vk = decode_hex(self.verifying_key).ok_or(VerificationError::InvalidEncoding)?;
+        let proof = decode_hex(self.proof).ok_or(VerificationError::InvalidEncoding)?;
+        Groth16::<E>::verify_bytes(&vk, &proof, self.public_inputs)
+    }
+}
+
+/// The compressed proving key of the BLS12-381 vectors, generated from a seeded RNG.
+const BLS12_381_PROVING_KEY: &str = concat!(
+    "8765ffb054b696bd868d6d0a862ed7eb89e8a90310f75f94464628553bd01ceae70d165a0d4eafc18f8ad0d1",
+    "d1516e78b08ad3e929ef6c071f3f0a763dfef61ddea4b8d7ac43c82f0f308644990684ca1fcd2a5ef6f5ee06",
+    "e4ba3e8ed0b17b450bd3d52ece8fd8bdea27ab33988fc90bcb8c282aacf37f38cb664b50c1c117b8b5facb6b",
+    "11e5ab399e117243197d9b91a11a41ac8076b4c80d5bfed3091afb0f23872714813cae79d6de174ac2855757",
+    "f5058ed896dfaf4ff09981868ddcf36207bf0824402de371ba2a10309de40e39343ae15f127fecfb2dd287b0",
+    "2f5faea66685bdc3a9a33f9e20ac0121cb363a058e5b42c6d65b376cb2560b4494938aeaec35f1c05b1e0adc",
+    "481ee136c0190896d2b852eef90a212d06f0cfae4931f10614bc70a6d7892cbeb46af2b7a22a416bf9eadd71",
+    "c409f36ca3d2f831c1558579282d3d59632f62b92a74e3f1f9c80f000200000000000000903912232c92426a",
+    "6de1010ba45f1025ea274072e0c0ff03dce4f0188c29bfcf03ec0ea46442a8a85a9a1da8e93484b180057363",
+    "5ff9a5176d989c7d0a4ba65ee2ec3dd7fdfefdc2b5671fda6d00490fbd2d736b39190fa31272a95372d054eb",
+    "b6d7d42c638385e953ec37878e08f9e8153d6372395a105b687ef986e6f84d1c60b64cb95e8f546c6b669cd6",
+    "bc6b32cc92fd9757686c22c91301cc77889d6e689b6c35ad5904f885796c7126c1ce6029f7f6c75f736c6ec6",
+    "b144af35f51fff220400000000000000a106f2ac09cf0514ac54bb233d7b02918eb9a8b1083f4149f746df60",
+    "089a85d755e013edb48bdd22bc32fba35ea77bf488284813dc7024ecc78e28f2a641394fde88e99fb59ad360",
+    "d57eba212f83e003da4f194785c4dfeebed39c06c0f00a598438fd38df102c8ded375ad64f052c31b71577f8",
+    "892abe61c87f0e7c2f50bc4e054f203e3fcd96d7feb0183d604d669fc0000000000000000000000000000000",
+    "00000000000000000000000000000000000000000000000000000000000000000400000000000000c0000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "c000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "00000000c0000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "00000000000000008438fd38df102c8ded375ad64f052c31b71577f8892abe61c87f0e7c2f50bc4e054f203e",
+    "3fcd96d7feb0183d604d669f0400000000000000c00000000000000000000000000000000000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "00000000000000000000000000000000000000000000000000000000c0000000000000000000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "000000000000000000000000000000000000000000000000000000000000000000000000c000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "a672c4130da965f5b77d811cc66f26010973c2b66a1226e4ef911d284a3e649f80824a27273c3b9f76199014",
+    "3e9ffec401716f6ce75a5f0745924b9c4f57a15a52c82c1ac1d4624fb5be52f9de838ebfa765d204114c2200",
+    "8b057195476d107a0300000000000000822f29a7015f0fa045132bf5669c46a6c94c8eae876dfe9e34897ab9",
+    "557492d193dda28aad4edc1b567a0e1e32fb4e8c92b3d11fd224875583f19dad8231257d7a326613342678be",
+    "99c08c26967c0a0dc5502a9ad0f95819273fed939a719838964fb63045dc06bb3062697be74dfa904a93f6a2",
+    "8eb4a97cf9fa5670547d1f768497b0678483e0d9d4a9f95a93b0c5550200000000000000a7a925f0309444d2",
+    "e2982e12a3f01496c43cbc6103ab27abc2a98c8120ab21562cba0a54b313202b6749fbb9209e1f8484aa5a7f",
+    "0cf458942b2843f4593e93c17786503b30858deec570337c9f00953279926a84cff70cc60eed2d176f1ce832",
+);
+
+/// The compressed verifying key of the BLS12-381 vectors, which is a prefix of the
+/// proving key.
+const BLS12_381_VERIFYING_KEY: &str = concat!(
+    "8765ffb054b696bd868d6d0a862ed7eb89e8a90310f75f94464628553bd01ceae70d165a0d4eafc18f8ad0d1",
+    "d1516e78b08ad3e929ef6c071f3f0a763dfef61ddea4b8d7ac43c82f0f308644990684ca1fcd2a5ef6f5ee06",
+    "e4ba3e8ed0b17b450bd3d52ece8fd8bdea27ab33988fc90bcb8c282aacf37f38cb664b50c1c117b8b5facb6b",
+    "11e5ab399e117243197d9b91a11a41ac8076b4c80d5bfed3091afb0f23872714813cae79d6de174ac2855757",
+    "f5058ed896dfaf4ff09981868ddcf36207bf0824402de371ba2a10309de40e39343ae15f127fecfb2dd287b0",
+    "2f5faea66685bdc3a9a33f9e20ac0121cb363a058e5b42c6d65b376cb2560b4494938aeaec35f1c05b1e0adc",
+    "481ee136c0190896d2b852eef90a212d06f0cfae4931f10614bc70a6d7892cbeb46af2b7a22a416bf9eadd71",
+    "c409f36ca3d2f831c1558579282d3d59632f62b92a74e3f1f9c80f000200000000000000903912232c92426a",
+    "6de1010ba45f1025ea274072e0c0ff03dce4f0188c29bfcf03ec0ea46442a8a85a9a1da8e93484b180057363",
+    "5ff9a5176d989c7d0a4ba65ee2ec3dd7fdfefdc2b5671fda6d00490fbd2d736b39190fa31272a95372d054eb",
+);
+
+/// The proof of `a = 3, b = 5` with `r = 0x12345678, s = 0x9abcdef0`.
+const BLS12_381_PROOF_15: &str = concat!(
+    "85f897dd1acbd7741f8dfc812aeb834258f48df2185343613c511e05966a7d7974171019c444ede61ba2e664",
+    "2daa4206adf12accb3ef424bb52e94f5b129e616a7a2a21ff126f7048a969cc1a4e72332205ef6a0cd8737b9",
+    "e7683fee4582f821130a2d007c9a6811cacdd6b0e4ed9d4708bf05fe657206d471cf7a6e393217d8201b13ab",
+    "524be2adef910d970760dba8805c940b543af5381d97ff882c79e0b310e859c03d9372738f46daf4e7fbc670",
+    "e565b40b484bea1d6565b315397742cb",
+);
+
+/// The proof of `a = 7, b = 11` with `r = 1, s = 2`.
+const BLS12_381_PROOF_77: &str = concat!(
+    "99f108fb8d554f0376c1158393ab33fa7d4f1f7389fca1df84308325430d57ebd1b6e3c5381c68bb03b9721f",
+    "9e375d9786c5995d35c7ba7954e8a3805db6682ed422634446be0365a94d460b74255de29aee60a5423714c6",
+    "c1a6fc5f358e75fa0343c2e9a87f38b2cd0d5bf3316d8d2c133b7ab637c0da577c07477e9d62f71f9a19dc37",
+    "325263c349a7e0457e643243a797ac2e26ecf9b726f5dcb25a289dee72a2b40759973481684bcaabc080befb",
+    "d8653ebcd9227dfe7914a7eccfc999c6",
+);
+
+/// The compressed proving key of the BN254 vectors, generated from a seeded RNG.
+const BN254_PROVING_KEY: &str = concat!(
+    "3976f1ba650dd8f291ff392c6a5d6d100ed90a811cebee0968a124d0a233b09a60315333f96840849d0f3a15",
+    "8f7f2bf887f9480c4bbcea83d124fcddca783e1d30fcf86150c612f645b8761e222d5f2c0ad55b9d94375518",
+    "5247fb5a1e16d4219edaae40e7fde83c9b6bd99c6a7b9f6dfc532d241223f9701740d2b04a348c13b171b783",
+    "546cd73a498851883dd619005582335d6ecb2983dee9fceeb92ce79b85b24c0448fee487793cd142094273af",
+    "fb989ee5d7805f8a4fdc69114d703218b472c3068489d920e88f34138622250b39232c0a169c8efc4a3612f8",
+    "b2c625a20200000000000000ce78a13e21174738d7a2e0d5e95196c913d33c02ee6b29af985129ff7b7c7118",
+    "aa9dce63fa2db4cf8e859522e0c1736799d12a26537e0ec82076013b24c94802b51aca43dc349b8f39a986ce",
+    "c18db917d2b63cf1b63a346c184ccf2a6cfe5d993eb6fb041a4ba664773ab18add9862db801b98803c4f1841",
+    "95023cc5a83fc39304000000000000005eb144ea1eb5f98f6d0bf127fafd4746bc0d9aeb95c4ec0c4c255778",
+    "67d61cae4f52db1dcbf6e2e4862378a1940cb38a2351675197c2d9725af45afda4ebaca5a5f482c7d7b4fd38",
+    "34e8cb54f13203031e1e770e8570975de9a09f91c2b1d9a90000000000000000000000000000000000000000",
+    "0000000000000000000000400400000000000000000000000000000000000000000000000000000000000000",
+    "0000000000000040000000000000000000000000000000000000000000000000000000000000004000000000",
+    "00000000000000000000000000000000000000000000000000000040a5f482c7d7b4fd3834e8cb54f1320303",
+    "1e1e770e8570975de9a09f91c2b1d9a904000000000000000000000000000000000000000000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+    "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "0000000000000000000000000000000000000040000000000000000000000000000000000000000000000000",
+    "00000000000000000000000000000000000000000000000000000000000000000000000000000040a15b6d97",
+    "7b33c426a53607290157c7398cc4867c41ede71e238dfa5a260e432dacabe852622f6859d11ab7695398ebe2",
+    "9fea9bd0f1dc5a4c8826c993501a7a120300000000000000648fa0c96703322457b1cf3d0247be22c88f087b",
+    "a2f26d26c7da22baf5f8728165ad68e17d097104f770c9e1a6184ba061cba230d45e4376fb5d7e2c357a81a0",
+    "e248557928e8431209a0005fbc50d734f5533db0b69d91edd9e3232d69b178240200000000000000881f0c08",
+    "990575ebd9f02feb508a40695b1f4526b24d34a2860aec55da587f14e84f0fc742308d83964cfb9b5994b759",
+    "ca8b29226db151fc47b3de7547e3df2a",
+);
+
+/// The compressed verifying key of the BN254 vectors, which is a prefix of the proving
+/// key.
+const BN254_VERIFYING_KEY: &str = concat!(
+    "3976f1ba650dd8f291ff392c6a5d6d100ed90a811cebee0968a124d0a233b09a60315333f96840849d0f3a15",
+    "8f7f2bf887f9480c4bbcea83d124fcddca783e1d30fcf86150c612f645b8761e222d5f2c0ad55b9d94375518",
+    "5247fb5a1e16d4219edaae40e7fde83c9b6bd99c6a7b9f6dfc532d241223f9701740d2b04a348c13b171b783",
+    "546cd73a498851883dd619005582335d6ecb2983dee9fceeb92ce79b85b24c0448fee487793cd142094273af",
+    "fb989ee5d7805f8a4fdc69114d703218b472c3068489d920e88f34138622250b39232c0a169c8efc4a3612f8",
+    "b2c625a20200000000000000ce78a13e21174738d7a2e0d5e95196c913d33c02ee6b29af985129ff7b7c7118",
+    "aa9dce63fa2db4cf8e859522e0c1736799d12a26537e0ec82076013b24c94802",
+);
+
+/// The proof of `a = 3, b = 5` with `r = 0x12345678, s = 0x9abcdef0`.
+const BN254_PROOF_15: &str = concat!(
+    "3f39dd8e976bdfc4b9f214b06083d9ce6516d2f9e6eeb42ead4b5797f95cb1a487edb03daf4a9be7e26aba6a",
+    "e4622eb31532d0ad72afc40b0e9240c48d8df60318dfe0dbc9a36a58920d07308bcd49d1da7518d96694c0d6",
+    "fde18574a61e681d04e1a9f3cfdaf24651d8ca687fbdc3f77adb1b17f2394613f6bfa021686ab79a",
+);
+
+/// The proof of `a = 7, b = 11` with `r = 1, s = 2`.
+const BN254_PROOF_77: &str = concat!(
+    "9fa1f6c8657301baa9ed91de7a9911dad72700532504fb9fe9a3eb8d33186c8f352251bbea960e9794ec2375",
+    "fabf46edf10642b6c9ee68cb84492fe51a953c0d647ff9fe538aca8219aeaf3c509dc7b2f0e8aa2406e075b2",
+    "5f9d452f1fafe3a6748ac37f0dd527e4bc3fe212383d5b61de2388cb5b8d1942f67473812d10c696",
+);
+
+const fn bls12_381(
+    name: &'static str,
+    (a, b, r, s, proof): (u64, u64, u64, u64, &'static str),
+    public_inputs: &'static str,
+    expected: Result<bool, VerificationError>,
+) -> TestVector {
+    TestVector {
+        name,
+        proving_key: BLS12_381_PROVING_KEY,
+        verifying_key: BLS12_381_VERIFYING_KEY,
+        a,
+        b,
+        r,
+        s,
+        proof,
+        public_inputs,
+        expected,
+    }
+}
+
+const fn bn254(
+    name: &'static str,
+    (a, b, r, s, proof): (u64, u64, u64, u64, &'static str),
+    public_inputs: &'static str,
+    expected: Result<bool, VerificationError>,
+) -> TestVector {
+    TestVector {
+        name,
+        proving_key: BN254_PROVING_KEY,
+        verifying_key: BN254_VERIFYING_KEY,
+        a,
+        b,
+        r,
+        s,
+        proof,
+        public_inputs,
+        expected,
+    }
+}
+
+const BLS12_381_15: (u64, u64, u64, u64, &str) =
+    (3, 5, 0x1234_5678, 0x9abc_def0, BLS12_381_PROOF_15);
+const BLS12_381_77: (u64, u64, u64, u64, &str) = (7, 11, 1, 2, BLS12_381_PROOF_77);
+const BN254_15: (u64, u64, u64, u64, &str) = (3, 5, 0x1234_5678, 0x9abc_def0, BN254_PROOF_15);
+const BN254_77: (u64, u64, u64, u64, &str) = (7, 11, 1, 2, BN254_PROOF_77);
+
+/// The test vectors for BLS12-381.
+pub const BLS12_381: &[TestVector] = &[
+    bls12_381("valid proof", BLS12_381_15, "0xf", Ok(true)),
+    bls12_381(
+        "valid proof with small randomness",
+        BLS12_381_77,
+        "0x4d",
+        Ok(true),
+    ),
+    bls12_381("wrong public input", BLS12_381_15, "0x10", Ok(false)),
+    bls12_381("proof of another statement", BLS12_381_77, "0xf", Ok(false)),
+    bls12_381(
+        "public input not in the scalar field",
+        BLS12_381_15,
+        "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        Err(VerificationError::InputNotInField { index: 0 }),
+    ),
+    bls12_381(
+        "too many public inputs",
+        BLS12_381_15,
+        "0xf, 0x1",
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2,
+        }),
+    ),
+];
+
+/// The test vectors for BN254.
+pub const BN254: &[TestVector] = &[
+    bn254("valid proof", BN254_15, "0xf", Ok(true)),
+    bn254(
+        "valid proof with small randomness",
+        BN254_77,
+        "0x4d",
+        Ok(true),
+    ),
+    bn254("wrong public input", BN254_15, "0x10", Ok(false)),
+    bn254("proof of another statement", BN254_77, "0xf", Ok(false)),
+    bn254(
+        "public input not in the scalar field",
+        BN254_15,
+        "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+        Err(VerificationError::InputNotInField { index: 0 }),
+    ),
+    bn254(
+        "too many public inputs",
+        BN254_15,
+        "0xf, 0x1",
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 2,
+        }),
+    ),
+];
+
+/// Decodes a string of hexadecimal digit pairs.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+
+    fn check_outcomes<E: Pairing>(vectors: &[TestVector]) {
+        for vector in vectors {
+            assert_eq!(vector.verify::<E>(), vector.expected, "{}", vector.name);
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    fn check_proofs<E: Pairing>(vectors: &[TestVector]) {
+        use crate::{ProvingKey, VerifyingKey};
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+        for vector in vectors {
+            let pk = decode_hex(vector.proving_key).unwrap();
+            let pk = ProvingKey::<E>::deserialize_compressed(&*pk).unwrap();
+            let vk = decode_hex(vector.verifying_key).unwrap();
+            assert_eq!(
+                VerifyingKey::<E>::deserialize_compressed(&*vk).unwrap(),
+                pk.vk
+            );
+
+            let circuit = MultiplicationCircuit {
+                a: Some(E::ScalarField::from(vector.a)),
+                b: Some(E::ScalarField::from(vector.b)),
+            };
+            let proof = Groth16::<E>::create_proof_with_reduction(
+                circuit,
+                &pk,
+                E::ScalarField::from(vector.r),
+                E::ScalarField::from(vector.s),
+            )
+            .unwrap();
+            let mut bytes = Vec::new();
+            proof.serialize_compressed(&mut bytes).unwrap();
+            assert_eq!(Some(bytes), decode_hex(vector.proof), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn bls12_381_outcomes() {
+        check_outcomes::<Bls12_381>(BLS12_381);
+    }
+
+    #[cfg(feature = "prover")]
+    #[test]
+    fn bls12_381_proofs() {
+        check_proofs::<Bls12_381>(BLS12_381);
+    }
+
+    #[test]
+    fn bn254_outcomes() {
+        check_outcomes::<Bn254>(BN254);
+    }
+
+    #[cfg(feature = "prover")]
+    #[test]
+    fn bn254_proofs() {
+        check_proofs::<Bn254>(BN254);
+    }
+}
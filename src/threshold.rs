@@ -0,0 +1,539 @@
+//! Threshold proving, in which several parties holding additive shares of a witness
+//! jointly create a single proof without reconstructing the witness.
+//!
+//! Each of the `n` parties holds an [`AssignmentShare`], and the shares sum to the
+//! assignment of the circuit. The FFTs and multi-scalar multiplications of the prover are
+//! linear, so each party applies them to its own share. The only products of secret values
+//! are the pointwise product `a · b` of the QAP evaluations on the coset and the product
+//! `r · s` of the blinding factors. The parties compute shares of them with Beaver
+//! triples, which a dealer hands out ahead of time with [`Groth16::deal_beaver_triples`].
+//! The protocol then has three rounds, in each of which every party broadcasts a message:
+//!
+//! 1. [`Groth16::start_threshold_proof`] returns the [`MaskedProducts`] of the party, the
+//!    factors of the products masked by its triples.
+//! 2. [`ThresholdProver::open_products`] takes the masked products of all parties and
+//!    returns the [`PartialProof`] of the party, its shares of `A`, `B` and of `B` in
+//!    `G1`, which are masked by its shares of `r` and `s`.
+//! 3. [`OpenedThresholdProver::share_c`] takes the partial proofs of all parties and
+//!    returns the [`ShareOfC`] of the party.
+//!
+//! Anyone can assemble the proof with [`Groth16::combine_threshold_proof`], which verifies
+//! it. The messages of the last two rounds carry the index of their party and the number
+//! of parties, so a missing or repeated party is detected. The messages reveal nothing about the shares beyond the proof, as long as the
+//! dealer is honest and every triple is used once. The protocol does not protect against
+//! parties that deviate from it, beyond the final check that the proof verifies.
+//!
+//! The shares must cover the whole assignment, including the intermediate variables of
+//! the circuit; computing them is up to the parties. The party with index `0` holds the
+//! constant variable, and the public inputs may be held by a single party.
+//! The QAP witness follows [`LibsnarkReduction`](crate::r1cs_to_qap::LibsnarkReduction),
+//! so `pk` must come from a setup with that reduction.
+use crate::{
+    prepare_verifying_key, r1cs_to_qap::evaluate_constraint, Groth16, Proof, ProverError,
+    ProvingKey,
+};
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{ConstraintMatrices, SynthesisError};
+use ark_serialize::*;
+use ark_std::{cfg_iter, cfg_iter_mut, fmt, ops::Mul, rand::Rng, vec, vec::Vec};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+type D<F> = GeneralEvaluationDomain<F>;
+
+/// The additive share of one party of the assignment of a circuit.
+///
+/// The share is zeroized when it is dropped.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AssignmentShare<F: PrimeField> {
+    /// The index of the party, below the number of parties.
+    pub party: usize,
+    /// The share of the instance variables, without the constant.
+    pub input_assignment: Vec<F>,
+    /// The share of the witness variables.
+    pub aux_assignment: Vec<F>,
+}
+
+impl<F: PrimeField> AssignmentShare<F> {
+    /// Splits an assignment into uniformly random shares for `num_parties` parties.
+    ///
+    /// This is for a dealer that holds the whole assignment, such as the owner of a
+    /// witness that is put into the custody of the parties.
+    pub fn split(
+        input_assignment: &[F],
+        aux_assignment: &[F],
+        num_parties: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<Self> {
+        let mut shares = (0..num_parties)
+            .map(|party| Self {
+                party,
+                input_assignment: Vec::with_capacity(input_assignment.len()),
+                aux_assignment: Vec::with_capacity(aux_assignment.len()),
+            })
+            .collect::<Vec<_>>();
+        for value in input_assignment {
+            for (share, value) in shares.iter_mut().zip(split_value(*value, num_parties, rng)) {
+                share.input_assignment.push(value);
+            }
+        }
+        for value in aux_assignment {
+            for (share, value) in shares.iter_mut().zip(split_value(*value, num_parties, rng)) {
+                share.aux_assignment.push(value);
+            }
+        }
+        shares
+    }
+}
+
+/// Splits `value` into `num_parties` uniformly random shares that sum to it.
+fn split_value<F: PrimeField>(value: F, num_parties: usize, rng: &mut impl Rng) -> Vec<F> {
+    let mut shares = (1..num_parties).map(|_| F::rand(rng)).collect::<Vec<_>>();
+    let last = value - shares.iter().sum::<F>();
+    shares.push(last);
+    shares
+}
+
+impl<F: PrimeField> Zeroize for AssignmentShare<F> {
+    fn zeroize(&mut self) {
+        self.input_assignment.zeroize();
+        self.aux_assignment.zeroize();
+    }
+}
+
+impl<F: PrimeField> Drop for AssignmentShare<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField> ZeroizeOnDrop for AssignmentShare<F> {}
+
+impl<F: PrimeField> fmt::Debug for AssignmentShare<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AssignmentShare { .. }")
+    }
+}
+
+/// The share of one party of a Beaver triple `(x, y, x · y)` of random values.
+///
+/// The triple is zeroized when it is dropped.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaverTriple<F: PrimeField> {
+    /// The share of `x`.
+    pub x: F,
+    /// The share of `y`.
+    pub y: F,
+    /// The share of `x · y`.
+    pub xy: F,
+}
+
+impl<F: PrimeField> BeaverTriple<F> {
+    /// Computes the share of the product of two values from the share of this triple and
+    /// the opened differences `d = u - x` and `e = v - y`. Exactly one party, the one with
+    /// index `0`, adds `d · e`.
+    fn product_share(&self, d: F, e: F, party: usize) -> F {
+        let share = self.xy + d * self.y + e * self.x;
+        if party == 0 {
+            share + d * e
+        } else {
+            share
+        }
+    }
+}
+
+impl<F: PrimeField> Zeroize for BeaverTriple<F> {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+        self.y.zeroize();
+        self.xy.zeroize();
+    }
+}
+
+impl<F: PrimeField> Drop for BeaverTriple<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: PrimeField> ZeroizeOnDrop for BeaverTriple<F> {}
+
+impl<F: PrimeField> fmt::Debug for BeaverTriple<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeaverTriple { .. }")
+    }
+}
+
+/// The message of a party in the first round: the differences between its shares of the
+/// factors of each product and its shares of the triple for it.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MaskedProducts<F: PrimeField> {
+    /// The masked left factors: the evaluations of `a` on the coset, followed by `r`.
+    pub d: Vec<F>,
+    /// The masked right factors: the evaluations of `b` on the coset, followed by `s`.
+    pub e: Vec<F>,
+}
+
+/// The message of a party in the second round: its shares of the proof elements that
+/// every party needs to compute its share of `C`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PartialProof<E: Pairing> {
+    /// The index of the party.
+    pub party: usize,
+    /// The number of parties.
+    pub num_parties: usize,
+    /// The share of `A`.
+    pub a: E::G1Affine,
+    /// The share of `B`.
+    pub b: E::G2Affine,
+    /// The share of `B` in `G1`.
+    pub b_g1: E::G1Affine,
+}
+
+/// The message of a party in the third round: its share of `C`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShareOfC<E: Pairing> {
+    /// The index of the party.
+    pub party: usize,
+    /// The number of parties.
+    pub num_parties: usize,
+    /// The share of `C`.
+    pub c: E::G1Affine,
+}
+
+/// The state of a party of a threshold proof after the first round.
+pub struct ThresholdProver<E: Pairing> {
+    party: usize,
+    num_parties: usize,
+    domain_size: usize,
+    assignment: Zeroizing<Vec<E::ScalarField>>,
+    num_inputs: usize,
+    c: Zeroizing<Vec<E::ScalarField>>,
+    triples: Vec<BeaverTriple<E::ScalarField>>,
+    r: Zeroizing<E::ScalarField>,
+    s: Zeroizing<E::ScalarField>,
+}
+
+impl<E: Pairing> fmt::Debug for ThresholdProver<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ThresholdProver { .. }")
+    }
+}
+
+/// The state of a party of a threshold proof after the second round.
+pub struct OpenedThresholdProver<E: Pairing> {
+    party: usize,
+    num_parties: usize,
+    delta_g1: E::G1Affine,
+    l_h: E::G1,
+    r: Zeroizing<E::ScalarField>,
+    s: Zeroizing<E::ScalarField>,
+    rs: Zeroizing<E::ScalarField>,
+}
+
+impl<E: Pairing> fmt::Debug for OpenedThresholdProver<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OpenedThresholdProver { .. }")
+    }
+}
+
+/// The evaluation domain of the QAP of the circuit with `matrices`.
+fn domain<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Result<D<F>, SynthesisError> {
+    D::new(matrices.num_constraints + matrices.num_instance_variables)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)
+}
+
+/// Checks that the `(party, num_parties)` pairs of a round name each of `num_parties`
+/// parties exactly once.
+fn check_parties(
+    parties: impl ExactSizeIterator<Item = (usize, usize)>,
+    num_parties: usize,
+) -> Result<(), ProverError> {
+    if parties.len() != num_parties {
+        return Err(ProverError::UnexpectedParties);
+    }
+    let mut seen = vec![false; num_parties];
+    for (party, n) in parties {
+        if n != num_parties || party >= num_parties || seen[party] {
+            return Err(ProverError::UnexpectedParties);
+        }
+        seen[party] = true;
+    }
+    Ok(())
+}
+
+/// Sums the entries of `values` position by position.
+fn open<'a, F: PrimeField>(values: impl Iterator<Item = &'a [F]>, len: usize) -> Vec<F> {
+    values.fold(vec![F::zero(); len], |mut sum, values| {
+        sum.iter_mut().zip(values).for_each(|(sum, v)| *sum += v);
+        sum
+    })
+}
+
+impl<E: Pairing> ThresholdProver<E> {
+    /// Opens the products from the [`MaskedProducts`] of all parties, ordered by party,
+    /// and computes the [`PartialProof`] of this party with the queries of `pk`.
+    ///
+    /// Returns [`ProverError::MalformedMessage`] if there is not one message of the right
+    /// length per party.
+    pub fn open_products(
+        self,
+        pk: &ProvingKey<E>,
+        messages: &[MaskedProducts<E::ScalarField>],
+    ) -> Result<(OpenedThresholdProver<E>, PartialProof<E>), ProverError> {
+        let len = self.domain_size + 1;
+        if messages.len() != self.num_parties
+            || messages
+                .iter()
+                .any(|m| m.d.len() != len || m.e.len() != len)
+        {
+            return Err(ProverError::MalformedMessage);
+        }
+        let d = open(messages.iter().map(|m| m.d.as_slice()), len);
+        let e = open(messages.iter().map(|m| m.e.as_slice()), len);
+
+        let domain = D::<E::ScalarField>::new(self.domain_size)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let coset_domain = domain.get_coset(E::ScalarField::GENERATOR).unwrap();
+        let vanishing_polynomial_over_coset = domain
+            .evaluate_vanishing_polynomial(E::ScalarField::GENERATOR)
+            .inverse()
+            .unwrap();
+
+        let party = self.party;
+        let mut h = Zeroizing::new(vec![E::ScalarField::zero(); self.domain_size]);
+        cfg_iter_mut!(h)
+            .zip(cfg_iter!(self.triples))
+            .zip(cfg_iter!(d))
+            .zip(cfg_iter!(e))
+            .zip(cfg_iter!(self.c))
+            .for_each(|((((h, triple), d), e), c)| {
+                *h = (triple.product_share(*d, *e, party) - c) * vanishing_polynomial_over_coset;
+            });
+        coset_domain.ifft_in_place(&mut h);
+        let rs = Zeroizing::new(self.triples[self.domain_size].product_share(
+            d[self.domain_size],
+            e[self.domain_size],
+            party,
+        ));
+
+        let assignment = &self.assignment[1..];
+        let a_acc = E::G1::msm_unchecked(&pk.a_query[1..], assignment);
+        let b_g1_acc = E::G1::msm_unchecked(&pk.b_g1_query[1..], assignment);
+        let b_g2_acc = E::G2::msm_unchecked(&pk.b_g2_query[1..], assignment);
+        let l_acc = E::G1::msm_unchecked(&pk.l_query, &assignment[self.num_inputs..]);
+        let h_acc = E::G1::msm_unchecked(&pk.h_query, &h);
+
+        let mut a = pk.delta_g1.mul(*self.r) + a_acc;
+        let mut b = pk.vk.delta_g2.mul(*self.s) + b_g2_acc;
+        let mut b_g1 = pk.delta_g1.mul(*self.s) + b_g1_acc;
+        if party == 0 {
+            a = Groth16::<E>::calculate_coeff(a, &pk.a_query, pk.vk.alpha_g1, E::G1::zero());
+            b = Groth16::<E>::calculate_coeff(b, &pk.b_g2_query, pk.vk.beta_g2, E::G2::zero());
+            b_g1 = Groth16::<E>::calculate_coeff(b_g1, &pk.b_g1_query, pk.beta_g1, E::G1::zero());
+        }
+
+        let opened = OpenedThresholdProver {
+            party,
+            num_parties: self.num_parties,
+            delta_g1: pk.delta_g1,
+            l_h: l_acc + h_acc,
+            r: self.r,
+            s: self.s,
+            rs,
+        };
+        let partial = PartialProof {
+            party,
+            num_parties: self.num_parties,
+            a: a.into_affine(),
+            b: b.into_affine(),
+            b_g1: b_g1.into_affine(),
+        };
+        Ok((opened, partial))
+    }
+}
+
+impl<E: Pairing> OpenedThresholdProver<E> {
+    /// Computes the share of `C` of this party from the [`PartialProof`]s of all parties.
+    ///
+    /// Returns [`ProverError::UnexpectedParties`] if there is not one partial proof per
+    /// party.
+    pub fn share_c(self, partials: &[PartialProof<E>]) -> Result<ShareOfC<E>, ProverError> {
+        check_parties(
+            partials.iter().map(|p| (p.party, p.num_parties)),
+            self.num_parties,
+        )?;
+        let a = partials.iter().map(|p| p.a).sum::<E::G1>();
+        let b_g1 = partials.iter().map(|p| p.b_g1).sum::<E::G1>();
+        let c = a * *self.s + b_g1 * *self.r - self.delta_g1.mul(*self.rs) + self.l_h;
+        Ok(ShareOfC {
+            party: self.party,
+            num_parties: self.num_parties,
+            c: c.into_affine(),
+        })
+    }
+}
+
+impl<E: Pairing> Groth16<E> {
+    /// The number of Beaver triples that each party needs for one proof of the circuit
+    /// with `matrices`.
+    pub fn threshold_triples_needed(
+        matrices: &ConstraintMatrices<E::ScalarField>,
+    ) -> Result<usize, SynthesisError> {
+        Ok(domain(matrices)?.size() + 1)
+    }
+
+    /// Deals `count` Beaver triples to each of `num_parties` parties, indexed by party.
+    ///
+    /// The dealer learns nothing about the witness, but must be trusted to deal correct
+    /// triples and to forget them.
+    pub fn deal_beaver_triples(
+        count: usize,
+        num_parties: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<Vec<BeaverTriple<E::ScalarField>>> {
+        let mut triples = (0..num_parties)
+            .map(|_| Vec::with_capacity(count))
+            .collect::<Vec<_>>();
+        for _ in 0..count {
+            let x = Zeroizing::new(E::ScalarField::rand(rng));
+            let y = Zeroizing::new(E::ScalarField::rand(rng));
+            let x_shares = split_value(*x, num_parties, rng);
+            let y_shares = split_value(*y, num_parties, rng);
+            let xy_shares = split_value(*x * *y, num_parties, rng);
+            for (party, ((x, y), xy)) in triples
+                .iter_mut()
+                .zip(x_shares.into_iter().zip(y_shares).zip(xy_shares))
+            {
+                party.push(BeaverTriple { x, y, xy });
+            }
+        }
+        triples
+    }
+
+    /// Starts a threshold proof with `pk` for the circuit with constraint `matrices`, as
+    /// the party holding `share` among `num_parties` parties, with its `triples` from
+    /// [`Groth16::deal_beaver_triples`].
+    /// This method samples the share of the party of the randomness for zero knowledge via
+    /// `rng`.
+    ///
+    /// Returns [`ProverError::MalformedProvingKey`] if the numbers of variables of `share`,
+    /// `matrices` and `pk` do not match, and [`ProverError::MalformedMessage`] if the
+    /// party index or the number of triples is wrong.
+    pub fn start_threshold_proof(
+        pk: &ProvingKey<E>,
+        matrices: &ConstraintMatrices<E::ScalarField>,
+        share: &AssignmentShare<E::ScalarField>,
+        num_parties: usize,
+        triples: Vec<BeaverTriple<E::ScalarField>>,
+        rng: &mut impl Rng,
+    ) -> Result<(ThresholdProver<E>, MaskedProducts<E::ScalarField>), ProverError> {
+        let num_inputs = matrices.num_instance_variables;
+        let num_constraints = matrices.num_constraints;
+        if share.input_assignment.len() + 1 != num_inputs
+            || share.aux_assignment.len() != matrices.num_witness_variables
+            || pk.a_query.len() != num_inputs + matrices.num_witness_variables
+            || pk.l_query.len() != matrices.num_witness_variables
+        {
+            return Err(ProverError::MalformedProvingKey);
+        }
+        let domain = domain(matrices)?;
+        let domain_size = domain.size();
+        if share.party >= num_parties || triples.len() != domain_size + 1 {
+            return Err(ProverError::MalformedMessage);
+        }
+        let coset_domain = domain.get_coset(E::ScalarField::GENERATOR).unwrap();
+
+        let constant = if share.party == 0 {
+            E::ScalarField::one()
+        } else {
+            E::ScalarField::zero()
+        };
+        let mut assignment = Zeroizing::new(Vec::with_capacity(pk.a_query.len()));
+        assignment.push(constant);
+        assignment.extend_from_slice(&share.input_assignment);
+        assignment.extend_from_slice(&share.aux_assignment);
+
+        // The shares of the QAP evaluations on the coset, as in `LibsnarkReduction`.
+        let zero = E::ScalarField::zero();
+        let mut a = Zeroizing::new(vec![zero; domain_size]);
+        let mut b = Zeroizing::new(vec![zero; domain_size]);
+        let mut c = Zeroizing::new(vec![zero; domain_size]);
+        cfg_iter_mut!(a[..num_constraints])
+            .zip(cfg_iter_mut!(b[..num_constraints]))
+            .zip(cfg_iter_mut!(c[..num_constraints]))
+            .enumerate()
+            .for_each(|(i, ((a, b), c))| {
+                *a = evaluate_constraint(&matrices.a[i], &assignment);
+                *b = evaluate_constraint(&matrices.b[i], &assignment);
+                *c = evaluate_constraint(&matrices.c[i], &assignment);
+            });
+        a[num_constraints..num_constraints + num_inputs].copy_from_slice(&assignment[..num_inputs]);
+        for evaluations in [&mut a, &mut b, &mut c] {
+            domain.ifft_in_place(evaluations);
+            coset_domain.fft_in_place(evaluations);
+        }
+
+        let r = Zeroizing::new(E::ScalarField::rand(rng));
+        let s = Zeroizing::new(E::ScalarField::rand(rng));
+        let left = a.iter().chain([&*r]);
+        let right = b.iter().chain([&*s]);
+        let message = MaskedProducts {
+            d: left.zip(&triples).map(|(u, t)| *u - t.x).collect(),
+            e: right.zip(&triples).map(|(v, t)| *v - t.y).collect(),
+        };
+
+        let prover = ThresholdProver {
+            party: share.party,
+            num_parties,
+            domain_size,
+            assignment,
+            num_inputs: num_inputs - 1,
+            c,
+            triples,
+            r,
+            s,
+        };
+        Ok((prover, message))
+    }
+
+    /// Assembles the threshold proof for `public_inputs` from the [`PartialProof`]s and
+    /// the shares of `C` of all parties, and verifies it against `pk`.
+    ///
+    /// Returns [`ProverError::UnexpectedParties`] if the partial proofs or the shares of
+    /// `C` do not come from each party exactly once, and [`ProverError::InvalidProof`] if
+    /// the proof does not verify.
+    pub fn combine_threshold_proof(
+        pk: &ProvingKey<E>,
+        public_inputs: &[E::ScalarField],
+        partials: &[PartialProof<E>],
+        c_shares: &[ShareOfC<E>],
+    ) -> Result<Proof<E>, ProverError> {
+        let num_parties = partials
+            .first()
+            .ok_or(ProverError::UnexpectedParties)?
+            .num_parties;
+        check_parties(
+            partials.iter().map(|p| (p.party, p.num_parties)),
+            num_parties,
+        )?;
+        check_parties(
+            c_shares.iter().map(|c| (c.party, c.num_parties)),
+            num_parties,
+        )?;
+        let proof = Proof {
+            a: partials.iter().map(|p| p.a).sum::<E::G1>().into_affine(),
+            b: partials.iter().map(|p| p.b).sum::<E::G2>().into_affine(),
+            c: c_shares.iter().map(|c| c.c).sum::<E::G1>().into_affine(),
+        };
+        let pvk = prepare_verifying_key(&pk.vk);
+        if !Self::verify_proof(&pvk, &proof, public_inputs)? {
+            return Err(ProverError::InvalidProof);
+        }
+        Ok(proof)
+    }
+}
@@ -1,69 +1,416 @@
-use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
-use ark_ff::PrimeField;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
 
-use crate::{r1cs_to_qap::R1CSToQAP, Groth16};
+use crate::{equation, prefilter::is_valid_proof_point, r1cs_to_qap::R1CSToQAP, Groth16};
 
-use super::{PreparedVerifyingKey, Proof, VerifyingKey};
+use super::{
+    PreparedInputs, PreparedProof, PreparedStatement, PreparedVerifyingKey, Proof, VerifyingKey,
+};
 
 use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate,
+};
+use ark_std::{cfg_iter, vec, vec::Vec};
+use sha2::{Digest, Sha256};
 
-use core::ops::{AddAssign, Neg};
+use core::{fmt, ops::Range};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The reason a proof was rejected before or during verification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The number of public inputs does not match the verifying key.
+    WrongNumberOfPublicInputs {
+        /// The number of public inputs expected by the verifying key.
+        expected: usize,
+        /// The number of public inputs that were supplied.
+        found: usize,
+    },
+    /// The public input at `index` is not the canonical encoding of a scalar field element.
+    InputNotInField {
+        /// The position of the offending input among the public inputs.
+        index: usize,
+    },
+    /// A proof element is the identity or lies outside the prime-order subgroup.
+    MalformedProofPoint,
+    /// The proof and the public inputs do not satisfy the verification equation.
+    PairingCheckFailed,
+    /// The proof-of-work tag attached to the proof does not meet the required difficulty.
+    InsufficientProofOfWork,
+    /// The encoding of the proof or the public inputs could not be read or is invalid.
+    InvalidEncoding,
+    /// A public input is named with a label that the verifying key does not define.
+    UnknownInputLabel,
+    /// An error raised by the underlying constraint system machinery.
+    Synthesis(SynthesisError),
+}
+
+impl From<SynthesisError> for VerificationError {
+    fn from(e: SynthesisError) -> Self {
+        VerificationError::Synthesis(e)
+    }
+}
+
+impl From<SerializationError> for VerificationError {
+    fn from(_: SerializationError) -> Self {
+        VerificationError::InvalidEncoding
+    }
+}
+
+impl ark_std::error::Error for VerificationError {}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::WrongNumberOfPublicInputs { expected, found } => write!(
+                f,
+                "expected {} public inputs, but {} were supplied",
+                expected, found
+            ),
+            VerificationError::InputNotInField { index } => {
+                write!(f, "public input {} is not in the scalar field", index)
+            },
+            VerificationError::MalformedProofPoint => {
+                write!(
+                    f,
+                    "proof element is the identity or not in the prime-order subgroup"
+                )
+            },
+            VerificationError::PairingCheckFailed => {
+                write!(f, "proof does not satisfy the verification equation")
+            },
+            VerificationError::InsufficientProofOfWork => {
+                write!(f, "proof-of-work tag does not meet the required difficulty")
+            },
+            VerificationError::InvalidEncoding => {
+                write!(f, "proof or public inputs are not validly encoded")
+            },
+            VerificationError::UnknownInputLabel => {
+                write!(f, "public input label is not defined by the verifying key")
+            },
+            VerificationError::Synthesis(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Checks that `found` public inputs are what `pvk` expects.
+pub(crate) fn check_num_public_inputs<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    found: usize,
+) -> Result<(), VerificationError> {
+    let expected = pvk.vk.num_public_inputs();
+    if found != expected {
+        return Err(VerificationError::WrongNumberOfPublicInputs { expected, found });
+    }
+    Ok(())
+}
+
+/// Parses public inputs given as big-endian hexadecimal integers, optionally prefixed with
+/// `0x` and separated by commas or whitespace.
+fn parse_hex_public_inputs<F: PrimeField>(hex: &str) -> Result<Vec<F>, VerificationError> {
+    hex.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|input| !input.is_empty())
+        .enumerate()
+        .map(|(index, input)| {
+            let digits = input
+                .strip_prefix("0x")
+                .or_else(|| input.strip_prefix("0X"))
+                .unwrap_or(input);
+            if digits.is_empty() {
+                return Err(VerificationError::InvalidEncoding);
+            }
+            let mut bigint = F::BigInt::default();
+            let limbs = bigint.as_mut();
+            for (i, digit) in digits.trim_start_matches('0').chars().rev().enumerate() {
+                let digit = digit
+                    .to_digit(16)
+                    .ok_or(VerificationError::InvalidEncoding)?;
+                let limb = limbs
+                    .get_mut(i / 16)
+                    .ok_or(VerificationError::InputNotInField { index })?;
+                *limb |= u64::from(digit) << (4 * (i % 16));
+            }
+            F::from_bigint(bigint).ok_or(VerificationError::InputNotInField { index })
+        })
+        .collect()
+}
+
+/// Folds the `expected` inputs of `public_inputs` into a sum of public input terms, one
+/// chunk of at most `chunk_size` inputs at a time. `msm` computes the terms of the inputs
+/// at the given positions.
+fn fold_public_inputs<E, I>(
+    expected: usize,
+    public_inputs: I,
+    chunk_size: usize,
+    mut msm: impl FnMut(Range<usize>, &[E::ScalarField]) -> Result<E::G1, VerificationError>,
+) -> Result<E::G1, VerificationError>
+where
+    E: Pairing,
+    I: IntoIterator<Item = E::ScalarField>,
+{
+    let chunk_size = chunk_size.max(1);
+    let mut inputs = public_inputs.into_iter();
+    let mut chunk = Vec::with_capacity(chunk_size.min(expected));
+    let mut acc = E::G1::zero();
+    let mut found = 0;
+    while found < expected {
+        chunk.clear();
+        chunk.extend(inputs.by_ref().take(chunk_size.min(expected - found)));
+        if chunk.is_empty() {
+            break;
+        }
+        acc += msm(found..found + chunk.len(), &chunk)?;
+        found += chunk.len();
+    }
+
+    let found = found + inputs.count();
+    if found != expected {
+        return Err(VerificationError::WrongNumberOfPublicInputs { expected, found });
+    }
+    Ok(acc)
+}
+
+/// Computes `scalar * base` with a doubling and an addition for every bit of the scalar's
+/// representation, without branching on the bits.
+fn mul_always_add<G: CurveGroup>(base: G, scalar: &G::ScalarField) -> G {
+    let mut acc = G::zero();
+    for bit in scalar.into_bigint().to_bits_be() {
+        acc.double_in_place();
+        let candidates = [acc, acc + base];
+        acc = candidates[bit as usize];
+    }
+    acc
+}
+
+/// Domain separator for the challenges of batch verification.
+const BATCH_DOMAIN: &[u8] = b"ark-groth16/batch-verification/v1";
+
+/// Derives one 128-bit challenge per proof from a hash of the whole batch, so that no
+/// proof can be chosen to cancel out another.
+fn batch_challenges<E: Pairing, I: AsRef<[E::ScalarField]>>(
+    vk: &VerifyingKey<E>,
+    proofs: &[(Proof<E>, I)],
+) -> Vec<E::ScalarField> {
+    let mut bytes = Vec::new();
+    vk.serialize_compressed(&mut bytes)
+        .expect("serializing into a `Vec` cannot fail");
+    for (proof, public_inputs) in proofs {
+        proof
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+        public_inputs
+            .as_ref()
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a `Vec` cannot fail");
+    }
+    let hasher = Sha256::new()
+        .chain_update(BATCH_DOMAIN)
+        .chain_update(&bytes);
+
+    (0..proofs.len() as u64)
+        .map(|i| {
+            let digest = hasher.clone().chain_update(i.to_le_bytes()).finalize();
+            E::ScalarField::from_le_bytes_mod_order(&digest[..16])
+        })
+        .collect()
+}
 
 /// Prepare the verifying key `vk` for use in proof verification.
 pub fn prepare_verifying_key<E: Pairing>(vk: &VerifyingKey<E>) -> PreparedVerifyingKey<E> {
     PreparedVerifyingKey {
         vk: vk.clone(),
-        alpha_g1_beta_g2: E::pairing(vk.alpha_g1, vk.beta_g2).0,
-        gamma_g2_neg_pc: vk.gamma_g2.into_group().neg().into_affine().into(),
-        delta_g2_neg_pc: vk.delta_g2.into_group().neg().into_affine().into(),
+        alpha_g1_beta_g2: equation::alpha_g1_beta_g2::<E>(vk.alpha_g1, vk.beta_g2),
+        gamma_g2_neg_pc: equation::negate_and_prepare::<E>(vk.gamma_g2),
+        delta_g2_neg_pc: equation::negate_and_prepare::<E>(vk.delta_g2),
+    }
+}
+
+/// Accumulates public inputs into [`PreparedInputs`] one at a time or in chunks, for
+/// inputs that are produced incrementally rather than available as one slice.
+///
+/// Finalizing yields the same value as [`Groth16::prepare_public_inputs`] over all inputs fed.
+#[derive(Clone, Debug)]
+pub struct PublicInputAccumulator<'a, E: Pairing> {
+    gamma_abc_g1: &'a [E::G1Affine],
+    acc: E::G1,
+    num_inputs: usize,
+}
+
+impl<'a, E: Pairing> PublicInputAccumulator<'a, E> {
+    /// Start accumulating the public inputs of a statement for the prepared verification
+    /// key `pvk`.
+    pub fn new(pvk: &'a PreparedVerifyingKey<E>) -> R1CSResult<Self> {
+        let (ic_0, gamma_abc_g1) = pvk
+            .vk
+            .gamma_abc_g1
+            .split_first()
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        Ok(Self {
+            gamma_abc_g1,
+            acc: ic_0.into_group(),
+            num_inputs: 0,
+        })
+    }
+
+    /// The number of public inputs fed so far.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// Feed the next public input. Fails if the verification key expects no more inputs.
+    pub fn push(&mut self, input: E::ScalarField) -> R1CSResult<()> {
+        self.extend_from_slice(&[input])
+    }
+
+    /// Feed the next public inputs. Fails, without feeding any of them, if the
+    /// verification key expects fewer inputs.
+    pub fn extend_from_slice(&mut self, inputs: &[E::ScalarField]) -> R1CSResult<()> {
+        let bases = self
+            .gamma_abc_g1
+            .get(self.num_inputs..self.num_inputs + inputs.len())
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        self.acc += E::G1::msm_unchecked(bases, inputs);
+        self.num_inputs += inputs.len();
+        Ok(())
+    }
+
+    /// Finish accumulating. Fails if fewer inputs were fed than the verification key
+    /// expects.
+    pub fn finalize(self) -> R1CSResult<PreparedInputs<E>> {
+        if self.num_inputs != self.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        Ok(PreparedInputs {
+            prepared_inputs: self.acc.into_affine(),
+        })
     }
 }
 
 impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
     /// Prepare proof inputs for use with [`verify_proof_with_prepared_inputs`], wrt the prepared
     /// verification key `pvk` and instance public inputs.
+    ///
+    /// [`prepare_public_inputs`] returns the same element as a reusable, serializable
+    /// [`PreparedInputs`] value.
+    ///
+    /// [`verify_proof_with_prepared_inputs`]: Self::verify_proof_with_prepared_inputs
+    /// [`prepare_public_inputs`]: Self::prepare_public_inputs
     pub fn prepare_inputs(
         pvk: &PreparedVerifyingKey<E>,
         public_inputs: &[E::ScalarField],
     ) -> R1CSResult<E::G1> {
-        if (public_inputs.len() + 1) != pvk.vk.gamma_abc_g1.len() {
-            return Err(SynthesisError::MalformedVerifyingKey);
-        }
-
-        let mut g_ic = pvk.vk.gamma_abc_g1[0].into_group();
-        for (i, b) in public_inputs.iter().zip(pvk.vk.gamma_abc_g1.iter().skip(1)) {
-            g_ic.add_assign(&b.mul_bigint(i.into_bigint()));
-        }
-
-        Ok(g_ic)
+        equation::prepared_inputs::<E>(&pvk.vk.gamma_abc_g1, public_inputs)
+            .ok_or(SynthesisError::MalformedVerifyingKey)
     }
 
     /// Verify a Groth16 proof `proof` against the prepared verification key `pvk` and prepared public
     /// inputs. This should be preferred over [`verify_proof`] if the instance's public inputs are
     /// known in advance.
+    ///
+    /// [`verify_proof`]: Self::verify_proof
     pub fn verify_proof_with_prepared_inputs(
         pvk: &PreparedVerifyingKey<E>,
         proof: &Proof<E>,
         prepared_inputs: &E::G1,
+    ) -> R1CSResult<bool> {
+        Self::verify_proof_with_prepared_public_inputs(
+            pvk,
+            proof,
+            &PreparedInputs {
+                prepared_inputs: prepared_inputs.into_affine(),
+            },
+        )
+    }
+
+    /// Prepare the instance `public_inputs` for use with [`verify_proof_with_prepared_public_inputs`],
+    /// wrt the prepared verification key `pvk`. The result can be reused to verify any
+    /// number of proofs of the same public inputs.
+    ///
+    /// Returns `SynthesisError::MalformedVerifyingKey` unless there are exactly
+    /// [`VerifyingKey::num_public_inputs`] inputs; [`verify_proof_detailed`] reports the
+    /// mismatch as [`VerificationError::WrongNumberOfPublicInputs`] instead.
+    ///
+    /// [`verify_proof_with_prepared_public_inputs`]: Self::verify_proof_with_prepared_public_inputs
+    /// [`verify_proof_detailed`]: Self::verify_proof_detailed
+    pub fn prepare_public_inputs(
+        pvk: &PreparedVerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<PreparedInputs<E>> {
+        let prepared_inputs = equation::prepared_inputs::<E>(&pvk.vk.gamma_abc_g1, public_inputs)
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        Ok(PreparedInputs {
+            prepared_inputs: prepared_inputs.into_affine(),
+        })
+    }
+
+    /// Verify a Groth16 proof `proof` against the prepared verification key `pvk` and prepared public
+    /// inputs. This should be preferred over [`verify_proof`] if the instance's public inputs are
+    /// known in advance, or shared by several proofs.
+    ///
+    /// [`verify_proof`]: Self::verify_proof
+    pub fn verify_proof_with_prepared_public_inputs(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        prepared_inputs: &PreparedInputs<E>,
+    ) -> R1CSResult<bool> {
+        equation::check::<E>(
+            pvk.alpha_g1_beta_g2,
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+            prepared_inputs.prepared_inputs,
+            proof.a,
+            proof.b,
+            proof.c,
+        )
+        .ok_or(SynthesisError::UnexpectedIdentity)
+    }
+
+    /// Prepare `proof` for use with [`verify_prepared_proof`], for a proof that is verified
+    /// many times.
+    ///
+    /// [`verify_prepared_proof`]: Self::verify_prepared_proof
+    pub fn prepare_proof(proof: &Proof<E>) -> PreparedProof<E> {
+        proof.clone().into()
+    }
+
+    /// Verify a prepared Groth16 proof `proof` against the prepared verification key `pvk`
+    /// and prepared public inputs.
+    pub fn verify_prepared_proof_with_prepared_inputs(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &PreparedProof<E>,
+        prepared_inputs: &PreparedInputs<E>,
     ) -> R1CSResult<bool> {
         let qap = E::multi_miller_loop(
             [
-                <E::G1Affine as Into<E::G1Prepared>>::into(proof.a),
-                prepared_inputs.into_affine().into(),
-                proof.c.into(),
+                proof.a.clone(),
+                prepared_inputs.prepared_inputs.into(),
+                proof.c.clone(),
             ],
             [
-                proof.b.into(),
+                proof.b.clone(),
                 pvk.gamma_g2_neg_pc.clone(),
                 pvk.delta_g2_neg_pc.clone(),
             ],
         );
-
         let test = E::final_exponentiation(qap).ok_or(SynthesisError::UnexpectedIdentity)?;
 
         Ok(test.0 == pvk.alpha_g1_beta_g2)
     }
 
+    /// Verify a prepared Groth16 proof `proof` against the prepared verification key `pvk`,
+    /// with respect to the instance `public_inputs`.
+    pub fn verify_prepared_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &PreparedProof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<bool> {
+        let prepared_inputs = Self::prepare_public_inputs(pvk, public_inputs)?;
+        Self::verify_prepared_proof_with_prepared_inputs(pvk, proof, &prepared_inputs)
+    }
+
     /// Verify a Groth16 proof `proof` against the prepared verification key `pvk`,
     /// with respect to the instance `public_inputs`.
     pub fn verify_proof(
@@ -71,7 +418,421 @@ impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
         proof: &Proof<E>,
         public_inputs: &[E::ScalarField],
     ) -> R1CSResult<bool> {
-        let prepared_inputs = Self::prepare_inputs(pvk, public_inputs)?;
-        Self::verify_proof_with_prepared_inputs(pvk, proof, &prepared_inputs)
+        let prepared_inputs = Self::prepare_public_inputs(pvk, public_inputs)?;
+        Self::verify_proof_with_prepared_public_inputs(pvk, proof, &prepared_inputs)
+    }
+
+    /// Verify a Groth16 proof `proof` against the prepared verification key `pvk`, with
+    /// respect to public inputs given by the names in `labeled_vk`, the verifying key of
+    /// `pvk` with its input labels.
+    ///
+    /// Unlike a vector of inputs, named inputs cannot be passed in the wrong order: an
+    /// input whose name `labeled_vk` does not define is rejected.
+    #[cfg(feature = "std")]
+    pub fn verify_proof_with_labeled_inputs<S: std::hash::BuildHasher>(
+        pvk: &PreparedVerifyingKey<E>,
+        labeled_vk: &crate::LabeledVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &std::collections::HashMap<std::string::String, E::ScalarField, S>,
+    ) -> Result<bool, VerificationError> {
+        if labeled_vk.vk != pvk.vk {
+            return Err(SynthesisError::MalformedVerifyingKey.into());
+        }
+        let public_inputs = labeled_vk.order_public_inputs(public_inputs)?;
+        Ok(Self::verify_proof(pvk, proof, &public_inputs)?)
+    }
+
+    /// Verify a Groth16 proof `proof` against the prepared verification key `pvk`, with
+    /// respect to public inputs read from an iterator, so that they never have to be
+    /// collected into one vector.
+    ///
+    /// The inputs are folded into the prepared inputs at most `chunk_size` at a time.
+    pub fn verify_proof_with_input_iter<I: IntoIterator<Item = E::ScalarField>>(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: I,
+        chunk_size: usize,
+    ) -> Result<bool, VerificationError> {
+        let (ic_0, gamma_abc_g1) = pvk
+            .vk
+            .gamma_abc_g1
+            .split_first()
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        let acc = fold_public_inputs::<E, _>(
+            gamma_abc_g1.len(),
+            public_inputs,
+            chunk_size,
+            |range, inputs| Ok(E::G1::msm_unchecked(&gamma_abc_g1[range], inputs)),
+        )?;
+
+        let prepared_inputs = PreparedInputs {
+            prepared_inputs: (acc + ic_0).into_affine(),
+        };
+        Ok(Self::verify_proof_with_prepared_public_inputs(
+            pvk,
+            proof,
+            &prepared_inputs,
+        )?)
+    }
+
+    /// Verify a Groth16 proof `proof` against a [`VerifyingKey`] serialized with
+    /// `compress` in `vk_reader`, with respect to public inputs read from an iterator.
+    ///
+    /// This is meant for circuits with so many public inputs that neither the inputs nor
+    /// the `gamma_abc_g1` points of the key should be held in memory: both are consumed
+    /// in a single pass, at most `chunk_size` at a time. The key is prepared anew on every
+    /// call, which costs one pairing.
+    pub fn verify_proof_with_streamed_key<R, I>(
+        mut vk_reader: R,
+        compress: Compress,
+        validate: Validate,
+        proof: &Proof<E>,
+        public_inputs: I,
+        chunk_size: usize,
+    ) -> Result<bool, VerificationError>
+    where
+        R: Read,
+        I: IntoIterator<Item = E::ScalarField>,
+    {
+        let alpha_g1 = E::G1Affine::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+        let beta_g2 = E::G2Affine::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+        let gamma_g2 = E::G2Affine::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+        let delta_g2 = E::G2Affine::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+        let len = u64::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+        let num_inputs = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_sub(1))
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        let ic_0 = E::G1Affine::deserialize_with_mode(&mut vk_reader, compress, validate)?;
+
+        let mut bases = Vec::new();
+        let acc =
+            fold_public_inputs::<E, _>(num_inputs, public_inputs, chunk_size, |_, inputs| {
+                bases.clear();
+                for _ in 0..inputs.len() {
+                    bases.push(E::G1Affine::deserialize_with_mode(
+                        &mut vk_reader,
+                        compress,
+                        validate,
+                    )?);
+                }
+                Ok(E::G1::msm_unchecked(&bases, inputs))
+            })?;
+
+        Ok(equation::check::<E>(
+            equation::alpha_g1_beta_g2::<E>(alpha_g1, beta_g2),
+            equation::negate_and_prepare::<E>(gamma_g2),
+            equation::negate_and_prepare::<E>(delta_g2),
+            (acc + ic_0).into_affine(),
+            proof.a,
+            proof.b,
+            proof.c,
+        )
+        .ok_or(SynthesisError::UnexpectedIdentity)?)
+    }
+
+    /// Verify a Groth16 proof `proof` like [`verify_proof`], but report why a proof is
+    /// rejected: a wrong number of public inputs, a proof element that is the identity, or
+    /// a failed pairing check.
+    ///
+    /// [`verify_proof`]: Self::verify_proof
+    pub fn verify_proof_detailed(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<(), VerificationError> {
+        check_num_public_inputs(pvk, public_inputs.len())?;
+        if proof.a.is_zero() || proof.b.is_zero() || proof.c.is_zero() {
+            return Err(VerificationError::MalformedProofPoint);
+        }
+        if Self::verify_proof(pvk, proof, public_inputs)? {
+            Ok(())
+        } else {
+            Err(VerificationError::PairingCheckFailed)
+        }
+    }
+
+    /// Verify a Groth16 proof `proof` like [`verify_proof_detailed`], after also checking
+    /// that `a`, `b` and `c` lie in the prime-order subgroup.
+    ///
+    /// Proofs from untrusted parties should be verified this way on curves with a
+    /// cofactor, unless they were deserialized with [`Validate::Yes`], which performs the
+    /// same checks.
+    ///
+    /// [`verify_proof_detailed`]: Self::verify_proof_detailed
+    /// [`Validate::Yes`]: ark_serialize::Validate::Yes
+    pub fn verify_proof_with_subgroup_checks(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<(), VerificationError> {
+        check_num_public_inputs(pvk, public_inputs.len())?;
+        if !(is_valid_proof_point(&proof.a)
+            && is_valid_proof_point(&proof.b)
+            && is_valid_proof_point(&proof.c))
+        {
+            return Err(VerificationError::MalformedProofPoint);
+        }
+        Self::verify_proof_detailed(pvk, proof, public_inputs)
+    }
+
+    /// Verify a Groth16 proof `proof` like [`verify_proof_with_subgroup_checks`], without
+    /// branching or returning early on the public inputs or on the reason for rejection.
+    ///
+    /// The public inputs are multiplied into the verifying key with a fixed sequence of
+    /// group operations, and the subgroup checks and the pairing check all run before
+    /// their results are combined. Only the number of public inputs, which is public, is
+    /// checked up front. The field and curve arithmetic of arkworks is not itself
+    /// constant-time, so this removes the large timing differences of the verifier, not
+    /// all of them.
+    ///
+    /// [`verify_proof_with_subgroup_checks`]: Self::verify_proof_with_subgroup_checks
+    pub fn verify_proof_constant_time(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<bool, VerificationError> {
+        check_num_public_inputs(pvk, public_inputs.len())?;
+        let (ic_0, ic) = pvk
+            .vk
+            .gamma_abc_g1
+            .split_first()
+            .ok_or(SynthesisError::MalformedVerifyingKey)?;
+        let mut prepared_inputs = ic_0.into_group();
+        for (x_i, ic_i) in public_inputs.iter().zip(ic) {
+            prepared_inputs += mul_always_add(ic_i.into_group(), x_i);
+        }
+
+        let points_ok = is_valid_proof_point(&proof.a)
+            & is_valid_proof_point(&proof.b)
+            & is_valid_proof_point(&proof.c);
+        let pairing_ok = equation::check::<E>(
+            pvk.alpha_g1_beta_g2,
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+            prepared_inputs.into_affine(),
+            proof.a,
+            proof.b,
+            proof.c,
+        )
+        .unwrap_or(false);
+
+        Ok(points_ok & pairing_ok)
+    }
+
+    /// Precompute everything in the verification equation that does not depend on the
+    /// proof, for a statement whose `public_inputs` are known before the proof arrives.
+    pub fn prepare_statement(
+        pvk: &PreparedVerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> R1CSResult<PreparedStatement<E>> {
+        let prepared_inputs = Self::prepare_public_inputs(pvk, public_inputs)?;
+        let ic_gamma = E::pairing(prepared_inputs.prepared_inputs, pvk.vk.gamma_g2);
+
+        Ok(PreparedStatement {
+            target: pvk.alpha_g1_beta_g2 * ic_gamma.0,
+            delta_g2_neg_pc: pvk.delta_g2_neg_pc.clone(),
+        })
+    }
+
+    /// Verify a Groth16 proof `proof` of the statement prepared by [`prepare_statement`].
+    ///
+    /// [`prepare_statement`]: Self::prepare_statement
+    pub fn verify_proof_with_prepared_statement(
+        statement: &PreparedStatement<E>,
+        proof: &Proof<E>,
+    ) -> R1CSResult<bool> {
+        let qap = E::multi_miller_loop(
+            [
+                <E::G1Affine as Into<E::G1Prepared>>::into(proof.a),
+                proof.c.into(),
+            ],
+            [proof.b.into(), statement.delta_g2_neg_pc.clone()],
+        );
+
+        let test = E::final_exponentiation(qap).ok_or(SynthesisError::UnexpectedIdentity)?;
+
+        Ok(test.0 == statement.target)
+    }
+
+    /// Verify many Groth16 proofs against the same prepared verification key `pvk`, each
+    /// with respect to its own public inputs, with a single multi-pairing check.
+    ///
+    /// The verification equations are combined with challenges derived from a hash of the
+    /// batch, which costs one Miller loop per proof plus two, and one final exponentiation
+    /// in total. The batch is accepted if and only if every proof would be accepted by
+    /// [`verify_proof`], except with probability about `2^-128`; a rejected batch does not
+    /// tell which proof is invalid. An empty batch is accepted.
+    ///
+    /// [`verify_proof`]: Self::verify_proof
+    pub fn verify_proofs_batch<I: AsRef<[E::ScalarField]>>(
+        pvk: &PreparedVerifyingKey<E>,
+        proofs: &[(Proof<E>, I)],
+    ) -> R1CSResult<bool> {
+        let num_inputs = pvk.vk.gamma_abc_g1.len();
+        if num_inputs == 0
+            || proofs
+                .iter()
+                .any(|(_, x)| x.as_ref().len() + 1 != num_inputs)
+        {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        if proofs.is_empty() {
+            return Ok(true);
+        }
+        let challenges = batch_challenges(&pvk.vk, proofs);
+
+        // With challenges `r_i`, check
+        // `prod_i e(r_i A_i, B_i) * e(sum_i r_i IC(x_i), -gamma) * e(sum_i r_i C_i, -delta)
+        // = e(alpha, beta)^(sum_i r_i)`, where `sum_i r_i IC(x_i)` is a single MSM over
+        // the combined public inputs.
+        let mut input_scalars = vec![E::ScalarField::zero(); num_inputs];
+        for ((_, public_inputs), r) in proofs.iter().zip(&challenges) {
+            input_scalars[0] += r;
+            for (acc, x) in input_scalars[1..].iter_mut().zip(public_inputs.as_ref()) {
+                *acc += *x * r;
+            }
+        }
+        let prepared_inputs = E::G1::msm_unchecked(&pvk.vk.gamma_abc_g1, &input_scalars);
+        let c_points = proofs.iter().map(|(proof, _)| proof.c).collect::<Vec<_>>();
+        let c = E::G1::msm_unchecked(&c_points, &challenges);
+        let a = E::G1::normalize_batch(
+            &proofs
+                .iter()
+                .zip(&challenges)
+                .map(|((proof, _), r)| proof.a.mul_bigint(r.into_bigint()))
+                .collect::<Vec<_>>(),
+        );
+
+        let g1 = a
+            .into_iter()
+            .chain([prepared_inputs.into_affine(), c.into_affine()])
+            .map(<E::G1Affine as Into<E::G1Prepared>>::into);
+        let g2 = proofs
+            .iter()
+            .map(|(proof, _)| proof.b.into())
+            .chain([pvk.gamma_g2_neg_pc.clone(), pvk.delta_g2_neg_pc.clone()]);
+        let qap = E::multi_miller_loop(g1, g2);
+        let test = E::final_exponentiation(qap).ok_or(SynthesisError::UnexpectedIdentity)?;
+
+        Ok(test.0 == pvk.alpha_g1_beta_g2.pow(input_scalars[0].into_bigint()))
+    }
+
+    /// Verify many Groth16 proofs against the same prepared verification key `pvk` like
+    /// [`verify_proofs_batch`], but return the outcome of every proof, as
+    /// [`verify_proof_detailed`] would report it.
+    ///
+    /// Proofs with a wrong number of public inputs or an identity element are rejected
+    /// upfront, and the others are verified with one batch check. If that check fails, the
+    /// batch is bisected, checking the halves in parallel, until every invalid proof is
+    /// found, so `k` invalid proofs among `n` cost about `2k log(n / k)` batch checks.
+    ///
+    /// [`verify_proofs_batch`]: Self::verify_proofs_batch
+    /// [`verify_proof_detailed`]: Self::verify_proof_detailed
+    pub fn verify_proofs_batch_detailed<I: AsRef<[E::ScalarField]> + Sync>(
+        pvk: &PreparedVerifyingKey<E>,
+        proofs: &[(Proof<E>, I)],
+    ) -> Vec<Result<(), VerificationError>> {
+        if pvk.vk.gamma_abc_g1.is_empty() {
+            let error = VerificationError::Synthesis(SynthesisError::MalformedVerifyingKey);
+            return vec![Err(error); proofs.len()];
+        }
+        let mut results = cfg_iter!(proofs)
+            .map(|(proof, public_inputs)| {
+                check_num_public_inputs(pvk, public_inputs.as_ref().len())?;
+                if proof.a.is_zero() || proof.b.is_zero() || proof.c.is_zero() {
+                    return Err(VerificationError::MalformedProofPoint);
+                }
+                Ok(())
+            })
+            .collect::<Vec<_>>();
+
+        let candidates = (0..proofs.len())
+            .filter(|&i| results[i].is_ok())
+            .collect::<Vec<_>>();
+        for (i, error) in Self::find_invalid_proofs(pvk, proofs, &candidates) {
+            results[i] = Err(error);
+        }
+        results
+    }
+
+    /// Returns the proofs among `proofs[indices]` that fail verification, with the reason.
+    fn find_invalid_proofs<I: AsRef<[E::ScalarField]> + Sync>(
+        pvk: &PreparedVerifyingKey<E>,
+        proofs: &[(Proof<E>, I)],
+        indices: &[usize],
+    ) -> Vec<(usize, VerificationError)> {
+        if let [i] = indices {
+            let (proof, public_inputs) = &proofs[*i];
+            return Self::verify_proof_detailed(pvk, proof, public_inputs.as_ref())
+                .err()
+                .map(|error| (*i, error))
+                .into_iter()
+                .collect();
+        }
+        let batch = indices
+            .iter()
+            .map(|&i| (proofs[i].0.clone(), proofs[i].1.as_ref()))
+            .collect::<Vec<_>>();
+        match Self::verify_proofs_batch(pvk, &batch) {
+            Ok(true) => return Vec::new(),
+            Ok(false) => {},
+            Err(e) => return indices.iter().map(|&i| (i, e.into())).collect(),
+        }
+
+        let (left, right) = indices.split_at(indices.len() / 2);
+        let (mut invalid, invalid_right) = crate::join(
+            || Self::find_invalid_proofs(pvk, proofs, left),
+            || Self::find_invalid_proofs(pvk, proofs, right),
+        );
+        invalid.extend(invalid_right);
+        invalid
+    }
+
+    /// Verify a compressed proof `proof_bytes` against the compressed verifying key
+    /// `vk_bytes`, with respect to the public inputs in `public_inputs_hex`: big-endian
+    /// hexadecimal integers, optionally prefixed with `0x` and separated by commas or
+    /// whitespace.
+    ///
+    /// Every argument is plain bytes or text, so this is suited for exposing verification
+    /// to other languages, such as JavaScript through `wasm-bindgen`.
+    pub fn verify_bytes(
+        vk_bytes: &[u8],
+        proof_bytes: &[u8],
+        public_inputs_hex: &str,
+    ) -> Result<bool, VerificationError> {
+        let vk = VerifyingKey::<E>::deserialize_compressed(vk_bytes)?;
+        let proof = Proof::<E>::deserialize_compressed(proof_bytes)?;
+        let public_inputs = parse_hex_public_inputs(public_inputs_hex)?;
+
+        let pvk = prepare_verifying_key(&vk);
+        check_num_public_inputs(&pvk, public_inputs.len())?;
+        Ok(Self::verify_proof(&pvk, &proof, &public_inputs)?)
+    }
+
+    /// Read a compressed proof followed by its compressed public inputs from `reader`, and
+    /// verify it against the prepared verification key `pvk`.
+    ///
+    /// The number of public inputs is checked against `pvk` before any input is read, so
+    /// at most one proof and the expected number of field elements are consumed; the reader
+    /// is not required to be exhausted afterwards.
+    pub fn verify_from_reader<R: Read>(
+        pvk: &PreparedVerifyingKey<E>,
+        mut reader: R,
+    ) -> Result<bool, VerificationError> {
+        let proof = Proof::<E>::deserialize_compressed(&mut reader)?;
+
+        let found = u64::deserialize_compressed(&mut reader)?;
+        check_num_public_inputs(pvk, usize::try_from(found).unwrap_or(usize::MAX))?;
+        let public_inputs = (0..found as usize)
+            .map(|index| {
+                E::ScalarField::deserialize_compressed(&mut reader).map_err(|e| match e {
+                    SerializationError::InvalidData => VerificationError::InputNotInField { index },
+                    e => e.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::verify_proof(pvk, &proof, &public_inputs)?)
     }
 }
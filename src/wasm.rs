@@ -0,0 +1,35 @@
+//! A JavaScript interface to verification over BLS12-381, built with `wasm-bindgen`.
+//!
+//! Build it as a WebAssembly module with
+//! `cargo rustc --release --target wasm32-unknown-unknown --no-default-features --features wasm --crate-type cdylib`
+//! and generate the JavaScript glue with `wasm-bindgen --target web` on the resulting `.wasm` file.
+//! The exported function corresponds to this TypeScript declaration:
+//!
+//! ```ts
+//! export function verify(vk_bytes: Uint8Array, proof_bytes: Uint8Array, public_inputs_hex: string): boolean;
+//! ```
+//!
+//! The verifying key and the proof use the compressed `ark-serialize` encoding, and the public
+//! inputs are parsed as by [`Groth16::verify_bytes`]. Malformed inputs throw an `Error` whose
+//! message describes the [`VerificationError`](crate::VerificationError).
+
+// `#[wasm_bindgen]` expands to unsafe glue code.
+#![allow(unsafe_code)]
+
+use crate::Groth16;
+use ark_bls12_381::Bls12_381;
+use ark_std::string::ToString;
+use wasm_bindgen::prelude::*;
+
+/// Verifies `proof_bytes` against `vk_bytes` and the hexadecimal `public_inputs_hex`.
+///
+/// Returns `false` for a well-formed proof that does not verify and throws for malformed inputs.
+#[wasm_bindgen]
+pub fn verify(
+    vk_bytes: &[u8],
+    proof_bytes: &[u8],
+    public_inputs_hex: &str,
+) -> Result<bool, JsError> {
+    Groth16::<Bls12_381>::verify_bytes(vk_bytes, proof_bytes, public_inputs_hex)
+        .map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,203 @@
+//! Reading of keys and proofs in the formats that ZoKrates exports.
+//!
+//! ZoKrates writes verifying keys (`verification.key`) and proofs (`proof.json`) of its
+//! `g16` scheme as JSON with every number as a big-endian hexadecimal string prefixed
+//! with `0x`:
+//!
+//! ```text
+//! G1 point:  ["x", "y"]
+//! G2 point:  [["x.c0", "x.c1"], ["y.c0", "y.c1"]]
+//! vk:        { "alpha": G1, "beta": G2, "gamma": G2, "delta": G2,
+//!              "gamma_abc": [G1, ...], "scheme": "g16", "curve": ..., ... }
+//! proof:     { "proof": { "a": G1, "b": G2, "c": G1 }, "inputs": ["x_1", ..., "x_n"],
+//!              "scheme": "g16", ... }
+//! ```
+//!
+//! Other members, such as `curve`, are ignored; a `scheme` other than `g16` is rejected.
+//! The proving key (`proving.key`) of the `ark` backend is a [`ProvingKey`] written with
+//! `serialize_uncompressed`, which [`Groth16::proving_key_from_zokrates`] reads.
+use crate::{
+    prepare_verifying_key,
+    r1cs_to_qap::R1CSToQAP,
+    snarkjs::{IntegerError, Json},
+    verifier::check_num_public_inputs,
+    Groth16, Proof, ProvingKey, VerificationError, VerifyingKey,
+};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, Read, SerializationError};
+use ark_std::vec::Vec;
+
+/// Parses a `0x`-prefixed big-endian hexadecimal integer into a field element, rejecting
+/// values that are not below the modulus.
+fn parse_hex<F: PrimeField>(hex: &str) -> Result<F, IntegerError> {
+    let digits = hex.strip_prefix("0x").ok_or(IntegerError::Malformed)?;
+    if digits.is_empty() {
+        return Err(IntegerError::Malformed);
+    }
+    let mut bigint = F::BigInt::default();
+    let limbs = bigint.as_mut();
+    for (i, digit) in digits.bytes().rev().enumerate() {
+        let digit = char::from(digit)
+            .to_digit(16)
+            .ok_or(IntegerError::Malformed)?;
+        match limbs.get_mut(i / 16) {
+            Some(limb) => *limb |= u64::from(digit) << (4 * (i % 16)),
+            None if digit == 0 => {},
+            None => return Err(IntegerError::TooLarge),
+        }
+    }
+    F::from_bigint(bigint).ok_or(IntegerError::TooLarge)
+}
+
+/// Parses a coordinate: a hexadecimal string, or an array of hexadecimal strings for a
+/// field extension.
+fn parse_coordinate<F: Field>(json: &Json<'_>) -> Option<F> {
+    let elements = match json {
+        Json::Array(elements) => elements
+            .iter()
+            .map(|e| parse_hex(e.as_str()?).ok())
+            .collect::<Option<Vec<_>>>()?,
+        json => ark_std::vec![parse_hex(json.as_str()?).ok()?],
+    };
+    F::from_base_prime_field_elems(&elements)
+}
+
+/// Parses a point, returning `Err(VerificationError::InvalidEncoding)` if the coordinates
+/// cannot be read and `Err(invalid_point)` if they are not a point of the prime-order
+/// subgroup.
+fn parse_point<P: SWCurveConfig>(
+    json: Option<&Json<'_>>,
+    invalid_point: VerificationError,
+) -> Result<Affine<P>, VerificationError> {
+    let coordinates = json
+        .and_then(Json::as_array)
+        .filter(|c| c.len() == 2)
+        .and_then(|c| {
+            c.iter()
+                .map(parse_coordinate::<P::BaseField>)
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or(VerificationError::InvalidEncoding)?;
+    let point = Affine::new_unchecked(coordinates[0], coordinates[1]);
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(invalid_point)
+    }
+}
+
+/// Parses a JSON object and rejects it if its `scheme` is present but not `g16`.
+fn parse_object(json: &str) -> Result<Json<'_>, VerificationError> {
+    let json = Json::parse(json)
+        .filter(|json| matches!(json, Json::Object(_)))
+        .ok_or(VerificationError::InvalidEncoding)?;
+    match json.get("scheme") {
+        None => Ok(json),
+        Some(scheme) if scheme.as_str() == Some("g16") => Ok(json),
+        Some(_) => Err(VerificationError::InvalidEncoding),
+    }
+}
+
+impl<E: Pairing, QAP: R1CSToQAP> Groth16<E, QAP> {
+    /// Read a verifying key from the contents of a ZoKrates `verification.key`.
+    ///
+    /// Coordinates are checked to be canonical field elements and points of the
+    /// prime-order subgroups. The curve is that of `E`; a key for another curve is rejected
+    /// because its points do not lie on the curve.
+    pub fn verifying_key_from_zokrates<P1, P2>(
+        json: &str,
+    ) -> Result<VerifyingKey<E>, VerificationError>
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        let json = parse_object(json)?;
+        let g1 = |key| parse_point::<P1>(json.get(key), VerificationError::InvalidEncoding);
+        let g2 = |key| parse_point::<P2>(json.get(key), VerificationError::InvalidEncoding);
+        let gamma_abc_g1 = json
+            .get("gamma_abc")
+            .and_then(Json::as_array)
+            .ok_or(VerificationError::InvalidEncoding)?
+            .iter()
+            .map(|point| parse_point::<P1>(Some(point), VerificationError::InvalidEncoding))
+            .collect::<Result<Vec<_>, _>>()?;
+        if gamma_abc_g1.is_empty() {
+            return Err(VerificationError::InvalidEncoding);
+        }
+
+        Ok(VerifyingKey {
+            alpha_g1: g1("alpha")?,
+            beta_g2: g2("beta")?,
+            gamma_g2: g2("gamma")?,
+            delta_g2: g2("delta")?,
+            gamma_abc_g1,
+        })
+    }
+
+    /// Read a proof and its public inputs from the contents of a ZoKrates `proof.json`,
+    /// checked like [`Groth16::verifying_key_from_zokrates`].
+    pub fn proof_from_zokrates<P1, P2>(
+        json: &str,
+    ) -> Result<(Proof<E>, Vec<E::ScalarField>), VerificationError>
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        let json = parse_object(json)?;
+        let points = json
+            .get("proof")
+            .ok_or(VerificationError::InvalidEncoding)?;
+        let proof = Proof {
+            a: parse_point::<P1>(points.get("a"), VerificationError::MalformedProofPoint)?,
+            b: parse_point::<P2>(points.get("b"), VerificationError::MalformedProofPoint)?,
+            c: parse_point::<P1>(points.get("c"), VerificationError::MalformedProofPoint)?,
+        };
+        let public_inputs = json
+            .get("inputs")
+            .and_then(Json::as_array)
+            .ok_or(VerificationError::InvalidEncoding)?
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let input = input.as_str().ok_or(VerificationError::InvalidEncoding)?;
+                parse_hex(input).map_err(|e| e.input_error(index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((proof, public_inputs))
+    }
+
+    /// Verify a proof produced by ZoKrates, given the contents of its `proof.json` in
+    /// `proof_json`, which includes the public inputs, against the verifying key in
+    /// `vk_json` (`verification.key`).
+    pub fn verify_zokrates<P1, P2>(
+        vk_json: &str,
+        proof_json: &str,
+    ) -> Result<bool, VerificationError>
+    where
+        E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+        P1: SWCurveConfig,
+        P2: SWCurveConfig,
+    {
+        let vk = Self::verifying_key_from_zokrates::<P1, P2>(vk_json)?;
+        let (proof, public_inputs) = Self::proof_from_zokrates::<P1, P2>(proof_json)?;
+
+        let pvk = prepare_verifying_key(&vk);
+        check_num_public_inputs(&pvk, public_inputs.len())?;
+        Ok(Self::verify_proof(&pvk, &proof, &public_inputs)?)
+    }
+
+    /// Read a proving key from a ZoKrates `proving.key` of the `ark` backend, checking
+    /// that its points are in the prime-order subgroups, so that a ZoKrates setup can be
+    /// reused for proving.
+    pub fn proving_key_from_zokrates<R: Read>(
+        reader: R,
+    ) -> Result<ProvingKey<E>, SerializationError> {
+        ProvingKey::deserialize_uncompressed(reader)
+    }
+}
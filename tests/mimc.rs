@@ -1,3 +1,4 @@
+#![cfg(feature = "prover")]
 #![warn(unused)]
 #![deny(
     trivial_casts,
@@ -6,7 +7,8 @@
     stable_features,
     non_shorthand_field_patterns,
     renamed_and_removed_lints,
-    private_in_public,
+    private_interfaces,
+    private_bounds,
     unsafe_code
 )]
 
@@ -44,6 +46,7 @@ const MIMC_ROUNDS: usize = 322;
 ///     return xL
 /// }
 /// ```
+#[allow(clippy::needless_range_loop)]
 fn mimc<F: Field>(mut xl: F, mut xr: F, constants: &[F]) -> F {
     assert_eq!(constants.len(), MIMC_ROUNDS);
 
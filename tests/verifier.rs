@@ -0,0 +1,67 @@
+//! The verifier paths, which must work without the `prover` and `std` features:
+//! `cargo test --no-default-features --test verifier` builds the library as `no_std`.
+//! The test itself only uses `core` and `alloc`; `std` is linked for the test harness.
+#![no_std]
+
+extern crate std;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{
+    prepare_verifying_key,
+    test_vectors::{decode_hex, BLS12_381, BN254},
+    Groth16, PreparedInputs, Proof, VerificationError, VerifyingKey,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+#[test]
+fn test_vectors() {
+    for vector in BLS12_381 {
+        assert_eq!(
+            vector.verify::<Bls12_381>(),
+            vector.expected,
+            "{}",
+            vector.name
+        );
+    }
+    for vector in BN254 {
+        assert_eq!(
+            vector.verify::<ark_bn254::Bn254>(),
+            vector.expected,
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn deserialized_keys_and_proofs() {
+    let vector = &BLS12_381[0];
+    let vk = VerifyingKey::<Bls12_381>::deserialize_compressed(
+        &decode_hex(vector.verifying_key).unwrap()[..],
+    )
+    .unwrap();
+    let proof =
+        Proof::<Bls12_381>::deserialize_compressed(&decode_hex(vector.proof).unwrap()[..]).unwrap();
+    let pvk = prepare_verifying_key(&vk);
+    let c = Fr::from(vector.a * vector.b);
+
+    assert!(Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[c]).unwrap());
+    assert!(!Groth16::<Bls12_381>::verify_proof(&pvk, &proof, &[c + Fr::from(1u64)]).unwrap());
+    assert_eq!(
+        Groth16::<Bls12_381>::verify_proof_detailed(&pvk, &proof, &[]),
+        Err(VerificationError::WrongNumberOfPublicInputs {
+            expected: 1,
+            found: 0
+        })
+    );
+
+    let prepared = Groth16::<Bls12_381>::prepare_public_inputs(&pvk, &[c]).unwrap();
+    let mut bytes = Vec::new();
+    prepared.serialize_compressed(&mut bytes).unwrap();
+    let prepared = PreparedInputs::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+    assert!(
+        Groth16::<Bls12_381>::verify_proof_with_prepared_public_inputs(&pvk, &proof, &prepared)
+            .unwrap()
+    );
+}